@@ -0,0 +1,14 @@
+-- Create document_number_sequences table
+-- 文書番号の連番をルール・年・月・部署ごとに永続管理する
+-- 採番は文書の INSERT と同一トランザクション内で last_sequence を加算して行う
+CREATE TABLE document_number_sequences (
+    rule_id INTEGER NOT NULL,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL,
+    department_code TEXT NOT NULL,
+    last_sequence INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (rule_id, year, month, department_code),
+    FOREIGN KEY (rule_id) REFERENCES document_number_generation_rules (id)
+);
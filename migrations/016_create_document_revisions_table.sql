@@ -0,0 +1,24 @@
+-- Create document_revisions table
+-- 既存文書番号に対する改訂版(r1, r2...)・ドラフト版(d1, d2...)を管理する
+CREATE TABLE document_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL,
+    revision_type TEXT NOT NULL,          -- 'revision' | 'draft'
+    version_number INTEGER NOT NULL,      -- 版番号（r1 → 1, d2 → 2）
+    number TEXT UNIQUE NOT NULL,          -- 親文書番号 + 接尾辞（例: TEC-2508001r1）
+    status TEXT NOT NULL,                 -- 'issued' | 'draft' | 'promoted'
+    notes TEXT,
+    promoted_revision_id INTEGER,         -- ドラフトから発行された改訂版
+    created_by INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (document_id) REFERENCES documents (id),
+    FOREIGN KEY (promoted_revision_id) REFERENCES document_revisions (id),
+    FOREIGN KEY (created_by) REFERENCES employees (id),
+    UNIQUE (document_id, revision_type, version_number),
+    CHECK (revision_type IN ('revision', 'draft')),
+    CHECK (status IN ('issued', 'draft', 'promoted')),
+    CHECK (version_number > 0)
+);
+
+CREATE INDEX idx_document_revisions_document ON document_revisions(document_id);
@@ -0,0 +1,6 @@
+-- Add role column to employees
+-- 'admin' は手動採番など管理者限定の操作を実行できる
+ALTER TABLE employees ADD COLUMN role TEXT NOT NULL DEFAULT 'user'
+    CHECK (role IN ('user', 'admin'));
+
+UPDATE employees SET role = 'admin' WHERE employee_number = 'EMP001';
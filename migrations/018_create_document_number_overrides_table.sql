@@ -0,0 +1,15 @@
+-- Create document_number_overrides table
+-- 管理者が採番ルールを使わずに手動で設定した文書番号の監査記録
+CREATE TABLE document_number_overrides (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL,
+    document_number TEXT NOT NULL,
+    assigned_by INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (document_id) REFERENCES documents (id),
+    FOREIGN KEY (assigned_by) REFERENCES employees (id)
+);
+
+CREATE INDEX idx_document_number_overrides_document ON document_number_overrides(document_id);
+CREATE INDEX idx_document_number_overrides_assigned_by ON document_number_overrides(assigned_by);
@@ -0,0 +1,31 @@
+-- Create path_generation_rules table
+-- 文書フォルダのネットワークパスを生成するルール（要件定義 7.2）
+-- 部署・作成年の条件と有効期間で適用ルールを決定し、テンプレートからパスを組み立てる
+CREATE TABLE path_generation_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_name TEXT NOT NULL,
+    path_template TEXT NOT NULL,
+    department_code TEXT,
+    year_from INTEGER,
+    year_to INTEGER,
+    effective_from DATE NOT NULL,
+    effective_until DATE,
+    priority INTEGER NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    CHECK (year_from IS NULL OR year_to IS NULL OR year_from <= year_to)
+);
+
+CREATE INDEX idx_path_generation_rules_department ON path_generation_rules(department_code);
+CREATE INDEX idx_path_generation_rules_effective ON path_generation_rules(effective_from, effective_until);
+CREATE INDEX idx_path_generation_rules_priority ON path_generation_rules(priority);
+
+-- パスの解決に使うため、文書の作成部署を保持する
+-- 既存の文書は作成者の所属部署で補完する
+ALTER TABLE documents ADD COLUMN department_code TEXT;
+
+UPDATE documents
+SET department_code = (SELECT e.department FROM employees e WHERE e.id = documents.created_by)
+WHERE department_code IS NULL;
+
+CREATE INDEX idx_documents_department_code ON documents(department_code);
@@ -0,0 +1,36 @@
+-- Create path_generation_rule_versions table
+-- パス生成ルールの変更履歴（F004）
+-- 各版は valid_from から valid_until の前日まで効力を持ち、過去の時点で有効だったルールでパスを再現できる
+CREATE TABLE path_generation_rule_versions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_id INTEGER NOT NULL,
+    version_number INTEGER NOT NULL,
+    rule_name TEXT NOT NULL,
+    path_template TEXT NOT NULL,
+    department_code TEXT,
+    year_from INTEGER,
+    year_to INTEGER,
+    effective_from DATE NOT NULL,
+    effective_until DATE,
+    priority INTEGER NOT NULL,
+    change_type TEXT NOT NULL CHECK (change_type IN ('created', 'updated', 'retired')),
+    changed_by INTEGER,
+    change_reason TEXT,
+    valid_from DATE NOT NULL,
+    valid_until DATE,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (rule_id, version_number),
+    CHECK (valid_until IS NULL OR valid_until >= valid_from),
+    FOREIGN KEY (rule_id) REFERENCES path_generation_rules (id),
+    FOREIGN KEY (changed_by) REFERENCES employees (id)
+);
+
+CREATE INDEX idx_path_generation_rule_versions_validity ON path_generation_rule_versions(valid_from, valid_until);
+
+-- 既存ルールを初版として登録する
+INSERT INTO path_generation_rule_versions
+    (rule_id, version_number, rule_name, path_template, department_code, year_from, year_to,
+     effective_from, effective_until, priority, change_type, valid_from)
+SELECT id, 1, rule_name, path_template, department_code, year_from, year_to,
+       effective_from, effective_until, priority, 'created', effective_from
+FROM path_generation_rules;
@@ -0,0 +1,15 @@
+-- Add clearance levels for document reads
+-- 機密レベル: 0=一般, 1=社内, 2=機密（情報クラスⅠ）, 3=個人情報を含む文書の保存場所まで閲覧可
+CREATE TABLE role_clearance_levels (
+    role TEXT PRIMARY KEY CHECK (role IN ('user', 'admin')),
+    clearance_level INTEGER NOT NULL CHECK (clearance_level BETWEEN 0 AND 3),
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO role_clearance_levels (role, clearance_level) VALUES
+    ('user', 1),
+    ('admin', 3);
+
+-- 社員個別の設定（NULLの場合はロールの既定値を使う）
+ALTER TABLE employees ADD COLUMN clearance_level INTEGER
+    CHECK (clearance_level IS NULL OR clearance_level BETWEEN 0 AND 3);
@@ -0,0 +1,87 @@
+-- Normalize document classification values
+-- 社内外区分: 'internal' | 'external'、重要度: 'class1' | 'class2'、個人情報: 'none' | 'present'
+
+-- 変換できなかった値は元の表記を残してからNULLにする
+CREATE TABLE legacy_document_classifications (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL,
+    column_name TEXT NOT NULL,
+    original_value TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (document_id) REFERENCES documents (id)
+);
+
+UPDATE documents SET internal_external = CASE LOWER(TRIM(internal_external))
+        WHEN 'internal' THEN 'internal'
+        WHEN '社内' THEN 'internal'
+        WHEN 'external' THEN 'external'
+        WHEN '社外' THEN 'external'
+        ELSE internal_external
+    END
+WHERE internal_external IS NOT NULL;
+
+UPDATE documents SET importance_class = CASE LOWER(TRIM(importance_class))
+        WHEN 'class1' THEN 'class1'
+        WHEN '情報クラスⅰ' THEN 'class1'
+        WHEN '情報クラスⅠ' THEN 'class1'
+        WHEN '情報クラスi' THEN 'class1'
+        WHEN '情報クラス1' THEN 'class1'
+        WHEN 'クラスⅠ' THEN 'class1'
+        WHEN 'class2' THEN 'class2'
+        WHEN '情報クラスⅱ' THEN 'class2'
+        WHEN '情報クラスⅡ' THEN 'class2'
+        WHEN '情報クラスii' THEN 'class2'
+        WHEN '情報クラス2' THEN 'class2'
+        WHEN 'クラスⅡ' THEN 'class2'
+        ELSE importance_class
+    END
+WHERE importance_class IS NOT NULL;
+
+UPDATE documents SET personal_info = CASE LOWER(TRIM(personal_info))
+        WHEN 'none' THEN 'none'
+        WHEN '無' THEN 'none'
+        WHEN 'なし' THEN 'none'
+        WHEN '無し' THEN 'none'
+        WHEN 'present' THEN 'present'
+        WHEN '有' THEN 'present'
+        WHEN 'あり' THEN 'present'
+        WHEN '有り' THEN 'present'
+        ELSE personal_info
+    END
+WHERE personal_info IS NOT NULL;
+
+INSERT INTO legacy_document_classifications (document_id, column_name, original_value)
+SELECT id, 'internal_external', internal_external FROM documents
+WHERE internal_external IS NOT NULL AND internal_external NOT IN ('internal', 'external')
+UNION ALL
+SELECT id, 'importance_class', importance_class FROM documents
+WHERE importance_class IS NOT NULL AND importance_class NOT IN ('class1', 'class2')
+UNION ALL
+SELECT id, 'personal_info', personal_info FROM documents
+WHERE personal_info IS NOT NULL AND personal_info NOT IN ('none', 'present');
+
+UPDATE documents SET internal_external = NULL
+WHERE internal_external NOT IN ('internal', 'external');
+UPDATE documents SET importance_class = NULL
+WHERE importance_class NOT IN ('class1', 'class2');
+UPDATE documents SET personal_info = NULL
+WHERE personal_info NOT IN ('none', 'present');
+
+-- 以降は未知の値の書き込みを拒否する（SQLiteでは既存列にCHECK制約を追加できないためトリガーで検証）
+CREATE TRIGGER documents_classification_insert
+BEFORE INSERT ON documents
+WHEN (NEW.internal_external IS NOT NULL AND NEW.internal_external NOT IN ('internal', 'external'))
+  OR (NEW.importance_class IS NOT NULL AND NEW.importance_class NOT IN ('class1', 'class2'))
+  OR (NEW.personal_info IS NOT NULL AND NEW.personal_info NOT IN ('none', 'present'))
+BEGIN
+    SELECT RAISE(ABORT, 'invalid document classification value');
+END;
+
+CREATE TRIGGER documents_classification_update
+BEFORE UPDATE OF internal_external, importance_class, personal_info ON documents
+WHEN (NEW.internal_external IS NOT NULL AND NEW.internal_external NOT IN ('internal', 'external'))
+  OR (NEW.importance_class IS NOT NULL AND NEW.importance_class NOT IN ('class1', 'class2'))
+  OR (NEW.personal_info IS NOT NULL AND NEW.personal_info NOT IN ('none', 'present'))
+BEGIN
+    SELECT RAISE(ABORT, 'invalid document classification value');
+END;
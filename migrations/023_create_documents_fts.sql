@@ -0,0 +1,49 @@
+-- Create full-text search index for documents
+-- 日本語を分かち書きせずに検索できるよう trigram トークナイザーを使う（rowid = documents.id）
+CREATE VIRTUAL TABLE documents_fts USING fts5(
+    title,
+    notes,
+    business_number,
+    creator_name,
+    tokenize = 'trigram'
+);
+
+INSERT INTO documents_fts (rowid, title, notes, business_number, creator_name)
+SELECT d.id, d.title, d.notes, d.business_number, e.name
+FROM documents d
+LEFT JOIN employees e ON e.id = d.created_by;
+
+CREATE TRIGGER documents_fts_insert
+AFTER INSERT ON documents
+BEGIN
+    INSERT INTO documents_fts (rowid, title, notes, business_number, creator_name)
+    VALUES (
+        NEW.id, NEW.title, NEW.notes, NEW.business_number,
+        (SELECT name FROM employees WHERE id = NEW.created_by)
+    );
+END;
+
+CREATE TRIGGER documents_fts_update
+AFTER UPDATE OF title, notes, business_number, created_by ON documents
+BEGIN
+    DELETE FROM documents_fts WHERE rowid = OLD.id;
+    INSERT INTO documents_fts (rowid, title, notes, business_number, creator_name)
+    VALUES (
+        NEW.id, NEW.title, NEW.notes, NEW.business_number,
+        (SELECT name FROM employees WHERE id = NEW.created_by)
+    );
+END;
+
+CREATE TRIGGER documents_fts_delete
+AFTER DELETE ON documents
+BEGIN
+    DELETE FROM documents_fts WHERE rowid = OLD.id;
+END;
+
+-- 作成者の氏名変更を索引に反映する
+CREATE TRIGGER documents_fts_creator_name_update
+AFTER UPDATE OF name ON employees
+BEGIN
+    UPDATE documents_fts SET creator_name = NEW.name
+    WHERE rowid IN (SELECT id FROM documents WHERE created_by = NEW.id);
+END;
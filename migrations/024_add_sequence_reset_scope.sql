@@ -0,0 +1,7 @@
+-- Add reset_scope to document_number_generation_rules
+-- 連番をリセットする単位（monthly: 年月ごと / yearly: 暦年ごと / fiscal_yearly: 4月始まりの年度ごと / never: リセットしない）
+-- document_number_sequences の year・month には単位に応じた値を保存する
+--   yearly: (暦年, 0) / fiscal_yearly: (年度, 0) / never: (0, 0)
+ALTER TABLE document_number_generation_rules
+    ADD COLUMN reset_scope TEXT NOT NULL DEFAULT 'monthly'
+    CHECK (reset_scope IN ('monthly', 'yearly', 'fiscal_yearly', 'never'));
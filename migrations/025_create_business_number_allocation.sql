@@ -0,0 +1,61 @@
+-- Business number allocation (F035 / F035a)
+-- 業務番号は 10000〜99990 を循環して再利用するため、完了・中止済みの業務と同じ番号を
+-- 新しい業務に割り当てられるよう businesses.business_number の UNIQUE 制約を
+-- 「進行中（active）の業務の間での一意」に緩める。
+-- SQLite は制約を変更できないため、外部キー検査をコミットまで遅延させてテーブルを作り直す。
+PRAGMA defer_foreign_keys = ON;
+
+CREATE TEMP TABLE businesses_backup AS SELECT * FROM businesses;
+
+DROP TABLE businesses;
+
+CREATE TABLE businesses (
+    id INTEGER PRIMARY KEY,
+    business_number TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    customer_name TEXT,
+    start_date DATE,
+    end_date DATE,
+    status TEXT CHECK(status IN ('active', 'completed', 'cancelled')) DEFAULT 'active',
+    created_by INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (created_by) REFERENCES employees (id)
+);
+
+INSERT INTO businesses SELECT * FROM businesses_backup;
+
+DROP TABLE businesses_backup;
+
+CREATE INDEX idx_businesses_number ON businesses(business_number);
+CREATE INDEX idx_businesses_customer ON businesses(customer_name);
+
+-- 進行中の業務同士での番号重複を禁止する
+CREATE UNIQUE INDEX idx_businesses_active_number
+    ON businesses(business_number)
+    WHERE status = 'active';
+
+-- 業務番号ごとの使用回数（循環で再利用された回数を含む）
+CREATE TABLE business_number_usages (
+    business_number TEXT PRIMARY KEY,
+    use_count INTEGER NOT NULL DEFAULT 0,
+    last_business_id INTEGER,
+    first_used_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    last_used_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 既存の業務を使用実績として取り込む
+INSERT INTO business_number_usages (business_number, use_count, last_business_id, first_used_at, last_used_at)
+SELECT business_number, COUNT(*), MAX(id), MIN(created_at), MAX(created_at)
+FROM businesses
+GROUP BY business_number;
+
+-- 自動採番の位置（単一行）。last_number が NULL の場合は下限から採番する
+CREATE TABLE business_number_allocator (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_number INTEGER,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO business_number_allocator (id, last_number) VALUES (1, NULL);
@@ -0,0 +1,46 @@
+-- Create document_number_reservations tables
+-- システムに接続できない現場作業のため、ルールの連番を期限付きでまとめて確保する
+-- 確保した番号は連番カウンターを進めて払い出すため、通常の採番とは重複しない
+CREATE TABLE document_number_reservations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_id INTEGER NOT NULL,
+    -- 連番カウンターのキー（document_number_sequences と同じ）
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL,
+    department_code TEXT NOT NULL,
+    document_type_code TEXT NOT NULL,
+    section_code TEXT,
+    created_date DATE NOT NULL,
+    reserved_by INTEGER NOT NULL,
+    expires_on DATE NOT NULL,
+    -- 期限までに使われなかった番号の扱い（release: 通常の採番で再利用 / void: 欠番として記録）
+    expiry_action TEXT NOT NULL DEFAULT 'release' CHECK (expiry_action IN ('release', 'void')),
+    purpose TEXT,
+    expired_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (rule_id) REFERENCES document_number_generation_rules (id),
+    FOREIGN KEY (reserved_by) REFERENCES employees (id)
+);
+
+-- 予約ブロック内の個々の番号
+-- reserved: 予約中 / claimed: 文書登録済み / released: 期限切れで解放 / reissued: 解放後に通常の採番で使用 / voided: 期限切れで欠番
+CREATE TABLE document_number_reserved_numbers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    reservation_id INTEGER NOT NULL,
+    document_number TEXT NOT NULL UNIQUE,
+    sequence_number INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'reserved'
+        CHECK (status IN ('reserved', 'claimed', 'released', 'reissued', 'voided')),
+    document_id INTEGER,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (reservation_id) REFERENCES document_number_reservations (id),
+    FOREIGN KEY (document_id) REFERENCES documents (id)
+);
+
+CREATE INDEX idx_document_number_reservations_key
+    ON document_number_reservations(rule_id, year, month, department_code);
+CREATE INDEX idx_document_number_reservations_expires ON document_number_reservations(expires_on);
+CREATE INDEX idx_document_number_reserved_numbers_reservation
+    ON document_number_reserved_numbers(reservation_id);
+CREATE INDEX idx_document_number_reserved_numbers_status
+    ON document_number_reserved_numbers(status);
@@ -0,0 +1,24 @@
+-- Create document_number_voids table
+-- 取り下げ・欠番とした文書番号の記録。記録した番号は再利用しない
+-- 連番カウンターのキー（rule_id, year, month, department_code）と連番は、
+-- 番号がルールのテンプレートに一致する場合だけ保存する（手動採番の番号などは NULL）
+CREATE TABLE document_number_voids (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_number TEXT NOT NULL UNIQUE,
+    rule_id INTEGER,
+    year INTEGER,
+    month INTEGER,
+    department_code TEXT,
+    sequence_number INTEGER,
+    -- 取り下げた文書（文書が登録されていない番号の場合は NULL）
+    document_id INTEGER,
+    reason TEXT NOT NULL,
+    voided_by INTEGER NOT NULL,
+    voided_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (rule_id) REFERENCES document_number_generation_rules (id),
+    FOREIGN KEY (document_id) REFERENCES documents (id),
+    FOREIGN KEY (voided_by) REFERENCES employees (id)
+);
+
+CREATE INDEX idx_document_number_voids_key
+    ON document_number_voids(rule_id, year, month, department_code);
@@ -0,0 +1,15 @@
+-- Create document_number_aliases table
+-- 組織変更や訂正で番号を付け替えた文書の旧番号。紙やメールに残る旧番号から現在の文書を引けるようにする
+-- 旧番号は文書番号・欠番・予約番号と同じ名前空間で一意とし、採番で再利用しない
+CREATE TABLE document_number_aliases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL,
+    alias_number TEXT NOT NULL UNIQUE,
+    reason TEXT,
+    created_by INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (document_id) REFERENCES documents (id),
+    FOREIGN KEY (created_by) REFERENCES employees (id)
+);
+
+CREATE INDEX idx_document_number_aliases_document ON document_number_aliases(document_id);
@@ -0,0 +1,19 @@
+-- Create document_changes table
+-- 文書メタデータの変更履歴。更新は変更した項目ごとに1行、削除（ゴミ箱へ移動）と復元は1行ずつ記録する
+-- 値は文字列で保存し、未設定は NULL とする
+CREATE TABLE document_changes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL,
+    change_type TEXT NOT NULL CHECK (change_type IN ('updated', 'deleted', 'restored')),
+    field_name TEXT,
+    old_value TEXT,
+    new_value TEXT,
+    changed_by INTEGER NOT NULL,
+    reason TEXT,
+    changed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (document_id) REFERENCES documents (id),
+    FOREIGN KEY (changed_by) REFERENCES employees (id)
+);
+
+CREATE INDEX idx_document_changes_document ON document_changes(document_id);
+CREATE INDEX idx_document_changes_type ON document_changes(change_type);
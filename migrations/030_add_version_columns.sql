@@ -0,0 +1,6 @@
+-- Add version columns for optimistic concurrency control
+-- 文書・業務・部署の更新のたびに1ずつ増やす。REST の ETag / If-Match と GraphQL の version に使い、
+-- 取得時から変わっていれば更新を拒否する
+ALTER TABLE documents ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE businesses ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE departments ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
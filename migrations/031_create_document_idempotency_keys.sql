@@ -0,0 +1,16 @@
+-- Create document_idempotency_keys table
+-- 文書作成リクエストの Idempotency-Key と作成結果。有効期限内に同じキーで再送されたリクエストは
+-- 新たに採番せず、保存した作成結果（CreatedDocumentWithNumber の JSON）を返す
+-- response が NULL の行は処理中で、作成に失敗した場合は削除する
+CREATE TABLE document_idempotency_keys (
+    idempotency_key TEXT PRIMARY KEY,
+    -- 同じキーで内容の異なるリクエストを検出するためのリクエスト本文（JSON）
+    request_fingerprint TEXT NOT NULL,
+    document_id INTEGER,
+    response TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    expires_at DATETIME NOT NULL,
+    FOREIGN KEY (document_id) REFERENCES documents (id)
+);
+
+CREATE INDEX idx_document_idempotency_keys_expires ON document_idempotency_keys(expires_at);
@@ -0,0 +1,101 @@
+-- Create organization history tables
+-- 部署・課の有効期間付きの記録と、組織改編（名称変更・統合・分割・移動）の履歴
+-- 1文字の部署コード・課コードは時期によって指す組織が変わるため、departments.code の一意制約を
+-- 有効な部署・課の中での一意に置き換える。departments は最新の状態を保持し、
+-- 過去の時点の状態は department_versions から求める
+
+ALTER TABLE departments RENAME TO departments_old;
+
+CREATE TABLE departments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    -- department: 部 / section: 課（課は親の部署を持つ）
+    kind TEXT NOT NULL DEFAULT 'department' CHECK (kind IN ('department', 'section')),
+    parent_id INTEGER,
+    level INTEGER DEFAULT 0,
+    manager_id INTEGER,
+    description TEXT,
+    location TEXT,
+    phone_number TEXT,
+    email TEXT,
+    budget REAL,
+    is_active INTEGER DEFAULT 1,
+    created_date DATE,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    version INTEGER NOT NULL DEFAULT 1,
+    CHECK (kind = 'department' OR parent_id IS NOT NULL),
+    FOREIGN KEY (parent_id) REFERENCES departments(id),
+    FOREIGN KEY (manager_id) REFERENCES employees(id)
+);
+
+INSERT INTO departments (id, code, name, kind, parent_id, level, manager_id, description, location,
+                         phone_number, email, budget, is_active, created_date, created_at, updated_at,
+                         version)
+SELECT id, code, name, 'department', parent_id, level, manager_id, description, location,
+       phone_number, email, budget, is_active, created_date, created_at, updated_at, version
+FROM departments_old;
+
+DROP TABLE departments_old;
+
+CREATE INDEX idx_departments_code ON departments(code);
+CREATE INDEX idx_departments_name ON departments(name);
+CREATE INDEX idx_departments_parent ON departments(parent_id);
+CREATE INDEX idx_departments_manager ON departments(manager_id);
+CREATE INDEX idx_departments_active ON departments(is_active);
+-- 部署コードは有効な部署の中で、課コードは同じ部署の有効な課の中で一意
+CREATE UNIQUE INDEX idx_departments_active_code
+    ON departments(kind, code, CASE WHEN kind = 'section' THEN parent_id ELSE 0 END)
+    WHERE is_active = 1;
+
+-- 組織改編
+-- rename: コード・名称の変更 / merge: 統合（統合元は廃止） / split: 分割（分割元は廃止） / move: 親部署の変更
+CREATE TABLE organization_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type TEXT NOT NULL CHECK (event_type IN ('rename', 'merge', 'split', 'move')),
+    effective_date DATE NOT NULL,
+    reason TEXT,
+    recorded_by INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (recorded_by) REFERENCES employees (id)
+);
+
+-- 改編の対象（source: 改編前の部署・課 / target: 改編後の部署・課。名称変更・移動では同じ部署が両方になる）
+CREATE TABLE organization_event_units (
+    event_id INTEGER NOT NULL,
+    department_id INTEGER NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('source', 'target')),
+    PRIMARY KEY (event_id, department_id, role),
+    FOREIGN KEY (event_id) REFERENCES organization_events (id),
+    FOREIGN KEY (department_id) REFERENCES departments (id)
+);
+
+-- 部署・課の有効期間付きの記録（valid_from から valid_to の前日まで有効。valid_to が NULL なら現在も有効）
+CREATE TABLE department_versions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    department_id INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('department', 'section')),
+    parent_id INTEGER,
+    level INTEGER NOT NULL DEFAULT 0,
+    valid_from DATE NOT NULL,
+    valid_to DATE,
+    -- この記録を作った組織改編（新設時・移行時は NULL）
+    event_id INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    CHECK (valid_to IS NULL OR valid_to > valid_from),
+    FOREIGN KEY (department_id) REFERENCES departments (id),
+    FOREIGN KEY (parent_id) REFERENCES departments (id),
+    FOREIGN KEY (event_id) REFERENCES organization_events (id)
+);
+
+CREATE INDEX idx_department_versions_department ON department_versions(department_id, valid_from);
+CREATE INDEX idx_department_versions_code ON department_versions(kind, code, valid_from);
+CREATE INDEX idx_organization_event_units_department ON organization_event_units(department_id);
+
+-- 既存の部署は作成日（不明な場合は最初期）から現在まで有効な記録にする
+INSERT INTO department_versions (department_id, code, name, kind, parent_id, level, valid_from)
+SELECT id, code, name, kind, parent_id, COALESCE(level, 0), COALESCE(created_date, '1900-01-01')
+FROM departments;
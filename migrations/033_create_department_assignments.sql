@@ -0,0 +1,34 @@
+-- Create department_assignments table
+-- 社員の所属の履歴。主務（primary）は同時に1つまで、兼務（concurrent）・出向（secondment）は主務と並行して持てる
+-- 社員の現在の所属は有効な主務から求める。employees.department / position は既存の集計との互換のため、
+-- 所属の登録・変更時に現在の主務に合わせて更新する
+CREATE TABLE department_assignments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    employee_id INTEGER NOT NULL,
+    department_id INTEGER NOT NULL,
+    -- 課（department_id の部署に属する課）
+    section_id INTEGER,
+    position TEXT,
+    assignment_type TEXT NOT NULL DEFAULT 'primary'
+        CHECK (assignment_type IN ('primary', 'concurrent', 'secondment')),
+    start_date DATE NOT NULL,
+    -- 最終日（NULL なら現在も継続）
+    end_date DATE,
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    CHECK (end_date IS NULL OR end_date >= start_date),
+    FOREIGN KEY (employee_id) REFERENCES employees (id),
+    FOREIGN KEY (department_id) REFERENCES departments (id),
+    FOREIGN KEY (section_id) REFERENCES departments (id)
+);
+
+CREATE INDEX idx_department_assignments_employee ON department_assignments(employee_id, start_date);
+CREATE INDEX idx_department_assignments_department ON department_assignments(department_id, start_date);
+
+-- 部署コードが登録済みの部署と一致する社員は、入社日（部署の新設より前なら新設日）からの主務にする
+INSERT INTO department_assignments (employee_id, department_id, position, assignment_type, start_date)
+SELECT e.id, d.id, e.position, 'primary',
+       COALESCE(MAX(e.hire_date, d.created_date), e.hire_date, d.created_date, '1900-01-01')
+FROM employees e
+JOIN departments d ON d.code = e.department AND d.kind = 'department';
@@ -0,0 +1,5 @@
+-- Add claim lease to document_idempotency_keys
+-- claimed_until は処理中（response が NULL）の確保の期限。文書を作成しないまま期限を過ぎた確保は、
+-- 同じキーの再送が引き継ぐ。document_id は文書の作成と同じトランザクションで記録し、作成結果の保存前に
+-- 中断した場合でも、再送時は作成済みの文書から作成結果を組み立て直して重複作成しない
+ALTER TABLE document_idempotency_keys ADD COLUMN claimed_until DATETIME;
@@ -0,0 +1,17 @@
+-- Create employee_current_departments view
+-- 社員の現在の部署は、参照する時点（今日）で有効な主務から求める。将来日付で登録した異動も開始日から反映される
+-- 有効な主務がない社員（所属履歴の導入前の社員など）は employees.department を使う。
+-- employees.department は所属の登録・変更時には更新しないため、現在の部署はこのビューから参照する
+CREATE VIEW employee_current_departments AS
+SELECT e.id AS employee_id,
+       COALESCE((
+           SELECT d.code
+           FROM department_assignments a
+           JOIN departments d ON d.id = a.department_id
+           WHERE a.employee_id = e.id AND a.assignment_type = 'primary'
+             AND a.start_date <= date('now', 'localtime')
+             AND (a.end_date IS NULL OR a.end_date >= date('now', 'localtime'))
+           ORDER BY a.start_date DESC
+           LIMIT 1
+       ), e.department) AS department_code
+FROM employees e;
@@ -0,0 +1,9 @@
+-- Add numbering details to document_idempotency_keys
+-- 文書の作成と同じトランザクションで、採番に使ったルール・連番カウンターのキー・連番を記録する。
+-- 作成結果の保存前に中断したリクエストの再送では、現在のルールから採番計画を立て直さずに、
+-- 記録したルールと連番から作成結果を組み立て直す
+ALTER TABLE document_idempotency_keys ADD COLUMN rule_id INTEGER REFERENCES document_number_generation_rules (id);
+ALTER TABLE document_idempotency_keys ADD COLUMN sequence_year INTEGER;
+ALTER TABLE document_idempotency_keys ADD COLUMN sequence_month INTEGER;
+ALTER TABLE document_idempotency_keys ADD COLUMN sequence_department_code TEXT;
+ALTER TABLE document_idempotency_keys ADD COLUMN sequence_number INTEGER;
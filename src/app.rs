@@ -1,14 +1,25 @@
 use crate::error::AppError;
-use axum::{Router, extract::DefaultBodyLimit};
+use axum::{
+    Router,
+    extract::{DefaultBodyLimit, FromRef},
+};
+use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::config::AppConfig;
 use crate::handlers::{DocumentHandlers, HealthHandler};
+use crate::models::DEFAULT_IDEMPOTENCY_KEY_TTL_HOURS;
 use crate::repositories::{
-    DepartmentRepository, SqliteDocumentNumberRuleRepository, SqliteDocumentRepository,
+    DepartmentRepository, SqliteBusinessRepository, SqliteDocumentNumberRuleRepository,
+    SqliteDocumentRepository, SqliteDocumentRevisionRepository, SqliteEmployeeRepository,
+    SqlitePathGenerationRuleRepository,
 };
 use crate::routes::create_routes;
-use crate::services::DocumentService;
+use crate::services::{
+    BusinessService, ClearanceService, DocumentRevisionService, DocumentService, EmployeeService,
+    PathGenerationService,
+};
 
 /// アプリケーション状態
 #[derive(Clone)]
@@ -16,6 +27,17 @@ pub struct AppState {
     pub document_handlers: DocumentHandlers,
     pub health_handler: HealthHandler,
     pub department_repository: DepartmentRepository,
+    pub revision_service: DocumentRevisionService,
+    pub path_service: PathGenerationService,
+    pub employee_service: EmployeeService,
+    pub clearance_service: ClearanceService,
+    pub business_service: Arc<BusinessService>,
+}
+
+impl FromRef<AppState> for Arc<BusinessService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.business_service.clone()
+    }
 }
 
 /// アプリケーションのメインエントリーポイント
@@ -35,6 +57,8 @@ pub async fn create_app_with_db_url(database_url: &str) -> Result<Router, AppErr
     // リポジトリの初期化（実際のデータベースファイルを使用）
     let doc_repo = SqliteDocumentRepository::new(pool.clone());
     let rule_repo = SqliteDocumentNumberRuleRepository::new(pool.clone());
+    let revision_repo = SqliteDocumentRevisionRepository::new(pool.clone());
+    let path_rule_repo = SqlitePathGenerationRuleRepository::new(pool.clone());
     let dept_repo = DepartmentRepository::new_with_file_db(database_url)
         .await
         .map_err(|e| {
@@ -43,7 +67,27 @@ pub async fn create_app_with_db_url(database_url: &str) -> Result<Router, AppErr
         })?;
 
     // サービス層の初期化
-    let document_service = DocumentService::new(doc_repo, rule_repo);
+    let path_service = PathGenerationService::new(path_rule_repo);
+    let idempotency_key_ttl_hours = AppConfig::load()
+        .map(|config| config.server.idempotency_key_ttl_hours)
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load configuration, using default idempotency key TTL: {e}");
+            DEFAULT_IDEMPOTENCY_KEY_TTL_HOURS
+        });
+    let employee_service = EmployeeService::new(SqliteEmployeeRepository::new(pool.clone()));
+    let clearance_service = ClearanceService::new(
+        SqliteEmployeeRepository::new(pool.clone()),
+        employee_service.clone(),
+    );
+    let document_service = DocumentService::new(doc_repo, rule_repo)
+        .with_employee_repository(SqliteEmployeeRepository::new(pool.clone()))
+        .with_employee_service(employee_service.clone())
+        .with_path_service(path_service.clone())
+        .with_idempotency_key_ttl(chrono::Duration::hours(idempotency_key_ttl_hours as i64));
+    let revision_service = DocumentRevisionService::new(revision_repo, document_service.clone());
+    let business_service = Arc::new(BusinessService::new(Arc::new(
+        SqliteBusinessRepository::new(pool.clone()),
+    )));
 
     // ハンドラーの初期化
     let document_handlers = DocumentHandlers::new(document_service);
@@ -54,6 +98,11 @@ pub async fn create_app_with_db_url(database_url: &str) -> Result<Router, AppErr
         document_handlers,
         health_handler,
         department_repository: dept_repo,
+        revision_service,
+        path_service,
+        employee_service,
+        clearance_service,
+        business_service,
     };
 
     // ルーターとミドルウェアの構築
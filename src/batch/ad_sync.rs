@@ -235,6 +235,8 @@ impl AdSyncService {
             email: Some("yamada@company.com".to_string()),
             ad_username: Some("yamada.taro".to_string()),
             department_id: Some(1),
+            section_id: None,
+            position: None,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -378,6 +380,8 @@ mod tests {
             email: Some("yamada@company.com".to_string()),
             ad_username: Some("yamada.taro".to_string()),
             department_id: Some(1),
+            section_id: None,
+            position: None,
             is_active: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -1,6 +1,6 @@
 use crate::batch::{BatchExecution, BatchStatus, BatchType};
 use crate::error::BatchError;
-use crate::models::Document;
+use crate::models::{Document, ImportanceClass, InternalExternal, PersonalInfo};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -258,10 +258,7 @@ impl FileCheckService {
     fn requires_approval(&self, document: &Document) -> bool {
         // TODO: 文書種別に基づく承認要否判定
         // 現在は重要度クラスⅠの文書は承認必要とする
-        document
-            .importance_class
-            .as_ref()
-            .is_some_and(|class| class == "class1")
+        document.importance_class == Some(ImportanceClass::Class1)
     }
 
     /// 確認対象文書を取得
@@ -278,12 +275,13 @@ impl FileCheckService {
                 created_by: 1,
                 created_by_name: Some("システム管理者".to_string()),
                 created_date: chrono::NaiveDate::from_ymd_opt(2024, 8, 15).unwrap(),
-                internal_external: Some("internal".to_string()),
-                importance_class: Some("class1".to_string()),
-                personal_info: Some("none".to_string()),
+                internal_external: Some(InternalExternal::Internal),
+                importance_class: Some(ImportanceClass::Class1),
+                personal_info: Some(PersonalInfo::None),
                 notes: None,
                 network_path: Some("\\\\server\\documents\\2024\\08\\CTA-2508001".to_string()),
                 is_active: true,
+                version: 1,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             },
@@ -296,12 +294,13 @@ impl FileCheckService {
                 created_by: 2,
                 created_by_name: Some("テストユーザー".to_string()),
                 created_date: chrono::NaiveDate::from_ymd_opt(2024, 8, 10).unwrap(),
-                internal_external: Some("internal".to_string()),
-                importance_class: Some("class2".to_string()),
-                personal_info: Some("none".to_string()),
+                internal_external: Some(InternalExternal::Internal),
+                importance_class: Some(ImportanceClass::Class2),
+                personal_info: Some(PersonalInfo::None),
                 notes: None,
                 network_path: Some("\\\\server\\documents\\2024\\08\\技術-25001".to_string()),
                 is_active: true,
+                version: 1,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             },
@@ -314,12 +313,13 @@ impl FileCheckService {
                 created_by: 3,
                 created_by_name: Some("運用管理者".to_string()),
                 created_date: chrono::NaiveDate::from_ymd_opt(2024, 8, 1).unwrap(),
-                internal_external: Some("internal".to_string()),
-                importance_class: Some("class2".to_string()),
-                personal_info: Some("none".to_string()),
+                internal_external: Some(InternalExternal::Internal),
+                importance_class: Some(ImportanceClass::Class2),
+                personal_info: Some(PersonalInfo::None),
                 notes: None,
                 network_path: Some("\\\\server\\documents\\2024\\08\\REP-2508001".to_string()),
                 is_active: true,
+                version: 1,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             },
@@ -432,11 +432,12 @@ mod tests {
             created_by_name: Some("テストユーザー".to_string()),
             created_date: chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
             internal_external: None,
-            importance_class: Some("class1".to_string()),
+            importance_class: Some(ImportanceClass::Class1),
             personal_info: None,
             notes: None,
             network_path: None,
             is_active: true,
+            version: 1,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -444,7 +445,7 @@ mod tests {
         assert!(service.requires_approval(&document_with_approval));
 
         let document_without_approval = Document {
-            importance_class: Some("class2".to_string()),
+            importance_class: Some(ImportanceClass::Class2),
             ..document_with_approval
         };
 
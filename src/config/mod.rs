@@ -20,6 +20,13 @@ pub struct ServerConfig {
     pub workers: Option<usize>,
     pub max_request_size: usize,
     pub timeout_seconds: u64,
+    /// Idempotency-Keyと文書作成結果を保持する期間（時間）
+    #[serde(default = "default_idempotency_key_ttl_hours")]
+    pub idempotency_key_ttl_hours: u64,
+}
+
+fn default_idempotency_key_ttl_hours() -> u64 {
+    crate::models::DEFAULT_IDEMPOTENCY_KEY_TTL_HOURS
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -118,6 +125,7 @@ impl Default for AppConfig {
                 workers: None,
                 max_request_size: 10 * 1024 * 1024, // 10MB
                 timeout_seconds: 30,
+                idempotency_key_ttl_hours: default_idempotency_key_ttl_hours(),
             },
             database: DatabaseConfig {
                 url: "sqlite://./data/dev.db".to_string(),
@@ -1,3 +1,4 @@
+use crate::models::PathGenerationError;
 use crate::repositories::RepositoryError;
 use crate::services::DocumentServiceError;
 
@@ -18,6 +19,9 @@ pub enum AppError {
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -38,6 +42,16 @@ pub enum AppError {
 
     #[error("Repository initialization error: {0}")]
     RepositoryInit(String),
+
+    #[error("Precondition required: {0}")]
+    PreconditionRequired(String),
+
+    /// 取得時から更新されていた（`current`は最新の状態）
+    #[error("Precondition failed: {message}")]
+    PreconditionFailed {
+        message: String,
+        current: serde_json::Value,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -90,6 +104,9 @@ pub enum BusinessError {
     #[error("Business number already exists: {0}")]
     BusinessNumberExists(String),
 
+    #[error("No business number available in range {min}-{max}")]
+    BusinessNumberExhausted { min: i32, max: i32 },
+
     #[error("Business member already exists")]
     MemberAlreadyExists,
 
@@ -114,6 +131,11 @@ pub enum BusinessError {
     #[error("Invalid date range")]
     InvalidDateRange,
 
+    #[error(
+        "Business was modified by another user (expected version {expected}, current {current})"
+    )]
+    VersionMismatch { expected: i32, current: i32 },
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -121,6 +143,26 @@ pub enum BusinessError {
     Serialization(#[from] serde_json::Error),
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum DepartmentError {
+    #[error(
+        "Department was modified by another user (expected version {expected}, current {current})"
+    )]
+    VersionMismatch { expected: i32, current: i32 },
+
+    #[error("Department not found: {0}")]
+    NotFound(i32),
+
+    #[error("Invalid organization change: {0}")]
+    Validation(String),
+
+    #[error("Department code conflict: {0}")]
+    CodeConflict(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum SearchError {
     #[error("Invalid search parameters: {0}")]
@@ -149,6 +191,13 @@ impl From<DocumentServiceError> for AppError {
                 AppError::InternalError(error.to_string())
             }
             DocumentServiceError::RepositoryError(repo_err) => AppError::Repository(repo_err),
+            DocumentServiceError::PermissionDenied(message) => AppError::Forbidden(message),
+            DocumentServiceError::PathGenerationError(ref path_error) => match path_error {
+                PathGenerationError::NoApplicableRule { .. } => {
+                    AppError::NotFound(error.to_string())
+                }
+                PathGenerationError::TemplateError(_) => AppError::InternalError(error.to_string()),
+            },
         }
     }
 }
@@ -156,11 +205,20 @@ impl From<DocumentServiceError> for AppError {
 impl From<AppError> for axum::http::StatusCode {
     fn from(error: AppError) -> Self {
         match error {
-            AppError::Repository(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Repository(ref repo_error) => match repo_error {
+                RepositoryError::NotFound { .. } => axum::http::StatusCode::NOT_FOUND,
+                RepositoryError::Validation(_) => axum::http::StatusCode::BAD_REQUEST,
+                RepositoryError::Conflict(_) => axum::http::StatusCode::CONFLICT,
+                RepositoryError::VersionMismatch { .. } => {
+                    axum::http::StatusCode::PRECONDITION_FAILED
+                }
+                RepositoryError::Database(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            },
             AppError::ValidationError(_) => axum::http::StatusCode::BAD_REQUEST,
             AppError::NotFound(_) => axum::http::StatusCode::NOT_FOUND,
             AppError::InternalError(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             AppError::BadRequest(_) => axum::http::StatusCode::BAD_REQUEST,
+            AppError::Forbidden(_) => axum::http::StatusCode::FORBIDDEN,
             AppError::Database(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             AppError::Deduplication(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             AppError::Batch(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
@@ -169,8 +227,11 @@ impl From<AppError> for axum::http::StatusCode {
                 | BusinessError::BusinessNotFound
                 | BusinessError::EmployeeNotFound => axum::http::StatusCode::NOT_FOUND,
                 BusinessError::PermissionDenied => axum::http::StatusCode::FORBIDDEN,
-                BusinessError::BusinessNumberExists(_) | BusinessError::MemberAlreadyExists => {
-                    axum::http::StatusCode::CONFLICT
+                BusinessError::BusinessNumberExists(_)
+                | BusinessError::BusinessNumberExhausted { .. }
+                | BusinessError::MemberAlreadyExists => axum::http::StatusCode::CONFLICT,
+                BusinessError::VersionMismatch { .. } => {
+                    axum::http::StatusCode::PRECONDITION_FAILED
                 }
                 BusinessError::InvalidStatus(_)
                 | BusinessError::InvalidRole(_)
@@ -186,6 +247,8 @@ impl From<AppError> for axum::http::StatusCode {
             },
             AppError::DatabaseConnection(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             AppError::RepositoryInit(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::PreconditionRequired(_) => axum::http::StatusCode::PRECONDITION_REQUIRED,
+            AppError::PreconditionFailed { .. } => axum::http::StatusCode::PRECONDITION_FAILED,
         }
     }
 }
@@ -1,36 +1,86 @@
 use crate::AppState;
 use crate::graphql::types::*;
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, ErrorExtensions, Object, Result};
 
 #[derive(Default)]
 pub struct QueryRoot;
 
 #[Object]
 impl QueryRoot {
-    /// Get a document by ID
-    async fn document(&self, ctx: &Context<'_>, id: i32) -> Result<Option<Document>> {
+    /// Get a document by ID, or by document number or former number (alias)
+    ///
+    /// A former number resolves to the current document.
+    /// Documents above the clearance level of `user_id` are returned as null.
+    async fn document(
+        &self,
+        ctx: &Context<'_>,
+        id: Option<i32>,
+        number: Option<String>,
+        user_id: Option<i32>,
+    ) -> Result<Option<Document>> {
         let state = ctx.data::<AppState>()?;
 
-        match state.document_handlers.get_document(id).await {
+        let result = match (id, number) {
+            (Some(id), None) => {
+                state
+                    .document_handlers
+                    .get_document_for_viewer(id, user_id)
+                    .await
+            }
+            (None, Some(number)) => {
+                state
+                    .document_handlers
+                    .get_document_by_number_for_viewer(&number, user_id)
+                    .await
+            }
+            _ => {
+                return Err(async_graphql::Error::new(
+                    "Specify exactly one of id or number",
+                ));
+            }
+        };
+
+        match result {
             Ok(doc) => Ok(Some(doc.into())),
             Err(crate::error::AppError::NotFound(_)) => Ok(None),
             Err(e) => Err(async_graphql::Error::new(format!("Database error: {e}"))),
         }
     }
 
-    /// Search documents
+    /// Search documents visible at the clearance level of `user_id`
     async fn search_documents(
         &self,
         ctx: &Context<'_>,
         filters: DocumentSearchFilters,
+        user_id: Option<i32>,
     ) -> Result<SearchDocumentsResult> {
-        println!("DEBUG: search_documents called with filters: {:?}", filters);
+        tracing::debug!("search_documents called with filters: {:?}", filters);
         let state = ctx.data::<AppState>()?;
-        let search_filters = filters.into();
+        let search_filters: crate::models::DocumentSearchFilters = filters.into();
+
+        let aggregations = state
+            .document_handlers
+            .search_aggregations_for_viewer(search_filters.clone(), user_id)
+            .await
+            .map_err(|e| async_graphql::Error::new(format!("Search error: {e}")))?;
+
+        if search_filters.query.is_some() {
+            let (hits, total) = state
+                .document_handlers
+                .full_text_search_for_viewer(search_filters, user_id)
+                .await
+                .map_err(|e| async_graphql::Error::new(format!("Search error: {e}")))?;
+            return Ok(SearchDocumentsResult {
+                documents: hits.iter().map(|hit| hit.document.clone().into()).collect(),
+                total,
+                hits: hits.into_iter().map(Into::into).collect(),
+                aggregations: Some(aggregations.into()),
+            });
+        }
 
         match state
             .document_handlers
-            .search_documents(search_filters)
+            .search_documents_for_viewer(search_filters, user_id)
             .await
         {
             Ok((documents, total)) => {
@@ -39,12 +89,203 @@ impl QueryRoot {
                 Ok(SearchDocumentsResult {
                     documents: graphql_documents,
                     total,
+                    hits: Vec::new(),
+                    aggregations: Some(aggregations.into()),
                 })
             }
             Err(e) => Err(async_graphql::Error::new(format!("Search error: {e}"))),
         }
     }
 
+    /// Get a document number generation rule by ID
+    async fn document_number_rule(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+    ) -> Result<Option<DocumentNumberRule>> {
+        let state = ctx.data::<AppState>()?;
+
+        match state.document_handlers.get_number_rule(id).await {
+            Ok(rule) => Ok(Some(rule.into())),
+            Err(crate::error::AppError::NotFound(_)) => Ok(None),
+            Err(e) => Err(async_graphql::Error::new(format!("Database error: {e}"))),
+        }
+    }
+
+    /// Search document number generation rules
+    async fn document_number_rules(
+        &self,
+        ctx: &Context<'_>,
+        department_code: Option<String>,
+        active_on: Option<String>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<DocumentNumberRulesResult> {
+        let state = ctx.data::<AppState>()?;
+        let active_on_date = active_on.as_deref().map(parse_date).transpose()?;
+
+        match state
+            .document_handlers
+            .search_number_rules(
+                department_code,
+                active_on_date,
+                limit.unwrap_or(50),
+                offset.unwrap_or(0),
+            )
+            .await
+        {
+            Ok((rules, total)) => Ok(DocumentNumberRulesResult {
+                rules: rules.into_iter().map(|r| r.into()).collect(),
+                total,
+            }),
+            Err(e) => Err(async_graphql::Error::new(format!("Search error: {e}"))),
+        }
+    }
+
+    /// Preview which document number rule would apply and the numbers it would issue next
+    async fn simulate_document_number_rule(
+        &self,
+        ctx: &Context<'_>,
+        input: SimulateDocumentNumberRuleInput,
+    ) -> Result<DocumentNumberRuleSimulation> {
+        let state = ctx.data::<AppState>()?;
+        let request = input.try_into()?;
+
+        match state.document_handlers.simulate_number_rules(request).await {
+            Ok(result) => Ok(result.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Simulation error: {e}"))),
+        }
+    }
+
+    /// Decompose an existing document number into the components of the rules that could produce it
+    ///
+    /// `department_code` is used as the counter department for rules whose template has no department.
+    async fn decompose_document_number(
+        &self,
+        ctx: &Context<'_>,
+        document_number: String,
+        department_code: Option<String>,
+    ) -> Result<DocumentNumberDecomposition> {
+        let state = ctx.data::<AppState>()?;
+
+        match state
+            .document_handlers
+            .decompose_document_number(&document_number, department_code.as_deref())
+            .await
+        {
+            Ok(decomposition) => Ok(decomposition.into()),
+            Err(e) => Err(async_graphql::Error::new(format!(
+                "Decomposition error: {e}"
+            ))),
+        }
+    }
+
+    /// Scan all document number rules for equal-priority overlaps and colliding templates
+    async fn document_number_rule_conflicts(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<DocumentNumberRuleConflicts> {
+        let state = ctx.data::<AppState>()?;
+
+        match state.document_handlers.find_number_rule_conflicts().await {
+            Ok(report) => Ok(report.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Database error: {e}"))),
+        }
+    }
+
+    /// Get the revision history of a document visible at the clearance level of `user_id`
+    async fn document_revisions(
+        &self,
+        ctx: &Context<'_>,
+        document_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<Vec<DocumentRevision>> {
+        let state = ctx.data::<AppState>()?;
+
+        match state
+            .revision_service
+            .list_revisions(document_id, user_id)
+            .await
+        {
+            Ok(revisions) => Ok(revisions.into_iter().map(|r| r.into()).collect()),
+            Err(e) => Err(async_graphql::Error::new(format!("Database error: {e}"))),
+        }
+    }
+
+    /// List documents in the trash visible at the clearance level of `user_id`,
+    /// most recently deleted first
+    async fn deleted_documents(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+        user_id: Option<i32>,
+    ) -> Result<DeletedDocumentsResult> {
+        let state = ctx.data::<AppState>()?;
+
+        match state
+            .document_handlers
+            .list_deleted_documents(limit.unwrap_or(10), offset.unwrap_or(0), user_id)
+            .await
+        {
+            Ok((documents, total)) => Ok(DeletedDocumentsResult {
+                documents: documents.into_iter().map(Into::into).collect(),
+                total,
+            }),
+            Err(e) => Err(async_graphql::Error::new(format!("Database error: {e}"))),
+        }
+    }
+
+    /// Get the change history (updates, deletion and restoration) of a document
+    /// visible at the clearance level of `user_id`
+    async fn document_changes(
+        &self,
+        ctx: &Context<'_>,
+        document_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<Vec<DocumentChange>> {
+        let state = ctx.data::<AppState>()?;
+
+        match state
+            .document_handlers
+            .list_document_changes(document_id, user_id)
+            .await
+        {
+            Ok(changes) => Ok(changes.into_iter().map(Into::into).collect()),
+            Err(e) => Err(async_graphql::Error::new(format!("Database error: {e}"))),
+        }
+    }
+
+    /// Resolve the network path of an existing document by its number,
+    /// optionally with the rules that were in effect on `as_of`.
+    /// `user_id` must be cleared to see the document's storage location
+    async fn resolve_network_path(
+        &self,
+        ctx: &Context<'_>,
+        document_number: String,
+        as_of: Option<String>,
+        user_id: Option<i32>,
+    ) -> Result<ResolvedNetworkPath> {
+        let state = ctx.data::<AppState>()?;
+        let as_of = as_of.as_deref().map(parse_date).transpose()?;
+        state
+            .document_handlers
+            .ensure_network_path_visible(&document_number, user_id)
+            .await
+            .map_err(|e| async_graphql::Error::new(format!("Path resolution error: {e}")))?;
+
+        match state
+            .path_service
+            .resolve_for_document_number(&document_number, as_of)
+            .await
+        {
+            Ok(resolved) => Ok(resolved.into()),
+            Err(e) => Err(async_graphql::Error::new(format!(
+                "Path resolution error: {e}"
+            ))),
+        }
+    }
+
     /// Get circulation workflows (placeholder implementation)
     async fn circulation_workflows(&self, _ctx: &Context<'_>) -> Result<Vec<CirculationWorkflow>> {
         // TODO: Implement when AppState includes circulation_service
@@ -73,11 +314,20 @@ impl QueryRoot {
 
         // Get total documents count by searching with no filters
         let filters = crate::models::DocumentSearchFilters {
+            query: None,
             title: None,
             document_type_id: None,
             created_by: None,
             created_date_from: None,
             created_date_to: None,
+            department_code: None,
+            include_historical_affiliations: false,
+            year: None,
+            importance_class: None,
+            internal_external: None,
+            business_number: None,
+            max_clearance: None,
+            affiliation_employee_id: None,
             limit: 1, // We only need the count
             offset: 0,
         };
@@ -95,11 +345,20 @@ impl QueryRoot {
             .ok_or_else(|| async_graphql::Error::new("Invalid date"))?;
 
         let monthly_filters = crate::models::DocumentSearchFilters {
+            query: None,
             title: None,
             document_type_id: None,
             created_by: None,
             created_date_from: Some(month_start),
             created_date_to: Some(now),
+            department_code: None,
+            include_historical_affiliations: false,
+            year: None,
+            importance_class: None,
+            internal_external: None,
+            business_number: None,
+            max_clearance: None,
+            affiliation_employee_id: None,
             limit: 1,
             offset: 0,
         };
@@ -128,11 +387,20 @@ impl QueryRoot {
         let database_status = match state
             .document_handlers
             .search_documents(crate::models::DocumentSearchFilters {
+                query: None,
                 title: None,
                 document_type_id: None,
                 created_by: None,
                 created_date_from: None,
                 created_date_to: None,
+                department_code: None,
+                include_historical_affiliations: false,
+                year: None,
+                importance_class: None,
+                internal_external: None,
+                business_number: None,
+                max_clearance: None,
+                affiliation_employee_id: None,
                 limit: 1,
                 offset: 0,
             })
@@ -160,29 +428,39 @@ impl QueryRoot {
         })
     }
 
-    /// Get recent activities
+    /// Get recent activities on documents visible at the clearance level of `user_id`
     async fn recent_activities(
         &self,
         ctx: &Context<'_>,
         limit: Option<i32>,
+        user_id: Option<i32>,
     ) -> Result<Vec<Activity>> {
         let limit = limit.unwrap_or(10);
         let state = ctx.data::<AppState>()?;
 
         // Get recent documents (ordered by creation time) to simulate activities
         let filters = crate::models::DocumentSearchFilters {
+            query: None,
             title: None,
             document_type_id: None,
             created_by: None,
             created_date_from: None,
             created_date_to: None,
+            department_code: None,
+            include_historical_affiliations: false,
+            year: None,
+            importance_class: None,
+            internal_external: None,
+            business_number: None,
+            max_clearance: None,
+            affiliation_employee_id: None,
             limit: limit as i64,
             offset: 0,
         };
 
         let (documents, _total) = state
             .document_handlers
-            .search_documents(filters)
+            .search_documents_for_viewer(filters, user_id)
             .await
             .map_err(|e| {
                 async_graphql::Error::new(format!("Failed to get recent documents: {e}"))
@@ -238,10 +516,26 @@ impl QueryRoot {
     // ========== Department Queries ==========
 
     /// Get all departments
-    async fn departments(&self, ctx: &Context<'_>) -> Result<Vec<DepartmentWithManager>> {
+    ///
+    /// With `as_of` (YYYY-MM-DD), returns the organization as it was on that date.
+    async fn departments(
+        &self,
+        ctx: &Context<'_>,
+        as_of: Option<String>,
+    ) -> Result<Vec<DepartmentWithManager>> {
         let state = ctx.data::<AppState>()?;
 
-        match state.department_repository.get_all_departments().await {
+        let result = match as_of {
+            Some(as_of) => {
+                state
+                    .department_repository
+                    .get_all_departments_as_of(parse_date(&as_of)?)
+                    .await
+            }
+            None => state.department_repository.get_all_departments().await,
+        };
+
+        match result {
             Ok(departments) => {
                 let graphql_departments: Vec<DepartmentWithManager> =
                     departments.into_iter().map(|d| d.into()).collect();
@@ -252,19 +546,65 @@ impl QueryRoot {
     }
 
     /// Get a department by ID
+    ///
+    /// With `as_of` (YYYY-MM-DD), returns the department as it was on that date
+    /// (null if it did not exist then).
     async fn department(
         &self,
         ctx: &Context<'_>,
         id: i32,
+        as_of: Option<String>,
     ) -> Result<Option<DepartmentWithManager>> {
         let state = ctx.data::<AppState>()?;
 
-        match state.department_repository.get_department_by_id(id).await {
+        let result = match as_of {
+            Some(as_of) => {
+                state
+                    .department_repository
+                    .get_department_by_id_as_of(id, parse_date(&as_of)?)
+                    .await
+            }
+            None => state.department_repository.get_department_by_id(id).await,
+        };
+
+        match result {
             Ok(department) => Ok(department.map(|d| d.into())),
             Err(e) => Err(async_graphql::Error::new(format!("Database error: {e}"))),
         }
     }
 
+    /// Get the effective-dated records of a department, oldest first
+    async fn department_history(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+    ) -> Result<Vec<DepartmentVersion>> {
+        let state = ctx.data::<AppState>()?;
+
+        match state.department_repository.get_department_history(id).await {
+            Ok(versions) => Ok(versions.into_iter().map(Into::into).collect()),
+            Err(e) => Err(async_graphql::Error::new(format!("Database error: {e}"))),
+        }
+    }
+
+    /// List organization restructures, newest first
+    async fn organization_events(
+        &self,
+        ctx: &Context<'_>,
+        department_id: Option<i32>,
+    ) -> Result<Vec<OrganizationEvent>> {
+        let state = ctx.data::<AppState>()?;
+
+        match state
+            .department_repository
+            .list_organization_events(department_id)
+            .await
+        {
+            Ok(events) => Ok(events.into_iter().map(Into::into).collect()),
+            Err(e) => Err(async_graphql::Error::new(format!("Database error: {e}"))),
+        }
+    }
+
     /// Search departments
     async fn search_departments(
         &self,
@@ -295,20 +635,249 @@ pub struct MutationRoot;
 #[Object]
 impl MutationRoot {
     /// Create a document with auto-generated number
+    ///
+    /// Retrying with the same `idempotencyKey` within the retention window returns the
+    /// original result instead of creating another document
     async fn create_document(
         &self,
         ctx: &Context<'_>,
         input: CreateDocumentInput,
+        idempotency_key: Option<String>,
     ) -> Result<CreatedDocumentWithNumber> {
         let state = ctx.data::<AppState>()?;
         let request = input.into();
 
-        match state.document_handlers.create_document(request).await {
+        let result = match idempotency_key {
+            Some(key) => state
+                .document_handlers
+                .create_document_idempotent(request, &key)
+                .await
+                .map(|(created, _)| created),
+            None => state.document_handlers.create_document(request).await,
+        };
+
+        match result {
+            Ok(created) => Ok(created.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Creation error: {e}"))),
+        }
+    }
+
+    /// Register several documents in one transaction, numbered in input order
+    ///
+    /// Every row is validated first. In `ALL_OR_NOTHING` mode nothing is registered if any
+    /// row fails; in `BEST_EFFORT` mode the remaining rows are registered. Failed rows carry
+    /// their error in `results`, and in `ALL_OR_NOTHING` mode every other row reports the
+    /// failed row it was rolled back for
+    async fn create_documents_bulk(
+        &self,
+        ctx: &Context<'_>,
+        input: BulkCreateDocumentsInput,
+    ) -> Result<BulkCreateDocumentsResult> {
+        let state = ctx.data::<AppState>()?;
+
+        match state
+            .document_handlers
+            .create_documents_bulk(input.into())
+            .await
+        {
+            Ok(result) => Ok(result.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Creation error: {e}"))),
+        }
+    }
+
+    /// Create a document with an explicitly assigned number
+    ///
+    /// `user_id` must be an administrator and is recorded as the assigner.
+    async fn create_document_with_manual_number(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateDocumentWithManualNumberInput,
+        user_id: Option<i32>,
+    ) -> Result<CreatedDocumentWithManualNumber> {
+        let state = ctx.data::<AppState>()?;
+        let request = input.try_into()?;
+
+        match state
+            .document_handlers
+            .create_document_with_manual_number(user_id, request)
+            .await
+        {
             Ok(created) => Ok(created.into()),
             Err(e) => Err(async_graphql::Error::new(format!("Creation error: {e}"))),
         }
     }
 
+    /// Update document metadata
+    ///
+    /// `version` is the version the client fetched; if the document has changed since,
+    /// the update is rejected with a VERSION_CONFLICT error carrying the current document.
+    async fn update_document(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        version: i32,
+        input: UpdateDocumentInput,
+    ) -> Result<Document> {
+        let state = ctx.data::<AppState>()?;
+        let viewer_id = Some(input.updated_by);
+
+        match state
+            .document_handlers
+            .update_document(id, input.into(), version, viewer_id)
+            .await
+        {
+            Ok(document) => Ok(document.into()),
+            Err(crate::error::AppError::PreconditionFailed { message, current }) => {
+                Err(version_conflict_error(message, current))
+            }
+            Err(e) => Err(async_graphql::Error::new(format!("Update error: {e}"))),
+        }
+    }
+
+    /// Move a document to the trash (soft delete)
+    async fn delete_document(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        deleted_by: i32,
+        reason: Option<String>,
+    ) -> Result<Document> {
+        let state = ctx.data::<AppState>()?;
+        let request = crate::models::ChangeDocumentStateRequest {
+            changed_by: deleted_by,
+            reason,
+        };
+
+        match state
+            .document_handlers
+            .delete_document(id, request, Some(deleted_by))
+            .await
+        {
+            Ok(document) => Ok(document.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Delete error: {e}"))),
+        }
+    }
+
+    /// Restore a document from the trash
+    async fn restore_document(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        restored_by: i32,
+        reason: Option<String>,
+    ) -> Result<Document> {
+        let state = ctx.data::<AppState>()?;
+        let request = crate::models::ChangeDocumentStateRequest {
+            changed_by: restored_by,
+            reason,
+        };
+
+        match state
+            .document_handlers
+            .restore_document(id, request, Some(restored_by))
+            .await
+        {
+            Ok(document) => Ok(document.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Restore error: {e}"))),
+        }
+    }
+
+    /// Create a document number generation rule
+    async fn create_document_number_rule(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateDocumentNumberRuleInput,
+    ) -> Result<DocumentNumberRule> {
+        let state = ctx.data::<AppState>()?;
+        let request = input.try_into()?;
+
+        match state.document_handlers.create_number_rule(request).await {
+            Ok(rule) => Ok(rule.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Creation error: {e}"))),
+        }
+    }
+
+    /// Update a document number generation rule
+    async fn update_document_number_rule(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        input: UpdateDocumentNumberRuleInput,
+    ) -> Result<DocumentNumberRule> {
+        let state = ctx.data::<AppState>()?;
+        let request = input.try_into()?;
+
+        match state
+            .document_handlers
+            .update_number_rule(id, request)
+            .await
+        {
+            Ok(rule) => Ok(rule.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Update error: {e}"))),
+        }
+    }
+
+    /// Retire a document number generation rule as of the given date
+    async fn retire_document_number_rule(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        effective_until: String,
+    ) -> Result<DocumentNumberRule> {
+        let state = ctx.data::<AppState>()?;
+        let effective_until = parse_date(&effective_until)?;
+
+        match state
+            .document_handlers
+            .retire_number_rule(id, effective_until)
+            .await
+        {
+            Ok(rule) => Ok(rule.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Retire error: {e}"))),
+        }
+    }
+
+    /// Create a revision or draft of an existing document
+    async fn create_document_revision(
+        &self,
+        ctx: &Context<'_>,
+        document_id: i32,
+        input: CreateDocumentRevisionInput,
+    ) -> Result<DocumentRevision> {
+        let state = ctx.data::<AppState>()?;
+
+        match state
+            .revision_service
+            .create_revision(document_id, input.into())
+            .await
+        {
+            Ok(revision) => Ok(revision.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Creation error: {e}"))),
+        }
+    }
+
+    /// Promote a draft to the next issued revision
+    async fn promote_draft_revision(
+        &self,
+        ctx: &Context<'_>,
+        document_id: i32,
+        revision_id: i32,
+        promoted_by: i32,
+        notes: Option<String>,
+    ) -> Result<DocumentRevision> {
+        let state = ctx.data::<AppState>()?;
+        let request = crate::models::PromoteDraftRequest { promoted_by, notes };
+
+        match state
+            .revision_service
+            .promote_draft(document_id, revision_id, request)
+            .await
+        {
+            Ok(revision) => Ok(revision.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Promotion error: {e}"))),
+        }
+    }
+
     /// Create a new circulation (placeholder implementation)
     async fn create_circulation(
         &self,
@@ -374,10 +943,14 @@ impl MutationRoot {
     }
 
     /// Update a department
+    ///
+    /// `version` is the version the client fetched; if the department has changed since,
+    /// the update is rejected with a VERSION_CONFLICT error carrying the current department.
     async fn update_department(
         &self,
         ctx: &Context<'_>,
         id: i32,
+        version: i32,
         input: UpdateDepartmentInput,
     ) -> Result<Option<Department>> {
         let state = ctx.data::<AppState>()?;
@@ -385,14 +958,42 @@ impl MutationRoot {
 
         match state
             .department_repository
-            .update_department(id, &request)
+            .update_department(id, &request, version)
             .await
         {
             Ok(department) => Ok(department.map(|d| d.into())),
+            Err(e @ crate::error::DepartmentError::VersionMismatch { .. }) => {
+                let current = state
+                    .department_repository
+                    .get_department_by_id(id)
+                    .await
+                    .map_err(|e| async_graphql::Error::new(format!("Database error: {e}")))?;
+                Err(version_conflict_error(
+                    e.to_string(),
+                    serde_json::to_value(current)?,
+                ))
+            }
             Err(e) => Err(async_graphql::Error::new(format!("Update error: {e}"))),
         }
     }
 
+    /// Record an organization restructure (rename, merge, split or move)
+    ///
+    /// Takes effect from `effectiveDate`; lookups for earlier dates keep the previous organization.
+    async fn restructure_departments(
+        &self,
+        ctx: &Context<'_>,
+        input: RestructureDepartmentsInput,
+    ) -> Result<OrganizationEvent> {
+        let state = ctx.data::<AppState>()?;
+        let request = input.try_into()?;
+
+        match state.department_repository.restructure(&request).await {
+            Ok(event) => Ok(event.into()),
+            Err(e) => Err(async_graphql::Error::new(format!("Restructure error: {e}"))),
+        }
+    }
+
     /// Delete (deactivate) a department
     async fn delete_department(&self, ctx: &Context<'_>, id: i32) -> Result<bool> {
         let state = ctx.data::<AppState>()?;
@@ -403,3 +1004,13 @@ impl MutationRoot {
         }
     }
 }
+
+/// 更新の競合を、最新の状態を`current`拡張に含むGraphQLエラーにする
+fn version_conflict_error(message: String, current: serde_json::Value) -> async_graphql::Error {
+    async_graphql::Error::new(format!("Version conflict: {message}")).extend_with(|_, e| {
+        e.set("code", "VERSION_CONFLICT");
+        if let Ok(current) = async_graphql::Value::from_json(current) {
+            e.set("current", current);
+        }
+    })
+}
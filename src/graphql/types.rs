@@ -1,4 +1,4 @@
-use async_graphql::{Enum, InputObject, SimpleObject};
+use async_graphql::{Enum, InputObject, MaybeUndefined, SimpleObject};
 
 /// GraphQL Document type
 #[derive(SimpleObject)]
@@ -11,8 +11,20 @@ pub struct Document {
     pub created_by: i32,
     pub created_by_name: Option<String>,
     pub created_date: String, // NaiveDate as ISO string
-    pub created_at: String,   // NaiveDateTime as ISO string
-    pub updated_at: String,   // NaiveDateTime as ISO string
+    pub internal_external: Option<InternalExternal>,
+    pub internal_external_label: Option<String>,
+    pub importance_class: Option<ImportanceClass>,
+    pub importance_class_label: Option<String>,
+    pub personal_info: Option<PersonalInfo>,
+    pub personal_info_label: Option<String>,
+    pub notes: Option<String>,
+    pub network_path: Option<String>,
+    /// False while the document is in the trash
+    pub is_active: bool,
+    /// Pass to updateDocument to detect concurrent updates
+    pub version: i32,
+    pub created_at: String, // NaiveDateTime as ISO string
+    pub updated_at: String, // NaiveDateTime as ISO string
 }
 
 impl From<crate::models::Document> for Document {
@@ -26,20 +38,118 @@ impl From<crate::models::Document> for Document {
             created_by: doc.created_by,
             created_by_name: doc.created_by_name,
             created_date: doc.created_date.format("%Y-%m-%d").to_string(),
+            internal_external: doc.internal_external.map(Into::into),
+            internal_external_label: doc.internal_external.map(|v| v.label().to_string()),
+            importance_class: doc.importance_class.map(Into::into),
+            importance_class_label: doc.importance_class.map(|v| v.label().to_string()),
+            personal_info: doc.personal_info.map(Into::into),
+            personal_info_label: doc.personal_info.map(|v| v.label().to_string()),
+            notes: doc.notes,
+            network_path: doc.network_path,
+            is_active: doc.is_active,
+            version: doc.version,
             created_at: doc.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
             updated_at: doc.updated_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
         }
     }
 }
 
+/// GraphQL InternalExternal enum
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InternalExternal {
+    /// 社内
+    Internal,
+    /// 社外
+    External,
+}
+
+impl From<crate::models::InternalExternal> for InternalExternal {
+    fn from(value: crate::models::InternalExternal) -> Self {
+        match value {
+            crate::models::InternalExternal::Internal => Self::Internal,
+            crate::models::InternalExternal::External => Self::External,
+        }
+    }
+}
+
+impl From<InternalExternal> for crate::models::InternalExternal {
+    fn from(value: InternalExternal) -> Self {
+        match value {
+            InternalExternal::Internal => Self::Internal,
+            InternalExternal::External => Self::External,
+        }
+    }
+}
+
+/// GraphQL ImportanceClass enum
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ImportanceClass {
+    /// 情報クラスⅠ
+    #[graphql(name = "CLASS1")]
+    Class1,
+    /// 情報クラスⅡ
+    #[graphql(name = "CLASS2")]
+    Class2,
+}
+
+impl From<crate::models::ImportanceClass> for ImportanceClass {
+    fn from(value: crate::models::ImportanceClass) -> Self {
+        match value {
+            crate::models::ImportanceClass::Class1 => Self::Class1,
+            crate::models::ImportanceClass::Class2 => Self::Class2,
+        }
+    }
+}
+
+impl From<ImportanceClass> for crate::models::ImportanceClass {
+    fn from(value: ImportanceClass) -> Self {
+        match value {
+            ImportanceClass::Class1 => Self::Class1,
+            ImportanceClass::Class2 => Self::Class2,
+        }
+    }
+}
+
+/// GraphQL PersonalInfo enum
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PersonalInfo {
+    /// 個人情報 無
+    None,
+    /// 個人情報 有
+    Present,
+}
+
+impl From<crate::models::PersonalInfo> for PersonalInfo {
+    fn from(value: crate::models::PersonalInfo) -> Self {
+        match value {
+            crate::models::PersonalInfo::None => Self::None,
+            crate::models::PersonalInfo::Present => Self::Present,
+        }
+    }
+}
+
+impl From<PersonalInfo> for crate::models::PersonalInfo {
+    fn from(value: PersonalInfo) -> Self {
+        match value {
+            PersonalInfo::None => Self::None,
+            PersonalInfo::Present => Self::Present,
+        }
+    }
+}
+
 /// GraphQL CreateDocumentInput type
 #[derive(InputObject)]
 pub struct CreateDocumentInput {
     pub title: String,
     pub document_type_code: String,
     pub department_code: String,
+    /// Section (課) code, required when the numbering template uses {課コード}
+    pub section_code: Option<String>,
     pub created_by: i32,
     pub created_date: String, // NaiveDate as string in GraphQL
+    pub internal_external: Option<InternalExternal>,
+    pub importance_class: Option<ImportanceClass>,
+    pub personal_info: Option<PersonalInfo>,
 }
 
 impl From<CreateDocumentInput> for crate::models::CreateDocumentWithNumberRequest {
@@ -48,21 +158,145 @@ impl From<CreateDocumentInput> for crate::models::CreateDocumentWithNumberReques
             title: val.title,
             document_type_code: val.document_type_code,
             department_code: val.department_code,
+            section_code: val.section_code,
             created_by: val.created_by,
             created_date: chrono::NaiveDate::parse_from_str(&val.created_date, "%Y-%m-%d")
                 .unwrap_or_else(|_| chrono::Utc::now().naive_utc().date()),
+            internal_external: val.internal_external.map(Into::into),
+            importance_class: val.importance_class.map(Into::into),
+            personal_info: val.personal_info.map(Into::into),
+        }
+    }
+}
+
+/// GraphQL UpdateDocumentInput type
+///
+/// Omitted fields are left unchanged; nullable fields set to null are cleared.
+#[derive(InputObject)]
+pub struct UpdateDocumentInput {
+    pub title: Option<String>,
+    pub document_type_id: Option<i32>,
+    pub business_number: MaybeUndefined<String>,
+    pub internal_external: MaybeUndefined<InternalExternal>,
+    pub importance_class: MaybeUndefined<ImportanceClass>,
+    pub personal_info: MaybeUndefined<PersonalInfo>,
+    pub notes: MaybeUndefined<String>,
+    pub network_path: MaybeUndefined<String>,
+    pub updated_by: i32,
+    pub reason: Option<String>,
+}
+
+impl From<UpdateDocumentInput> for crate::models::UpdateDocumentRequest {
+    fn from(val: UpdateDocumentInput) -> Self {
+        crate::models::UpdateDocumentRequest {
+            title: val.title,
+            document_type_id: val.document_type_id,
+            business_number: val.business_number.into(),
+            internal_external: val.internal_external.map_value(Into::into).into(),
+            importance_class: val.importance_class.map_value(Into::into).into(),
+            personal_info: val.personal_info.map_value(Into::into).into(),
+            notes: val.notes.into(),
+            network_path: val.network_path.into(),
+            updated_by: val.updated_by,
+            reason: val.reason,
         }
     }
 }
 
+/// GraphQL DocumentChangeType enum
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DocumentChangeType {
+    Updated,
+    Deleted,
+    Restored,
+}
+
+impl From<crate::models::DocumentChangeType> for DocumentChangeType {
+    fn from(value: crate::models::DocumentChangeType) -> Self {
+        match value {
+            crate::models::DocumentChangeType::Updated => Self::Updated,
+            crate::models::DocumentChangeType::Deleted => Self::Deleted,
+            crate::models::DocumentChangeType::Restored => Self::Restored,
+        }
+    }
+}
+
+/// GraphQL DocumentChange type
+#[derive(SimpleObject)]
+pub struct DocumentChange {
+    pub id: i32,
+    pub document_id: i32,
+    pub change_type: DocumentChangeType,
+    pub field_name: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by: i32,
+    pub reason: Option<String>,
+    pub changed_at: String,
+}
+
+impl From<crate::models::DocumentChange> for DocumentChange {
+    fn from(change: crate::models::DocumentChange) -> Self {
+        Self {
+            id: change.id,
+            document_id: change.document_id,
+            change_type: change.change_type.into(),
+            field_name: change.field_name,
+            old_value: change.old_value,
+            new_value: change.new_value,
+            changed_by: change.changed_by,
+            reason: change.reason,
+            changed_at: change.changed_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// GraphQL DeletedDocument type
+#[derive(SimpleObject)]
+pub struct DeletedDocument {
+    pub document: Document,
+    pub deleted_by: i32,
+    pub deleted_at: String,
+    pub reason: Option<String>,
+}
+
+impl From<crate::models::DeletedDocument> for DeletedDocument {
+    fn from(deleted: crate::models::DeletedDocument) -> Self {
+        Self {
+            document: deleted.document.into(),
+            deleted_by: deleted.deleted_by,
+            deleted_at: deleted.deleted_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            reason: deleted.reason,
+        }
+    }
+}
+
+/// GraphQL DeletedDocumentsResult type
+#[derive(SimpleObject)]
+pub struct DeletedDocumentsResult {
+    pub documents: Vec<DeletedDocument>,
+    pub total: i64,
+}
+
 /// GraphQL DocumentSearchFilters type
 #[derive(InputObject, Debug)]
 pub struct DocumentSearchFilters {
+    /// Full-text keywords matched against title, notes, business number and creator name
+    pub query: Option<String>,
     pub title: Option<String>,
     pub document_type_id: Option<i32>,
     pub created_by: Option<i32>,
     pub created_date_from: Option<String>,
     pub created_date_to: Option<String>,
+    pub department_code: Option<String>,
+    /// Widen `departmentCode` to also include documents of departments the caller
+    /// (userId) belonged to, created while they were there; no effect without `departmentCode`
+    pub include_historical_affiliations: Option<bool>,
+    /// Year of the created date
+    pub year: Option<i32>,
+    pub importance_class: Option<ImportanceClass>,
+    pub internal_external: Option<InternalExternal>,
+    pub business_number: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
@@ -70,6 +304,7 @@ pub struct DocumentSearchFilters {
 impl From<DocumentSearchFilters> for crate::models::DocumentSearchFilters {
     fn from(val: DocumentSearchFilters) -> Self {
         crate::models::DocumentSearchFilters {
+            query: val.query.filter(|q| !q.trim().is_empty()),
             title: val.title,
             document_type_id: val.document_type_id,
             created_by: val.created_by,
@@ -79,6 +314,14 @@ impl From<DocumentSearchFilters> for crate::models::DocumentSearchFilters {
             created_date_to: val
                 .created_date_to
                 .and_then(|s| chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok()),
+            department_code: val.department_code,
+            include_historical_affiliations: val.include_historical_affiliations.unwrap_or(false),
+            year: val.year,
+            importance_class: val.importance_class.map(Into::into),
+            internal_external: val.internal_external.map(Into::into),
+            business_number: val.business_number,
+            max_clearance: None,
+            affiliation_employee_id: None,
             limit: val.limit.unwrap_or(10),
             offset: val.offset.unwrap_or(0),
         }
@@ -121,11 +364,851 @@ impl From<crate::models::CreatedDocumentWithNumber> for CreatedDocumentWithNumbe
     }
 }
 
+/// GraphQL BulkCreateMode enum
+#[derive(Enum, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum BulkCreateMode {
+    /// Register nothing if any row fails
+    #[default]
+    AllOrNothing,
+    /// Register every row that succeeds and report the failed rows
+    BestEffort,
+}
+
+impl From<BulkCreateMode> for crate::models::BulkCreateMode {
+    fn from(value: BulkCreateMode) -> Self {
+        match value {
+            BulkCreateMode::AllOrNothing => Self::AllOrNothing,
+            BulkCreateMode::BestEffort => Self::BestEffort,
+        }
+    }
+}
+
+impl From<crate::models::BulkCreateMode> for BulkCreateMode {
+    fn from(value: crate::models::BulkCreateMode) -> Self {
+        match value {
+            crate::models::BulkCreateMode::AllOrNothing => Self::AllOrNothing,
+            crate::models::BulkCreateMode::BestEffort => Self::BestEffort,
+        }
+    }
+}
+
+/// GraphQL BulkCreateDocumentsInput type
+#[derive(InputObject)]
+pub struct BulkCreateDocumentsInput {
+    /// Defaults to `ALL_OR_NOTHING`
+    pub mode: Option<BulkCreateMode>,
+    /// Documents to register, numbered in this order
+    pub documents: Vec<CreateDocumentInput>,
+}
+
+impl From<BulkCreateDocumentsInput> for crate::models::BulkCreateDocumentsRequest {
+    fn from(val: BulkCreateDocumentsInput) -> Self {
+        crate::models::BulkCreateDocumentsRequest {
+            mode: val.mode.unwrap_or_default().into(),
+            documents: val.documents.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// GraphQL BulkCreateDocumentRowResult type
+#[derive(SimpleObject)]
+pub struct BulkCreateDocumentRowResult {
+    /// Position of the row in the input `documents` (0-based)
+    pub index: i32,
+    pub created: Option<CreatedDocumentWithNumber>,
+    pub error: Option<String>,
+}
+
+/// GraphQL BulkCreateDocumentsResult type
+#[derive(SimpleObject)]
+pub struct BulkCreateDocumentsResult {
+    pub mode: BulkCreateMode,
+    /// Whether any document was registered
+    pub committed: bool,
+    pub created_count: i32,
+    pub failed_count: i32,
+    pub results: Vec<BulkCreateDocumentRowResult>,
+}
+
+impl From<crate::models::BulkCreateDocumentsResult> for BulkCreateDocumentsResult {
+    fn from(result: crate::models::BulkCreateDocumentsResult) -> Self {
+        Self {
+            mode: result.mode.into(),
+            committed: result.committed,
+            created_count: result.created_count as i32,
+            failed_count: result.failed_count as i32,
+            results: result
+                .results
+                .into_iter()
+                .map(|row| BulkCreateDocumentRowResult {
+                    index: row.index as i32,
+                    created: row.created.map(Into::into),
+                    error: row.error,
+                })
+                .collect(),
+        }
+    }
+}
+
 /// GraphQL SearchDocumentsResult type
 #[derive(SimpleObject)]
 pub struct SearchDocumentsResult {
     pub documents: Vec<Document>,
     pub total: i64,
+    /// Ranked matches with highlighted snippets (empty unless `query` is given)
+    pub hits: Vec<DocumentSearchHit>,
+    /// Facet counts over all documents matching the filters
+    pub aggregations: Option<DocumentSearchAggregations>,
+}
+
+/// GraphQL DocumentSearchAggregations type
+#[derive(SimpleObject)]
+pub struct DocumentSearchAggregations {
+    pub document_type_counts: Vec<DocumentTypeCount>,
+    pub department_counts: Vec<DocumentDepartmentCount>,
+    pub year_counts: Vec<DocumentYearCount>,
+    pub importance_class_counts: Vec<ImportanceClassCount>,
+    pub internal_external_counts: Vec<InternalExternalCount>,
+    pub business_number_counts: Vec<BusinessNumberCount>,
+}
+
+#[derive(SimpleObject)]
+pub struct DocumentTypeCount {
+    pub document_type_id: i32,
+    pub document_type_code: Option<String>,
+    pub document_type_name: Option<String>,
+    pub count: i32,
+}
+
+#[derive(SimpleObject)]
+pub struct DocumentDepartmentCount {
+    pub department_code: Option<String>,
+    pub department_name: Option<String>,
+    pub count: i32,
+}
+
+#[derive(SimpleObject)]
+pub struct DocumentYearCount {
+    pub year: i32,
+    pub count: i32,
+}
+
+#[derive(SimpleObject)]
+pub struct ImportanceClassCount {
+    pub importance_class: Option<ImportanceClass>,
+    pub count: i32,
+}
+
+#[derive(SimpleObject)]
+pub struct InternalExternalCount {
+    pub internal_external: Option<InternalExternal>,
+    pub count: i32,
+}
+
+#[derive(SimpleObject)]
+pub struct BusinessNumberCount {
+    pub business_number: String,
+    pub count: i32,
+}
+
+impl From<crate::models::DocumentSearchAggregations> for DocumentSearchAggregations {
+    fn from(aggregations: crate::models::DocumentSearchAggregations) -> Self {
+        Self {
+            document_type_counts: aggregations
+                .document_type_counts
+                .into_iter()
+                .map(|c| DocumentTypeCount {
+                    document_type_id: c.document_type_id,
+                    document_type_code: c.document_type_code,
+                    document_type_name: c.document_type_name,
+                    count: c.count,
+                })
+                .collect(),
+            department_counts: aggregations
+                .department_counts
+                .into_iter()
+                .map(|c| DocumentDepartmentCount {
+                    department_code: c.department_code,
+                    department_name: c.department_name,
+                    count: c.count,
+                })
+                .collect(),
+            year_counts: aggregations
+                .year_counts
+                .into_iter()
+                .map(|c| DocumentYearCount {
+                    year: c.year,
+                    count: c.count,
+                })
+                .collect(),
+            importance_class_counts: aggregations
+                .importance_class_counts
+                .into_iter()
+                .map(|c| ImportanceClassCount {
+                    importance_class: c.importance_class.map(Into::into),
+                    count: c.count,
+                })
+                .collect(),
+            internal_external_counts: aggregations
+                .internal_external_counts
+                .into_iter()
+                .map(|c| InternalExternalCount {
+                    internal_external: c.internal_external.map(Into::into),
+                    count: c.count,
+                })
+                .collect(),
+            business_number_counts: aggregations
+                .business_number_counts
+                .into_iter()
+                .map(|c| BusinessNumberCount {
+                    business_number: c.business_number,
+                    count: c.count,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// GraphQL DocumentSearchHit type
+#[derive(SimpleObject)]
+pub struct DocumentSearchHit {
+    pub document: Document,
+    /// bm25 relevance (lower is more relevant); null for short-term substring matches
+    pub rank: Option<f64>,
+    /// Matched text with hits wrapped in `<mark>`
+    pub snippet: Option<String>,
+}
+
+impl From<crate::models::DocumentSearchHit> for DocumentSearchHit {
+    fn from(hit: crate::models::DocumentSearchHit) -> Self {
+        Self {
+            document: hit.document.into(),
+            rank: hit.rank,
+            snippet: hit.snippet,
+        }
+    }
+}
+
+/// GraphQL CreateDocumentWithManualNumberInput type
+#[derive(InputObject)]
+pub struct CreateDocumentWithManualNumberInput {
+    pub number: String,
+    pub title: String,
+    pub document_type_code: String,
+    pub department_code: String,
+    pub created_by: i32,
+    pub created_date: String,
+    pub internal_external: Option<InternalExternal>,
+    pub importance_class: Option<ImportanceClass>,
+    pub personal_info: Option<PersonalInfo>,
+    pub reason: String,
+}
+
+impl TryFrom<CreateDocumentWithManualNumberInput>
+    for crate::models::CreateDocumentWithManualNumberRequest
+{
+    type Error = async_graphql::Error;
+
+    fn try_from(val: CreateDocumentWithManualNumberInput) -> Result<Self, Self::Error> {
+        Ok(crate::models::CreateDocumentWithManualNumberRequest {
+            number: val.number,
+            document: crate::models::CreateDocumentWithNumberRequest {
+                title: val.title,
+                document_type_code: val.document_type_code,
+                department_code: val.department_code,
+                section_code: None,
+                created_by: val.created_by,
+                created_date: parse_date(&val.created_date)?,
+                internal_external: val.internal_external.map(Into::into),
+                importance_class: val.importance_class.map(Into::into),
+                personal_info: val.personal_info.map(Into::into),
+            },
+            reason: val.reason,
+        })
+    }
+}
+
+/// GraphQL DocumentNumberOverride type
+#[derive(SimpleObject)]
+pub struct DocumentNumberOverride {
+    pub id: i32,
+    pub document_id: i32,
+    pub document_number: String,
+    pub assigned_by: i32,
+    pub reason: String,
+    pub created_at: String,
+}
+
+impl From<crate::models::DocumentNumberOverride> for DocumentNumberOverride {
+    fn from(record: crate::models::DocumentNumberOverride) -> Self {
+        Self {
+            id: record.id,
+            document_id: record.document_id,
+            document_number: record.document_number,
+            assigned_by: record.assigned_by,
+            reason: record.reason,
+            created_at: record.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// GraphQL CreatedDocumentWithManualNumber type
+#[derive(SimpleObject)]
+pub struct CreatedDocumentWithManualNumber {
+    pub document: Document,
+    pub document_number: String,
+    pub number_override: DocumentNumberOverride,
+}
+
+impl From<crate::models::CreatedDocumentWithManualNumber> for CreatedDocumentWithManualNumber {
+    fn from(created: crate::models::CreatedDocumentWithManualNumber) -> Self {
+        Self {
+            document: created.document.into(),
+            document_number: created.document_number,
+            number_override: created.number_override.into(),
+        }
+    }
+}
+
+// ========== Document Number Rule Types ==========
+
+/// GraphQLの日付文字列（YYYY-MM-DD）を解析する
+pub(crate) fn parse_date(value: &str) -> async_graphql::Result<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| async_graphql::Error::new(format!("Invalid date: {value}")))
+}
+
+/// GraphQL DocumentNumberRule type
+#[derive(SimpleObject)]
+pub struct DocumentNumberRule {
+    pub id: i32,
+    pub rule_name: String,
+    pub template: String,
+    pub sequence_digits: i32,
+    pub department_code: Option<String>,
+    pub document_type_codes: Vec<String>,
+    pub effective_from: String,
+    pub effective_until: Option<String>,
+    pub priority: i32,
+    pub reset_scope: SequenceResetScope,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// GraphQL SequenceResetScope enum
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SequenceResetScope {
+    /// 年月ごと
+    Monthly,
+    /// 暦年ごと
+    Yearly,
+    /// 4月始まりの年度ごと
+    FiscalYearly,
+    /// リセットしない
+    Never,
+}
+
+impl From<crate::models::SequenceResetScope> for SequenceResetScope {
+    fn from(scope: crate::models::SequenceResetScope) -> Self {
+        match scope {
+            crate::models::SequenceResetScope::Monthly => Self::Monthly,
+            crate::models::SequenceResetScope::Yearly => Self::Yearly,
+            crate::models::SequenceResetScope::FiscalYearly => Self::FiscalYearly,
+            crate::models::SequenceResetScope::Never => Self::Never,
+        }
+    }
+}
+
+impl From<SequenceResetScope> for crate::models::SequenceResetScope {
+    fn from(scope: SequenceResetScope) -> Self {
+        match scope {
+            SequenceResetScope::Monthly => Self::Monthly,
+            SequenceResetScope::Yearly => Self::Yearly,
+            SequenceResetScope::FiscalYearly => Self::FiscalYearly,
+            SequenceResetScope::Never => Self::Never,
+        }
+    }
+}
+
+impl From<crate::models::DocumentNumberGenerationRule> for DocumentNumberRule {
+    fn from(rule: crate::models::DocumentNumberGenerationRule) -> Self {
+        Self {
+            document_type_codes: rule.parsed_document_type_codes(),
+            id: rule.id,
+            rule_name: rule.rule_name,
+            template: rule.template,
+            sequence_digits: rule.sequence_digits,
+            department_code: rule.department_code,
+            effective_from: rule.effective_from.format("%Y-%m-%d").to_string(),
+            effective_until: rule
+                .effective_until
+                .map(|d| d.format("%Y-%m-%d").to_string()),
+            priority: rule.priority,
+            reset_scope: rule.reset_scope.into(),
+            created_at: rule.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            updated_at: rule.updated_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// GraphQL DocumentNumberRulesResult type
+#[derive(SimpleObject)]
+pub struct DocumentNumberRulesResult {
+    pub rules: Vec<DocumentNumberRule>,
+    pub total: i64,
+}
+
+/// GraphQL CreateDocumentNumberRuleInput type
+#[derive(InputObject)]
+pub struct CreateDocumentNumberRuleInput {
+    pub rule_name: String,
+    pub template: String,
+    pub sequence_digits: i32,
+    pub department_code: Option<String>,
+    pub document_type_codes: Vec<String>,
+    pub effective_from: String,
+    pub effective_until: Option<String>,
+    pub priority: i32,
+    /// Defaults to MONTHLY
+    pub reset_scope: Option<SequenceResetScope>,
+}
+
+impl TryFrom<CreateDocumentNumberRuleInput>
+    for crate::models::CreateDocumentNumberGenerationRuleRequest
+{
+    type Error = async_graphql::Error;
+
+    fn try_from(val: CreateDocumentNumberRuleInput) -> Result<Self, Self::Error> {
+        Ok(crate::models::CreateDocumentNumberGenerationRuleRequest {
+            rule_name: val.rule_name,
+            template: val.template,
+            sequence_digits: val.sequence_digits,
+            department_code: val.department_code,
+            document_type_codes: val.document_type_codes,
+            effective_from: parse_date(&val.effective_from)?,
+            effective_until: val.effective_until.as_deref().map(parse_date).transpose()?,
+            priority: val.priority,
+            reset_scope: val.reset_scope.map(Into::into).unwrap_or_default(),
+        })
+    }
+}
+
+/// GraphQL UpdateDocumentNumberRuleInput type
+#[derive(InputObject)]
+pub struct UpdateDocumentNumberRuleInput {
+    pub rule_name: Option<String>,
+    pub template: Option<String>,
+    pub sequence_digits: Option<i32>,
+    pub department_code: Option<String>,
+    pub document_type_codes: Option<Vec<String>>,
+    pub effective_from: Option<String>,
+    /// Set to `null` to make the rule open-ended again
+    pub effective_until: MaybeUndefined<String>,
+    pub priority: Option<i32>,
+    pub reset_scope: Option<SequenceResetScope>,
+}
+
+impl TryFrom<UpdateDocumentNumberRuleInput>
+    for crate::models::UpdateDocumentNumberGenerationRuleRequest
+{
+    type Error = async_graphql::Error;
+
+    fn try_from(val: UpdateDocumentNumberRuleInput) -> Result<Self, Self::Error> {
+        Ok(crate::models::UpdateDocumentNumberGenerationRuleRequest {
+            rule_name: val.rule_name,
+            template: val.template,
+            sequence_digits: val.sequence_digits,
+            department_code: val.department_code,
+            document_type_codes: val.document_type_codes,
+            effective_from: val.effective_from.as_deref().map(parse_date).transpose()?,
+            effective_until: match val.effective_until {
+                MaybeUndefined::Undefined => None,
+                MaybeUndefined::Null => Some(None),
+                MaybeUndefined::Value(date) => Some(Some(parse_date(&date)?)),
+            },
+            priority: val.priority,
+            reset_scope: val.reset_scope.map(Into::into),
+        })
+    }
+}
+
+/// GraphQL SimulateDocumentNumberRuleInput type
+#[derive(InputObject)]
+pub struct SimulateDocumentNumberRuleInput {
+    pub document_type_code: String,
+    pub department_code: String,
+    pub section_code: Option<String>,
+    pub created_date: String,
+    /// Number of upcoming numbers to preview (defaults to 5)
+    pub count: Option<i32>,
+}
+
+impl TryFrom<SimulateDocumentNumberRuleInput> for crate::models::RuleSimulationRequest {
+    type Error = async_graphql::Error;
+
+    fn try_from(val: SimulateDocumentNumberRuleInput) -> Result<Self, Self::Error> {
+        Ok(crate::models::RuleSimulationRequest {
+            document_type_code: val.document_type_code,
+            department_code: val.department_code,
+            section_code: val.section_code,
+            created_date: parse_date(&val.created_date)?,
+            count: val.count,
+        })
+    }
+}
+
+/// GraphQL DocumentNumberRuleSimulation type
+#[derive(SimpleObject)]
+pub struct DocumentNumberRuleSimulation {
+    pub document_type_code: String,
+    pub department_code: String,
+    pub created_date: String,
+    /// Candidate rules in the order they are selected
+    pub candidates: Vec<DocumentNumberRule>,
+    pub selected_rule_id: Option<i32>,
+    pub next_numbers: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl From<crate::models::RuleSimulationResult> for DocumentNumberRuleSimulation {
+    fn from(result: crate::models::RuleSimulationResult) -> Self {
+        Self {
+            document_type_code: result.document_type_code,
+            department_code: result.department_code,
+            created_date: result.created_date.format("%Y-%m-%d").to_string(),
+            candidates: result.candidates.into_iter().map(Into::into).collect(),
+            selected_rule_id: result.selected_rule_id,
+            next_numbers: result.next_numbers,
+            error: result.error,
+        }
+    }
+}
+
+/// GraphQL DocumentNumberRulePriorityOverlap type
+#[derive(SimpleObject)]
+pub struct DocumentNumberRulePriorityOverlap {
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub other_rule_id: i32,
+    pub other_rule_name: String,
+    pub priority: i32,
+    pub department_code: Option<String>,
+    pub document_type_codes: Vec<String>,
+    pub overlap_from: String,
+    pub overlap_until: Option<String>,
+}
+
+impl From<crate::models::RulePriorityOverlap> for DocumentNumberRulePriorityOverlap {
+    fn from(overlap: crate::models::RulePriorityOverlap) -> Self {
+        Self {
+            rule_id: overlap.rule_id,
+            rule_name: overlap.rule_name,
+            other_rule_id: overlap.other_rule_id,
+            other_rule_name: overlap.other_rule_name,
+            priority: overlap.priority,
+            department_code: overlap.department_code,
+            document_type_codes: overlap.document_type_codes,
+            overlap_from: overlap.overlap_from.format("%Y-%m-%d").to_string(),
+            overlap_until: overlap
+                .overlap_until
+                .map(|d| d.format("%Y-%m-%d").to_string()),
+        }
+    }
+}
+
+/// GraphQL TemplateCollisionKind enum
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TemplateCollisionKind {
+    SharedPattern,
+    DepartmentCounter,
+    ResetPeriod,
+}
+
+impl From<crate::models::TemplateCollisionKind> for TemplateCollisionKind {
+    fn from(kind: crate::models::TemplateCollisionKind) -> Self {
+        match kind {
+            crate::models::TemplateCollisionKind::SharedPattern => Self::SharedPattern,
+            crate::models::TemplateCollisionKind::DepartmentCounter => Self::DepartmentCounter,
+            crate::models::TemplateCollisionKind::ResetPeriod => Self::ResetPeriod,
+        }
+    }
+}
+
+/// GraphQL DocumentNumberRuleCollision type
+#[derive(SimpleObject)]
+pub struct DocumentNumberRuleCollision {
+    pub kind: TemplateCollisionKind,
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub template: String,
+    pub other_rule_id: i32,
+    pub other_rule_name: String,
+    pub other_template: String,
+    pub example_number: Option<String>,
+    pub periods_overlap: bool,
+}
+
+impl From<crate::models::RuleTemplateCollision> for DocumentNumberRuleCollision {
+    fn from(collision: crate::models::RuleTemplateCollision) -> Self {
+        Self {
+            kind: collision.kind.into(),
+            rule_id: collision.rule_id,
+            rule_name: collision.rule_name,
+            template: collision.template,
+            other_rule_id: collision.other_rule_id,
+            other_rule_name: collision.other_rule_name,
+            other_template: collision.other_template,
+            example_number: collision.example_number,
+            periods_overlap: collision.periods_overlap,
+        }
+    }
+}
+
+/// GraphQL DocumentNumberRuleConflicts type
+#[derive(SimpleObject)]
+pub struct DocumentNumberRuleConflicts {
+    pub checked_rules: i64,
+    pub priority_overlaps: Vec<DocumentNumberRulePriorityOverlap>,
+    pub template_collisions: Vec<DocumentNumberRuleCollision>,
+}
+
+impl From<crate::models::RuleConflictReport> for DocumentNumberRuleConflicts {
+    fn from(report: crate::models::RuleConflictReport) -> Self {
+        Self {
+            checked_rules: report.checked_rules,
+            priority_overlaps: report
+                .priority_overlaps
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            template_collisions: report
+                .template_collisions
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+/// GraphQL DocumentNumberComponents type
+#[derive(SimpleObject)]
+pub struct DocumentNumberComponents {
+    pub document_type_code: Option<String>,
+    pub document_type_name: Option<String>,
+    pub department_code: Option<String>,
+    pub section_code: Option<String>,
+    pub year: Option<i32>,
+    pub month: Option<i32>,
+    pub fiscal_year: Option<i32>,
+    pub era: Option<String>,
+    pub era_year: Option<i32>,
+    pub sequence_number: i32,
+}
+
+impl From<crate::models::ParsedDocumentNumber> for DocumentNumberComponents {
+    fn from(parsed: crate::models::ParsedDocumentNumber) -> Self {
+        Self {
+            document_type_code: parsed.document_type_code,
+            document_type_name: parsed.document_type_name,
+            department_code: parsed.department_code,
+            section_code: parsed.section_code,
+            year: parsed.year,
+            month: parsed.month,
+            fiscal_year: parsed.fiscal_year,
+            era: parsed.era,
+            era_year: parsed.era_year,
+            sequence_number: parsed.sequence_number,
+        }
+    }
+}
+
+/// GraphQL DocumentNumberSequenceKey type
+#[derive(SimpleObject)]
+pub struct DocumentNumberSequenceKey {
+    pub rule_id: i32,
+    /// Calendar or fiscal year of the counter period (0 when the rule never resets)
+    pub year: i32,
+    /// Month of the counter period (0 unless the rule resets monthly)
+    pub month: i32,
+    pub department_code: String,
+}
+
+impl From<crate::models::SequenceKey> for DocumentNumberSequenceKey {
+    fn from(key: crate::models::SequenceKey) -> Self {
+        Self {
+            rule_id: key.rule_id,
+            year: key.year,
+            month: key.month,
+            department_code: key.department_code,
+        }
+    }
+}
+
+/// GraphQL DecomposedDocumentNumber type
+#[derive(SimpleObject)]
+pub struct DecomposedDocumentNumber {
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub template: String,
+    pub priority: i32,
+    pub components: DocumentNumberComponents,
+    pub effective: Option<bool>,
+    pub sequence_key: Option<DocumentNumberSequenceKey>,
+    pub next_sequence: Option<i32>,
+}
+
+impl From<crate::models::DecomposedDocumentNumber> for DecomposedDocumentNumber {
+    fn from(decomposed: crate::models::DecomposedDocumentNumber) -> Self {
+        Self {
+            rule_id: decomposed.rule_id,
+            rule_name: decomposed.rule_name,
+            template: decomposed.template,
+            priority: decomposed.priority,
+            components: decomposed.components.into(),
+            effective: decomposed.effective,
+            sequence_key: decomposed.sequence_key.map(Into::into),
+            next_sequence: decomposed.next_sequence,
+        }
+    }
+}
+
+/// GraphQL DocumentNumberDecomposition type
+#[derive(SimpleObject)]
+pub struct DocumentNumberDecomposition {
+    pub document_number: String,
+    pub registered: bool,
+    pub matches: Vec<DecomposedDocumentNumber>,
+}
+
+impl From<crate::models::DocumentNumberDecomposition> for DocumentNumberDecomposition {
+    fn from(decomposition: crate::models::DocumentNumberDecomposition) -> Self {
+        Self {
+            document_number: decomposition.document_number,
+            registered: decomposition.registered,
+            matches: decomposition.matches.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+// ========== Document Revision Types ==========
+
+/// GraphQL RevisionType enum
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum RevisionType {
+    Revision,
+    Draft,
+}
+
+impl From<crate::models::RevisionType> for RevisionType {
+    fn from(revision_type: crate::models::RevisionType) -> Self {
+        match revision_type {
+            crate::models::RevisionType::Revision => Self::Revision,
+            crate::models::RevisionType::Draft => Self::Draft,
+        }
+    }
+}
+
+impl From<RevisionType> for crate::models::RevisionType {
+    fn from(revision_type: RevisionType) -> Self {
+        match revision_type {
+            RevisionType::Revision => Self::Revision,
+            RevisionType::Draft => Self::Draft,
+        }
+    }
+}
+
+/// GraphQL RevisionStatus enum
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum RevisionStatus {
+    Issued,
+    Draft,
+    Promoted,
+}
+
+impl From<crate::models::RevisionStatus> for RevisionStatus {
+    fn from(status: crate::models::RevisionStatus) -> Self {
+        match status {
+            crate::models::RevisionStatus::Issued => Self::Issued,
+            crate::models::RevisionStatus::Draft => Self::Draft,
+            crate::models::RevisionStatus::Promoted => Self::Promoted,
+        }
+    }
+}
+
+/// GraphQL DocumentRevision type
+#[derive(SimpleObject)]
+pub struct DocumentRevision {
+    pub id: i32,
+    pub document_id: i32,
+    pub revision_type: RevisionType,
+    pub version_number: i32,
+    pub number: String,
+    pub status: RevisionStatus,
+    pub notes: Option<String>,
+    pub promoted_revision_id: Option<i32>,
+    pub created_by: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<crate::models::DocumentRevision> for DocumentRevision {
+    fn from(revision: crate::models::DocumentRevision) -> Self {
+        Self {
+            id: revision.id,
+            document_id: revision.document_id,
+            revision_type: revision.revision_type.into(),
+            version_number: revision.version_number,
+            number: revision.number,
+            status: revision.status.into(),
+            notes: revision.notes,
+            promoted_revision_id: revision.promoted_revision_id,
+            created_by: revision.created_by,
+            created_at: revision.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            updated_at: revision.updated_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// GraphQL ResolvedNetworkPath type
+#[derive(SimpleObject)]
+pub struct ResolvedNetworkPath {
+    pub document_number: String,
+    pub network_path: String,
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub rule_version: i32,
+    pub as_of: String, // NaiveDate as ISO string
+}
+
+impl From<crate::models::ResolvedNetworkPath> for ResolvedNetworkPath {
+    fn from(resolved: crate::models::ResolvedNetworkPath) -> Self {
+        Self {
+            document_number: resolved.document_number,
+            network_path: resolved.network_path,
+            rule_id: resolved.rule_id,
+            rule_name: resolved.rule_name,
+            rule_version: resolved.rule_version,
+            as_of: resolved.as_of.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// GraphQL CreateDocumentRevisionInput type
+#[derive(InputObject)]
+pub struct CreateDocumentRevisionInput {
+    pub revision_type: RevisionType,
+    pub created_by: i32,
+    pub notes: Option<String>,
+}
+
+impl From<CreateDocumentRevisionInput> for crate::models::CreateDocumentRevisionRequest {
+    fn from(val: CreateDocumentRevisionInput) -> Self {
+        crate::models::CreateDocumentRevisionRequest {
+            revision_type: val.revision_type.into(),
+            created_by: val.created_by,
+            notes: val.notes,
+        }
+    }
 }
 
 // ========== Circulation Types ==========
@@ -440,12 +1523,41 @@ pub struct PendingApproval {
 
 // ========== Department Types ==========
 
+/// GraphQL OrganizationUnitKind enum
+#[derive(Enum, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum OrganizationUnitKind {
+    /// 部
+    #[default]
+    Department,
+    /// 課 (requires a parent department)
+    Section,
+}
+
+impl From<OrganizationUnitKind> for crate::models::OrganizationUnitKind {
+    fn from(value: OrganizationUnitKind) -> Self {
+        match value {
+            OrganizationUnitKind::Department => Self::Department,
+            OrganizationUnitKind::Section => Self::Section,
+        }
+    }
+}
+
+impl From<crate::models::OrganizationUnitKind> for OrganizationUnitKind {
+    fn from(value: crate::models::OrganizationUnitKind) -> Self {
+        match value {
+            crate::models::OrganizationUnitKind::Department => Self::Department,
+            crate::models::OrganizationUnitKind::Section => Self::Section,
+        }
+    }
+}
+
 /// GraphQL Department type
 #[derive(SimpleObject)]
 pub struct Department {
     pub id: i32,
     pub code: String,
     pub name: String,
+    pub kind: OrganizationUnitKind,
     pub parent_id: Option<i32>,
     pub level: i32,
     pub manager_id: Option<i32>,
@@ -455,6 +1567,8 @@ pub struct Department {
     pub email: Option<String>,
     pub budget: Option<f64>,
     pub is_active: bool,
+    /// Pass to updateDepartment to detect concurrent updates
+    pub version: i32,
     pub created_date: Option<String>,
     pub created_at: String,
     pub updated_at: String,
@@ -466,6 +1580,7 @@ impl From<crate::models::Department> for Department {
             id: dept.id,
             code: dept.code,
             name: dept.name,
+            kind: dept.kind.into(),
             parent_id: dept.parent_id,
             level: dept.level,
             manager_id: dept.manager_id,
@@ -475,6 +1590,7 @@ impl From<crate::models::Department> for Department {
             email: dept.email,
             budget: dept.budget,
             is_active: dept.is_active,
+            version: dept.version,
             created_date: dept.created_date.map(|d| d.format("%Y-%m-%d").to_string()),
             created_at: dept.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
             updated_at: dept.updated_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
@@ -488,6 +1604,7 @@ pub struct DepartmentWithManager {
     pub id: i32,
     pub code: String,
     pub name: String,
+    pub kind: OrganizationUnitKind,
     pub parent_id: Option<i32>,
     pub parent_name: Option<String>,
     pub level: i32,
@@ -500,6 +1617,8 @@ pub struct DepartmentWithManager {
     pub budget: Option<f64>,
     pub is_active: bool,
     pub employee_count: i64,
+    /// Pass to updateDepartment to detect concurrent updates
+    pub version: i32,
     pub created_date: Option<String>,
     pub created_at: String,
     pub updated_at: String,
@@ -511,6 +1630,7 @@ impl From<crate::models::DepartmentWithManager> for DepartmentWithManager {
             id: dept.id,
             code: dept.code,
             name: dept.name,
+            kind: dept.kind.into(),
             parent_id: dept.parent_id,
             parent_name: dept.parent_name,
             level: dept.level,
@@ -523,6 +1643,7 @@ impl From<crate::models::DepartmentWithManager> for DepartmentWithManager {
             budget: dept.budget,
             is_active: dept.is_active,
             employee_count: dept.employee_count,
+            version: dept.version,
             created_date: dept.created_date.map(|d| d.format("%Y-%m-%d").to_string()),
             created_at: dept.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
             updated_at: dept.updated_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
@@ -559,6 +1680,8 @@ impl From<DepartmentSearchFilters> for crate::models::DepartmentSearchFilters {
 pub struct CreateDepartmentInput {
     pub code: String,
     pub name: String,
+    /// Defaults to `DEPARTMENT`
+    pub kind: Option<OrganizationUnitKind>,
     pub parent_id: Option<i32>,
     pub manager_id: Option<i32>,
     pub description: Option<String>,
@@ -574,6 +1697,7 @@ impl From<CreateDepartmentInput> for crate::models::CreateDepartmentRequest {
         crate::models::CreateDepartmentRequest {
             code: val.code,
             name: val.name,
+            kind: val.kind.unwrap_or_default().into(),
             parent_id: val.parent_id,
             manager_id: val.manager_id,
             description: val.description,
@@ -588,6 +1712,164 @@ impl From<CreateDepartmentInput> for crate::models::CreateDepartmentRequest {
     }
 }
 
+/// GraphQL DepartmentVersion type
+#[derive(SimpleObject)]
+pub struct DepartmentVersion {
+    pub id: i32,
+    pub department_id: i32,
+    pub code: String,
+    pub name: String,
+    pub kind: OrganizationUnitKind,
+    pub parent_id: Option<i32>,
+    pub level: i32,
+    pub valid_from: String,
+    /// First day the record is no longer valid (null while still valid)
+    pub valid_to: Option<String>,
+    pub event_id: Option<i32>,
+}
+
+impl From<crate::models::DepartmentVersion> for DepartmentVersion {
+    fn from(version: crate::models::DepartmentVersion) -> Self {
+        Self {
+            id: version.id,
+            department_id: version.department_id,
+            code: version.code,
+            name: version.name,
+            kind: version.kind.into(),
+            parent_id: version.parent_id,
+            level: version.level,
+            valid_from: version.valid_from.format("%Y-%m-%d").to_string(),
+            valid_to: version.valid_to.map(|d| d.format("%Y-%m-%d").to_string()),
+            event_id: version.event_id,
+        }
+    }
+}
+
+/// GraphQL OrganizationEventType enum
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OrganizationEventType {
+    /// Code and/or name change (departmentId, code, name)
+    Rename,
+    /// Sources are absorbed into the target and abolished (sourceIds, targetId)
+    Merge,
+    /// The source is abolished and replaced by new units (departmentId, units)
+    Split,
+    /// Parent department change (departmentId, parentId)
+    Move,
+}
+
+impl From<crate::models::OrganizationEventType> for OrganizationEventType {
+    fn from(value: crate::models::OrganizationEventType) -> Self {
+        match value {
+            crate::models::OrganizationEventType::Rename => Self::Rename,
+            crate::models::OrganizationEventType::Merge => Self::Merge,
+            crate::models::OrganizationEventType::Split => Self::Split,
+            crate::models::OrganizationEventType::Move => Self::Move,
+        }
+    }
+}
+
+/// GraphQL OrganizationEvent type
+#[derive(SimpleObject)]
+pub struct OrganizationEvent {
+    pub id: i32,
+    pub event_type: OrganizationEventType,
+    pub effective_date: String,
+    pub reason: Option<String>,
+    pub recorded_by: Option<i32>,
+    pub source_ids: Vec<i32>,
+    pub target_ids: Vec<i32>,
+    pub created_at: String,
+}
+
+impl From<crate::models::OrganizationEvent> for OrganizationEvent {
+    fn from(event: crate::models::OrganizationEvent) -> Self {
+        Self {
+            id: event.id,
+            event_type: event.event_type.into(),
+            effective_date: event.effective_date.format("%Y-%m-%d").to_string(),
+            reason: event.reason,
+            recorded_by: event.recorded_by,
+            source_ids: event.source_ids,
+            target_ids: event.target_ids,
+            created_at: event.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// GraphQL NewOrganizationUnitInput type
+#[derive(InputObject)]
+pub struct NewOrganizationUnitInput {
+    pub code: String,
+    pub name: String,
+}
+
+/// GraphQL RestructureDepartmentsInput type
+#[derive(InputObject)]
+pub struct RestructureDepartmentsInput {
+    pub event_type: OrganizationEventType,
+    /// First day the new organization is in effect (YYYY-MM-DD)
+    pub effective_date: String,
+    pub reason: Option<String>,
+    pub recorded_by: Option<i32>,
+    /// Unit to rename, move or split
+    pub department_id: Option<i32>,
+    pub code: Option<String>,
+    pub name: Option<String>,
+    /// New parent for MOVE (null makes it a top-level department)
+    pub parent_id: Option<i32>,
+    pub source_ids: Option<Vec<i32>>,
+    pub target_id: Option<i32>,
+    pub units: Option<Vec<NewOrganizationUnitInput>>,
+}
+
+impl TryFrom<RestructureDepartmentsInput> for crate::models::RestructureRequest {
+    type Error = async_graphql::Error;
+
+    fn try_from(val: RestructureDepartmentsInput) -> async_graphql::Result<Self> {
+        let department_id = || {
+            val.department_id
+                .ok_or_else(|| async_graphql::Error::new("departmentId is required"))
+        };
+        let change = match val.event_type {
+            OrganizationEventType::Rename => crate::models::RestructureChange::Rename {
+                department_id: department_id()?,
+                code: val.code,
+                name: val.name,
+            },
+            OrganizationEventType::Move => crate::models::RestructureChange::Move {
+                department_id: department_id()?,
+                parent_id: val.parent_id,
+            },
+            OrganizationEventType::Merge => crate::models::RestructureChange::Merge {
+                source_ids: val.source_ids.unwrap_or_default(),
+                target_id: val
+                    .target_id
+                    .ok_or_else(|| async_graphql::Error::new("targetId is required"))?,
+            },
+            OrganizationEventType::Split => crate::models::RestructureChange::Split {
+                source_id: department_id()?,
+                units: val
+                    .units
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|unit| crate::models::NewOrganizationUnit {
+                        code: unit.code,
+                        name: unit.name,
+                    })
+                    .collect(),
+            },
+        };
+
+        Ok(crate::models::RestructureRequest {
+            effective_date: parse_date(&val.effective_date)?,
+            reason: val.reason,
+            recorded_by: val.recorded_by,
+            change,
+        })
+    }
+}
+
 /// GraphQL UpdateDepartmentInput type
 #[derive(InputObject)]
 pub struct UpdateDepartmentInput {
@@ -1,8 +1,21 @@
 use crate::error::AppError;
+use chrono::NaiveDate;
+
 use crate::models::{
-    CreateDocumentWithNumberRequest, CreatedDocumentWithNumber, Document, DocumentSearchFilters,
+    BulkCreateDocumentsRequest, BulkCreateDocumentsResult, ChangeDocumentStateRequest,
+    ClaimReservedNumberRequest, CreateDocumentNumberAliasRequest,
+    CreateDocumentNumberGenerationRuleRequest, CreateDocumentNumberReservationRequest,
+    CreateDocumentWithManualNumberRequest, CreateDocumentWithNumberRequest,
+    CreatedDocumentWithManualNumber, CreatedDocumentWithNumber, DeletedDocument, Document,
+    DocumentChange, DocumentNumberAlias, DocumentNumberDecomposition, DocumentNumberGapReport,
+    DocumentNumberGenerationRule, DocumentNumberReservation, DocumentSearchAggregations,
+    DocumentSearchFilters, DocumentSearchHit, NumberGapFilters, ReservationExpiryResult,
+    RuleConflictReport, RuleSimulationRequest, RuleSimulationResult,
+    UpdateDocumentNumberGenerationRuleRequest, UpdateDocumentRequest, VoidDocumentNumberRequest,
+    VoidedDocumentNumber,
 };
-use crate::services::DocumentService;
+use crate::repositories::RepositoryError;
+use crate::services::{DocumentService, DocumentServiceError};
 
 /// ビジネスロジック層のDocumentハンドラー
 /// HTTPハンドラーから呼び出される
@@ -34,6 +47,45 @@ impl DocumentHandlers {
             .map_err(AppError::from)
     }
 
+    /// Idempotency-Key付きで文書を作成する（戻り値の2番目は再送に対する応答かどうか）
+    pub async fn create_document_idempotent(
+        &self,
+        request: CreateDocumentWithNumberRequest,
+        idempotency_key: &str,
+    ) -> Result<(CreatedDocumentWithNumber, bool), AppError> {
+        if request.title.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Title cannot be empty".to_string(),
+            ));
+        }
+
+        self.document_service
+            .create_document_idempotent(request, idempotency_key)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn create_documents_bulk(
+        &self,
+        request: BulkCreateDocumentsRequest,
+    ) -> Result<BulkCreateDocumentsResult, AppError> {
+        self.document_service
+            .create_documents_bulk(request)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn create_document_with_manual_number(
+        &self,
+        actor_id: Option<i32>,
+        request: CreateDocumentWithManualNumberRequest,
+    ) -> Result<CreatedDocumentWithManualNumber, AppError> {
+        self.document_service
+            .create_document_with_manual_number(actor_id, request)
+            .await
+            .map_err(AppError::from)
+    }
+
     pub async fn get_document(&self, id: i32) -> Result<Document, AppError> {
         match self.document_service.get_document_by_id(id).await? {
             Some(document) => Ok(document),
@@ -43,6 +95,85 @@ impl DocumentHandlers {
         }
     }
 
+    /// 文書を更新する（取得後に他の利用者が更新していた場合は最新の文書を付けて拒否する）
+    ///
+    /// 返す文書は閲覧者の機密レベルで見られない項目を伏せる
+    pub async fn update_document(
+        &self,
+        id: i32,
+        request: UpdateDocumentRequest,
+        expected_version: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Document, AppError> {
+        let clearance = self.document_service.resolve_clearance(viewer_id).await?;
+
+        match self
+            .document_service
+            .update_document(id, request, expected_version)
+            .await
+        {
+            Ok(document) => Ok(document.redact_for(clearance)),
+            Err(DocumentServiceError::RepositoryError(
+                err @ RepositoryError::VersionMismatch { .. },
+            )) => match self.document_service.get_document_by_id(id).await? {
+                Some(current) => Err(AppError::PreconditionFailed {
+                    message: err.to_string(),
+                    current: serde_json::to_value(current.redact_for(clearance))
+                        .map_err(|e| AppError::InternalError(e.to_string()))?,
+                }),
+                None => Err(AppError::NotFound(format!(
+                    "Document with id {id} not found"
+                ))),
+            },
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub async fn delete_document(
+        &self,
+        id: i32,
+        request: ChangeDocumentStateRequest,
+        viewer_id: Option<i32>,
+    ) -> Result<Document, AppError> {
+        let clearance = self.document_service.resolve_clearance(viewer_id).await?;
+        let document = self.document_service.delete_document(id, request).await?;
+        Ok(document.redact_for(clearance))
+    }
+
+    pub async fn restore_document(
+        &self,
+        id: i32,
+        request: ChangeDocumentStateRequest,
+        viewer_id: Option<i32>,
+    ) -> Result<Document, AppError> {
+        let clearance = self.document_service.resolve_clearance(viewer_id).await?;
+        let document = self.document_service.restore_document(id, request).await?;
+        Ok(document.redact_for(clearance))
+    }
+
+    pub async fn list_deleted_documents(
+        &self,
+        limit: i64,
+        offset: i64,
+        viewer_id: Option<i32>,
+    ) -> Result<(Vec<DeletedDocument>, i64), AppError> {
+        self.document_service
+            .list_deleted_documents(limit, offset, viewer_id)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn list_document_changes(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<DocumentChange>, AppError> {
+        self.document_service
+            .list_document_changes(id, viewer_id)
+            .await
+            .map_err(AppError::from)
+    }
+
     pub async fn search_documents(
         &self,
         filters: DocumentSearchFilters,
@@ -52,6 +183,269 @@ impl DocumentHandlers {
             .await
             .map_err(AppError::from)
     }
+
+    /// 閲覧者の機密レベルで見られる文書を取得する
+    pub async fn get_document_for_viewer(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Document, AppError> {
+        match self
+            .document_service
+            .get_document_for_viewer(id, viewer_id)
+            .await?
+        {
+            Some(document) => Ok(document),
+            None => Err(AppError::NotFound(format!(
+                "Document with id {id} not found"
+            ))),
+        }
+    }
+
+    /// 閲覧者の機密レベルで見られる文書を文書番号または旧番号で取得する
+    pub async fn get_document_by_number_for_viewer(
+        &self,
+        document_number: &str,
+        viewer_id: Option<i32>,
+    ) -> Result<Document, AppError> {
+        match self
+            .document_service
+            .get_document_by_number_for_viewer(document_number, viewer_id)
+            .await?
+        {
+            Some(document) => Ok(document),
+            None => Err(AppError::NotFound(format!(
+                "Document with number {document_number} not found"
+            ))),
+        }
+    }
+
+    /// 閲覧者が文書のネットワークパスを見られることを確認する
+    pub async fn ensure_network_path_visible(
+        &self,
+        document_number: &str,
+        viewer_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        Ok(self
+            .document_service
+            .ensure_network_path_visible(document_number, viewer_id)
+            .await?)
+    }
+
+    /// 閲覧者の機密レベルで見られる文書に限って検索する
+    pub async fn search_documents_for_viewer(
+        &self,
+        filters: DocumentSearchFilters,
+        viewer_id: Option<i32>,
+    ) -> Result<(Vec<Document>, i64), AppError> {
+        self.document_service
+            .search_documents_for_viewer(filters, viewer_id)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// 閲覧者の機密レベルで見られる文書に限って全文検索する
+    pub async fn full_text_search_for_viewer(
+        &self,
+        filters: DocumentSearchFilters,
+        viewer_id: Option<i32>,
+    ) -> Result<(Vec<DocumentSearchHit>, i64), AppError> {
+        self.document_service
+            .full_text_search_for_viewer(filters, viewer_id)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// 閲覧者の機密レベルで見られる文書に限って検索結果の内訳を集計する
+    pub async fn search_aggregations_for_viewer(
+        &self,
+        filters: DocumentSearchFilters,
+        viewer_id: Option<i32>,
+    ) -> Result<DocumentSearchAggregations, AppError> {
+        self.document_service
+            .search_aggregations_for_viewer(filters, viewer_id)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn create_number_rule(
+        &self,
+        request: CreateDocumentNumberGenerationRuleRequest,
+    ) -> Result<DocumentNumberGenerationRule, AppError> {
+        self.document_service
+            .create_number_rule(request)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn get_number_rule(&self, id: i32) -> Result<DocumentNumberGenerationRule, AppError> {
+        match self.document_service.get_number_rule(id).await? {
+            Some(rule) => Ok(rule),
+            None => Err(AppError::NotFound(format!(
+                "Document number rule with id {id} not found"
+            ))),
+        }
+    }
+
+    pub async fn search_number_rules(
+        &self,
+        department_code: Option<String>,
+        active_on_date: Option<NaiveDate>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<DocumentNumberGenerationRule>, i64), AppError> {
+        self.document_service
+            .search_number_rules(department_code, active_on_date, limit, offset)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn update_number_rule(
+        &self,
+        id: i32,
+        request: UpdateDocumentNumberGenerationRuleRequest,
+    ) -> Result<DocumentNumberGenerationRule, AppError> {
+        self.document_service
+            .update_number_rule(id, request)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn retire_number_rule(
+        &self,
+        id: i32,
+        effective_until: NaiveDate,
+    ) -> Result<DocumentNumberGenerationRule, AppError> {
+        self.document_service
+            .retire_number_rule(id, effective_until)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn simulate_number_rules(
+        &self,
+        request: RuleSimulationRequest,
+    ) -> Result<RuleSimulationResult, AppError> {
+        self.document_service
+            .simulate_number_rules(request)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn decompose_document_number(
+        &self,
+        document_number: &str,
+        department_code: Option<&str>,
+    ) -> Result<DocumentNumberDecomposition, AppError> {
+        self.document_service
+            .decompose_document_number(document_number, department_code)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn find_number_rule_conflicts(&self) -> Result<RuleConflictReport, AppError> {
+        self.document_service
+            .find_number_rule_conflicts()
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn reserve_document_numbers(
+        &self,
+        request: CreateDocumentNumberReservationRequest,
+    ) -> Result<DocumentNumberReservation, AppError> {
+        self.document_service
+            .reserve_document_numbers(request)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn get_number_reservation(
+        &self,
+        id: i32,
+    ) -> Result<DocumentNumberReservation, AppError> {
+        match self.document_service.get_number_reservation(id).await? {
+            Some(reservation) => Ok(reservation),
+            None => Err(AppError::NotFound(format!(
+                "Number reservation with id {id} not found"
+            ))),
+        }
+    }
+
+    pub async fn search_number_reservations(
+        &self,
+        rule_id: Option<i32>,
+        department_code: Option<&str>,
+        include_expired: bool,
+    ) -> Result<Vec<DocumentNumberReservation>, AppError> {
+        self.document_service
+            .search_number_reservations(rule_id, department_code, include_expired)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn claim_reserved_number(
+        &self,
+        reservation_id: i32,
+        request: ClaimReservedNumberRequest,
+    ) -> Result<CreatedDocumentWithNumber, AppError> {
+        self.document_service
+            .claim_reserved_number(reservation_id, request)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn expire_number_reservations(
+        &self,
+        as_of: NaiveDate,
+    ) -> Result<ReservationExpiryResult, AppError> {
+        self.document_service
+            .expire_number_reservations(as_of)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn add_number_alias(
+        &self,
+        document_id: i32,
+        request: CreateDocumentNumberAliasRequest,
+    ) -> Result<DocumentNumberAlias, AppError> {
+        self.document_service
+            .add_number_alias(document_id, request)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn list_number_aliases(
+        &self,
+        document_id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<DocumentNumberAlias>, AppError> {
+        self.document_service
+            .list_number_aliases(document_id, viewer_id)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn void_document_number(
+        &self,
+        request: VoidDocumentNumberRequest,
+    ) -> Result<VoidedDocumentNumber, AppError> {
+        self.document_service
+            .void_document_number(request)
+            .await
+            .map_err(AppError::from)
+    }
+
+    pub async fn find_number_gaps(
+        &self,
+        filters: NumberGapFilters,
+    ) -> Result<DocumentNumberGapReport, AppError> {
+        self.document_service
+            .find_number_gaps(filters)
+            .await
+            .map_err(AppError::from)
+    }
 }
 
 /// ヘルスチェック用ハンドラー
@@ -1,5 +1,8 @@
+use crate::error::BusinessError;
+use crate::handlers::etag::{etag_headers, if_match_version};
 use crate::models::{
-    BusinessSearchFilters, CreateBusinessMemberRequest, CreateBusinessRequest, PaginationInput,
+    BusinessNumberAllocation, BusinessNumberUsage, BusinessSearchFilters,
+    CreateBusinessMemberRequest, CreateBusinessRequest, PaginationInput,
     UpdateBusinessMemberRequest, UpdateBusinessRequest,
 };
 use crate::services::{
@@ -8,8 +11,8 @@ use crate::services::{
 use axum::{
     Router,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
     routing::{get, post, put},
 };
 use serde::Serialize;
@@ -69,15 +72,21 @@ async fn create_business(
     }
 }
 
-/// 業務を取得する
+/// 業務を取得する（更新時のIf-Matchに使うETagを返す）
 async fn get_business(
     Path(id): Path<i32>,
     State(service): State<Arc<BusinessService>>,
-) -> Result<Json<Option<crate::models::Business>>, (StatusCode, String)> {
+) -> Result<(HeaderMap, Json<Option<crate::models::Business>>), (StatusCode, String)> {
     let user_permissions = get_user_permissions();
 
     match service.get_business(id, &user_permissions).await {
-        Ok(business) => Ok(Json(business)),
+        Ok(business) => {
+            let headers = business
+                .as_ref()
+                .map(|b| etag_headers(b.version))
+                .unwrap_or_default();
+            Ok((headers, Json(business)))
+        }
         Err(err) => {
             let error_msg = err.to_string();
             Err((
@@ -137,24 +146,50 @@ async fn search_businesses(
 }
 
 /// 業務を更新する
+///
+/// 取得時のETagをIf-Matchで指定する。他の利用者が先に更新していた場合は412と最新の業務を返す
 async fn update_business(
     Path(id): Path<i32>,
     State(service): State<Arc<BusinessService>>,
+    headers: HeaderMap,
     Json(request): Json<UpdateBusinessRequest>,
-) -> Result<Json<BusinessResponse>, (StatusCode, String)> {
+) -> Result<(HeaderMap, Json<BusinessResponse>), Response> {
     let user_permissions = get_user_permissions();
+    let expected_version = if_match_version(&headers).map_err(|err| {
+        let error_msg = err.to_string();
+        (StatusCode::from(err), error_msg).into_response()
+    })?;
 
     match service
-        .update_business(id, request, &user_permissions)
+        .update_business(id, request, expected_version, &user_permissions)
         .await
     {
-        Ok(business) => Ok(Json(BusinessResponse::from(business))),
+        Ok(business) => Ok((
+            etag_headers(business.version),
+            Json(BusinessResponse::from(business)),
+        )),
+        Err(err @ BusinessError::VersionMismatch { .. }) => {
+            let current = service
+                .get_business(id, &user_permissions)
+                .await
+                .ok()
+                .flatten();
+            Err((
+                StatusCode::PRECONDITION_FAILED,
+                Json(serde_json::json!({
+                    "error": err.to_string(),
+                    "current": current
+                })),
+            )
+                .into_response())
+        }
         Err(err) => {
             let error_msg = err.to_string();
             Err((
                 StatusCode::from(crate::error::AppError::Business(err)),
                 error_msg,
-            ))
+            )
+                .into_response())
         }
     }
 }
@@ -317,13 +352,28 @@ async fn get_circulation_candidates(
 }
 
 /// 業務番号を生成する
-async fn generate_business_number(
+pub async fn generate_business_number(
     State(service): State<Arc<BusinessService>>,
-) -> Result<Json<BusinessNumberResponse>, (StatusCode, String)> {
+) -> Result<Json<BusinessNumberAllocation>, (StatusCode, String)> {
     match service.generate_business_number().await {
-        Ok(number) => Ok(Json(BusinessNumberResponse {
-            business_number: number,
-        })),
+        Ok(allocation) => Ok(Json(allocation)),
+        Err(err) => {
+            let error_msg = err.to_string();
+            Err((
+                StatusCode::from(crate::error::AppError::Business(err)),
+                error_msg,
+            ))
+        }
+    }
+}
+
+/// 業務番号の使用状況を取得する
+pub async fn get_business_number_usage(
+    Path(business_number): Path<String>,
+    State(service): State<Arc<BusinessService>>,
+) -> Result<Json<BusinessNumberUsage>, (StatusCode, String)> {
+    match service.get_business_number_usage(&business_number).await {
+        Ok(usage) => Ok(Json(usage)),
         Err(err) => {
             let error_msg = err.to_string();
             Err((
@@ -343,11 +393,6 @@ pub struct BusinessSearchResponse {
     pub has_next_page: bool,
 }
 
-#[derive(Debug, Serialize)]
-pub struct BusinessNumberResponse {
-    pub business_number: String,
-}
-
 // 型定義はservicesモジュールから再利用
 
 // ヘルパー関数
@@ -0,0 +1,62 @@
+use axum::{Json, extract};
+use serde_json;
+use std::collections::HashMap;
+
+use super::http::error_response;
+use crate::error::AppError;
+use crate::{AppState, models};
+
+/// 社員の機密レベル取得エンドポイント
+pub async fn get_employee_clearance_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(employee_id): extract::Path<i32>,
+) -> Result<Json<models::EmployeeClearance>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    match state
+        .clearance_service
+        .get_employee_clearance(employee_id)
+        .await
+    {
+        Ok(clearance) => Ok(Json(clearance)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// 社員個別の機密レベル設定エンドポイント（管理者のみ）
+pub async fn update_employee_clearance_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(employee_id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+    Json(request): Json<models::UpdateEmployeeClearanceRequest>,
+) -> Result<Json<models::EmployeeClearance>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    // TODO: Get from auth context
+    let actor_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .clearance_service
+        .update_employee_clearance(actor_id, employee_id, request)
+        .await
+    {
+        Ok(clearance) => Ok(Json(clearance)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// ロールの機密レベル設定エンドポイント（管理者のみ）
+pub async fn update_role_clearance_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(role): extract::Path<models::EmployeeRole>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+    Json(request): Json<models::UpdateRoleClearanceRequest>,
+) -> Result<Json<models::RoleClearance>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    // TODO: Get from auth context
+    let actor_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .clearance_service
+        .update_role_clearance(actor_id, role, request)
+        .await
+    {
+        Ok(clearance) => Ok(Json(clearance)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
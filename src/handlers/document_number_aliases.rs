@@ -0,0 +1,47 @@
+use axum::{Json, extract};
+use serde_json;
+use std::collections::HashMap;
+
+use super::http::error_response;
+use crate::{AppState, models};
+
+/// 文書の旧番号一覧エンドポイント（閲覧者の機密レベルで見られる文書のみ）
+pub async fn list_number_aliases_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(document_id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .document_handlers
+        .list_number_aliases(document_id, viewer_id)
+        .await
+    {
+        Ok(aliases) => Ok(Json(serde_json::json!({
+            "document_id": document_id,
+            "aliases": aliases
+        }))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書の旧番号登録エンドポイント
+pub async fn create_number_alias_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(document_id): extract::Path<i32>,
+    Json(request): Json<models::CreateDocumentNumberAliasRequest>,
+) -> Result<
+    (axum::http::StatusCode, Json<models::DocumentNumberAlias>),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state
+        .document_handlers
+        .add_number_alias(document_id, request)
+        .await
+    {
+        Ok(alias) => Ok((axum::http::StatusCode::CREATED, Json(alias))),
+        Err(err) => Err(error_response(err)),
+    }
+}
@@ -0,0 +1,108 @@
+use axum::{Json, extract};
+use serde_json;
+use std::collections::HashMap;
+
+use super::http::error_response;
+use crate::{AppState, models};
+
+/// 文書番号の予約エンドポイント
+pub async fn create_number_reservation_handler(
+    extract::State(state): extract::State<AppState>,
+    Json(request): Json<models::CreateDocumentNumberReservationRequest>,
+) -> Result<
+    (
+        axum::http::StatusCode,
+        Json<models::DocumentNumberReservation>,
+    ),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state
+        .document_handlers
+        .reserve_document_numbers(request)
+        .await
+    {
+        Ok(reservation) => Ok((axum::http::StatusCode::CREATED, Json(reservation))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号の予約取得エンドポイント
+pub async fn get_number_reservation_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+) -> Result<
+    Json<models::DocumentNumberReservation>,
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state.document_handlers.get_number_reservation(id).await {
+        Ok(reservation) => Ok(Json(reservation)),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号の予約検索エンドポイント
+pub async fn search_number_reservations_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let rule_id = params.get("rule_id").and_then(|s| s.parse().ok());
+    let include_expired = params.get("include_expired").is_some_and(|s| s == "true");
+
+    match state
+        .document_handlers
+        .search_number_reservations(
+            rule_id,
+            params.get("department_code").map(String::as_str),
+            include_expired,
+        )
+        .await
+    {
+        Ok(reservations) => Ok(Json(serde_json::json!({
+            "total": reservations.len(),
+            "reservations": reservations,
+        }))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 予約番号での文書登録エンドポイント
+pub async fn claim_reserved_number_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    Json(request): Json<models::ClaimReservedNumberRequest>,
+) -> Result<
+    (
+        axum::http::StatusCode,
+        Json<models::CreatedDocumentWithNumber>,
+    ),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state
+        .document_handlers
+        .claim_reserved_number(id, request)
+        .await
+    {
+        Ok(created) => Ok((axum::http::StatusCode::CREATED, Json(created))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 期限切れ予約の処理エンドポイント（バッチからの定期実行用）
+pub async fn expire_number_reservations_handler(
+    extract::State(state): extract::State<AppState>,
+    Json(request): Json<models::ExpireReservationsRequest>,
+) -> Result<Json<models::ReservationExpiryResult>, (axum::http::StatusCode, Json<serde_json::Value>)>
+{
+    let as_of = request
+        .as_of
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+
+    match state
+        .document_handlers
+        .expire_number_reservations(as_of)
+        .await
+    {
+        Ok(result) => Ok(Json(result)),
+        Err(err) => Err(error_response(err)),
+    }
+}
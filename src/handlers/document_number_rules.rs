@@ -0,0 +1,187 @@
+use axum::{Json, extract};
+use serde_json;
+use std::collections::HashMap;
+
+use super::http::error_response;
+use crate::{AppState, models};
+
+/// 文書番号生成ルール作成エンドポイント
+pub async fn create_number_rule_handler(
+    extract::State(state): extract::State<AppState>,
+    Json(request): Json<models::CreateDocumentNumberGenerationRuleRequest>,
+) -> Result<
+    (
+        axum::http::StatusCode,
+        Json<models::DocumentNumberGenerationRule>,
+    ),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state.document_handlers.create_number_rule(request).await {
+        Ok(rule) => Ok((axum::http::StatusCode::CREATED, Json(rule))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号生成ルール取得エンドポイント
+pub async fn get_number_rule_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+) -> Result<
+    Json<models::DocumentNumberGenerationRule>,
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state.document_handlers.get_number_rule(id).await {
+        Ok(rule) => Ok(Json(rule)),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号生成ルール検索エンドポイント
+pub async fn search_number_rules_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let department_code = params.get("department_code").cloned();
+    let active_on_date = params
+        .get("active_on")
+        .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(50);
+    let offset = params
+        .get("offset")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0);
+
+    match state
+        .document_handlers
+        .search_number_rules(department_code, active_on_date, limit, offset)
+        .await
+    {
+        Ok((rules, total)) => Ok(Json(serde_json::json!({
+            "rules": rules,
+            "total": total
+        }))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号生成ルール更新エンドポイント
+pub async fn update_number_rule_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    Json(request): Json<models::UpdateDocumentNumberGenerationRuleRequest>,
+) -> Result<
+    Json<models::DocumentNumberGenerationRule>,
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state
+        .document_handlers
+        .update_number_rule(id, request)
+        .await
+    {
+        Ok(rule) => Ok(Json(rule)),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号生成ルール廃止エンドポイント
+pub async fn retire_number_rule_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    Json(request): Json<models::RetireDocumentNumberGenerationRuleRequest>,
+) -> Result<
+    Json<models::DocumentNumberGenerationRule>,
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state
+        .document_handlers
+        .retire_number_rule(id, request.effective_until)
+        .await
+    {
+        Ok(rule) => Ok(Json(rule)),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号生成ルール適用のシミュレーション（ドライラン）エンドポイント
+pub async fn simulate_number_rules_handler(
+    extract::State(state): extract::State<AppState>,
+    Json(request): Json<models::RuleSimulationRequest>,
+) -> Result<Json<models::RuleSimulationResult>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    match state.document_handlers.simulate_number_rules(request).await {
+        Ok(result) => Ok(Json(result)),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号生成ルールの重複・衝突検査エンドポイント
+pub async fn number_rule_conflicts_handler(
+    extract::State(state): extract::State<AppState>,
+) -> Result<Json<models::RuleConflictReport>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    match state.document_handlers.find_number_rule_conflicts().await {
+        Ok(report) => Ok(Json(report)),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号の逆引き（ルールの構成要素への分解）エンドポイント
+pub async fn decompose_document_number_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<
+    Json<models::DocumentNumberDecomposition>,
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    let Some(document_number) = params.get("document_number") else {
+        return Err(error_response(crate::error::AppError::BadRequest(
+            "document_number is required".to_string(),
+        )));
+    };
+
+    match state
+        .document_handlers
+        .decompose_document_number(
+            document_number,
+            params.get("department_code").map(String::as_str),
+        )
+        .await
+    {
+        Ok(decomposition) => Ok(Json(decomposition)),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書番号の欠番登録（文書の取り下げを含む）エンドポイント
+pub async fn void_document_number_handler(
+    extract::State(state): extract::State<AppState>,
+    Json(request): Json<models::VoidDocumentNumberRequest>,
+) -> Result<
+    (axum::http::StatusCode, Json<models::VoidedDocumentNumber>),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state.document_handlers.void_document_number(request).await {
+        Ok(voided) => Ok((axum::http::StatusCode::CREATED, Json(voided))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 連番の欠番レポートエンドポイント
+pub async fn number_gaps_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<Json<models::DocumentNumberGapReport>, (axum::http::StatusCode, Json<serde_json::Value>)>
+{
+    let filters = models::NumberGapFilters {
+        rule_id: params.get("rule_id").and_then(|s| s.parse().ok()),
+        year: params.get("year").and_then(|s| s.parse().ok()),
+        month: params.get("month").and_then(|s| s.parse().ok()),
+        department_code: params.get("department_code").cloned(),
+    };
+
+    match state.document_handlers.find_number_gaps(filters).await {
+        Ok(report) => Ok(Json(report)),
+        Err(err) => Err(error_response(err)),
+    }
+}
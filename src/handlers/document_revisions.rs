@@ -0,0 +1,67 @@
+use axum::{Json, extract};
+use serde_json;
+use std::collections::HashMap;
+
+use super::http::error_response;
+use crate::error::AppError;
+use crate::{AppState, models};
+
+/// 文書の版履歴取得エンドポイント（閲覧者の機密レベルで見られる文書のみ）
+pub async fn list_revisions_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(document_id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .revision_service
+        .list_revisions(document_id, viewer_id)
+        .await
+    {
+        Ok(revisions) => Ok(Json(serde_json::json!({
+            "document_id": document_id,
+            "revisions": revisions
+        }))),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// 改訂版・ドラフト版作成エンドポイント
+pub async fn create_revision_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(document_id): extract::Path<i32>,
+    Json(request): Json<models::CreateDocumentRevisionRequest>,
+) -> Result<
+    (axum::http::StatusCode, Json<models::DocumentRevision>),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state
+        .revision_service
+        .create_revision(document_id, request)
+        .await
+    {
+        Ok(revision) => Ok((axum::http::StatusCode::CREATED, Json(revision))),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// ドラフト版の発行エンドポイント
+pub async fn promote_draft_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path((document_id, revision_id)): extract::Path<(i32, i32)>,
+    Json(request): Json<models::PromoteDraftRequest>,
+) -> Result<
+    (axum::http::StatusCode, Json<models::DocumentRevision>),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state
+        .revision_service
+        .promote_draft(document_id, revision_id, request)
+        .await
+    {
+        Ok(revision) => Ok((axum::http::StatusCode::CREATED, Json(revision))),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
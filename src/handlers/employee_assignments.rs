@@ -0,0 +1,108 @@
+use axum::{Json, extract};
+use serde_json;
+use std::collections::HashMap;
+
+use super::http::error_response;
+use crate::error::AppError;
+use crate::{AppState, models};
+
+/// 社員取得エンドポイント（部署・課・役職は現在の主務）
+pub async fn get_employee_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(employee_id): extract::Path<i32>,
+) -> Result<Json<models::Employee>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    match state.employee_service.get_employee(employee_id).await {
+        Ok(employee) => Ok(Json(employee)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// 社員の所属履歴エンドポイント（`as_of`でその日の所属に絞り込む）
+pub async fn list_employee_assignments_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(employee_id): extract::Path<i32>,
+    extract::Query(query): extract::Query<models::EmployeeAssignmentQuery>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let as_of = query.as_of;
+    match state
+        .employee_service
+        .list_assignments(employee_id, query)
+        .await
+    {
+        Ok(assignments) => Ok(Json(serde_json::json!({
+            "employee_id": employee_id,
+            "as_of": as_of,
+            "assignments": assignments
+        }))),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// 社員の所属登録エンドポイント（管理者のみ）
+pub async fn create_employee_assignment_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(employee_id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+    Json(request): Json<models::EmployeeAssignmentRequest>,
+) -> Result<
+    (axum::http::StatusCode, Json<models::EmployeeAssignment>),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    // TODO: Get from auth context
+    let actor_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .employee_service
+        .create_assignment(actor_id, employee_id, request)
+        .await
+    {
+        Ok(assignment) => Ok((axum::http::StatusCode::CREATED, Json(assignment))),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// 所属取得エンドポイント
+pub async fn get_employee_assignment_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+) -> Result<Json<models::EmployeeAssignment>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    match state.employee_service.get_assignment(id).await {
+        Ok(assignment) => Ok(Json(assignment)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// 所属変更エンドポイント（所属全体を置き換える。管理者のみ）
+pub async fn update_employee_assignment_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+    Json(request): Json<models::EmployeeAssignmentRequest>,
+) -> Result<Json<models::EmployeeAssignment>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    // TODO: Get from auth context
+    let actor_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .employee_service
+        .update_assignment(actor_id, id, request)
+        .await
+    {
+        Ok(assignment) => Ok(Json(assignment)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// 所属削除エンドポイント（誤登録の取り消し用。管理者のみ）
+pub async fn delete_employee_assignment_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<axum::http::StatusCode, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    // TODO: Get from auth context
+    let actor_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state.employee_service.delete_assignment(actor_id, id).await {
+        Ok(()) => Ok(axum::http::StatusCode::NO_CONTENT),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
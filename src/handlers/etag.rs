@@ -0,0 +1,33 @@
+// ETag / If-Match による楽観的排他制御のヘルパー
+//
+// ETagはリソースのバージョン番号を引用符で囲んだもの（例: "3"）
+
+use crate::error::AppError;
+use axum::http::{HeaderMap, HeaderValue, header};
+
+/// バージョンをETagヘッダーにする
+pub(crate) fn etag_headers(version: i32) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&format!("\"{version}\"")) {
+        headers.insert(header::ETAG, value);
+    }
+    headers
+}
+
+/// If-Matchヘッダーから更新元のバージョンを取り出す
+///
+/// ヘッダーがない場合は428、取得時のETagとして解釈できない場合は400とする
+pub(crate) fn if_match_version(headers: &HeaderMap) -> Result<i32, AppError> {
+    let value = headers.get(header::IF_MATCH).ok_or_else(|| {
+        AppError::PreconditionRequired(
+            "If-Match header with the ETag from GET is required".to_string(),
+        )
+    })?;
+
+    value
+        .to_str()
+        .ok()
+        .map(|v| v.trim().trim_start_matches("W/").trim_matches('"'))
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| AppError::BadRequest("If-Match must be an ETag returned by GET".to_string()))
+}
@@ -1,7 +1,8 @@
-use axum::{Json, extract};
+use axum::{Json, extract, http::HeaderMap};
 use serde_json;
 use std::collections::HashMap;
 
+use crate::handlers::etag::{etag_headers, if_match_version};
 use crate::{AppState, models};
 
 /// ヘルスチェックエンドポイント
@@ -23,36 +24,153 @@ pub async fn health_check_handler(
 }
 
 /// 文書作成エンドポイント
+///
+/// Idempotency-Keyヘッダーを付けると、保持期間内の同じキーでの再送には最初の作成結果を返す
+/// （再送に対する応答には`Idempotent-Replayed: true`を付ける）
 pub async fn create_document_handler(
     extract::State(state): extract::State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<models::CreateDocumentWithNumberRequest>,
 ) -> Result<
     (
         axum::http::StatusCode,
+        HeaderMap,
         Json<models::CreatedDocumentWithNumber>,
     ),
     (axum::http::StatusCode, Json<serde_json::Value>),
 > {
-    match state.document_handlers.create_document(request).await {
-        Ok(created) => Ok((axum::http::StatusCode::CREATED, Json(created))),
-        Err(err) => {
-            let error_message = err.to_string();
-            let status = axum::http::StatusCode::from(err);
-            let error_body = serde_json::json!({
-                "error": error_message
-            });
-            Err((status, Json(error_body)))
+    let result = match idempotency_key(&headers).map_err(error_response)? {
+        Some(key) => {
+            state
+                .document_handlers
+                .create_document_idempotent(request, &key)
+                .await
         }
+        None => state
+            .document_handlers
+            .create_document(request)
+            .await
+            .map(|created| (created, false)),
+    };
+
+    let (created, replayed) = result.map_err(error_response)?;
+    let mut response_headers = HeaderMap::new();
+    if replayed {
+        response_headers.insert(
+            IDEMPOTENT_REPLAYED_HEADER,
+            axum::http::HeaderValue::from_static("true"),
+        );
+    }
+    Ok((
+        axum::http::StatusCode::CREATED,
+        response_headers,
+        Json(created),
+    ))
+}
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Idempotency-Keyヘッダーを取り出す（ヘッダーがなければNone）
+fn idempotency_key(headers: &HeaderMap) -> Result<Option<String>, crate::error::AppError> {
+    headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|value| {
+            value.to_str().map(str::to_string).map_err(|_| {
+                crate::error::AppError::BadRequest(
+                    "Idempotency-Key must be visible ASCII characters".to_string(),
+                )
+            })
+        })
+        .transpose()
+}
+
+/// 文書一括登録エンドポイント
+///
+/// 全件登録できた場合は201、一部の行だけ登録できた場合は200、
+/// 何も登録しなかった場合は422を返す（いずれも行ごとの結果を返す）
+pub async fn create_documents_bulk_handler(
+    extract::State(state): extract::State<AppState>,
+    Json(request): Json<models::BulkCreateDocumentsRequest>,
+) -> Result<
+    (
+        axum::http::StatusCode,
+        Json<models::BulkCreateDocumentsResult>,
+    ),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    let result = state
+        .document_handlers
+        .create_documents_bulk(request)
+        .await
+        .map_err(error_response)?;
+
+    let status = if !result.committed {
+        axum::http::StatusCode::UNPROCESSABLE_ENTITY
+    } else if result.failed_count > 0 {
+        axum::http::StatusCode::OK
+    } else {
+        axum::http::StatusCode::CREATED
+    };
+    Ok((status, Json(result)))
+}
+
+/// 手動採番による文書作成エンドポイント（管理者限定）
+pub async fn create_document_with_manual_number_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+    Json(request): Json<models::CreateDocumentWithManualNumberRequest>,
+) -> Result<
+    (
+        axum::http::StatusCode,
+        Json<models::CreatedDocumentWithManualNumber>,
+    ),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    // TODO: Get from auth context
+    let actor_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .document_handlers
+        .create_document_with_manual_number(actor_id, request)
+        .await
+    {
+        Ok(created) => Ok((axum::http::StatusCode::CREATED, Json(created))),
+        Err(err) => Err(error_response(err)),
     }
 }
 
 /// 文書取得エンドポイント
+///
+/// パスが数値ならID、それ以外は文書番号または旧番号として扱い、旧番号の場合は現在の文書を返す。
+/// `user_id`の機密レベルで閲覧できない文書は404とする。
+/// 更新時のIf-Matchに使うETagを返す
 pub async fn get_document_handler(
     extract::State(state): extract::State<AppState>,
-    extract::Path(id): extract::Path<i32>,
-) -> Result<Json<models::Document>, (axum::http::StatusCode, Json<serde_json::Value>)> {
-    match state.document_handlers.get_document(id).await {
-        Ok(document) => Ok(Json(document)),
+    extract::Path(id): extract::Path<String>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<(HeaderMap, Json<models::Document>), (axum::http::StatusCode, Json<serde_json::Value>)>
+{
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    let result = match id.parse::<i32>() {
+        Ok(id) => {
+            state
+                .document_handlers
+                .get_document_for_viewer(id, viewer_id)
+                .await
+        }
+        Err(_) => {
+            state
+                .document_handlers
+                .get_document_by_number_for_viewer(&id, viewer_id)
+                .await
+        }
+    };
+
+    match result {
+        Ok(document) => Ok((etag_headers(document.version), Json(document))),
         Err(err) => {
             let error_message = err.to_string();
             let status = axum::http::StatusCode::from(err);
@@ -64,12 +182,141 @@ pub async fn get_document_handler(
     }
 }
 
+/// 文書更新エンドポイント（指定した項目だけを変更する）
+///
+/// 取得時のETagをIf-Matchで指定する。他の利用者が先に更新していた場合は412と最新の文書を返す
+pub async fn update_document_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    Json(request): Json<models::UpdateDocumentRequest>,
+) -> Result<(HeaderMap, Json<models::Document>), (axum::http::StatusCode, Json<serde_json::Value>)>
+{
+    let expected_version = if_match_version(&headers).map_err(error_response)?;
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .document_handlers
+        .update_document(id, request, expected_version, viewer_id)
+        .await
+    {
+        Ok(document) => Ok((etag_headers(document.version), Json(document))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書削除エンドポイント（ゴミ箱へ移動する論理削除）
+///
+/// 実行者は`changed_by`、理由は`reason`クエリパラメータで指定する
+pub async fn delete_document_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<(HeaderMap, Json<models::Document>), (axum::http::StatusCode, Json<serde_json::Value>)>
+{
+    let Some(changed_by) = params.get("changed_by").and_then(|s| s.parse().ok()) else {
+        return Err(error_response(crate::error::AppError::BadRequest(
+            "changed_by is required".to_string(),
+        )));
+    };
+    let request = models::ChangeDocumentStateRequest {
+        changed_by,
+        reason: params.get("reason").cloned(),
+    };
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .document_handlers
+        .delete_document(id, request, viewer_id)
+        .await
+    {
+        Ok(document) => Ok((etag_headers(document.version), Json(document))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// ゴミ箱の文書の復元エンドポイント
+pub async fn restore_document_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+    Json(request): Json<models::ChangeDocumentStateRequest>,
+) -> Result<(HeaderMap, Json<models::Document>), (axum::http::StatusCode, Json<serde_json::Value>)>
+{
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .document_handlers
+        .restore_document(id, request, viewer_id)
+        .await
+    {
+        Ok(document) => Ok((etag_headers(document.version), Json(document))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// ゴミ箱の一覧エンドポイント
+pub async fn list_deleted_documents_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(10);
+    let offset = params
+        .get("offset")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0);
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .document_handlers
+        .list_deleted_documents(limit, offset, viewer_id)
+        .await
+    {
+        Ok((documents, total)) => Ok(Json(serde_json::json!({
+            "documents": documents,
+            "total": total
+        }))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
+/// 文書の変更履歴エンドポイント
+pub async fn list_document_changes_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    match state
+        .document_handlers
+        .list_document_changes(id, viewer_id)
+        .await
+    {
+        Ok(changes) => Ok(Json(serde_json::json!({
+            "document_id": id,
+            "changes": changes
+        }))),
+        Err(err) => Err(error_response(err)),
+    }
+}
+
 /// 文書検索エンドポイント
 pub async fn search_documents_handler(
     extract::State(state): extract::State<AppState>,
     extract::Query(params): extract::Query<HashMap<String, String>>,
 ) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
     let filters = models::DocumentSearchFilters {
+        query: params.get("q").filter(|q| !q.trim().is_empty()).cloned(),
         title: params.get("title").cloned(),
         document_type_id: params.get("document_type_id").and_then(|s| s.parse().ok()),
         created_by: params.get("created_by").and_then(|s| s.parse().ok()),
@@ -79,6 +326,16 @@ pub async fn search_documents_handler(
         created_date_to: params
             .get("created_date_to")
             .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()),
+        department_code: params.get("department_code").cloned(),
+        include_historical_affiliations: params
+            .get("include_historical_affiliations")
+            .is_some_and(|s| s == "true"),
+        year: params.get("year").and_then(|s| s.parse().ok()),
+        importance_class: parse_classification_param(&params, "importance_class")?,
+        internal_external: parse_classification_param(&params, "internal_external")?,
+        business_number: params.get("business_number").cloned(),
+        max_clearance: None,
+        affiliation_employee_id: None,
         limit: params
             .get("limit")
             .and_then(|s| s.parse().ok())
@@ -89,10 +346,52 @@ pub async fn search_documents_handler(
             .unwrap_or(0),
     };
 
-    match state.document_handlers.search_documents(filters).await {
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+
+    // 検索条件に一致する文書の内訳（絞り込み候補）
+    let aggregations = state
+        .document_handlers
+        .search_aggregations_for_viewer(filters.clone(), viewer_id)
+        .await
+        .map_err(error_response)?;
+
+    // 全文検索キーワードがある場合は関連度順に並べ、ハイライト付きの抜粋を添える
+    if filters.query.is_some() {
+        let (hits, total) = state
+            .document_handlers
+            .full_text_search_for_viewer(filters, viewer_id)
+            .await
+            .map_err(error_response)?;
+        let matches: Vec<_> = hits
+            .iter()
+            .map(|hit| {
+                serde_json::json!({
+                    "document_id": hit.document.id,
+                    "rank": hit.rank,
+                    "snippet": hit.snippet
+                })
+            })
+            .collect();
+        let documents: Vec<_> = hits.into_iter().map(|hit| hit.document).collect();
+
+        return Ok(Json(serde_json::json!({
+            "documents": documents,
+            "total": total,
+            "hits": matches,
+            "aggregations": aggregations
+        })));
+    }
+
+    match state
+        .document_handlers
+        .search_documents_for_viewer(filters, viewer_id)
+        .await
+    {
         Ok((documents, total)) => Ok(Json(serde_json::json!({
             "documents": documents,
-            "total": total
+            "total": total,
+            "aggregations": aggregations
         }))),
         Err(err) => {
             let error_message = err.to_string();
@@ -104,3 +403,35 @@ pub async fn search_documents_handler(
         }
     }
 }
+
+/// 分類のクエリパラメータを解釈する（未指定はNone、解釈できない値は400）
+fn parse_classification_param<T>(
+    params: &HashMap<String, String>,
+    name: &str,
+) -> Result<Option<T>, (axum::http::StatusCode, Json<serde_json::Value>)>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    params
+        .get(name)
+        .map(|value| value.parse::<T>())
+        .transpose()
+        .map_err(|e| error_response(crate::error::AppError::BadRequest(e.to_string())))
+}
+
+/// AppErrorをJSONエラーレスポンスに変換する
+pub(crate) fn error_response(
+    err: crate::error::AppError,
+) -> (axum::http::StatusCode, Json<serde_json::Value>) {
+    let error_message = err.to_string();
+    let mut error_body = serde_json::json!({
+        "error": error_message
+    });
+    // 更新の競合は最新の状態を返し、クライアントが差分を確認できるようにする
+    if let crate::error::AppError::PreconditionFailed { ref current, .. } = err {
+        error_body["current"] = current.clone();
+    }
+    let status = axum::http::StatusCode::from(err);
+    (status, Json(error_body))
+}
@@ -5,11 +5,19 @@ pub mod business;
 pub mod business_management;
 pub mod business_search;
 pub mod circulation;
+pub mod clearance;
 pub mod deduplication;
+pub mod document_number_aliases;
+pub mod document_number_reservations;
+pub mod document_number_rules;
+pub mod document_revisions;
+pub mod employee_assignments;
+pub(crate) mod etag;
 pub mod graphql;
 pub mod http;
 pub mod migration;
 pub mod monitoring;
+pub mod path_generation_rules;
 pub mod validation;
 
 // Re-export business handlers for compatibility
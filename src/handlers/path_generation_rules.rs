@@ -0,0 +1,155 @@
+use axum::{Json, extract};
+use serde_json;
+use std::collections::HashMap;
+
+use super::http::error_response;
+use crate::error::AppError;
+use crate::{AppState, models};
+
+/// パス生成ルール作成エンドポイント
+pub async fn create_path_rule_handler(
+    extract::State(state): extract::State<AppState>,
+    Json(request): Json<models::CreatePathGenerationRuleRequest>,
+) -> Result<
+    (axum::http::StatusCode, Json<models::PathGenerationRule>),
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state.path_service.create_rule(request).await {
+        Ok(rule) => Ok((axum::http::StatusCode::CREATED, Json(rule))),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// パス生成ルール取得エンドポイント
+pub async fn get_path_rule_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+) -> Result<Json<models::PathGenerationRule>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    match state.path_service.get_rule(id).await {
+        Ok(rule) => Ok(Json(rule)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// パス生成ルール検索エンドポイント
+pub async fn search_path_rules_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let department_code = params.get("department_code").cloned();
+    let active_on_date = params
+        .get("active_on")
+        .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(50);
+    let offset = params
+        .get("offset")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0);
+
+    match state
+        .path_service
+        .search_rules(department_code, active_on_date, limit, offset)
+        .await
+    {
+        Ok((rules, total)) => Ok(Json(serde_json::json!({
+            "rules": rules,
+            "total": total
+        }))),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// パス生成ルール更新エンドポイント
+pub async fn update_path_rule_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    Json(request): Json<models::UpdatePathGenerationRuleRequest>,
+) -> Result<Json<models::PathGenerationRule>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    match state.path_service.update_rule(id, request).await {
+        Ok(rule) => Ok(Json(rule)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// パス生成ルール廃止エンドポイント
+pub async fn retire_path_rule_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+    Json(request): Json<models::RetirePathGenerationRuleRequest>,
+) -> Result<Json<models::PathGenerationRule>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    match state.path_service.retire_rule(id, request).await {
+        Ok(rule) => Ok(Json(rule)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// パス生成ルール変更履歴エンドポイント
+pub async fn list_path_rule_versions_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(id): extract::Path<i32>,
+) -> Result<
+    Json<Vec<models::PathGenerationRuleVersion>>,
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state.path_service.list_versions(id).await {
+        Ok(versions) => Ok(Json(versions)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// 文書番号からネットワークパスを解決するエンドポイント
+///
+/// `as_of`を指定すると、その時点で有効だったルールでパスを再現する
+pub async fn resolve_network_path_handler(
+    extract::State(state): extract::State<AppState>,
+    extract::Path(document_number): extract::Path<String>,
+    extract::Query(params): extract::Query<HashMap<String, String>>,
+) -> Result<Json<models::ResolvedNetworkPath>, (axum::http::StatusCode, Json<serde_json::Value>)> {
+    let as_of = match params.get("as_of") {
+        Some(value) => match chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                return Err(error_response(AppError::ValidationError(format!(
+                    "Invalid as_of date: {value}"
+                ))));
+            }
+        },
+        None => None,
+    };
+
+    // TODO: Get from auth context
+    let viewer_id = params.get("user_id").and_then(|s| s.parse().ok());
+    if let Err(err) = state
+        .document_handlers
+        .ensure_network_path_visible(&document_number, viewer_id)
+        .await
+    {
+        return Err(error_response(err));
+    }
+
+    match state
+        .path_service
+        .resolve_for_document_number(&document_number, as_of)
+        .await
+    {
+        Ok(resolved) => Ok(Json(resolved)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
+
+/// 保存済みネットワークパスの一括再解決エンドポイント
+pub async fn verify_network_paths_handler(
+    extract::State(state): extract::State<AppState>,
+    Json(request): Json<models::NetworkPathVerificationRequest>,
+) -> Result<
+    Json<models::NetworkPathVerificationReport>,
+    (axum::http::StatusCode, Json<serde_json::Value>),
+> {
+    match state.path_service.verify_stored_paths(request).await {
+        Ok(report) => Ok(Json(report)),
+        Err(err) => Err(error_response(AppError::from(err))),
+    }
+}
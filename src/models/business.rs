@@ -13,6 +13,8 @@ pub struct Business {
     pub end_date: Option<NaiveDate>,
     pub status: BusinessStatus,
     pub created_by: i32,
+    /// 楽観的排他制御のバージョン（更新のたびに1ずつ増える）
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -56,6 +58,39 @@ pub struct BusinessExternalContact {
     pub created_at: DateTime<Utc>,
 }
 
+/// 業務番号の採番範囲（F035a: 上限に達したら下限へ戻って再利用する）
+pub const BUSINESS_NUMBER_MIN: i32 = 10000;
+pub const BUSINESS_NUMBER_MAX: i32 = 99990;
+
+/// 空き番号がこの件数を下回ったら採番時に警告する
+pub const BUSINESS_NUMBER_LOW_CAPACITY_THRESHOLD: i64 = 1000;
+
+/// 次に割り当てる業務番号と採番範囲の空き状況
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessNumberAllocation {
+    pub business_number: String,
+    /// この番号が過去に使用された回数（今回の割り当ては含まない）
+    pub previous_use_count: i32,
+    /// 上限を超えて下限側へ循環したか
+    pub wrapped: bool,
+    /// この番号を割り当てた後に残る空き番号数
+    pub remaining_capacity: i64,
+    pub capacity_warning: Option<String>,
+}
+
+/// 業務番号の使用状況（F035）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessNumberUsage {
+    pub business_number: String,
+    pub use_count: i32,
+    /// 現在この番号を保持している進行中の業務
+    pub active_business_id: Option<i32>,
+    /// この番号を使用した業務（新しい順）
+    pub business_ids: Vec<i32>,
+    pub first_used_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum BusinessStatus {
     Active,
@@ -292,6 +327,7 @@ impl Business {
             end_date: row.try_get("end_date")?,
             status: BusinessStatus::from(row.try_get::<String, _>("status")?),
             created_by: row.try_get("created_by")?,
+            version: row.try_get("version")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
         })
@@ -0,0 +1,145 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// 分類値の解析エラー
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown {kind} value: {value}")]
+pub struct ClassificationParseError {
+    pub kind: &'static str,
+    pub value: String,
+}
+
+/// 社内外区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum InternalExternal {
+    #[serde(alias = "社内")]
+    Internal,
+    #[serde(alias = "社外")]
+    External,
+}
+
+impl InternalExternal {
+    /// 画面表示用の名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            InternalExternal::Internal => "社内",
+            InternalExternal::External => "社外",
+        }
+    }
+}
+
+/// 重要度（情報クラス）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum ImportanceClass {
+    #[serde(alias = "情報クラスⅠ")]
+    Class1,
+    #[serde(alias = "情報クラスⅡ")]
+    Class2,
+}
+
+impl ImportanceClass {
+    /// 画面表示用の名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportanceClass::Class1 => "情報クラスⅠ",
+            ImportanceClass::Class2 => "情報クラスⅡ",
+        }
+    }
+}
+
+/// 個人情報の有無
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum PersonalInfo {
+    #[serde(alias = "無")]
+    None,
+    #[serde(alias = "有")]
+    Present,
+}
+
+impl PersonalInfo {
+    /// 画面表示用の名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            PersonalInfo::None => "無",
+            PersonalInfo::Present => "有",
+        }
+    }
+}
+
+// 保存値（internal等）と表示名（社内等）のどちらからも変換できるようにする
+// 取込データの表記ゆれはマイグレーション（022）と同じ範囲で受け付ける
+
+impl FromStr for InternalExternal {
+    type Err = ClassificationParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "internal" | "社内" => Ok(InternalExternal::Internal),
+            "external" | "社外" => Ok(InternalExternal::External),
+            _ => Err(ClassificationParseError {
+                kind: "internal_external",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl FromStr for ImportanceClass {
+    type Err = ClassificationParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "class1" | "情報クラスⅰ" | "情報クラスi" | "情報クラス1" | "クラスⅰ" => {
+                Ok(ImportanceClass::Class1)
+            }
+            "class2" | "情報クラスⅱ" | "情報クラスii" | "情報クラス2" | "クラスⅱ" => {
+                Ok(ImportanceClass::Class2)
+            }
+            _ => Err(ClassificationParseError {
+                kind: "importance_class",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl FromStr for PersonalInfo {
+    type Err = ClassificationParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "none" | "無" | "なし" | "無し" => Ok(PersonalInfo::None),
+            "present" | "有" | "あり" | "有り" => Ok(PersonalInfo::Present),
+            _ => Err(ClassificationParseError {
+                kind: "personal_info",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for InternalExternal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl fmt::Display for ImportanceClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl fmt::Display for PersonalInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Document, EmployeeRole, ImportanceClass, InternalExternal, PersonalInfo};
+
+/// 文書の閲覧に必要な機密レベル（数値が大きいほど機密性が高い）
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
+#[repr(i32)]
+pub enum ClearanceLevel {
+    /// 社外文書など、誰でも閲覧できる
+    #[default]
+    Public = 0,
+    /// 社内文書
+    Internal = 1,
+    /// 情報クラスⅠの文書
+    Confidential = 2,
+    /// 個人情報を含む文書の保存場所
+    Restricted = 3,
+}
+
+impl Document {
+    /// 文書の存在・書誌情報の閲覧に必要な機密レベル
+    ///
+    /// 検索時の絞り込みはリポジトリのSQLで同じ判定を行う
+    pub fn required_clearance(&self) -> ClearanceLevel {
+        if self.importance_class == Some(ImportanceClass::Class1) {
+            ClearanceLevel::Confidential
+        } else if self.internal_external == Some(InternalExternal::Internal) {
+            ClearanceLevel::Internal
+        } else {
+            ClearanceLevel::Public
+        }
+    }
+
+    /// ネットワークパス（保存場所）の閲覧に必要な機密レベル
+    pub fn path_clearance(&self) -> ClearanceLevel {
+        if self.personal_info == Some(PersonalInfo::Present) {
+            ClearanceLevel::Restricted
+        } else {
+            self.required_clearance()
+        }
+    }
+
+    /// 閲覧者の機密レベルで見られない項目を伏せる
+    pub fn redact_for(mut self, clearance: ClearanceLevel) -> Self {
+        if clearance < self.path_clearance() {
+            self.network_path = None;
+        }
+        self
+    }
+}
+
+/// 社員の機密レベル（ロールの既定値と社員個別の設定）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmployeeClearance {
+    pub employee_id: i32,
+    pub role: EmployeeRole,
+    pub role_clearance_level: ClearanceLevel,
+    /// 社員個別の設定（設定時はロールの既定値より優先する）
+    pub employee_clearance_level: Option<ClearanceLevel>,
+    pub effective_clearance_level: ClearanceLevel,
+}
+
+/// 社員個別の機密レベル設定リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateEmployeeClearanceRequest {
+    /// nullの場合は個別設定を解除してロールの既定値に戻す
+    pub clearance_level: Option<ClearanceLevel>,
+}
+
+/// ロールごとの機密レベル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleClearance {
+    pub role: EmployeeRole,
+    pub clearance_level: ClearanceLevel,
+}
+
+/// ロールの機密レベル設定リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateRoleClearanceRequest {
+    pub clearance_level: ClearanceLevel,
+}
@@ -2,12 +2,36 @@ use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+/// Level of an organizational unit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum OrganizationUnitKind {
+    /// 部
+    #[default]
+    Department,
+    /// 課 (always belongs to a parent department)
+    Section,
+}
+
+impl OrganizationUnitKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Department => "department",
+            Self::Section => "section",
+        }
+    }
+}
+
 /// Department model for organizational management
+///
+/// Holds the latest state; past states are kept in `DepartmentVersion`.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Department {
     pub id: i32,
     pub code: String,
     pub name: String,
+    pub kind: OrganizationUnitKind,
     pub parent_id: Option<i32>,
     pub level: i32,
     pub manager_id: Option<i32>,
@@ -17,6 +41,8 @@ pub struct Department {
     pub email: Option<String>,
     pub budget: Option<f64>,
     pub is_active: bool,
+    /// Optimistic concurrency version, incremented on every update
+    pub version: i32,
     pub created_date: Option<NaiveDate>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -28,6 +54,7 @@ pub struct DepartmentWithManager {
     pub id: i32,
     pub code: String,
     pub name: String,
+    pub kind: OrganizationUnitKind,
     pub parent_id: Option<i32>,
     pub parent_name: Option<String>,
     pub level: i32,
@@ -40,6 +67,7 @@ pub struct DepartmentWithManager {
     pub budget: Option<f64>,
     pub is_active: bool,
     pub employee_count: i64,
+    pub version: i32,
     pub created_date: Option<NaiveDate>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -61,6 +89,9 @@ pub struct DepartmentSearchFilters {
 pub struct CreateDepartmentRequest {
     pub code: String,
     pub name: String,
+    /// Sections require `parent_id`
+    #[serde(default)]
+    pub kind: OrganizationUnitKind,
     pub parent_id: Option<i32>,
     pub manager_id: Option<i32>,
     pub description: Option<String>,
@@ -85,3 +116,165 @@ pub struct UpdateDepartmentRequest {
     pub budget: Option<f64>,
     pub is_active: Option<bool>,
 }
+
+/// Effective-dated record of a department or section
+///
+/// Valid from `valid_from` up to the day before `valid_to` (`None` while still valid).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DepartmentVersion {
+    pub id: i32,
+    pub department_id: i32,
+    pub code: String,
+    pub name: String,
+    pub kind: OrganizationUnitKind,
+    pub parent_id: Option<i32>,
+    pub level: i32,
+    pub valid_from: NaiveDate,
+    pub valid_to: Option<NaiveDate>,
+    /// Restructure event that started this record (`None` when the unit was created)
+    pub event_id: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+impl DepartmentVersion {
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.valid_from <= date && self.valid_to.is_none_or(|valid_to| date < valid_to)
+    }
+}
+
+/// Kind of organization restructure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum OrganizationEventType {
+    /// Code and/or name change
+    Rename,
+    /// Sources are absorbed into the target and abolished
+    Merge,
+    /// The source is abolished and replaced by new units
+    Split,
+    /// Parent department change
+    Move,
+}
+
+/// Recorded organization restructure
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrganizationEvent {
+    pub id: i32,
+    pub event_type: OrganizationEventType,
+    pub effective_date: NaiveDate,
+    pub reason: Option<String>,
+    pub recorded_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+    /// Units before the restructure
+    #[sqlx(skip)]
+    pub source_ids: Vec<i32>,
+    /// Units after the restructure
+    #[sqlx(skip)]
+    pub target_ids: Vec<i32>,
+}
+
+/// New unit created by a split
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewOrganizationUnit {
+    pub code: String,
+    pub name: String,
+}
+
+/// Change applied by a restructure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum RestructureChange {
+    Rename {
+        department_id: i32,
+        code: Option<String>,
+        name: Option<String>,
+    },
+    Merge {
+        source_ids: Vec<i32>,
+        target_id: i32,
+    },
+    Split {
+        source_id: i32,
+        units: Vec<NewOrganizationUnit>,
+    },
+    Move {
+        department_id: i32,
+        parent_id: Option<i32>,
+    },
+}
+
+impl RestructureChange {
+    pub fn event_type(&self) -> OrganizationEventType {
+        match self {
+            Self::Rename { .. } => OrganizationEventType::Rename,
+            Self::Merge { .. } => OrganizationEventType::Merge,
+            Self::Split { .. } => OrganizationEventType::Split,
+            Self::Move { .. } => OrganizationEventType::Move,
+        }
+    }
+}
+
+/// Organization restructure request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestructureRequest {
+    /// First day the new organization is in effect
+    pub effective_date: NaiveDate,
+    pub reason: Option<String>,
+    pub recorded_by: Option<i32>,
+    #[serde(flatten)]
+    pub change: RestructureChange,
+}
+
+impl RestructureRequest {
+    /// Check the request shape (existence and dates are checked against the history)
+    pub fn validate(&self) -> Result<(), String> {
+        let blank = |value: &Option<String>| value.as_deref().is_none_or(|v| v.trim().is_empty());
+
+        match &self.change {
+            RestructureChange::Rename { code, name, .. } => {
+                if blank(code) && blank(name) {
+                    return Err("rename requires a new code or name".to_string());
+                }
+            }
+            RestructureChange::Merge {
+                source_ids,
+                target_id,
+            } => {
+                if source_ids.is_empty() {
+                    return Err("merge requires at least one source".to_string());
+                }
+                if source_ids.contains(target_id) {
+                    return Err("merge target cannot also be a source".to_string());
+                }
+            }
+            RestructureChange::Split { units, .. } => {
+                if units.len() < 2 {
+                    return Err("split requires at least two new units".to_string());
+                }
+                if units
+                    .iter()
+                    .any(|unit| unit.code.trim().is_empty() || unit.name.trim().is_empty())
+                {
+                    return Err("new units require a code and a name".to_string());
+                }
+                let mut codes: Vec<&str> = units.iter().map(|unit| unit.code.trim()).collect();
+                codes.sort_unstable();
+                codes.dedup();
+                if codes.len() != units.len() {
+                    return Err("new units must have distinct codes".to_string());
+                }
+            }
+            RestructureChange::Move {
+                department_id,
+                parent_id,
+            } => {
+                if *parent_id == Some(*department_id) {
+                    return Err("a department cannot be its own parent".to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
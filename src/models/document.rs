@@ -1,6 +1,8 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::{ClearanceLevel, ImportanceClass, InternalExternal, PersonalInfo, YearCount};
+
 // バリデーションエラー型
 #[derive(Debug, thiserror::Error, PartialEq)]
 pub enum DocumentValidationError {
@@ -30,6 +32,38 @@ pub enum DocumentValidationError {
     EmptyDocumentTypeCodes,
     #[error("Department code cannot be empty")]
     EmptyDepartmentCode,
+    #[error("Document number cannot be empty or contain whitespace")]
+    InvalidDocumentNumber,
+    #[error("Reason for manual number assignment cannot be empty")]
+    EmptyOverrideReason,
+    #[error("Year range start must not be after its end")]
+    InvalidYearRange,
+    #[error("Section code is required by the document number template")]
+    MissingSectionCode,
+    #[error("Invalid document number template: {0}")]
+    InvalidNumberTemplate(String),
+    #[error("Invalid path template: {0}")]
+    InvalidPathTemplate(String),
+    #[error("Simulation count must be between 1 and {max}")]
+    InvalidSimulationCount { max: i32 },
+    #[error("Reservation count must be between 1 and {max}")]
+    InvalidReservationCount { max: i32 },
+    #[error("Reservation expiry date must not be before the created date")]
+    InvalidReservationExpiry,
+    #[error("Reason for voiding a document number cannot be empty")]
+    EmptyVoidReason,
+    #[error("Business number cannot be empty")]
+    EmptyBusinessNumber,
+    #[error("Network path cannot be empty")]
+    EmptyNetworkPath,
+    #[error("Update must change at least one field")]
+    EmptyUpdate,
+    #[error("Idempotency key must be 1-{max} characters without whitespace")]
+    InvalidIdempotencyKey { max: usize },
+    #[error("Bulk registration must contain between 1 and {max} documents")]
+    InvalidBulkCreateCount { max: usize },
+    #[error("Searching historical affiliations requires the caller's user_id")]
+    MissingAffiliationViewer,
 }
 
 // 文書モデル（データベースから取得用）
@@ -43,12 +77,14 @@ pub struct Document {
     pub created_by: i32,
     pub created_by_name: Option<String>,
     pub created_date: NaiveDate,
-    pub internal_external: Option<String>,
-    pub importance_class: Option<String>,
-    pub personal_info: Option<String>,
+    pub internal_external: Option<InternalExternal>,
+    pub importance_class: Option<ImportanceClass>,
+    pub personal_info: Option<PersonalInfo>,
     pub notes: Option<String>,
     pub network_path: Option<String>,
     pub is_active: bool,
+    /// 楽観的排他制御のバージョン（更新のたびに1ずつ増える）
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -62,9 +98,9 @@ pub struct CreateDocumentRequest {
     pub business_number: Option<String>,
     pub created_by: i32,
     pub created_date: NaiveDate,
-    pub internal_external: Option<String>,
-    pub importance_class: Option<String>,
-    pub personal_info: Option<String>,
+    pub internal_external: Option<InternalExternal>,
+    pub importance_class: Option<ImportanceClass>,
+    pub personal_info: Option<PersonalInfo>,
     pub notes: Option<String>,
 }
 
@@ -90,21 +126,109 @@ impl CreateDocumentRequest {
     }
 }
 
-// 文書更新リクエスト（将来的に使用）
-#[derive(Debug, Clone, Deserialize)]
+// 文書更新リクエスト（指定した項目だけを変更する）
+//
+// 未設定にできる項目は`Some(None)`（JSONでは`null`）で値を消し、省略した項目は変更しない
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct UpdateDocumentRequest {
     pub title: Option<String>,
     pub document_type_id: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    pub business_number: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    pub internal_external: Option<Option<InternalExternal>>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    pub importance_class: Option<Option<ImportanceClass>>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    pub personal_info: Option<Option<PersonalInfo>>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    pub notes: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    pub network_path: Option<Option<String>>,
+    pub updated_by: i32,
+    pub reason: Option<String>,
+}
+
+impl UpdateDocumentRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        if self
+            .title
+            .as_deref()
+            .is_some_and(|title| title.trim().is_empty())
+        {
+            return Err(DocumentValidationError::EmptyTitle);
+        }
+
+        if self.document_type_id.is_some_and(|id| id < 1) {
+            return Err(DocumentValidationError::InvalidDocumentTypeId);
+        }
+
+        if let Some(Some(business_number)) = &self.business_number
+            && business_number.trim().is_empty()
+        {
+            return Err(DocumentValidationError::EmptyBusinessNumber);
+        }
+
+        if let Some(Some(network_path)) = &self.network_path
+            && network_path.trim().is_empty()
+        {
+            return Err(DocumentValidationError::EmptyNetworkPath);
+        }
+
+        if self.updated_by < 1 {
+            return Err(DocumentValidationError::InvalidCreatedBy);
+        }
+
+        if self.title.is_none()
+            && self.document_type_id.is_none()
+            && self.business_number.is_none()
+            && self.internal_external.is_none()
+            && self.importance_class.is_none()
+            && self.personal_info.is_none()
+            && self.notes.is_none()
+            && self.network_path.is_none()
+        {
+            return Err(DocumentValidationError::EmptyUpdate);
+        }
+
+        Ok(())
+    }
+}
+
+/// 省略（変更しない）と`null`（値を消す）を区別して読み込む
+pub(crate) fn deserialize_patch<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 // 文書検索フィルター（将来的に使用）
 #[derive(Debug, Clone)]
 pub struct DocumentSearchFilters {
+    /// 全文検索キーワード（タイトル・備考・業務番号・作成者名、空白区切りで全ての語を含む）
+    pub query: Option<String>,
     pub title: Option<String>,
     pub document_type_id: Option<i32>,
     pub created_by: Option<i32>,
     pub created_date_from: Option<NaiveDate>,
     pub created_date_to: Option<NaiveDate>,
+    pub department_code: Option<String>,
+    /// 閲覧者が過去に所属した部署の文書（所属していた期間に作成されたもの）も対象にする
+    ///
+    /// `department_code`と併用した場合はどちらかに一致する文書。部署を指定しない場合は絞り込まないため効果はない
+    pub include_historical_affiliations: bool,
+    /// 作成日の年
+    pub year: Option<i32>,
+    pub importance_class: Option<ImportanceClass>,
+    pub internal_external: Option<InternalExternal>,
+    pub business_number: Option<String>,
+    /// 閲覧者の機密レベル（指定時はこのレベルで閲覧できる文書に限る）
+    pub max_clearance: Option<ClearanceLevel>,
+    /// 所属履歴を照合する社員（`include_historical_affiliations`指定時に閲覧者を設定する）
+    pub affiliation_employee_id: Option<i32>,
     pub limit: i64,
     pub offset: i64,
 }
@@ -112,25 +236,100 @@ pub struct DocumentSearchFilters {
 impl Default for DocumentSearchFilters {
     fn default() -> Self {
         Self {
+            query: None,
             title: None,
             document_type_id: None,
             created_by: None,
             created_date_from: None,
             created_date_to: None,
+            department_code: None,
+            include_historical_affiliations: false,
+            year: None,
+            importance_class: None,
+            internal_external: None,
+            business_number: None,
+            max_clearance: None,
+            affiliation_employee_id: None,
             limit: 50,
             offset: 0,
         }
     }
 }
 
+// 全文検索の結果（関連度とハイライト付きの抜粋）
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentSearchHit {
+    pub document: Document,
+    /// bm25による関連度（小さいほど関連が高い）。短い語を部分一致で探した場合はNone
+    pub rank: Option<f64>,
+    /// 一致箇所を<mark>で囲んだ抜粋
+    pub snippet: Option<String>,
+}
+
+// 文書検索の集計情報（検索条件に一致する文書の内訳）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentSearchAggregations {
+    pub document_type_counts: Vec<DocumentTypeCount>,
+    pub department_counts: Vec<DocumentDepartmentCount>,
+    pub year_counts: Vec<YearCount>,
+    pub importance_class_counts: Vec<ImportanceClassCount>,
+    pub internal_external_counts: Vec<InternalExternalCount>,
+    pub business_number_counts: Vec<BusinessNumberCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentTypeCount {
+    pub document_type_id: i32,
+    pub document_type_code: Option<String>,
+    pub document_type_name: Option<String>,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentDepartmentCount {
+    /// 部署未設定の文書はNone
+    pub department_code: Option<String>,
+    pub department_name: Option<String>,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportanceClassCount {
+    /// 重要度未設定の文書はNone
+    pub importance_class: Option<ImportanceClass>,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InternalExternalCount {
+    /// 社内外区分未設定の文書はNone
+    pub internal_external: Option<InternalExternal>,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessNumberCount {
+    pub business_number: String,
+    pub count: i32,
+}
+
 // 文書番号付き文書作成リクエスト
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateDocumentWithNumberRequest {
     pub title: String,
     pub document_type_code: String,
     pub department_code: String,
+    /// 課コード（採番ルールのテンプレートに`{課コード}`を含む場合に必要）
+    #[serde(default)]
+    pub section_code: Option<String>,
     pub created_by: i32,
     pub created_date: NaiveDate,
+    #[serde(default)]
+    pub internal_external: Option<InternalExternal>,
+    #[serde(default)]
+    pub importance_class: Option<ImportanceClass>,
+    #[serde(default)]
+    pub personal_info: Option<PersonalInfo>,
 }
 
 impl CreateDocumentWithNumberRequest {
@@ -171,3 +370,50 @@ impl CreateDocumentWithNumberRequest {
         Ok(())
     }
 }
+
+// 手動採番による文書作成リクエスト（管理者限定）
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateDocumentWithManualNumberRequest {
+    pub number: String,
+    #[serde(flatten)]
+    pub document: CreateDocumentWithNumberRequest,
+    pub reason: String,
+}
+
+impl CreateDocumentWithManualNumberRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        self.document.validate()?;
+
+        // 文書番号が空でなく空白を含まないことをチェック
+        if self.number.is_empty() || self.number.chars().any(char::is_whitespace) {
+            return Err(DocumentValidationError::InvalidDocumentNumber);
+        }
+
+        // 手動採番の理由が記載されていることをチェック
+        if self.reason.trim().is_empty() {
+            return Err(DocumentValidationError::EmptyOverrideReason);
+        }
+
+        Ok(())
+    }
+}
+
+// 手動採番の監査記録
+#[derive(Serialize, Deserialize, Debug, Clone, sqlx::FromRow)]
+pub struct DocumentNumberOverride {
+    pub id: i32,
+    pub document_id: i32,
+    pub document_number: String,
+    pub assigned_by: i32,
+    pub reason: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+// 手動採番による文書作成結果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreatedDocumentWithManualNumber {
+    pub document: Document,
+    pub document_number: String,
+    pub number_override: DocumentNumberOverride,
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    CreateDocumentRequest, CreateDocumentWithNumberRequest, CreatedDocumentWithNumber,
+    DocumentLocation, DocumentNumberFormatter, DocumentValidationError, SequenceKey,
+};
+
+/// 一括登録で一度に作成できる文書の最大数
+pub const MAX_BULK_CREATE_COUNT: usize = 100;
+
+/// 一括登録で一部の行が失敗した場合の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkCreateMode {
+    /// 1行でも失敗したら何も登録しない
+    #[default]
+    AllOrNothing,
+    /// 失敗した行だけを除いて登録する
+    BestEffort,
+}
+
+// 文書の一括登録リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkCreateDocumentsRequest {
+    #[serde(default)]
+    pub mode: BulkCreateMode,
+    /// 登録する文書（この順に採番する）
+    pub documents: Vec<CreateDocumentWithNumberRequest>,
+}
+
+impl BulkCreateDocumentsRequest {
+    /// 件数のバリデーションを実行（各行の内容は行ごとに検証する）
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        if !(1..=MAX_BULK_CREATE_COUNT).contains(&self.documents.len()) {
+            return Err(DocumentValidationError::InvalidBulkCreateCount {
+                max: MAX_BULK_CREATE_COUNT,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// 一括登録の行ごとの結果（作成結果またはエラーのどちらか）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkCreateDocumentRowResult {
+    /// リクエストの`documents`内の位置（0始まり）
+    pub index: usize,
+    pub created: Option<CreatedDocumentWithNumber>,
+    pub error: Option<String>,
+}
+
+// 文書の一括登録結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkCreateDocumentsResult {
+    pub mode: BulkCreateMode,
+    /// 1件以上の文書を登録したか（全件一括で失敗した場合はfalse）
+    pub committed: bool,
+    pub created_count: usize,
+    pub failed_count: usize,
+    pub results: Vec<BulkCreateDocumentRowResult>,
+}
+
+// 一括登録で採番して作成する文書（ルールの解決と行の検証はサービス層で済ませたもの）
+pub struct AllocatedDocumentDraft<'a> {
+    pub request: CreateDocumentRequest,
+    pub sequence_key: SequenceKey,
+    pub format_number: &'a DocumentNumberFormatter<'a>,
+    pub location: DocumentLocation<'a>,
+}
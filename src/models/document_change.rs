@@ -0,0 +1,60 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::{Document, DocumentValidationError};
+
+/// 文書の変更の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum DocumentChangeType {
+    /// メタデータの更新（項目ごとに記録）
+    Updated,
+    /// ゴミ箱へ移動
+    Deleted,
+    /// ゴミ箱から復元
+    Restored,
+}
+
+// 文書の変更履歴
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct DocumentChange {
+    pub id: i32,
+    pub document_id: i32,
+    pub change_type: DocumentChangeType,
+    /// 更新した項目（削除・復元ではNone）
+    pub field_name: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by: i32,
+    pub reason: Option<String>,
+    pub changed_at: NaiveDateTime,
+}
+
+// 文書の削除・復元リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangeDocumentStateRequest {
+    pub changed_by: i32,
+    pub reason: Option<String>,
+}
+
+impl ChangeDocumentStateRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        if self.changed_by < 1 {
+            return Err(DocumentValidationError::InvalidCreatedBy);
+        }
+
+        Ok(())
+    }
+}
+
+// ゴミ箱内の文書
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeletedDocument {
+    pub document: Document,
+    pub deleted_by: i32,
+    pub deleted_at: NaiveDateTime,
+    pub reason: Option<String>,
+}
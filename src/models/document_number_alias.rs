@@ -0,0 +1,40 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DocumentValidationError;
+
+// 文書の旧番号（付け替え前の番号）
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct DocumentNumberAlias {
+    pub id: i32,
+    pub document_id: i32,
+    pub alias_number: String,
+    pub reason: Option<String>,
+    pub created_by: i32,
+    pub created_at: NaiveDateTime,
+}
+
+// 旧番号の登録リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateDocumentNumberAliasRequest {
+    pub alias_number: String,
+    pub reason: Option<String>,
+    pub created_by: i32,
+}
+
+impl CreateDocumentNumberAliasRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        let alias_number = self.alias_number.trim();
+        if alias_number.is_empty() || alias_number.contains(char::is_whitespace) {
+            return Err(DocumentValidationError::InvalidDocumentNumber);
+        }
+
+        if self.created_by < 1 {
+            return Err(DocumentValidationError::InvalidCreatedBy);
+        }
+
+        Ok(())
+    }
+}
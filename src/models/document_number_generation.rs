@@ -1,8 +1,9 @@
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
 use super::DocumentValidationError;
+use super::document::deserialize_patch;
 
 // 文書番号生成ルールモデル（データベースから取得用）
 #[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
@@ -16,10 +17,43 @@ pub struct DocumentNumberGenerationRule {
     pub effective_from: NaiveDate,
     pub effective_until: Option<NaiveDate>,
     pub priority: i32,
+    pub reset_scope: SequenceResetScope,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
 
+/// 連番をリセットする単位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum SequenceResetScope {
+    /// 年月ごと
+    #[default]
+    Monthly,
+    /// 暦年ごと
+    Yearly,
+    /// 4月始まりの年度ごと
+    #[serde(alias = "fiscal-yearly")]
+    FiscalYearly,
+    /// リセットしない
+    Never,
+}
+
+impl SequenceResetScope {
+    /// 作成日が属する連番カウンターの期間（year, month）を返す
+    ///
+    /// 単位に含まれない要素は0とする（年度ごとの場合のyearは年度）
+    pub fn period(self, date: NaiveDate) -> (i32, i32) {
+        match self {
+            SequenceResetScope::Monthly => (date.year(), date.month() as i32),
+            SequenceResetScope::Yearly => (date.year(), 0),
+            SequenceResetScope::FiscalYearly if date.month() >= 4 => (date.year(), 0),
+            SequenceResetScope::FiscalYearly => (date.year() - 1, 0),
+            SequenceResetScope::Never => (0, 0),
+        }
+    }
+}
+
 // 文書番号生成ルール作成リクエスト
 #[derive(Debug, Clone, Deserialize)]
 pub struct CreateDocumentNumberGenerationRuleRequest {
@@ -31,6 +65,8 @@ pub struct CreateDocumentNumberGenerationRuleRequest {
     pub effective_from: NaiveDate,
     pub effective_until: Option<NaiveDate>,
     pub priority: i32,
+    #[serde(default)]
+    pub reset_scope: SequenceResetScope,
 }
 
 impl CreateDocumentNumberGenerationRuleRequest {
@@ -56,6 +92,15 @@ impl CreateDocumentNumberGenerationRuleRequest {
             return Err(DocumentValidationError::EmptyDocumentTypeCodes);
         }
 
+        // 文書種別コードが空でないことをチェック
+        if self
+            .document_type_codes
+            .iter()
+            .any(|code| code.trim().is_empty())
+        {
+            return Err(DocumentValidationError::EmptyDocumentTypeCode);
+        }
+
         // 部署コードが指定されている場合、1文字であることをチェック
         if let Some(dept_code) = &self.department_code
             && !dept_code.trim().is_empty()
@@ -75,11 +120,102 @@ impl CreateDocumentNumberGenerationRuleRequest {
     }
 }
 
+// 文書番号生成ルール更新リクエスト
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateDocumentNumberGenerationRuleRequest {
+    pub rule_name: Option<String>,
+    pub template: Option<String>,
+    pub sequence_digits: Option<i32>,
+    pub department_code: Option<String>,
+    pub document_type_codes: Option<Vec<String>>,
+    pub effective_from: Option<NaiveDate>,
+    /// `null`を指定すると終了日を消して無期限に戻す
+    #[serde(default, deserialize_with = "deserialize_patch")]
+    pub effective_until: Option<Option<NaiveDate>>,
+    pub priority: Option<i32>,
+    pub reset_scope: Option<SequenceResetScope>,
+}
+
+impl UpdateDocumentNumberGenerationRuleRequest {
+    /// 既存ルールに変更を適用した結果を作成リクエストとして返す
+    pub fn apply_to(
+        &self,
+        rule: &DocumentNumberGenerationRule,
+    ) -> Result<CreateDocumentNumberGenerationRuleRequest, DocumentValidationError> {
+        let document_type_codes = match &self.document_type_codes {
+            Some(codes) => codes.clone(),
+            None => rule.parsed_document_type_codes(),
+        };
+
+        let merged = CreateDocumentNumberGenerationRuleRequest {
+            rule_name: self
+                .rule_name
+                .clone()
+                .unwrap_or_else(|| rule.rule_name.clone()),
+            template: self
+                .template
+                .clone()
+                .unwrap_or_else(|| rule.template.clone()),
+            sequence_digits: self.sequence_digits.unwrap_or(rule.sequence_digits),
+            // 空文字は「全部署共通」への変更として扱う
+            department_code: match &self.department_code {
+                Some(code) if code.trim().is_empty() => None,
+                Some(code) => Some(code.clone()),
+                None => rule.department_code.clone(),
+            },
+            document_type_codes,
+            effective_from: self.effective_from.unwrap_or(rule.effective_from),
+            effective_until: self.effective_until.unwrap_or(rule.effective_until),
+            priority: self.priority.unwrap_or(rule.priority),
+            reset_scope: self.reset_scope.unwrap_or(rule.reset_scope),
+        };
+
+        merged.validate()?;
+        Ok(merged)
+    }
+}
+
+// 文書番号生成ルール廃止リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct RetireDocumentNumberGenerationRuleRequest {
+    /// この日付をもってルールの適用を終了する
+    pub effective_until: NaiveDate,
+}
+
+impl DocumentNumberGenerationRule {
+    /// 保存されているJSON配列から文書種別コードを取り出す
+    pub fn parsed_document_type_codes(&self) -> Vec<String> {
+        serde_json::from_str(&self.document_type_codes).unwrap_or_default()
+    }
+
+    /// 指定日にルールが有効かどうか
+    pub fn is_effective_on(&self, date: NaiveDate) -> bool {
+        self.effective_from <= date && self.effective_until.is_none_or(|until| date <= until)
+    }
+
+    /// 他のルールと有効期間が重なる場合、その期間（開始日・終了日）を返す
+    pub fn overlapping_period(
+        &self,
+        other: &DocumentNumberGenerationRule,
+    ) -> Option<(NaiveDate, Option<NaiveDate>)> {
+        let from = self.effective_from.max(other.effective_from);
+        let until = match (self.effective_until, other.effective_until) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        until
+            .is_none_or(|until| from <= until)
+            .then_some((from, until))
+    }
+}
+
 // 文書番号生成リクエスト
 #[derive(Debug, Clone)]
 pub struct DocumentNumberRequest {
     pub document_type_code: String,
     pub department_code: String,
+    /// 課コード（テンプレートに`{課コード}`を含む場合に必要）
+    pub section_code: Option<String>,
     pub created_date: NaiveDate,
     pub created_by: i32,
 }
@@ -118,6 +254,23 @@ impl DocumentNumberRequest {
     }
 }
 
+// 連番カウンターのキー（ルール・期間・部署の組み合わせ、期間はSequenceResetScope::periodで求める）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SequenceKey {
+    pub rule_id: i32,
+    pub year: i32,
+    pub month: i32,
+    pub department_code: String,
+}
+
+/// 採番された連番から文書番号を組み立てる関数
+pub type DocumentNumberFormatter<'a> =
+    dyn Fn(i32) -> Result<String, DocumentNumberGenerationError> + Send + Sync + 'a;
+
+/// ルールが将来採番し得る番号と衝突する場合に、その連番カウンターのキーと連番を返す関数
+pub type RuleCollisionFinder<'a> =
+    dyn Fn(&DocumentNumberGenerationRule) -> Option<(SequenceKey, i32)> + Send + Sync + 'a;
+
 // 生成された文書番号
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneratedDocumentNumber {
@@ -127,6 +280,199 @@ pub struct GeneratedDocumentNumber {
     pub template_used: String,
 }
 
+// ルールのテンプレートに沿って分解した文書番号
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParsedDocumentNumber {
+    pub rule_id: i32,
+    pub document_type_code: Option<String>,
+    /// テンプレートの`{文書種別名}`に当たる文字列
+    pub document_type_name: Option<String>,
+    pub department_code: Option<String>,
+    pub section_code: Option<String>,
+    /// 西暦年（テンプレートに無い場合は和暦または年度と月から求める）
+    pub year: Option<i32>,
+    pub month: Option<i32>,
+    /// 年度（4月始まり）
+    pub fiscal_year: Option<i32>,
+    /// 元号（"令和"など）
+    pub era: Option<String>,
+    pub era_year: Option<i32>,
+    pub sequence_number: i32,
+}
+
+impl ParsedDocumentNumber {
+    /// 番号から分かる作成日の範囲（開始日・終了日）
+    ///
+    /// 年月があればその月、年だけなら暦年、年度だけなら年度の範囲とする
+    pub fn date_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let first_day = |year: i32, month: u32| NaiveDate::from_ymd_opt(year, month, 1);
+        match (self.year, self.month, self.fiscal_year) {
+            (Some(year), Some(month), _) => {
+                let start = first_day(year, month as u32)?;
+                let end = start
+                    .checked_add_months(chrono::Months::new(1))?
+                    .pred_opt()?;
+                Some((start, end))
+            }
+            (Some(year), None, _) => {
+                Some((first_day(year, 1)?, first_day(year + 1, 1)?.pred_opt()?))
+            }
+            (None, _, Some(fiscal_year)) => Some((
+                first_day(fiscal_year, 4)?,
+                first_day(fiscal_year + 1, 4)?.pred_opt()?,
+            )),
+            _ => None,
+        }
+    }
+}
+
+// 既存の文書番号をルールのテンプレートで逆引きした結果（一致したルールごと）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecomposedDocumentNumber {
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub template: String,
+    pub priority: i32,
+    pub components: ParsedDocumentNumber,
+    /// 番号の年月時点でルールが有効だったか（年月が分からない場合はNone）
+    pub effective: Option<bool>,
+    /// この番号が属する連番カウンター（部署や期間が決まらない場合はNone）
+    pub sequence_key: Option<SequenceKey>,
+    /// 連番カウンターが次に採番する連番（カウンターが決まらない場合はNone）
+    pub next_sequence: Option<i32>,
+}
+
+// 文書番号の逆引き結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentNumberDecomposition {
+    pub document_number: String,
+    /// 文書として登録済みか
+    pub registered: bool,
+    /// 番号を生成し得るルール（番号の時点で有効だったもの・選択される順を先にする）
+    pub matches: Vec<DecomposedDocumentNumber>,
+}
+
+/// ルール適用のシミュレーションで表示する番号数の既定値
+pub const DEFAULT_SIMULATION_COUNT: i32 = 5;
+/// ルール適用のシミュレーションで表示できる番号数の上限
+pub const MAX_SIMULATION_COUNT: i32 = 100;
+
+// ルール適用のシミュレーション（ドライラン）リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuleSimulationRequest {
+    pub document_type_code: String,
+    pub department_code: String,
+    #[serde(default)]
+    pub section_code: Option<String>,
+    pub created_date: NaiveDate,
+    /// 表示する番号の数（省略時は5件）
+    pub count: Option<i32>,
+}
+
+impl RuleSimulationRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        if self.document_type_code.trim().is_empty() {
+            return Err(DocumentValidationError::EmptyDocumentTypeCode);
+        }
+
+        if self.department_code.trim().is_empty() {
+            return Err(DocumentValidationError::EmptyDepartmentCode);
+        }
+
+        if !(1..=MAX_SIMULATION_COUNT).contains(&self.count()) {
+            return Err(DocumentValidationError::InvalidSimulationCount {
+                max: MAX_SIMULATION_COUNT,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 表示する番号の数
+    pub fn count(&self) -> i32 {
+        self.count.unwrap_or(DEFAULT_SIMULATION_COUNT)
+    }
+
+    /// 採番と同じ条件でルールを解決するための文書番号生成リクエスト
+    pub fn number_request(&self) -> DocumentNumberRequest {
+        DocumentNumberRequest {
+            document_type_code: self.document_type_code.trim().to_string(),
+            department_code: self.department_code.trim().to_string(),
+            section_code: self.section_code.clone(),
+            created_date: self.created_date,
+            created_by: 0,
+        }
+    }
+}
+
+// ルール適用のシミュレーション結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleSimulationResult {
+    pub document_type_code: String,
+    pub department_code: String,
+    pub created_date: NaiveDate,
+    /// 適用候補のルール（選択される順）
+    pub candidates: Vec<DocumentNumberGenerationRule>,
+    /// 実際に選択されるルール
+    pub selected_rule_id: Option<i32>,
+    /// 選択されるルールが次に採番する番号（登録済みの番号は読み飛ばす）
+    pub next_numbers: Vec<String>,
+    /// 番号を要求数まで組み立てられなかった理由
+    pub error: Option<String>,
+}
+
+// 同じ優先度で適用範囲と有効期間が重なるルールの組
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePriorityOverlap {
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub other_rule_id: i32,
+    pub other_rule_name: String,
+    pub priority: i32,
+    pub department_code: Option<String>,
+    /// 両方のルールが対象とする文書種別コード
+    pub document_type_codes: Vec<String>,
+    pub overlap_from: NaiveDate,
+    pub overlap_until: Option<NaiveDate>,
+}
+
+/// 文書番号が衝突する原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateCollisionKind {
+    /// 2つのルールのテンプレートが同じ番号を生成し得る
+    SharedPattern,
+    /// 全部署共通ルールの連番は部署ごとに数えるが、テンプレートに部署コードが無い
+    DepartmentCounter,
+    /// 連番はリセット単位ごとに数えるが、テンプレートで期間を区別できない
+    ResetPeriod,
+}
+
+// 同じ文書番号を生成し得るルール（単独のルールの場合は両方のIDが同じ）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleTemplateCollision {
+    pub kind: TemplateCollisionKind,
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub template: String,
+    pub other_rule_id: i32,
+    pub other_rule_name: String,
+    pub other_template: String,
+    /// 両方から生成され得る番号の例（数字部分は0、任意の文字列部分はXで表す）
+    pub example_number: Option<String>,
+    /// 有効期間が重なっているか
+    pub periods_overlap: bool,
+}
+
+// ルール全体の重複・衝突の検査結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleConflictReport {
+    pub checked_rules: i64,
+    pub priority_overlaps: Vec<RulePriorityOverlap>,
+    pub template_collisions: Vec<RuleTemplateCollision>,
+}
+
 // 文書番号生成エラー
 #[derive(Debug, thiserror::Error)]
 pub enum DocumentNumberGenerationError {
@@ -0,0 +1,163 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::{CreateDocumentWithNumberRequest, DocumentNumberRequest, DocumentValidationError};
+
+/// 一度に予約できる番号の最大数
+pub const MAX_RESERVATION_COUNT: i32 = 100;
+
+/// 予約期限までに使われなかった番号の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum ReservationExpiryAction {
+    /// 解放して通常の採番で再利用する
+    #[default]
+    Release,
+    /// 欠番として記録する
+    Void,
+}
+
+/// 予約番号の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum ReservedNumberStatus {
+    /// 予約中
+    Reserved,
+    /// 文書登録済み
+    Claimed,
+    /// 期限切れで解放済み
+    Released,
+    /// 解放後に通常の採番で使用済み
+    Reissued,
+    /// 期限切れで欠番
+    Voided,
+}
+
+// 予約ブロック内の個々の番号
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct ReservedDocumentNumber {
+    pub document_number: String,
+    pub sequence_number: i32,
+    pub status: ReservedNumberStatus,
+    pub document_id: Option<i32>,
+    pub updated_at: NaiveDateTime,
+}
+
+// 文書番号の予約ブロック
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct DocumentNumberReservation {
+    pub id: i32,
+    pub rule_id: i32,
+    pub year: i32,
+    pub month: i32,
+    pub department_code: String,
+    pub document_type_code: String,
+    pub section_code: Option<String>,
+    pub created_date: NaiveDate,
+    pub reserved_by: i32,
+    /// この日まで予約番号で文書を登録できる
+    pub expires_on: NaiveDate,
+    pub expiry_action: ReservationExpiryAction,
+    pub purpose: Option<String>,
+    /// 期限切れ処理を行った日時
+    pub expired_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    #[sqlx(skip)]
+    pub numbers: Vec<ReservedDocumentNumber>,
+}
+
+// 文書番号の予約リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateDocumentNumberReservationRequest {
+    pub document_type_code: String,
+    pub department_code: String,
+    #[serde(default)]
+    pub section_code: Option<String>,
+    /// 番号の年月・年度を決める作成日
+    pub created_date: NaiveDate,
+    pub count: i32,
+    pub expires_on: NaiveDate,
+    pub reserved_by: i32,
+    #[serde(default)]
+    pub expiry_action: ReservationExpiryAction,
+    pub purpose: Option<String>,
+}
+
+impl CreateDocumentNumberReservationRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        if self.document_type_code.trim().is_empty() {
+            return Err(DocumentValidationError::EmptyDocumentTypeCode);
+        }
+
+        if self.department_code.trim().is_empty() {
+            return Err(DocumentValidationError::EmptyDepartmentCode);
+        }
+
+        if !(1..=MAX_RESERVATION_COUNT).contains(&self.count) {
+            return Err(DocumentValidationError::InvalidReservationCount {
+                max: MAX_RESERVATION_COUNT,
+            });
+        }
+
+        if self.expires_on < self.created_date {
+            return Err(DocumentValidationError::InvalidReservationExpiry);
+        }
+
+        if self.reserved_by < 1 {
+            return Err(DocumentValidationError::InvalidCreatedBy);
+        }
+
+        Ok(())
+    }
+
+    /// 採番と同じ条件でルールを解決するための文書番号生成リクエスト
+    pub fn number_request(&self) -> DocumentNumberRequest {
+        DocumentNumberRequest {
+            document_type_code: self.document_type_code.trim().to_string(),
+            department_code: self.department_code.trim().to_string(),
+            section_code: self.section_code.clone(),
+            created_date: self.created_date,
+            created_by: self.reserved_by,
+        }
+    }
+}
+
+// 予約番号で文書を登録するリクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClaimReservedNumberRequest {
+    pub number: String,
+    #[serde(flatten)]
+    pub document: CreateDocumentWithNumberRequest,
+}
+
+impl ClaimReservedNumberRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        self.document.validate()?;
+
+        if self.number.is_empty() || self.number.chars().any(char::is_whitespace) {
+            return Err(DocumentValidationError::InvalidDocumentNumber);
+        }
+
+        Ok(())
+    }
+}
+
+// 予約の期限切れ処理リクエスト
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExpireReservationsRequest {
+    /// この日付より前に期限を迎えた予約を処理する（省略時は当日）
+    pub as_of: Option<NaiveDate>,
+}
+
+// 予約の期限切れ処理の結果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReservationExpiryResult {
+    pub expired_reservations: i64,
+    pub released_numbers: i64,
+    pub voided_numbers: i64,
+}
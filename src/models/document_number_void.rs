@@ -0,0 +1,153 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::{DocumentValidationError, ReservedNumberStatus, SequenceKey};
+
+// 欠番とした文書番号（取り下げた文書の番号を含む）
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct VoidedDocumentNumber {
+    pub id: i32,
+    pub document_number: String,
+    /// 番号が属する連番カウンター（ルールのテンプレートに一致しない番号はNone）
+    pub rule_id: Option<i32>,
+    pub year: Option<i32>,
+    pub month: Option<i32>,
+    pub department_code: Option<String>,
+    pub sequence_number: Option<i32>,
+    /// 取り下げた文書（文書が登録されていない番号の場合はNone）
+    pub document_id: Option<i32>,
+    pub reason: String,
+    pub voided_by: i32,
+    pub voided_at: NaiveDateTime,
+}
+
+// 文書番号を欠番にするリクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct VoidDocumentNumberRequest {
+    pub document_number: String,
+    pub reason: String,
+    pub voided_by: i32,
+    /// 文書が登録されていない番号の連番カウンターを決める部署（番号に部署コードを含まない場合に指定）
+    #[serde(default)]
+    pub department_code: Option<String>,
+}
+
+impl VoidDocumentNumberRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        let document_number = self.document_number.trim();
+        if document_number.is_empty() || document_number.contains(char::is_whitespace) {
+            return Err(DocumentValidationError::InvalidDocumentNumber);
+        }
+
+        if self.reason.trim().is_empty() {
+            return Err(DocumentValidationError::EmptyVoidReason);
+        }
+
+        if self.voided_by < 1 {
+            return Err(DocumentValidationError::InvalidCreatedBy);
+        }
+
+        Ok(())
+    }
+}
+
+// 欠番として記録する内容（連番カウンターへの帰属はサービス層で解決済み）
+#[derive(Debug, Clone)]
+pub struct NewVoidedDocumentNumber {
+    pub document_number: String,
+    pub sequence_key: Option<SequenceKey>,
+    pub sequence_number: Option<i32>,
+    pub document_id: Option<i32>,
+    pub reason: String,
+    pub voided_by: i32,
+}
+
+// 連番カウンターの現在値
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct SequenceCounter {
+    pub rule_id: i32,
+    pub year: i32,
+    pub month: i32,
+    pub department_code: String,
+    pub last_sequence: i32,
+}
+
+impl SequenceCounter {
+    pub fn key(&self) -> SequenceKey {
+        SequenceKey {
+            rule_id: self.rule_id,
+            year: self.year,
+            month: self.month,
+            department_code: self.department_code.clone(),
+        }
+    }
+}
+
+// 登録済みの文書番号（欠番レポートで連番カウンターへ振り分けるための最小限の情報）
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct IssuedDocumentNumber {
+    pub document_id: i32,
+    pub number: String,
+    pub department_code: Option<String>,
+    pub is_active: bool,
+}
+
+/// 連番の欠けの分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceGapKind {
+    /// 欠番として記録済み（取り下げ・予約期限切れ）
+    Voided,
+    /// 予約中、または予約期限切れで解放され再利用待ち
+    Reserved,
+    /// 記録がなく理由が分からない
+    Unexplained,
+}
+
+// 連番の欠け
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceGap {
+    pub sequence_number: i32,
+    pub kind: SequenceGapKind,
+    /// 欠番・予約の記録に残っている番号（記録がない場合はNone）
+    pub document_number: Option<String>,
+    pub reason: Option<String>,
+    pub voided_by: Option<i32>,
+    pub reservation_id: Option<i32>,
+    pub reservation_status: Option<ReservedNumberStatus>,
+}
+
+// 連番カウンターごとの欠けの一覧
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceGapSummary {
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub year: i32,
+    pub month: i32,
+    pub department_code: String,
+    pub last_sequence: i32,
+    /// 連番のうち文書として登録されているものの数
+    pub issued_count: i32,
+    pub gaps: Vec<SequenceGap>,
+}
+
+// 欠番レポート
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentNumberGapReport {
+    pub sequences: Vec<SequenceGapSummary>,
+    pub total_gaps: i64,
+    pub voided_gaps: i64,
+    pub reserved_gaps: i64,
+    pub unexplained_gaps: i64,
+}
+
+// 欠番レポートの対象（省略した条件は絞り込まない）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NumberGapFilters {
+    pub rule_id: Option<i32>,
+    pub year: Option<i32>,
+    pub month: Option<i32>,
+    pub department_code: Option<String>,
+}
@@ -0,0 +1,99 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DocumentValidationError;
+
+/// 版種別（改訂版: r1, r2... / ドラフト版: d1, d2...）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum RevisionType {
+    Revision,
+    Draft,
+}
+
+impl RevisionType {
+    /// 文書番号に付与する接尾辞の文字
+    pub fn suffix(self) -> &'static str {
+        match self {
+            RevisionType::Revision => "r",
+            RevisionType::Draft => "d",
+        }
+    }
+
+    /// 作成直後の状態
+    pub fn initial_status(self) -> RevisionStatus {
+        match self {
+            RevisionType::Revision => RevisionStatus::Issued,
+            RevisionType::Draft => RevisionStatus::Draft,
+        }
+    }
+}
+
+/// 版の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum RevisionStatus {
+    /// 発行済みの改訂版
+    Issued,
+    /// 作業中のドラフト版
+    Draft,
+    /// 改訂版として発行済みのドラフト版
+    Promoted,
+}
+
+// 文書の版（改訂版・ドラフト版）
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DocumentRevision {
+    pub id: i32,
+    pub document_id: i32,
+    pub revision_type: RevisionType,
+    pub version_number: i32,
+    pub number: String,
+    pub status: RevisionStatus,
+    pub notes: Option<String>,
+    pub promoted_revision_id: Option<i32>,
+    pub created_by: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// 版作成リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateDocumentRevisionRequest {
+    pub revision_type: RevisionType,
+    pub created_by: i32,
+    pub notes: Option<String>,
+}
+
+impl CreateDocumentRevisionRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        // 作成者IDが有効であることをチェック
+        if self.created_by < 1 {
+            return Err(DocumentValidationError::InvalidCreatedBy);
+        }
+
+        Ok(())
+    }
+}
+
+// ドラフト版の発行リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct PromoteDraftRequest {
+    pub promoted_by: i32,
+    pub notes: Option<String>,
+}
+
+impl PromoteDraftRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        if self.promoted_by < 1 {
+            return Err(DocumentValidationError::InvalidCreatedBy);
+        }
+
+        Ok(())
+    }
+}
@@ -9,12 +9,30 @@ pub struct Employee {
     pub name: String,
     pub email: Option<String>,
     pub ad_username: Option<String>,
+    /// 現在の主務の部署
     pub department_id: Option<i32>,
+    /// 現在の主務の課
+    #[serde(default)]
+    pub section_id: Option<i32>,
+    /// 現在の主務の役職
+    #[serde(default)]
+    pub position: Option<String>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// 社員の権限ロール
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum EmployeeRole {
+    #[default]
+    User,
+    /// 手動採番など管理者限定の操作が可能
+    Admin,
+}
+
 /// 社員作成リクエスト
 #[derive(Debug, Deserialize)]
 pub struct CreateEmployeeRequest {
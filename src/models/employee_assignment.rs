@@ -0,0 +1,89 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 所属の種別
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum AssignmentType {
+    /// 主務（同時に1つまで）
+    #[default]
+    Primary,
+    /// 兼務
+    Concurrent,
+    /// 出向
+    Secondment,
+}
+
+/// 社員の所属（所属履歴の1件）
+///
+/// 部署・課のコードと名称は現在のもの
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmployeeAssignment {
+    pub id: i32,
+    pub employee_id: i32,
+    pub department_id: i32,
+    pub department_code: String,
+    pub department_name: String,
+    pub section_id: Option<i32>,
+    pub section_code: Option<String>,
+    pub section_name: Option<String>,
+    pub position: Option<String>,
+    pub assignment_type: AssignmentType,
+    pub start_date: NaiveDate,
+    /// 最終日（Noneなら現在も継続）
+    pub end_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl EmployeeAssignment {
+    /// 指定日に有効な所属か
+    pub fn is_active_on(&self, date: NaiveDate) -> bool {
+        self.start_date <= date && self.end_date.is_none_or(|end_date| date <= end_date)
+    }
+}
+
+/// 所属の登録・変更リクエスト（変更時は所属全体を置き換える）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmployeeAssignmentRequest {
+    pub department_id: i32,
+    pub section_id: Option<i32>,
+    pub position: Option<String>,
+    #[serde(default)]
+    pub assignment_type: AssignmentType,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+impl EmployeeAssignmentRequest {
+    /// バリデーションを実行（部署・課の存在と主務の重複はリポジトリで確認する）
+    pub fn validate(&self) -> Result<(), String> {
+        if self
+            .end_date
+            .is_some_and(|end_date| end_date < self.start_date)
+        {
+            return Err("end_date must not be before start_date".to_string());
+        }
+
+        if self
+            .position
+            .as_deref()
+            .is_some_and(|position| position.trim().is_empty())
+        {
+            return Err("position must not be blank".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// 所属履歴の取得条件
+#[derive(Debug, Default, Deserialize)]
+pub struct EmployeeAssignmentQuery {
+    /// 指定日に有効な所属だけに絞り込む
+    pub as_of: Option<NaiveDate>,
+}
@@ -0,0 +1,47 @@
+use super::{DocumentValidationError, SequenceKey};
+
+/// Idempotency-Keyと作成結果を保持する既定の期間（時間）
+pub const DEFAULT_IDEMPOTENCY_KEY_TTL_HOURS: u64 = 24;
+
+/// 処理中のIdempotency-Keyの確保を、同じキーの再送が引き継げるようになるまでの時間（秒）
+pub const IDEMPOTENCY_CLAIM_LEASE_SECONDS: i64 = 300;
+
+/// Idempotency-Keyの最大長
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// Idempotency-Keyのバリデーション
+pub fn validate_idempotency_key(key: &str) -> Result<(), DocumentValidationError> {
+    if key.is_empty()
+        || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH
+        || key.chars().any(|c| c.is_whitespace() || c.is_control())
+    {
+        return Err(DocumentValidationError::InvalidIdempotencyKey {
+            max: MAX_IDEMPOTENCY_KEY_LENGTH,
+        });
+    }
+
+    Ok(())
+}
+
+/// Idempotency-Keyを確保した結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyKeyClaim {
+    /// 新しいキーとして確保した（文書を作成して結果を保存する）
+    ///
+    /// 文書を作成しないまま確保の期限が過ぎたキーを引き継いだ場合も含む
+    Claimed,
+    /// 同じキーのリクエストで文書は作成済みだが、作成結果を保存する前に中断した
+    Created(IdempotentNumberedDocument),
+    /// 同じキーのリクエストが完了済み（保存した作成結果のJSON）
+    Completed(String),
+}
+
+/// Idempotency-Keyで作成した文書と、その採番に使った連番カウンターのキー・連番
+///
+/// 文書の作成と同じトランザクションでキーに記録し、作成結果を組み立て直すときに使う
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotentNumberedDocument {
+    pub document_id: i32,
+    pub sequence_key: SequenceKey,
+    pub sequence_number: i32,
+}
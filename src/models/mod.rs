@@ -5,12 +5,23 @@ pub mod backup;
 pub mod business;
 pub mod business_search;
 pub mod circulation;
+pub mod classification;
+pub mod clearance;
 pub mod department;
 pub mod document;
+pub mod document_bulk;
+pub mod document_change;
+pub mod document_number_alias;
 pub mod document_number_generation;
+pub mod document_number_reservation;
+pub mod document_number_void;
+pub mod document_revision;
 pub mod document_type;
 pub mod employee;
+pub mod employee_assignment;
+pub mod idempotency_key;
 pub mod migration;
+pub mod path_generation;
 pub mod search_history;
 pub mod validation;
 
@@ -20,11 +31,22 @@ pub use backup::*;
 pub use business::*;
 pub use business_search::*;
 pub use circulation::*;
+pub use classification::*;
+pub use clearance::*;
 pub use department::*;
 pub use document::*;
+pub use document_bulk::*;
+pub use document_change::*;
+pub use document_number_alias::*;
 pub use document_number_generation::*;
+pub use document_number_reservation::*;
+pub use document_number_void::*;
+pub use document_revision::*;
 pub use document_type::*;
 pub use employee::*;
+pub use employee_assignment::*;
+pub use idempotency_key::*;
 pub use migration::*;
+pub use path_generation::*;
 pub use search_history::*;
 pub use validation::*;
@@ -0,0 +1,258 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::DocumentValidationError;
+
+// ネットワークパス生成ルールモデル（データベースから取得用）
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct PathGenerationRule {
+    pub id: i32,
+    pub rule_name: String,
+    pub path_template: String,
+    pub department_code: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    pub effective_from: NaiveDate,
+    pub effective_until: Option<NaiveDate>,
+    pub priority: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// ネットワークパス生成ルール作成リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePathGenerationRuleRequest {
+    pub rule_name: String,
+    pub path_template: String,
+    pub department_code: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    pub effective_from: NaiveDate,
+    pub effective_until: Option<NaiveDate>,
+    pub priority: i32,
+    #[serde(flatten)]
+    pub change: PathRuleChange,
+}
+
+impl CreatePathGenerationRuleRequest {
+    /// バリデーションを実行
+    pub fn validate(&self) -> Result<(), DocumentValidationError> {
+        // ルール名が空でないことをチェック
+        if self.rule_name.trim().is_empty() {
+            return Err(DocumentValidationError::EmptyRuleName);
+        }
+
+        // テンプレートが空でないことをチェック
+        if self.path_template.trim().is_empty() {
+            return Err(DocumentValidationError::EmptyTemplate);
+        }
+
+        // 部署コードが指定されている場合、適切な長さであることをチェック (1-10文字)
+        if let Some(dept_code) = &self.department_code
+            && !dept_code.trim().is_empty()
+            && !(1..=10).contains(&dept_code.trim().len())
+        {
+            return Err(DocumentValidationError::InvalidDepartmentCodeLength);
+        }
+
+        // 対象年範囲の整合性をチェック
+        if let (Some(year_from), Some(year_to)) = (self.year_from, self.year_to)
+            && year_from > year_to
+        {
+            return Err(DocumentValidationError::InvalidYearRange);
+        }
+
+        // 有効期間の整合性をチェック
+        if let Some(effective_until) = self.effective_until
+            && effective_until <= self.effective_from
+        {
+            return Err(DocumentValidationError::InvalidEffectivePeriod);
+        }
+
+        Ok(())
+    }
+}
+
+// ネットワークパス生成ルール更新リクエスト
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdatePathGenerationRuleRequest {
+    pub rule_name: Option<String>,
+    pub path_template: Option<String>,
+    pub department_code: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    pub effective_from: Option<NaiveDate>,
+    pub effective_until: Option<NaiveDate>,
+    pub priority: Option<i32>,
+    /// 変更後の内容が効力を持つ日（省略時は当日）
+    pub apply_from: Option<NaiveDate>,
+    #[serde(flatten)]
+    pub change: PathRuleChange,
+}
+
+impl UpdatePathGenerationRuleRequest {
+    /// 既存ルールに変更を適用した結果を作成リクエストとして返す
+    pub fn apply_to(
+        &self,
+        rule: &PathGenerationRule,
+    ) -> Result<CreatePathGenerationRuleRequest, DocumentValidationError> {
+        let merged = CreatePathGenerationRuleRequest {
+            rule_name: self
+                .rule_name
+                .clone()
+                .unwrap_or_else(|| rule.rule_name.clone()),
+            path_template: self
+                .path_template
+                .clone()
+                .unwrap_or_else(|| rule.path_template.clone()),
+            // 空文字は「全部署共通」への変更として扱う
+            department_code: match &self.department_code {
+                Some(code) if code.trim().is_empty() => None,
+                Some(code) => Some(code.clone()),
+                None => rule.department_code.clone(),
+            },
+            year_from: self.year_from.or(rule.year_from),
+            year_to: self.year_to.or(rule.year_to),
+            effective_from: self.effective_from.unwrap_or(rule.effective_from),
+            effective_until: self.effective_until.or(rule.effective_until),
+            priority: self.priority.unwrap_or(rule.priority),
+            change: self.change.clone(),
+        };
+
+        merged.validate()?;
+        Ok(merged)
+    }
+}
+
+// ネットワークパス生成ルール廃止リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct RetirePathGenerationRuleRequest {
+    /// この日付をもってルールの適用を終了する
+    pub effective_until: NaiveDate,
+    #[serde(flatten)]
+    pub change: PathRuleChange,
+}
+
+// ルール変更の記録者と理由
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PathRuleChange {
+    pub changed_by: Option<i32>,
+    pub change_reason: Option<String>,
+}
+
+/// パス生成ルールの変更種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum PathRuleChangeType {
+    Created,
+    Updated,
+    Retired,
+}
+
+// パス生成ルールの版（変更履歴）
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct PathGenerationRuleVersion {
+    pub id: i32,
+    pub rule_id: i32,
+    pub version_number: i32,
+    pub rule_name: String,
+    pub path_template: String,
+    pub department_code: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    pub effective_from: NaiveDate,
+    pub effective_until: Option<NaiveDate>,
+    pub priority: i32,
+    pub change_type: PathRuleChangeType,
+    pub changed_by: Option<i32>,
+    pub change_reason: Option<String>,
+    /// この版が効力を持ち始めた日
+    pub valid_from: NaiveDate,
+    /// 次の版に置き換えられた日（この日以降は効力を持たない）
+    pub valid_until: Option<NaiveDate>,
+    pub created_at: NaiveDateTime,
+}
+
+// パステンプレートに埋め込む文書の属性
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentPathContext {
+    pub document_number: String,
+    pub department_code: String,
+    pub department_name: Option<String>,
+    pub document_type_code: String,
+    pub document_type_name: String,
+    pub created_date: NaiveDate,
+}
+
+/// 確定した文書番号から文書のネットワークパスを生成する関数（解決できない場合は`None`）
+pub type DocumentPathFormatter<'a> = dyn Fn(&str) -> Option<String> + Send + Sync + 'a;
+
+/// 文書の登録と同じトランザクションで記録する所在（作成部署とネットワークパス）
+pub struct DocumentLocation<'a> {
+    pub department_code: &'a str,
+    pub format_path: &'a DocumentPathFormatter<'a>,
+}
+
+// 登録済み文書の保存されているパスと解決に必要な属性
+#[derive(Debug, Clone)]
+pub struct StoredDocumentPath {
+    pub document_id: i32,
+    pub network_path: Option<String>,
+    pub context: DocumentPathContext,
+}
+
+// 解決されたネットワークパス
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedNetworkPath {
+    pub document_number: String,
+    pub network_path: String,
+    pub rule_id: i32,
+    pub rule_name: String,
+    pub rule_version: i32,
+    /// どの時点で有効だったルールで解決したか
+    pub as_of: NaiveDate,
+}
+
+// 保存済みパスの一括再解決リクエスト
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NetworkPathVerificationRequest {
+    /// 指定時点で有効なルールで解決する（省略時は各文書の作成日時点）
+    pub as_of: Option<NaiveDate>,
+    pub department_code: Option<String>,
+}
+
+// 保存済みパスとルールによる解決結果の不一致
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkPathMismatch {
+    pub document_id: i32,
+    pub document_number: String,
+    pub stored_path: Option<String>,
+    pub resolved_path: Option<String>,
+    pub rule_id: Option<i32>,
+    pub rule_version: Option<i32>,
+    /// ルールで解決できなかった場合の理由
+    pub error: Option<String>,
+}
+
+// 保存済みパスの一括再解決結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkPathVerificationReport {
+    pub as_of: Option<NaiveDate>,
+    pub checked_documents: i64,
+    pub matched_documents: i64,
+    pub mismatches: Vec<NetworkPathMismatch>,
+}
+
+// ネットワークパス生成エラー
+#[derive(Debug, thiserror::Error)]
+pub enum PathGenerationError {
+    #[error("No applicable path rule found for department {department_code} on {date}")]
+    NoApplicableRule {
+        department_code: String,
+        date: NaiveDate,
+    },
+    #[error("Path template error: {0}")]
+    TemplateError(String),
+}
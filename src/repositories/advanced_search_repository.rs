@@ -143,9 +143,11 @@ impl AdvancedSearchRepository for SqliteAdvancedSearchRepository {
         // メインクエリ実行
         let query = format!(
             r#"
-            SELECT DISTINCT e.*, d.name as department_name
+            SELECT DISTINCT e.*, d.name as department_name, da2.department_id,
+                da2.section_id, da2.position as current_position
             FROM employees e
             LEFT JOIN department_assignments da2 ON e.id = da2.employee_id 
+                AND da2.assignment_type = 'primary' AND da2.start_date <= DATE('now')
                 AND (da2.end_date IS NULL OR da2.end_date >= DATE('now'))
             LEFT JOIN departments d ON da2.department_id = d.id
             {joins_clause}
@@ -174,6 +176,8 @@ impl AdvancedSearchRepository for SqliteAdvancedSearchRepository {
                 email: row.get("email"),
                 ad_username: row.get("ad_username"),
                 department_id: row.get("department_id"),
+                section_id: row.get("section_id"),
+                position: row.get("current_position"),
                 is_active: row.get("is_active"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
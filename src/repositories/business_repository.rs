@@ -1,10 +1,13 @@
 use crate::error::BusinessError;
 use crate::models::{
-    Business, BusinessMember, BusinessSearchFilters, CreateBusinessMemberRequest,
-    CreateBusinessRequest, ExternalContact, UpdateBusinessMemberRequest, UpdateBusinessRequest,
+    BUSINESS_NUMBER_LOW_CAPACITY_THRESHOLD, BUSINESS_NUMBER_MAX, BUSINESS_NUMBER_MIN, Business,
+    BusinessMember, BusinessNumberAllocation, BusinessNumberUsage, BusinessSearchFilters,
+    CreateBusinessMemberRequest, CreateBusinessRequest, ExternalContact,
+    UpdateBusinessMemberRequest, UpdateBusinessRequest,
 };
 use async_trait::async_trait;
-use sqlx::{Row, SqlitePool};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::HashSet;
 
 #[async_trait]
 pub trait BusinessRepository: Send + Sync {
@@ -18,10 +21,12 @@ pub trait BusinessRepository: Send + Sync {
         &self,
         filters: BusinessSearchFilters,
     ) -> Result<(Vec<Business>, i64), BusinessError>;
+    /// 取得時のバージョン`expected_version`のままであれば業務を更新する
     async fn update(
         &self,
         id: i32,
         business: UpdateBusinessRequest,
+        expected_version: i32,
     ) -> Result<Business, BusinessError>;
     async fn delete(&self, id: i32) -> Result<(), BusinessError>;
 
@@ -63,7 +68,12 @@ pub trait BusinessRepository: Send + Sync {
     ) -> Result<(), BusinessError>;
 
     // 業務番号生成
-    async fn generate_business_number(&self) -> Result<String, BusinessError>;
+    /// 次に自動採番される業務番号を返す（採番位置は進めない）
+    async fn generate_business_number(&self) -> Result<BusinessNumberAllocation, BusinessError>;
+    async fn get_business_number_usage(
+        &self,
+        business_number: &str,
+    ) -> Result<Option<BusinessNumberUsage>, BusinessError>;
 }
 
 pub struct SqliteBusinessRepository {
@@ -74,22 +84,113 @@ impl SqliteBusinessRepository {
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
     }
-}
 
-#[async_trait]
-impl BusinessRepository for SqliteBusinessRepository {
-    async fn create(&self, request: CreateBusinessRequest) -> Result<Business, BusinessError> {
+    /// 前回の採番位置の次から循環して、進行中の業務が保持していない番号を探す
+    async fn next_business_number(
+        conn: &mut SqliteConnection,
+    ) -> Result<(i32, BusinessNumberAllocation), BusinessError> {
+        let last_number: Option<i32> =
+            sqlx::query_scalar("SELECT last_number FROM business_number_allocator WHERE id = 1")
+                .fetch_optional(&mut *conn)
+                .await?
+                .flatten();
+
+        let held: HashSet<i32> = sqlx::query_scalar::<_, String>(
+            "SELECT business_number FROM businesses WHERE status = 'active'",
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .filter_map(|number| number.parse().ok())
+        .filter(|number| (BUSINESS_NUMBER_MIN..=BUSINESS_NUMBER_MAX).contains(number))
+        .collect();
+
+        let capacity = i64::from(BUSINESS_NUMBER_MAX - BUSINESS_NUMBER_MIN + 1);
+        let start = match last_number {
+            Some(last) if (BUSINESS_NUMBER_MIN..BUSINESS_NUMBER_MAX).contains(&last) => last + 1,
+            _ => BUSINESS_NUMBER_MIN,
+        };
+
+        let mut candidate = start;
+        let mut wrapped = false;
+        for _ in 0..capacity {
+            if !held.contains(&candidate) {
+                let business_number = candidate.to_string();
+                let previous_use_count: i32 = sqlx::query_scalar(
+                    "SELECT use_count FROM business_number_usages WHERE business_number = ?",
+                )
+                .bind(&business_number)
+                .fetch_optional(&mut *conn)
+                .await?
+                .unwrap_or(0);
+
+                let remaining_capacity = capacity - held.len() as i64 - 1;
+                let capacity_warning = (remaining_capacity
+                    < BUSINESS_NUMBER_LOW_CAPACITY_THRESHOLD)
+                    .then(|| {
+                        format!(
+                            "業務番号の空きが残り{remaining_capacity}件です（{BUSINESS_NUMBER_MIN}〜{BUSINESS_NUMBER_MAX}）"
+                        )
+                    });
+
+                return Ok((
+                    candidate,
+                    BusinessNumberAllocation {
+                        business_number,
+                        previous_use_count,
+                        wrapped,
+                        remaining_capacity,
+                        capacity_warning,
+                    },
+                ));
+            }
+
+            if candidate == BUSINESS_NUMBER_MAX {
+                candidate = BUSINESS_NUMBER_MIN;
+                wrapped = true;
+            } else {
+                candidate += 1;
+            }
+        }
+
+        Err(BusinessError::BusinessNumberExhausted {
+            min: BUSINESS_NUMBER_MIN,
+            max: BUSINESS_NUMBER_MAX,
+        })
+    }
+
+    /// 業務を1件登録し、使用回数を記録する（番号未指定の場合は採番する）
+    async fn insert_business(&self, request: &CreateBusinessRequest) -> Result<i64, BusinessError> {
         let mut tx = self.pool.begin().await?;
 
         // 業務番号生成（未指定の場合）
-        let business_number = if let Some(number) = request.business_number {
-            // 重複チェック
-            if self.get_by_business_number(&number).await?.is_some() {
+        let business_number = if let Some(number) = request.business_number.clone() {
+            // 重複チェック（完了・中止済みの業務の番号は再利用できる）
+            let held: Option<i32> = sqlx::query_scalar(
+                "SELECT id FROM businesses WHERE business_number = ? AND status = 'active'",
+            )
+            .bind(&number)
+            .fetch_optional(&mut *tx)
+            .await?;
+            if held.is_some() {
                 return Err(BusinessError::BusinessNumberExists(number));
             }
             number
         } else {
-            self.generate_business_number().await?
+            // 先に採番位置の行へ書き込んで書き込みロックを取り、同時の採番を直列にする
+            sqlx::query(
+                "UPDATE business_number_allocator SET updated_at = CURRENT_TIMESTAMP WHERE id = 1",
+            )
+            .execute(&mut *tx)
+            .await?;
+            let (number, allocation) = Self::next_business_number(&mut tx).await?;
+            sqlx::query(
+                "UPDATE business_number_allocator SET last_number = ?, updated_at = CURRENT_TIMESTAMP WHERE id = 1",
+            )
+            .bind(number)
+            .execute(&mut *tx)
+            .await?;
+            allocation.business_number
         };
 
         let business_id = sqlx::query(
@@ -106,14 +207,71 @@ impl BusinessRepository for SqliteBusinessRepository {
         .bind(&request.customer_name)
         .bind(request.start_date)
         .bind(request.end_date)
-        .bind(request.status.unwrap_or_else(|| "active".to_string()))
+        .bind(
+            request
+                .status
+                .clone()
+                .unwrap_or_else(|| "active".to_string()),
+        )
         .bind(request.created_by)
         .execute(&mut *tx)
-        .await?
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                BusinessError::BusinessNumberExists(business_number.clone())
+            } else {
+                e.into()
+            }
+        })?
         .last_insert_rowid();
 
+        // 使用回数を記録
+        sqlx::query(
+            r#"
+            INSERT INTO business_number_usages (business_number, use_count, last_business_id)
+            VALUES (?, 1, ?)
+            ON CONFLICT(business_number) DO UPDATE SET
+                use_count = use_count + 1,
+                last_business_id = excluded.last_business_id,
+                last_used_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(&business_number)
+        .bind(business_id)
+        .execute(&mut *tx)
+        .await?;
+
         tx.commit().await?;
 
+        Ok(business_id)
+    }
+}
+
+/// 自動採番した番号が同時に使われた場合に採番し直す回数の上限
+const BUSINESS_NUMBER_ALLOCATION_ATTEMPTS: u32 = 5;
+
+/// 進行中の業務間の番号一意制約（idx_businesses_active_number）違反か
+fn is_unique_violation(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Database(db_error) if db_error.is_unique_violation())
+}
+
+#[async_trait]
+impl BusinessRepository for SqliteBusinessRepository {
+    async fn create(&self, request: CreateBusinessRequest) -> Result<Business, BusinessError> {
+        // 自動採番した番号を同時に作成された業務が先に使った場合は、採番し直す
+        let mut attempts = 1;
+        let business_id = loop {
+            match self.insert_business(&request).await {
+                Err(BusinessError::BusinessNumberExists(_))
+                    if request.business_number.is_none()
+                        && attempts < BUSINESS_NUMBER_ALLOCATION_ATTEMPTS =>
+                {
+                    attempts += 1;
+                }
+                result => break result?,
+            }
+        };
+
         self.get_by_id(business_id as i32)
             .await?
             .ok_or(BusinessError::NotFound)
@@ -143,6 +301,7 @@ impl BusinessRepository for SqliteBusinessRepository {
                 end_date: row.get("end_date"),
                 status: row.get::<String, _>("status").into(),
                 created_by: row.get("created_by"),
+                version: row.get("version"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             }))
@@ -161,6 +320,8 @@ impl BusinessRepository for SqliteBusinessRepository {
             FROM businesses b
             JOIN employees e ON b.created_by = e.id
             WHERE b.business_number = ?
+            ORDER BY (b.status = 'active') DESC, b.id DESC
+            LIMIT 1
             "#,
         )
         .bind(business_number)
@@ -178,6 +339,7 @@ impl BusinessRepository for SqliteBusinessRepository {
                 end_date: row.get("end_date"),
                 status: row.get::<String, _>("status").into(),
                 created_by: row.get("created_by"),
+                version: row.get("version"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             }))
@@ -278,6 +440,7 @@ impl BusinessRepository for SqliteBusinessRepository {
                 end_date: row.get("end_date"),
                 status: row.get::<String, _>("status").into(),
                 created_by: row.get("created_by"),
+                version: row.get("version"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             })
@@ -290,7 +453,16 @@ impl BusinessRepository for SqliteBusinessRepository {
         &self,
         id: i32,
         request: UpdateBusinessRequest,
+        expected_version: i32,
     ) -> Result<Business, BusinessError> {
+        let current = self.get_by_id(id).await?.ok_or(BusinessError::NotFound)?;
+        if current.version != expected_version {
+            return Err(BusinessError::VersionMismatch {
+                expected: expected_version,
+                current: current.version,
+            });
+        }
+
         let mut set_clauses = Vec::new();
         let mut bind_values = Vec::new();
 
@@ -325,13 +497,14 @@ impl BusinessRepository for SqliteBusinessRepository {
         }
 
         if set_clauses.is_empty() {
-            return self.get_by_id(id).await?.ok_or(BusinessError::NotFound);
+            return Ok(current);
         }
 
+        set_clauses.push("version = version + 1");
         set_clauses.push("updated_at = CURRENT_TIMESTAMP");
 
         let update_query = format!(
-            "UPDATE businesses SET {} WHERE id = ?",
+            "UPDATE businesses SET {} WHERE id = ? AND version = ?",
             set_clauses.join(", ")
         );
 
@@ -339,12 +512,27 @@ impl BusinessRepository for SqliteBusinessRepository {
         for value in bind_values {
             query_builder = query_builder.bind(value);
         }
-        query_builder = query_builder.bind(id);
-
-        let result = query_builder.execute(&self.pool).await?;
+        query_builder = query_builder.bind(id).bind(expected_version);
+
+        let result = match query_builder.execute(&self.pool).await {
+            Ok(result) => result,
+            // 完了済みの業務を再開した際に、同じ番号を進行中の業務が使っている
+            Err(e) if is_unique_violation(&e) => {
+                let business = self.get_by_id(id).await?.ok_or(BusinessError::NotFound)?;
+                return Err(BusinessError::BusinessNumberExists(
+                    business.business_number,
+                ));
+            }
+            Err(e) => return Err(e.into()),
+        };
 
+        // 確認後に他の更新が先に反映された
         if result.rows_affected() == 0 {
-            return Err(BusinessError::NotFound);
+            let latest = self.get_by_id(id).await?.ok_or(BusinessError::NotFound)?;
+            return Err(BusinessError::VersionMismatch {
+                expected: expected_version,
+                current: latest.version,
+            });
         }
 
         self.get_by_id(id).await?.ok_or(BusinessError::NotFound)
@@ -685,35 +873,47 @@ impl BusinessRepository for SqliteBusinessRepository {
         Ok(())
     }
 
-    async fn generate_business_number(&self) -> Result<String, BusinessError> {
-        let current_year = chrono::Utc::now().format("%y").to_string();
+    async fn generate_business_number(&self) -> Result<BusinessNumberAllocation, BusinessError> {
+        let mut conn = self.pool.acquire().await?;
+        let (_, allocation) = Self::next_business_number(&mut conn).await?;
+        Ok(allocation)
+    }
 
-        // 今年度の最大番号を取得
-        let max_number = sqlx::query(
+    async fn get_business_number_usage(
+        &self,
+        business_number: &str,
+    ) -> Result<Option<BusinessNumberUsage>, BusinessError> {
+        let Some(row) = sqlx::query(
             r#"
-            SELECT business_number 
-            FROM businesses 
-            WHERE business_number LIKE ?
-            ORDER BY business_number DESC 
-            LIMIT 1
+            SELECT business_number, use_count, first_used_at, last_used_at
+            FROM business_number_usages
+            WHERE business_number = ?
             "#,
         )
-        .bind(format!("BUS-{current_year}%"))
+        .bind(business_number)
         .fetch_optional(&self.pool)
-        .await?;
-
-        let next_number = if let Some(row) = max_number {
-            let current_number: String = row.get("business_number");
-            let number_part: i32 = current_number
-                .split('-')
-                .nth(1)
-                .and_then(|s| s[2..].parse().ok())
-                .unwrap_or(0);
-            number_part + 1
-        } else {
-            1
+        .await?
+        else {
+            return Ok(None);
         };
 
-        Ok(format!("BUS-{current_year}{next_number:03}"))
+        let holders = sqlx::query(
+            "SELECT id, status FROM businesses WHERE business_number = ? ORDER BY id DESC",
+        )
+        .bind(business_number)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Some(BusinessNumberUsage {
+            business_number: row.get("business_number"),
+            use_count: row.get("use_count"),
+            active_business_id: holders
+                .iter()
+                .find(|h| h.get::<Option<String>, _>("status").as_deref() == Some("active"))
+                .map(|h| h.get("id")),
+            business_ids: holders.iter().map(|h| h.get("id")).collect(),
+            first_used_at: row.get("first_used_at"),
+            last_used_at: row.get("last_used_at"),
+        }))
     }
 }
@@ -199,6 +199,7 @@ impl BusinessSearchRepository for SqliteBusinessSearchRepository {
                 end_date: row.get("end_date"),
                 status: row.get::<String, _>("status").into(),
                 created_by: row.get("created_by"),
+                version: row.get("version"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             })
@@ -433,6 +434,7 @@ impl BusinessSearchRepository for SqliteBusinessSearchRepository {
                     end_date: row.get("end_date"),
                     status: row.get::<String, _>("status").into(),
                     created_by: row.get("created_by"),
+                    version: row.get("version"),
                     created_at: row.get("created_at"),
                     updated_at: row.get("updated_at"),
                 };
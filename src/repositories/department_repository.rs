@@ -1,8 +1,12 @@
+use crate::error::DepartmentError;
 use crate::models::{
-    CreateDepartmentRequest, Department, DepartmentSearchFilters, DepartmentWithManager,
-    UpdateDepartmentRequest,
+    CreateDepartmentRequest, Department, DepartmentSearchFilters, DepartmentVersion,
+    DepartmentWithManager, OrganizationEvent, OrganizationUnitKind, RestructureChange,
+    RestructureRequest, UpdateDepartmentRequest,
 };
-use sqlx::{Pool, Row, Sqlite};
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Pool, Row, Sqlite, Transaction};
 
 #[derive(Clone)]
 pub struct DepartmentRepository {
@@ -25,6 +29,7 @@ impl DepartmentRepository {
                 d.id,
                 d.code,
                 d.name,
+                d.kind,
                 d.parent_id,
                 parent_dept.name as parent_name,
                 d.level,
@@ -37,46 +42,24 @@ impl DepartmentRepository {
                 d.budget,
                 d.is_active,
                 COUNT(e.id) as employee_count,
+                d.version,
                 d.created_date,
                 d.created_at,
                 d.updated_at
             FROM departments d
             LEFT JOIN departments parent_dept ON d.parent_id = parent_dept.id
             LEFT JOIN employees manager ON d.manager_id = manager.id
-            LEFT JOIN employees e ON d.code = e.department AND e.is_active = 1
-            GROUP BY d.id, d.code, d.name, d.parent_id, parent_dept.name, d.level, 
+            LEFT JOIN employee_current_departments current ON current.department_code = d.code
+            LEFT JOIN employees e ON e.id = current.employee_id AND e.is_active = 1
+            GROUP BY d.id, d.code, d.name, d.kind, d.parent_id, parent_dept.name, d.level, 
                      d.manager_id, manager.name, d.description, d.location, d.phone_number, 
-                     d.email, d.budget, d.is_active, d.created_date, d.created_at, d.updated_at
+                     d.email, d.budget, d.is_active, d.version, d.created_date, d.created_at, d.updated_at
             ORDER BY d.level, d.code
         "#;
 
         let rows = sqlx::query(query).fetch_all(&self.pool).await?;
 
-        let mut departments = Vec::new();
-        for row in rows {
-            departments.push(DepartmentWithManager {
-                id: row.get("id"),
-                code: row.get("code"),
-                name: row.get("name"),
-                parent_id: row.get("parent_id"),
-                parent_name: row.get("parent_name"),
-                level: row.get("level"),
-                manager_id: row.get("manager_id"),
-                manager_name: row.get("manager_name"),
-                description: row.get("description"),
-                location: row.get("location"),
-                phone_number: row.get("phone_number"),
-                email: row.get("email"),
-                budget: row.get("budget"),
-                is_active: row.get::<i32, _>("is_active") == 1,
-                employee_count: row.get("employee_count"),
-                created_date: row.get("created_date"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            });
-        }
-
-        Ok(departments)
+        Ok(rows.iter().map(department_with_manager_from_row).collect())
     }
 
     pub async fn get_department_by_id(
@@ -88,6 +71,7 @@ impl DepartmentRepository {
                 d.id,
                 d.code,
                 d.name,
+                d.kind,
                 d.parent_id,
                 parent_dept.name as parent_name,
                 d.level,
@@ -100,17 +84,19 @@ impl DepartmentRepository {
                 d.budget,
                 d.is_active,
                 COUNT(e.id) as employee_count,
+                d.version,
                 d.created_date,
                 d.created_at,
                 d.updated_at
             FROM departments d
             LEFT JOIN departments parent_dept ON d.parent_id = parent_dept.id
             LEFT JOIN employees manager ON d.manager_id = manager.id
-            LEFT JOIN employees e ON d.code = e.department AND e.is_active = 1
+            LEFT JOIN employee_current_departments current ON current.department_code = d.code
+            LEFT JOIN employees e ON e.id = current.employee_id AND e.is_active = 1
             WHERE d.id = ?
-            GROUP BY d.id, d.code, d.name, d.parent_id, parent_dept.name, d.level, 
+            GROUP BY d.id, d.code, d.name, d.kind, d.parent_id, parent_dept.name, d.level, 
                      d.manager_id, manager.name, d.description, d.location, d.phone_number, 
-                     d.email, d.budget, d.is_active, d.created_date, d.created_at, d.updated_at
+                     d.email, d.budget, d.is_active, d.version, d.created_date, d.created_at, d.updated_at
         "#;
 
         let row = sqlx::query(query)
@@ -118,26 +104,7 @@ impl DepartmentRepository {
             .fetch_optional(&self.pool)
             .await?;
 
-        Ok(row.map(|row| DepartmentWithManager {
-            id: row.get("id"),
-            code: row.get("code"),
-            name: row.get("name"),
-            parent_id: row.get("parent_id"),
-            parent_name: row.get("parent_name"),
-            level: row.get("level"),
-            manager_id: row.get("manager_id"),
-            manager_name: row.get("manager_name"),
-            description: row.get("description"),
-            location: row.get("location"),
-            phone_number: row.get("phone_number"),
-            email: row.get("email"),
-            budget: row.get("budget"),
-            is_active: row.get::<i32, _>("is_active") == 1,
-            employee_count: row.get("employee_count"),
-            created_date: row.get("created_date"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        }))
+        Ok(row.as_ref().map(department_with_manager_from_row))
     }
 
     pub async fn search_departments(
@@ -189,15 +156,29 @@ impl DepartmentRepository {
         request: &CreateDepartmentRequest,
     ) -> Result<Department, sqlx::Error> {
         let now = chrono::Utc::now().naive_utc();
+        let mut tx = self.pool.begin().await?;
+
+        let level = match request.parent_id {
+            Some(parent_id) => sqlx::query_scalar::<_, i32>(
+                "SELECT COALESCE(level, 0) + 1 FROM departments WHERE id = ?",
+            )
+            .bind(parent_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .unwrap_or(0),
+            None => 0,
+        };
 
         let result = sqlx::query(r#"
-            INSERT INTO departments (code, name, parent_id, manager_id, description, location, 
+            INSERT INTO departments (code, name, kind, parent_id, level, manager_id, description, location,
                                    phone_number, email, budget, created_date, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#)
         .bind(&request.code)
         .bind(&request.name)
+        .bind(request.kind)
         .bind(request.parent_id)
+        .bind(level)
         .bind(request.manager_id)
         .bind(&request.description)
         .bind(&request.location)
@@ -207,11 +188,34 @@ impl DepartmentRepository {
         .bind(request.created_date)
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
         let department_id = result.last_insert_rowid() as i32;
 
+        // The history starts on the creation date
+        sqlx::query(
+            r#"
+            INSERT INTO department_versions (department_id, code, name, kind, parent_id, level, valid_from)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+        )
+        .bind(department_id)
+        .bind(&request.code)
+        .bind(&request.name)
+        .bind(request.kind)
+        .bind(request.parent_id)
+        .bind(level)
+        .bind(
+            request
+                .created_date
+                .unwrap_or_else(|| chrono::Local::now().date_naive()),
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
         // Return the created department
         let department = sqlx::query_as::<_, Department>(
             r#"
@@ -225,15 +229,45 @@ impl DepartmentRepository {
         Ok(department)
     }
 
+    /// Update a department that is still at `expected_version`
+    ///
+    /// Returns `VersionMismatch` if another user has updated it since it was fetched.
     pub async fn update_department(
         &self,
         id: i32,
         request: &UpdateDepartmentRequest,
-    ) -> Result<Option<Department>, sqlx::Error> {
+        expected_version: i32,
+    ) -> Result<Option<Department>, DepartmentError> {
         // For now, implement a simplified version that handles each field individually
         // TODO: Improve with dynamic query building
 
         let now = chrono::Utc::now().naive_utc();
+        let mut tx = self.pool.begin().await?;
+
+        // Bump the version first so that concurrent updates serialize on this row
+        let bumped = sqlx::query(
+            "UPDATE departments SET version = version + 1, updated_at = ? WHERE id = ? AND version = ?",
+        )
+        .bind(now)
+        .bind(id)
+        .bind(expected_version)
+        .execute(&mut *tx)
+        .await?;
+
+        if bumped.rows_affected() == 0 {
+            let current: Option<i32> =
+                sqlx::query_scalar("SELECT version FROM departments WHERE id = ?")
+                    .bind(id)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            return match current {
+                Some(current) => Err(DepartmentError::VersionMismatch {
+                    expected: expected_version,
+                    current,
+                }),
+                None => Ok(None),
+            };
+        }
 
         // Execute individual update statements for each provided field
         if let Some(code) = &request.code {
@@ -241,7 +275,7 @@ impl DepartmentRepository {
                 .bind(code)
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -250,7 +284,7 @@ impl DepartmentRepository {
                 .bind(name)
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -259,7 +293,7 @@ impl DepartmentRepository {
                 .bind(parent_id)
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -268,7 +302,7 @@ impl DepartmentRepository {
                 .bind(manager_id)
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -277,7 +311,7 @@ impl DepartmentRepository {
                 .bind(description)
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -286,7 +320,7 @@ impl DepartmentRepository {
                 .bind(location)
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -295,7 +329,7 @@ impl DepartmentRepository {
                 .bind(phone_number)
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -304,7 +338,7 @@ impl DepartmentRepository {
                 .bind(email)
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -313,7 +347,7 @@ impl DepartmentRepository {
                 .bind(budget)
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -322,16 +356,32 @@ impl DepartmentRepository {
                 .bind(if is_active { 1 } else { 0 })
                 .bind(now)
                 .bind(id)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
-        self.get_department_by_id_simple(id).await
+        // A plain update is a correction, not a restructure: fix the current record in place
+        sqlx::query(
+            r#"
+            UPDATE department_versions
+            SET code = (SELECT code FROM departments WHERE id = ?1),
+                name = (SELECT name FROM departments WHERE id = ?1),
+                parent_id = (SELECT parent_id FROM departments WHERE id = ?1)
+            WHERE department_id = ?1 AND valid_to IS NULL
+        "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(self.get_department_by_id_simple(id).await?)
     }
 
     pub async fn delete_department(&self, id: i32) -> Result<bool, sqlx::Error> {
         let result =
-            sqlx::query("UPDATE departments SET is_active = 0, updated_at = ? WHERE id = ?")
+            sqlx::query("UPDATE departments SET is_active = 0, version = version + 1, updated_at = ? WHERE id = ?")
                 .bind(chrono::Utc::now().naive_utc())
                 .bind(id)
                 .execute(&self.pool)
@@ -340,6 +390,330 @@ impl DepartmentRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Get all departments and sections as they were on `as_of`
+    ///
+    /// Units that did not exist on that date are left out.
+    pub async fn get_all_departments_as_of(
+        &self,
+        as_of: NaiveDate,
+    ) -> Result<Vec<DepartmentWithManager>, sqlx::Error> {
+        let query = format!("{DEPARTMENT_AS_OF_QUERY} ORDER BY v.level, v.code");
+        let rows = sqlx::query(&query)
+            .bind(as_of)
+            .bind(None::<i32>)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(department_with_manager_from_row).collect())
+    }
+
+    /// Get a department as it was on `as_of` (`None` if it did not exist on that date)
+    pub async fn get_department_by_id_as_of(
+        &self,
+        id: i32,
+        as_of: NaiveDate,
+    ) -> Result<Option<DepartmentWithManager>, sqlx::Error> {
+        let row = sqlx::query(DEPARTMENT_AS_OF_QUERY)
+            .bind(as_of)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(department_with_manager_from_row))
+    }
+
+    /// Find the department or section that used `code` on `as_of`
+    ///
+    /// Section codes are only unique within their department, so pass `parent_id` for sections.
+    pub async fn find_department_by_code_as_of(
+        &self,
+        code: &str,
+        kind: OrganizationUnitKind,
+        parent_id: Option<i32>,
+        as_of: NaiveDate,
+    ) -> Result<Option<DepartmentVersion>, sqlx::Error> {
+        sqlx::query_as::<_, DepartmentVersion>(
+            r#"
+            SELECT * FROM department_versions
+            WHERE code = ? AND kind = ? AND (? IS NULL OR parent_id = ?)
+              AND valid_from <= ? AND (valid_to IS NULL OR valid_to > ?)
+            ORDER BY id
+            LIMIT 1
+        "#,
+        )
+        .bind(code)
+        .bind(kind)
+        .bind(parent_id)
+        .bind(parent_id)
+        .bind(as_of)
+        .bind(as_of)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Get the effective-dated records of a department, oldest first
+    pub async fn get_department_history(
+        &self,
+        id: i32,
+    ) -> Result<Vec<DepartmentVersion>, sqlx::Error> {
+        sqlx::query_as::<_, DepartmentVersion>(
+            "SELECT * FROM department_versions WHERE department_id = ? ORDER BY valid_from, id",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// List restructures, newest first (only those involving `department_id` if given)
+    pub async fn list_organization_events(
+        &self,
+        department_id: Option<i32>,
+    ) -> Result<Vec<OrganizationEvent>, sqlx::Error> {
+        let mut events = sqlx::query_as::<_, OrganizationEvent>(
+            r#"
+            SELECT * FROM organization_events
+            WHERE ? IS NULL OR id IN (
+                SELECT event_id FROM organization_event_units WHERE department_id = ?
+            )
+            ORDER BY effective_date DESC, id DESC
+        "#,
+        )
+        .bind(department_id)
+        .bind(department_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for event in &mut events {
+            let units = sqlx::query_as::<_, (i32, String)>(
+                "SELECT department_id, role FROM organization_event_units WHERE event_id = ? ORDER BY department_id",
+            )
+            .bind(event.id)
+            .fetch_all(&self.pool)
+            .await?;
+            for (department_id, role) in units {
+                match role.as_str() {
+                    "source" => event.source_ids.push(department_id),
+                    _ => event.target_ids.push(department_id),
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Record a restructure and update the effective-dated records from its effective date
+    ///
+    /// Restructures must be recorded in date order: the effective date has to be after the
+    /// start of every affected unit's current record. Abolished units (merge and split sources)
+    /// are deactivated, and the sections of a merged department move to the merge target.
+    pub async fn restructure(
+        &self,
+        request: &RestructureRequest,
+    ) -> Result<OrganizationEvent, DepartmentError> {
+        request.validate().map_err(DepartmentError::Validation)?;
+
+        let date = request.effective_date;
+        let now = chrono::Utc::now().naive_utc();
+        let mut tx = self.pool.begin().await?;
+
+        let event_id = sqlx::query(
+            "INSERT INTO organization_events (event_type, effective_date, reason, recorded_by) VALUES (?, ?, ?, ?)",
+        )
+        .bind(request.change.event_type())
+        .bind(date)
+        .bind(&request.reason)
+        .bind(request.recorded_by)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid() as i32;
+
+        let (source_ids, target_ids) = match &request.change {
+            RestructureChange::Rename {
+                department_id,
+                code,
+                name,
+            } => {
+                let current = current_version(&mut tx, *department_id, date).await?;
+                let code = non_blank(code).unwrap_or(&current.code).to_string();
+                let name = non_blank(name).unwrap_or(&current.name).to_string();
+                ensure_code_available(
+                    &mut tx,
+                    &code,
+                    current.kind,
+                    current.parent_id,
+                    date,
+                    *department_id,
+                )
+                .await?;
+
+                let renamed = DepartmentVersion {
+                    code,
+                    name,
+                    ..current.clone()
+                };
+                replace_version(&mut tx, &current, &renamed, date, event_id, now).await?;
+                (vec![*department_id], vec![*department_id])
+            }
+            RestructureChange::Move {
+                department_id,
+                parent_id,
+            } => {
+                let current = current_version(&mut tx, *department_id, date).await?;
+                let level = match parent_id {
+                    Some(parent_id) => {
+                        let parent = current_version(&mut tx, *parent_id, date).await?;
+                        if parent.kind == OrganizationUnitKind::Section {
+                            return Err(DepartmentError::Validation(
+                                "a section cannot be a parent".to_string(),
+                            ));
+                        }
+                        ensure_not_descendant(&mut tx, *department_id, *parent_id).await?;
+                        parent.level + 1
+                    }
+                    None if current.kind == OrganizationUnitKind::Section => {
+                        return Err(DepartmentError::Validation(
+                            "a section must belong to a department".to_string(),
+                        ));
+                    }
+                    None => 0,
+                };
+                ensure_code_available(
+                    &mut tx,
+                    &current.code,
+                    current.kind,
+                    *parent_id,
+                    date,
+                    *department_id,
+                )
+                .await?;
+
+                let moved = DepartmentVersion {
+                    parent_id: *parent_id,
+                    level,
+                    ..current.clone()
+                };
+                replace_version(&mut tx, &current, &moved, date, event_id, now).await?;
+                (vec![*department_id], vec![*department_id])
+            }
+            RestructureChange::Merge {
+                source_ids,
+                target_id,
+            } => {
+                let target = current_version(&mut tx, *target_id, date).await?;
+                for source_id in source_ids {
+                    let source = current_version(&mut tx, *source_id, date).await?;
+                    if source.kind != target.kind {
+                        return Err(DepartmentError::Validation(
+                            "a department and a section cannot be merged".to_string(),
+                        ));
+                    }
+
+                    // Sections of an absorbed department continue under the target
+                    for child in current_children(&mut tx, *source_id, date).await? {
+                        ensure_code_available(
+                            &mut tx,
+                            &child.code,
+                            child.kind,
+                            Some(*target_id),
+                            date,
+                            child.department_id,
+                        )
+                        .await?;
+                        let moved = DepartmentVersion {
+                            parent_id: Some(*target_id),
+                            level: target.level + 1,
+                            ..child.clone()
+                        };
+                        replace_version(&mut tx, &child, &moved, date, event_id, now).await?;
+                    }
+
+                    abolish(&mut tx, &source, date, now).await?;
+                }
+                (source_ids.clone(), vec![*target_id])
+            }
+            RestructureChange::Split { source_id, units } => {
+                let source = current_version(&mut tx, *source_id, date).await?;
+                if !current_children(&mut tx, *source_id, date)
+                    .await?
+                    .is_empty()
+                {
+                    return Err(DepartmentError::Validation(
+                        "move or merge the sections of a department before splitting it"
+                            .to_string(),
+                    ));
+                }
+                abolish(&mut tx, &source, date, now).await?;
+
+                let mut target_ids = Vec::with_capacity(units.len());
+                for unit in units {
+                    let code = unit.code.trim();
+                    let name = unit.name.trim();
+                    ensure_code_available(&mut tx, code, source.kind, source.parent_id, date, 0)
+                        .await?;
+
+                    let department_id = sqlx::query(
+                        r#"
+                        INSERT INTO departments (code, name, kind, parent_id, level, created_date, created_at, updated_at)
+                        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                    )
+                    .bind(code)
+                    .bind(name)
+                    .bind(source.kind)
+                    .bind(source.parent_id)
+                    .bind(source.level)
+                    .bind(date)
+                    .bind(now)
+                    .bind(now)
+                    .execute(&mut *tx)
+                    .await?
+                    .last_insert_rowid() as i32;
+
+                    insert_version(
+                        &mut tx,
+                        &DepartmentVersion {
+                            department_id,
+                            code: code.to_string(),
+                            name: name.to_string(),
+                            ..source.clone()
+                        },
+                        date,
+                        event_id,
+                    )
+                    .await?;
+                    target_ids.push(department_id);
+                }
+                (vec![*source_id], target_ids)
+            }
+        };
+
+        for (role, ids) in [("source", &source_ids), ("target", &target_ids)] {
+            for department_id in ids {
+                sqlx::query(
+                    "INSERT INTO organization_event_units (event_id, department_id, role) VALUES (?, ?, ?)",
+                )
+                .bind(event_id)
+                .bind(department_id)
+                .bind(role)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        let mut event = sqlx::query_as::<_, OrganizationEvent>(
+            "SELECT * FROM organization_events WHERE id = ?",
+        )
+        .bind(event_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        event.source_ids = source_ids;
+        event.target_ids = target_ids;
+
+        tx.commit().await?;
+
+        Ok(event)
+    }
+
     async fn get_department_by_id_simple(
         &self,
         id: i32,
@@ -352,3 +726,285 @@ impl DepartmentRepository {
         Ok(department)
     }
 }
+
+/// Departments joined with the record valid on `?1` (the parent name is also taken as of `?1`)
+///
+/// `?2` restricts the result to one department when set. `employee_count` counts the primary
+/// assignments valid on `?1`.
+const DEPARTMENT_AS_OF_QUERY: &str = r#"
+    SELECT
+        d.id,
+        v.code,
+        v.name,
+        v.kind,
+        v.parent_id,
+        parent_version.name as parent_name,
+        v.level,
+        d.manager_id,
+        manager.name as manager_name,
+        d.description,
+        d.location,
+        d.phone_number,
+        d.email,
+        d.budget,
+        CASE WHEN v.valid_to IS NULL THEN d.is_active ELSE 1 END as is_active,
+        COUNT(DISTINCT assignment.employee_id) as employee_count,
+        d.version,
+        d.created_date,
+        d.created_at,
+        d.updated_at
+    FROM departments d
+    JOIN department_versions v
+        ON v.department_id = d.id AND v.valid_from <= ?1 AND (v.valid_to IS NULL OR v.valid_to > ?1)
+    LEFT JOIN department_versions parent_version
+        ON parent_version.department_id = v.parent_id
+        AND parent_version.valid_from <= ?1
+        AND (parent_version.valid_to IS NULL OR parent_version.valid_to > ?1)
+    LEFT JOIN employees manager ON d.manager_id = manager.id
+    LEFT JOIN department_assignments assignment
+        ON assignment.department_id = d.id
+        AND assignment.assignment_type = 'primary'
+        AND assignment.start_date <= ?1
+        AND (assignment.end_date IS NULL OR assignment.end_date >= ?1)
+    WHERE (?2 IS NULL OR d.id = ?2)
+    GROUP BY d.id, v.id, parent_version.id, manager.id
+"#;
+
+fn department_with_manager_from_row(row: &SqliteRow) -> DepartmentWithManager {
+    DepartmentWithManager {
+        id: row.get("id"),
+        code: row.get("code"),
+        name: row.get("name"),
+        kind: row.get("kind"),
+        parent_id: row.get("parent_id"),
+        parent_name: row.get("parent_name"),
+        level: row.get("level"),
+        manager_id: row.get("manager_id"),
+        manager_name: row.get("manager_name"),
+        description: row.get("description"),
+        location: row.get("location"),
+        phone_number: row.get("phone_number"),
+        email: row.get("email"),
+        budget: row.get("budget"),
+        is_active: row.get::<i32, _>("is_active") == 1,
+        employee_count: row.get("employee_count"),
+        version: row.get("version"),
+        created_date: row.get("created_date"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn non_blank(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// The open record of a unit, which a restructure on `date` replaces
+async fn current_version(
+    tx: &mut Transaction<'_, Sqlite>,
+    department_id: i32,
+    date: NaiveDate,
+) -> Result<DepartmentVersion, DepartmentError> {
+    let current = sqlx::query_as::<_, DepartmentVersion>(
+        "SELECT * FROM department_versions WHERE department_id = ? AND valid_to IS NULL",
+    )
+    .bind(department_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    match current {
+        Some(current) if current.valid_from < date => Ok(current),
+        Some(current) => Err(DepartmentError::Validation(format!(
+            "department {department_id} has a record starting {}; restructures must be recorded in date order",
+            current.valid_from
+        ))),
+        None => {
+            let exists: bool =
+                sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM departments WHERE id = ?)")
+                    .bind(department_id)
+                    .fetch_one(&mut **tx)
+                    .await?;
+            if exists {
+                Err(DepartmentError::Validation(format!(
+                    "department {department_id} has already been abolished"
+                )))
+            } else {
+                Err(DepartmentError::NotFound(department_id))
+            }
+        }
+    }
+}
+
+/// Open records of the units directly under `parent_id`
+async fn current_children(
+    tx: &mut Transaction<'_, Sqlite>,
+    parent_id: i32,
+    date: NaiveDate,
+) -> Result<Vec<DepartmentVersion>, DepartmentError> {
+    let children = sqlx::query_as::<_, DepartmentVersion>(
+        "SELECT * FROM department_versions WHERE parent_id = ? AND valid_to IS NULL ORDER BY id",
+    )
+    .bind(parent_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    if let Some(child) = children.iter().find(|child| child.valid_from >= date) {
+        return Err(DepartmentError::Validation(format!(
+            "department {} has a record starting {}; restructures must be recorded in date order",
+            child.department_id, child.valid_from
+        )));
+    }
+
+    Ok(children)
+}
+
+/// Fail if another unit of the same level uses `code` on or after `date`
+async fn ensure_code_available(
+    tx: &mut Transaction<'_, Sqlite>,
+    code: &str,
+    kind: OrganizationUnitKind,
+    parent_id: Option<i32>,
+    date: NaiveDate,
+    except_id: i32,
+) -> Result<(), DepartmentError> {
+    let taken: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM department_versions
+            WHERE code = ? AND kind = ? AND department_id != ?
+              AND (kind = 'department' OR parent_id IS ?)
+              AND (valid_to IS NULL OR valid_to > ?)
+        )
+    "#,
+    )
+    .bind(code)
+    .bind(kind)
+    .bind(except_id)
+    .bind(parent_id)
+    .bind(date)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if taken {
+        return Err(DepartmentError::CodeConflict(format!(
+            "{} code {code} is already used on {date}",
+            kind.as_str()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Fail if `parent_id` is `department_id` itself or one of its descendants
+async fn ensure_not_descendant(
+    tx: &mut Transaction<'_, Sqlite>,
+    department_id: i32,
+    parent_id: i32,
+) -> Result<(), DepartmentError> {
+    let mut ancestor = Some(parent_id);
+    while let Some(id) = ancestor {
+        if id == department_id {
+            return Err(DepartmentError::Validation(
+                "a department cannot move under its own descendant".to_string(),
+            ));
+        }
+        ancestor = sqlx::query_scalar("SELECT parent_id FROM departments WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .flatten();
+    }
+
+    Ok(())
+}
+
+async fn insert_version(
+    tx: &mut Transaction<'_, Sqlite>,
+    version: &DepartmentVersion,
+    valid_from: NaiveDate,
+    event_id: i32,
+) -> Result<(), DepartmentError> {
+    sqlx::query(
+        r#"
+        INSERT INTO department_versions (department_id, code, name, kind, parent_id, level, valid_from, event_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+    "#,
+    )
+    .bind(version.department_id)
+    .bind(&version.code)
+    .bind(&version.name)
+    .bind(version.kind)
+    .bind(version.parent_id)
+    .bind(version.level)
+    .bind(valid_from)
+    .bind(event_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+async fn close_version(
+    tx: &mut Transaction<'_, Sqlite>,
+    version: &DepartmentVersion,
+    valid_to: NaiveDate,
+) -> Result<(), DepartmentError> {
+    sqlx::query("UPDATE department_versions SET valid_to = ? WHERE id = ?")
+        .bind(valid_to)
+        .bind(version.id)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// End `current` the day before `date` and continue the unit as `next`
+async fn replace_version(
+    tx: &mut Transaction<'_, Sqlite>,
+    current: &DepartmentVersion,
+    next: &DepartmentVersion,
+    date: NaiveDate,
+    event_id: i32,
+    now: NaiveDateTime,
+) -> Result<(), DepartmentError> {
+    close_version(tx, current, date).await?;
+    insert_version(tx, next, date, event_id).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE departments
+        SET code = ?, name = ?, parent_id = ?, level = ?, version = version + 1, updated_at = ?
+        WHERE id = ?
+    "#,
+    )
+    .bind(&next.code)
+    .bind(&next.name)
+    .bind(next.parent_id)
+    .bind(next.level)
+    .bind(now)
+    .bind(next.department_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// End `current` the day before `date` and deactivate the unit
+async fn abolish(
+    tx: &mut Transaction<'_, Sqlite>,
+    current: &DepartmentVersion,
+    date: NaiveDate,
+    now: NaiveDateTime,
+) -> Result<(), DepartmentError> {
+    close_version(tx, current, date).await?;
+
+    sqlx::query(
+        "UPDATE departments SET is_active = 0, version = version + 1, updated_at = ? WHERE id = ?",
+    )
+    .bind(now)
+    .bind(current.department_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
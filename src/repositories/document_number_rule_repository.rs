@@ -15,7 +15,17 @@ pub trait DocumentNumberRuleRepository: Send + Sync {
         date: NaiveDate,
     ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError>;
 
-    /// 指定されたルール、年月、部署の組み合わせで次の連番を取得
+    /// 指定された条件に適用可能なすべてのルールを選択される順に取得
+    async fn find_candidate_rules(
+        &self,
+        document_type_code: &str,
+        department_code: &str,
+        date: NaiveDate,
+    ) -> Result<Vec<DocumentNumberGenerationRule>, RepositoryError>;
+
+    /// 指定されたルール、期間、部署の組み合わせで次に採番される連番を取得（採番はしない）
+    ///
+    /// `year`・`month`はルールのリセット単位に応じた期間（`SequenceResetScope::period`）
     async fn get_next_sequence_number(
         &self,
         rule_id: i32,
@@ -50,12 +60,45 @@ pub trait DocumentNumberRuleRepository: Send + Sync {
         limit: i64,
         offset: i64,
     ) -> Result<(Vec<DocumentNumberGenerationRule>, i64), RepositoryError>;
+
+    /// ルールを更新
+    async fn update_rule(
+        &self,
+        id: i32,
+        request: CreateDocumentNumberGenerationRuleRequest,
+    ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError>;
+
+    /// ルールの有効期間終了日を設定して廃止する
+    async fn retire_rule(
+        &self,
+        id: i32,
+        effective_until: NaiveDate,
+    ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError>;
+    /// 文書種別コード（document_typesのprefix）から文書種別名を取得
+    async fn find_document_type_name(
+        &self,
+        document_type_code: &str,
+    ) -> Result<Option<String>, RepositoryError>;
+
+    /// 部署コード・課コードを指定日時点の組織のコードに読み替える
+    ///
+    /// 組織改編の前後どちらのコードで指定されても、その日に有効だったコードを返す。
+    /// 読み替え先が決まらない場合（分割された部署や、存在しなかった時期）は指定されたコードのまま返す
+    async fn resolve_organization_codes(
+        &self,
+        department_code: &str,
+        section_code: Option<String>,
+        date: NaiveDate,
+    ) -> Result<(String, Option<String>), RepositoryError>;
 }
 
+pub(crate) const RULE_COLUMNS: &str = "id, rule_name, template, sequence_digits, department_code, \
+     document_type_codes, effective_from, effective_until, priority, reset_scope, created_at, \
+     updated_at";
+
 // SQLite実装
 
 pub struct SqliteDocumentNumberRuleRepository {
-    #[allow(dead_code)]
     pool: SqlitePool,
 }
 
@@ -73,62 +116,38 @@ impl DocumentNumberRuleRepository for SqliteDocumentNumberRuleRepository {
         department_code: &str,
         date: NaiveDate,
     ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError> {
-        // データベースから適用可能なルールを検索
-        println!(
-            "DEBUG: find_applicable_rule called with doc_type='{}', dept='{}'",
-            document_type_code, department_code
-        );
-
-        // 1. 部署固有ルールを優先検索
-        let specific_rule = sqlx::query_as::<_, DocumentNumberGenerationRule>(
-            r#"
-            SELECT id, rule_name, template, sequence_digits, department_code, 
-                   document_type_codes, effective_from, effective_until, priority, 
-                   created_at, updated_at
-            FROM document_number_generation_rules
-            WHERE department_code = ? 
-              AND JSON_EXTRACT(document_type_codes, '$') LIKE '%' || ? || '%'
-              AND effective_from <= ?
-              AND (effective_until IS NULL OR effective_until >= ?)
-            ORDER BY priority ASC
-            LIMIT 1
-            "#,
-        )
-        .bind(department_code)
-        .bind(document_type_code)
-        .bind(date)
-        .bind(date)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(RepositoryError::Database)?;
-
-        if specific_rule.is_some() {
-            return Ok(specific_rule);
-        }
-
-        // 2. 汎用ルール（department_code = NULL）を検索
-        let generic_rule = sqlx::query_as::<_, DocumentNumberGenerationRule>(
-            r#"
-            SELECT id, rule_name, template, sequence_digits, department_code, 
-                   document_type_codes, effective_from, effective_until, priority, 
-                   created_at, updated_at
-            FROM document_number_generation_rules
-            WHERE department_code IS NULL
-              AND JSON_EXTRACT(document_type_codes, '$') LIKE '%' || ? || '%'
-              AND effective_from <= ?
-              AND (effective_until IS NULL OR effective_until >= ?)
-            ORDER BY priority ASC
-            LIMIT 1
-            "#,
-        )
-        .bind(document_type_code)
-        .bind(date)
-        .bind(date)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(RepositoryError::Database)?;
+        let query = format!("{} LIMIT 1", applicable_rules_query());
+
+        let rule = sqlx::query_as::<_, DocumentNumberGenerationRule>(&query)
+            .bind(department_code)
+            .bind(document_type_code)
+            .bind(date)
+            .bind(date)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        Ok(rule)
+    }
 
-        Ok(generic_rule)
+    async fn find_candidate_rules(
+        &self,
+        document_type_code: &str,
+        department_code: &str,
+        date: NaiveDate,
+    ) -> Result<Vec<DocumentNumberGenerationRule>, RepositoryError> {
+        let query = applicable_rules_query();
+
+        let rules = sqlx::query_as::<_, DocumentNumberGenerationRule>(&query)
+            .bind(department_code)
+            .bind(document_type_code)
+            .bind(date)
+            .bind(date)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        Ok(rules)
     }
 
     async fn get_next_sequence_number(
@@ -136,52 +155,40 @@ impl DocumentNumberRuleRepository for SqliteDocumentNumberRuleRepository {
         rule_id: i32,
         year: i32,
         month: i32,
-        _department_code: &str,
+        department_code: &str,
     ) -> Result<i32, RepositoryError> {
-        // 該当する年月のパターンで既存の文書番号から最大連番を取得
-        let year_month_pattern = format!("{:02}{:02}", year % 100, month);
-        let number_pattern = format!("%-{year_month_pattern}%");
-
-        let max_sequence = sqlx::query_scalar::<_, Option<i32>>(
+        // 連番カウンターの現在値を参照する（カウンターは進めない）
+        let last_sequence = sqlx::query_scalar::<_, i32>(
             r#"
-            SELECT MAX(
-                CAST(
-                    SUBSTR(number, LENGTH(number) - 2, 3) AS INTEGER
-                )
-            ) as max_seq
-            FROM documents 
-            WHERE number LIKE ?
+            SELECT last_sequence FROM document_number_sequences
+            WHERE rule_id = ? AND year = ? AND month = ? AND department_code = ?
             "#,
         )
-        .bind(number_pattern)
+        .bind(rule_id)
+        .bind(year)
+        .bind(month)
+        .bind(department_code)
         .fetch_optional(&self.pool)
         .await
         .map_err(RepositoryError::Database)?
-        .flatten()
         .unwrap_or(0);
 
-        let next_sequence = max_sequence + 1;
-
-        // 連番上限チェック（3桁の場合は999まで）
-        if next_sequence > 999 {
-            return Err(RepositoryError::Validation(format!(
-                "Sequence numbers exhausted for rule {}, year {}, month {}. Maximum reached: 999",
-                rule_id, year, month
-            )));
-        }
-
-        Ok(next_sequence)
+        Ok(last_sequence + 1)
     }
 
     async fn is_document_number_exists(
         &self,
         document_number: &str,
     ) -> Result<bool, RepositoryError> {
-        // データベースで文書番号の存在をチェック
+        // データベースで文書番号の存在をチェック（欠番・旧番号も使用済みとみなす）
         let exists = sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM documents WHERE number = ?)",
+            "SELECT EXISTS(SELECT 1 FROM documents WHERE number = ?) \
+             OR EXISTS(SELECT 1 FROM document_number_voids WHERE document_number = ?) \
+             OR EXISTS(SELECT 1 FROM document_number_aliases WHERE alias_number = ?)",
         )
         .bind(document_number)
+        .bind(document_number)
+        .bind(document_number)
         .fetch_one(&self.pool)
         .await
         .map_err(RepositoryError::Database)?;
@@ -191,29 +198,361 @@ impl DocumentNumberRuleRepository for SqliteDocumentNumberRuleRepository {
 
     async fn create_rule(
         &self,
-        _request: CreateDocumentNumberGenerationRuleRequest,
+        request: CreateDocumentNumberGenerationRuleRequest,
     ) -> Result<DocumentNumberGenerationRule, RepositoryError> {
-        todo!("Not needed for current tests")
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let document_type_codes =
+            serde_json::to_string(&request.document_type_codes).map_err(|e| {
+                RepositoryError::Validation(format!("Invalid document type codes: {e}"))
+            })?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO document_number_generation_rules
+                (rule_name, template, sequence_digits, department_code, document_type_codes,
+                 effective_from, effective_until, priority, reset_scope)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(request.rule_name.trim())
+        .bind(&request.template)
+        .bind(request.sequence_digits)
+        .bind(normalize_department_code(
+            request.department_code.as_deref(),
+        ))
+        .bind(document_type_codes)
+        .bind(request.effective_from)
+        .bind(request.effective_until)
+        .bind(request.priority)
+        .bind(request.reset_scope)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        let id = result.last_insert_rowid() as i32;
+
+        self.get_rule_by_id(id)
+            .await?
+            .ok_or(RepositoryError::NotFound { id: id.to_string() })
     }
 
     async fn get_rule_by_id(
         &self,
-        _id: i32,
+        id: i32,
     ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError> {
-        todo!("Not needed for current tests")
+        let query =
+            format!("SELECT {RULE_COLUMNS} FROM document_number_generation_rules WHERE id = ?");
+
+        let rule = sqlx::query_as::<_, DocumentNumberGenerationRule>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        Ok(rule)
     }
 
     async fn search_rules(
         &self,
-        _department_code: Option<String>,
-        _active_on_date: Option<NaiveDate>,
-        _limit: i64,
-        _offset: i64,
+        department_code: Option<String>,
+        active_on_date: Option<NaiveDate>,
+        limit: i64,
+        offset: i64,
     ) -> Result<(Vec<DocumentNumberGenerationRule>, i64), RepositoryError> {
-        todo!("Not needed for current tests")
+        let mut conditions = String::from(" WHERE 1=1");
+
+        if department_code.is_some() {
+            conditions.push_str(" AND department_code = ?");
+        }
+
+        if active_on_date.is_some() {
+            conditions.push_str(
+                " AND effective_from <= ? AND (effective_until IS NULL OR effective_until >= ?)",
+            );
+        }
+
+        // カウントクエリ実行
+        let count_query =
+            format!("SELECT COUNT(*) FROM document_number_generation_rules{conditions}");
+        let mut count_stmt = sqlx::query_scalar::<_, i64>(&count_query);
+        if let Some(ref code) = department_code {
+            count_stmt = count_stmt.bind(code);
+        }
+        if let Some(date) = active_on_date {
+            count_stmt = count_stmt.bind(date).bind(date);
+        }
+
+        let total = count_stmt
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        // メインクエリ実行
+        let query = format!(
+            "SELECT {RULE_COLUMNS} FROM document_number_generation_rules{conditions} \
+             ORDER BY priority ASC, effective_from DESC, id ASC LIMIT ? OFFSET ?"
+        );
+        let mut stmt = sqlx::query_as::<_, DocumentNumberGenerationRule>(&query);
+        if let Some(ref code) = department_code {
+            stmt = stmt.bind(code);
+        }
+        if let Some(date) = active_on_date {
+            stmt = stmt.bind(date).bind(date);
+        }
+
+        let rules = stmt
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        Ok((rules, total))
+    }
+
+    async fn update_rule(
+        &self,
+        id: i32,
+        request: CreateDocumentNumberGenerationRuleRequest,
+    ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let document_type_codes =
+            serde_json::to_string(&request.document_type_codes).map_err(|e| {
+                RepositoryError::Validation(format!("Invalid document type codes: {e}"))
+            })?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE document_number_generation_rules
+            SET rule_name = ?, template = ?, sequence_digits = ?, department_code = ?,
+                document_type_codes = ?, effective_from = ?, effective_until = ?, priority = ?,
+                reset_scope = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+        )
+        .bind(request.rule_name.trim())
+        .bind(&request.template)
+        .bind(request.sequence_digits)
+        .bind(normalize_department_code(
+            request.department_code.as_deref(),
+        ))
+        .bind(document_type_codes)
+        .bind(request.effective_from)
+        .bind(request.effective_until)
+        .bind(request.priority)
+        .bind(request.reset_scope)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        self.get_rule_by_id(id).await
+    }
+
+    async fn retire_rule(
+        &self,
+        id: i32,
+        effective_until: NaiveDate,
+    ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError> {
+        let Some(rule) = self.get_rule_by_id(id).await? else {
+            return Ok(None);
+        };
+
+        if effective_until <= rule.effective_from {
+            return Err(RepositoryError::Validation(
+                "Effective until date must be after effective from date".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE document_number_generation_rules
+            SET effective_until = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+        )
+        .bind(effective_until)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        self.get_rule_by_id(id).await
+    }
+
+    async fn find_document_type_name(
+        &self,
+        document_type_code: &str,
+    ) -> Result<Option<String>, RepositoryError> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT name FROM document_types WHERE prefix = ? AND is_active = 1",
+        )
+        .bind(document_type_code)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    async fn resolve_organization_codes(
+        &self,
+        department_code: &str,
+        section_code: Option<String>,
+        date: NaiveDate,
+    ) -> Result<(String, Option<String>), RepositoryError> {
+        let department_code = department_code.trim();
+        let (resolved_department, department_id) = self
+            .resolve_unit_code(department_code, "department", None, date)
+            .await?;
+
+        let resolved_section = match section_code {
+            Some(section_code) if !section_code.trim().is_empty() => Some(
+                self.resolve_unit_code(section_code.trim(), "section", department_id, date)
+                    .await?
+                    .0,
+            ),
+            other => other,
+        };
+
+        Ok((resolved_department, resolved_section))
+    }
+}
+
+impl SqliteDocumentNumberRuleRepository {
+    /// 部署・課のコードを指定日に有効だったコードに読み替え、その日の部署・課のIDとともに返す
+    ///
+    /// 課コードは`parent_id`の部署の課の中で探す（部署が決まらない場合はすべての課から探す）
+    async fn resolve_unit_code(
+        &self,
+        code: &str,
+        kind: &str,
+        parent_id: Option<i32>,
+        date: NaiveDate,
+    ) -> Result<(String, Option<i32>), RepositoryError> {
+        let valid_on_date: Option<i32> = sqlx::query_scalar(
+            r#"
+            SELECT department_id FROM department_versions
+            WHERE code = ? AND kind = ? AND (? IS NULL OR parent_id = ?)
+              AND valid_from <= ? AND (valid_to IS NULL OR valid_to > ?)
+            ORDER BY id
+            LIMIT 1
+            "#,
+        )
+        .bind(code)
+        .bind(kind)
+        .bind(parent_id)
+        .bind(parent_id)
+        .bind(date)
+        .bind(date)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+        if valid_on_date.is_some() {
+            return Ok((code.to_string(), valid_on_date));
+        }
+
+        // その日には使われていないコードは、コードを使ったことのある部署・課が1つに決まる場合だけたどる
+        let ever_used: Vec<i32> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT department_id FROM department_versions
+            WHERE code = ? AND kind = ? AND (? IS NULL OR parent_id = ?)
+            "#,
+        )
+        .bind(code)
+        .bind(kind)
+        .bind(parent_id)
+        .bind(parent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+        let [mut department_id] = ever_used[..] else {
+            return Ok((code.to_string(), None));
+        };
+
+        // 統合で廃止されている場合は統合先をたどる（統合の連鎖が循環しないよう回数を制限する）
+        for _ in 0..MAX_MERGE_HOPS {
+            let version: Option<String> = sqlx::query_scalar(
+                r#"
+                SELECT code FROM department_versions
+                WHERE department_id = ? AND valid_from <= ? AND (valid_to IS NULL OR valid_to > ?)
+                "#,
+            )
+            .bind(department_id)
+            .bind(date)
+            .bind(date)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+            if let Some(code) = version {
+                return Ok((code, Some(department_id)));
+            }
+
+            let merged_into: Option<i32> = sqlx::query_scalar(
+                r#"
+                SELECT target.department_id
+                FROM organization_event_units source
+                JOIN organization_events e ON e.id = source.event_id
+                JOIN organization_event_units target
+                    ON target.event_id = e.id AND target.role = 'target'
+                WHERE source.department_id = ? AND source.role = 'source'
+                  AND e.event_type = 'merge' AND e.effective_date <= ?
+                "#,
+            )
+            .bind(department_id)
+            .bind(date)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+            match merged_into {
+                Some(target_id) => department_id = target_id,
+                None => break,
+            }
+        }
+
+        Ok((code.to_string(), None))
     }
 }
 
+/// 統合先をたどる回数の上限
+const MAX_MERGE_HOPS: usize = 10;
+
+/// 部署・文書種別・日付に適用可能なルールを選択される順に並べるクエリ
+///
+/// 部署固有ルールを汎用ルール（department_code = NULL）より優先し、
+/// 同じ区分内では優先度・有効開始日の新しい順で選択する
+fn applicable_rules_query() -> String {
+    format!(
+        r#"
+        SELECT {RULE_COLUMNS}
+        FROM document_number_generation_rules
+        WHERE (department_code = ? OR department_code IS NULL)
+          AND EXISTS (
+              SELECT 1 FROM json_each(document_type_codes) WHERE json_each.value = ?
+          )
+          AND effective_from <= ?
+          AND (effective_until IS NULL OR effective_until >= ?)
+        ORDER BY department_code IS NULL, priority ASC, effective_from DESC, id ASC
+        "#
+    )
+}
+
+/// 空文字の部署コードは全部署共通（NULL）として保存する
+fn normalize_department_code(department_code: Option<&str>) -> Option<String> {
+    department_code
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(str::to_string)
+}
+
 // テスト用のモックリポジトリ（mockallを使用）
 #[cfg(test)]
 pub use mock::*;
@@ -234,6 +573,13 @@ mod mock {
             date: NaiveDate,
         ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError>;
 
+        async fn find_candidate_rules(
+            &self,
+            document_type_code: &str,
+            department_code: &str,
+            date: NaiveDate,
+        ) -> Result<Vec<DocumentNumberGenerationRule>, RepositoryError>;
+
         async fn get_next_sequence_number(
             &self,
             rule_id: i32,
@@ -258,6 +604,30 @@ mod mock {
             limit: i64,
             offset: i64,
         ) -> Result<(Vec<DocumentNumberGenerationRule>, i64), RepositoryError>;
+
+        async fn update_rule(
+            &self,
+            id: i32,
+            request: CreateDocumentNumberGenerationRuleRequest,
+        ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError>;
+
+        async fn retire_rule(
+            &self,
+            id: i32,
+            effective_until: NaiveDate,
+        ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError>;
+
+        async fn find_document_type_name(
+            &self,
+            document_type_code: &str,
+        ) -> Result<Option<String>, RepositoryError>;
+
+        async fn resolve_organization_codes(
+            &self,
+            department_code: &str,
+            section_code: Option<String>,
+            date: NaiveDate,
+        ) -> Result<(String, Option<String>), RepositoryError>;
     }
     }
 }
@@ -1,9 +1,23 @@
 // Document Repository - データベースアクセス層
 
-use crate::models::{CreateDocumentRequest, Document, DocumentSearchFilters};
+use super::document_number_rule_repository::RULE_COLUMNS;
+use crate::models::{
+    AllocatedDocumentDraft, BusinessNumberCount, ChangeDocumentStateRequest, ClearanceLevel,
+    CreateDocumentNumberAliasRequest, CreateDocumentNumberReservationRequest,
+    CreateDocumentRequest, DeletedDocument, Document, DocumentChange, DocumentDepartmentCount,
+    DocumentLocation, DocumentNumberAlias, DocumentNumberFormatter, DocumentNumberGenerationError,
+    DocumentNumberGenerationRule, DocumentNumberOverride, DocumentNumberReservation,
+    DocumentSearchAggregations, DocumentSearchFilters, DocumentSearchHit, DocumentTypeCount,
+    IdempotencyKeyClaim, IdempotentNumberedDocument, ImportanceClassCount, InternalExternalCount,
+    IssuedDocumentNumber, NewVoidedDocumentNumber, NumberGapFilters, ReservationExpiryResult,
+    ReservedDocumentNumber, RuleCollisionFinder, SequenceCounter, SequenceKey,
+    UpdateDocumentRequest, VoidedDocumentNumber, YearCount,
+};
 use async_trait::async_trait;
-use chrono::{DateTime, Datelike, Utc};
-use sqlx::{Row, SqlitePool};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Utc};
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqliteRow};
+use sqlx::{Connection, Row, Sqlite, SqlitePool, Transaction};
 
 // Repository エラー型
 #[derive(Debug, thiserror::Error)]
@@ -14,21 +28,196 @@ pub enum RepositoryError {
     NotFound { id: String },
     #[error("Validation failed: {0}")]
     Validation(String),
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    #[error("Version mismatch: expected {expected}, current {current}")]
+    VersionMismatch { expected: i32, current: i32 },
 }
 
 // Document Repository trait
 #[async_trait]
 pub trait DocumentRepository: Send + Sync {
     async fn create(&self, request: CreateDocumentRequest) -> Result<Document, RepositoryError>;
+    /// 連番カウンターから採番し、同一トランザクション内で文書を所在とともに作成する
+    ///
+    /// `idempotency_key`を指定した場合は、確保済みのキーに作成した文書を同じトランザクションで対応付ける。
+    /// 戻り値は作成された文書と採番された連番
+    async fn create_with_allocated_number(
+        &self,
+        request: CreateDocumentRequest,
+        sequence_key: &SequenceKey,
+        format_number: &DocumentNumberFormatter<'_>,
+        location: &DocumentLocation<'_>,
+        idempotency_key: Option<&str>,
+    ) -> Result<(Document, i32), DocumentNumberGenerationError>;
+    /// 複数の文書を1つのトランザクションで順に採番・作成する
+    ///
+    /// 戻り値は`drafts`と同じ順の行ごとの結果。`all_or_nothing`の場合は最初に失敗した行で処理を止めて
+    /// 全体をロールバックする（戻り値はその行までの結果）。そうでない場合は失敗した行だけを取り消す
+    async fn create_many_with_allocated_numbers(
+        &self,
+        drafts: Vec<AllocatedDocumentDraft<'_>>,
+        all_or_nothing: bool,
+    ) -> Result<Vec<Result<(Document, i32), DocumentNumberGenerationError>>, RepositoryError>;
+    /// 指定された文書番号で文書を作成し、手動採番の監査記録を残す
+    ///
+    /// 現在または将来有効なルールについて`find_collision`が返す連番がまだ採番されていなければ、
+    /// そのルールの将来の採番と衝突するとみなして作成しない
+    async fn create_with_manual_number(
+        &self,
+        request: CreateDocumentRequest,
+        location: &DocumentLocation<'_>,
+        assigned_by: i32,
+        reason: &str,
+        find_collision: &RuleCollisionFinder<'_>,
+    ) -> Result<(Document, DocumentNumberOverride), RepositoryError>;
     async fn get_by_id(&self, id: i32) -> Result<Option<Document>, RepositoryError>;
+    /// 文書のメタデータを更新し、変更した項目ごとに変更履歴を残す
+    ///
+    /// 現在のバージョンが`expected_version`と異なる場合は`VersionMismatch`を返す
+    async fn update(
+        &self,
+        id: i32,
+        request: UpdateDocumentRequest,
+        expected_version: i32,
+    ) -> Result<Document, RepositoryError>;
+    /// 文書を無効にしてゴミ箱へ移動する（論理削除）
+    async fn soft_delete(
+        &self,
+        id: i32,
+        request: ChangeDocumentStateRequest,
+    ) -> Result<Document, RepositoryError>;
+    /// ゴミ箱の文書を有効に戻す（欠番にした番号の文書は戻せない）
+    async fn restore(
+        &self,
+        id: i32,
+        request: ChangeDocumentStateRequest,
+    ) -> Result<Document, RepositoryError>;
+    /// ゴミ箱の文書を削除の新しい順に返す
+    /// ゴミ箱の文書のうち、閲覧に必要な機密レベルが`max_clearance`以下のものを一覧する
+    async fn list_deleted(
+        &self,
+        limit: i64,
+        offset: i64,
+        max_clearance: ClearanceLevel,
+    ) -> Result<(Vec<DeletedDocument>, i64), RepositoryError>;
+    async fn list_changes(&self, document_id: i32) -> Result<Vec<DocumentChange>, RepositoryError>;
+    /// 文書作成のIdempotency-Keyを確保する（期限切れのキーは先に削除する）
+    ///
+    /// 文書を作成しないまま`claimed_until`を過ぎた処理中のキーは引き継いで確保し直す。
+    /// 同じキーが内容の異なるリクエストで使われている、または処理中の場合は`Conflict`を返す
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        request_fingerprint: &str,
+        expires_at: NaiveDateTime,
+        claimed_until: NaiveDateTime,
+    ) -> Result<IdempotencyKeyClaim, RepositoryError>;
+    /// 確保したIdempotency-Keyに作成結果を保存する
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        document_id: i32,
+        response: &str,
+    ) -> Result<(), RepositoryError>;
+    /// 作成に失敗したリクエストのIdempotency-Keyを解放し、再送で作成し直せるようにする
+    ///
+    /// 文書を作成済みのキーは解放しない
+    async fn release_idempotency_key(&self, key: &str) -> Result<(), RepositoryError>;
     async fn search(
         &self,
         filters: DocumentSearchFilters,
     ) -> Result<(Vec<Document>, i64), RepositoryError>;
+    /// 全文検索キーワード（`filters.query`）で検索し、関連度順に抜粋付きで返す
+    async fn full_text_search(
+        &self,
+        filters: DocumentSearchFilters,
+    ) -> Result<(Vec<DocumentSearchHit>, i64), RepositoryError>;
+    /// 検索条件に一致する文書を文書種別・部署・年・重要度・社内外・業務番号ごとに集計する
+    async fn search_aggregations(
+        &self,
+        filters: &DocumentSearchFilters,
+    ) -> Result<DocumentSearchAggregations, RepositoryError>;
     async fn get_document_type_id_by_code(
         &self,
         document_type_code: &str,
     ) -> Result<i32, RepositoryError>;
+    /// 連番カウンターを進めて番号をまとめて予約する
+    async fn reserve_numbers(
+        &self,
+        request: &CreateDocumentNumberReservationRequest,
+        sequence_key: &SequenceKey,
+        format_number: &DocumentNumberFormatter<'_>,
+    ) -> Result<DocumentNumberReservation, DocumentNumberGenerationError>;
+    async fn get_number_reservation(
+        &self,
+        id: i32,
+    ) -> Result<Option<DocumentNumberReservation>, RepositoryError>;
+    /// 予約ブロックを新しい順に返す（期限切れ処理済みのものは include_expired の場合だけ含める）
+    async fn search_number_reservations(
+        &self,
+        rule_id: Option<i32>,
+        department_code: Option<&str>,
+        include_expired: bool,
+    ) -> Result<Vec<DocumentNumberReservation>, RepositoryError>;
+    /// 予約番号（`request.number`）で文書を所在とともに作成し、番号を登録済みにする
+    async fn claim_reserved_number(
+        &self,
+        reservation_id: i32,
+        request: CreateDocumentRequest,
+        location: &DocumentLocation<'_>,
+    ) -> Result<Document, RepositoryError>;
+    /// 期限を過ぎた予約の未使用番号を、予約ごとの指定に従って解放または欠番にする
+    async fn expire_number_reservations(
+        &self,
+        as_of: NaiveDate,
+    ) -> Result<ReservationExpiryResult, RepositoryError>;
+    async fn find_issued_number(
+        &self,
+        document_number: &str,
+    ) -> Result<Option<IssuedDocumentNumber>, RepositoryError>;
+    /// 文書番号または旧番号で文書を取得する（旧番号の場合は現在の文書を返す）
+    async fn get_by_number(
+        &self,
+        document_number: &str,
+    ) -> Result<Option<Document>, RepositoryError>;
+    /// 文書に旧番号を登録する
+    ///
+    /// 文書番号・欠番・予約番号・改訂版の番号と重複する番号は登録できない
+    async fn add_number_alias(
+        &self,
+        document_id: i32,
+        request: CreateDocumentNumberAliasRequest,
+    ) -> Result<DocumentNumberAlias, RepositoryError>;
+    async fn list_number_aliases(
+        &self,
+        document_id: i32,
+    ) -> Result<Vec<DocumentNumberAlias>, RepositoryError>;
+    /// 番号を含む予約ブロックを取得する
+    async fn find_reservation_by_number(
+        &self,
+        document_number: &str,
+    ) -> Result<Option<DocumentNumberReservation>, RepositoryError>;
+    /// 番号を欠番として記録する
+    ///
+    /// 文書があれば無効にし、予約中・解放済みの予約番号は欠番にする。記録済みの番号は再利用されない
+    async fn void_document_number(
+        &self,
+        voided: NewVoidedDocumentNumber,
+    ) -> Result<VoidedDocumentNumber, RepositoryError>;
+    async fn list_voided_numbers(
+        &self,
+        filters: &NumberGapFilters,
+    ) -> Result<Vec<VoidedDocumentNumber>, RepositoryError>;
+    async fn list_sequence_counters(
+        &self,
+        filters: &NumberGapFilters,
+    ) -> Result<Vec<SequenceCounter>, RepositoryError>;
+    /// 登録済みの文書番号を返す（部署を指定した場合は部署が未設定の文書も含める）
+    async fn list_issued_numbers(
+        &self,
+        department_code: Option<&str>,
+    ) -> Result<Vec<IssuedDocumentNumber>, RepositoryError>;
 }
 
 // SQLite実装
@@ -40,6 +229,744 @@ impl SqliteDocumentRepository {
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
     }
+
+    /// 連番カウンターを1つ進め、進めた後の値を返す
+    ///
+    /// カウンター行の更新で書き込みロックを取り、コミットまで他の採番を待たせる
+    /// （採番では先に期限切れ予約を解放するため、トランザクション内の最初の書き込みとは限らない）
+    async fn next_sequence_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        key: &SequenceKey,
+    ) -> Result<i32, RepositoryError> {
+        sqlx::query_scalar::<_, i32>(
+            r#"
+            INSERT INTO document_number_sequences (rule_id, year, month, department_code, last_sequence)
+            VALUES (?, ?, ?, ?, 1)
+            ON CONFLICT (rule_id, year, month, department_code)
+            DO UPDATE SET last_sequence = last_sequence + 1, updated_at = CURRENT_TIMESTAMP
+            RETURNING last_sequence
+            "#,
+        )
+        .bind(key.rule_id)
+        .bind(key.year)
+        .bind(key.month)
+        .bind(&key.department_code)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    /// 期限切れの予約を解放または欠番にする（`key`を指定した場合はその連番カウンターの予約に限る）
+    async fn expire_reservations_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        as_of: NaiveDate,
+        key: Option<&SequenceKey>,
+    ) -> Result<ReservationExpiryResult, RepositoryError> {
+        let expiring = format!(
+            "SELECT id FROM document_number_reservations \
+             WHERE expired_at IS NULL AND expires_on < ?{}",
+            if key.is_some() {
+                " AND rule_id = ? AND year = ? AND month = ? AND department_code = ?"
+            } else {
+                ""
+            }
+        );
+
+        // 欠番にする番号は、状態を更新する前に欠番の記録へ移す
+        let void_query = format!(
+            r#"
+            INSERT INTO document_number_voids (
+                document_number, rule_id, year, month, department_code, sequence_number, reason, voided_by
+            )
+            SELECT n.document_number, r.rule_id, r.year, r.month, r.department_code, n.sequence_number,
+                   '予約期限切れ', r.reserved_by
+            FROM document_number_reserved_numbers n
+            JOIN document_number_reservations r ON r.id = n.reservation_id
+            WHERE n.status = 'reserved' AND r.expiry_action = 'void' AND r.id IN ({expiring})
+            ON CONFLICT (document_number) DO NOTHING
+            "#
+        );
+        bind_reservation_scope(sqlx::query(&void_query), as_of, key)
+            .execute(&mut **tx)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        let expire_query = format!(
+            r#"
+            UPDATE document_number_reserved_numbers
+            SET status = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE status = 'reserved' AND reservation_id IN ({expiring} AND expiry_action = ?)
+            "#
+        );
+        let expire_numbers = |action: &'static str, status: &'static str| {
+            bind_reservation_scope(sqlx::query(&expire_query).bind(status), as_of, key).bind(action)
+        };
+        let released_numbers = expire_numbers("release", "released")
+            .execute(&mut **tx)
+            .await
+            .map_err(RepositoryError::Database)?
+            .rows_affected() as i64;
+        let voided_numbers = expire_numbers("void", "voided")
+            .execute(&mut **tx)
+            .await
+            .map_err(RepositoryError::Database)?
+            .rows_affected() as i64;
+
+        let expire_reservations_query = format!(
+            "UPDATE document_number_reservations SET expired_at = CURRENT_TIMESTAMP \
+             WHERE id IN ({expiring})"
+        );
+        let expired_reservations =
+            bind_reservation_scope(sqlx::query(&expire_reservations_query), as_of, key)
+                .execute(&mut **tx)
+                .await
+                .map_err(RepositoryError::Database)?
+                .rows_affected() as i64;
+
+        Ok(ReservationExpiryResult {
+            expired_reservations,
+            released_numbers,
+            voided_numbers,
+        })
+    }
+
+    /// 期限切れで解放された予約番号のうち最小の連番を再発行済みにし、その行IDと連番を返す
+    async fn reissue_released_number_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        key: &SequenceKey,
+    ) -> Result<Option<(i32, i32)>, RepositoryError> {
+        sqlx::query_as::<_, (i32, i32)>(
+            r#"
+            UPDATE document_number_reserved_numbers
+            SET status = 'reissued', updated_at = CURRENT_TIMESTAMP
+            WHERE id = (
+                SELECT n.id
+                FROM document_number_reserved_numbers n
+                JOIN document_number_reservations r ON r.id = n.reservation_id
+                WHERE r.rule_id = ? AND r.year = ? AND r.month = ? AND r.department_code = ?
+                  AND n.status = 'released'
+                ORDER BY n.sequence_number
+                LIMIT 1
+            )
+            RETURNING id, sequence_number
+            "#,
+        )
+        .bind(key.rule_id)
+        .bind(key.year)
+        .bind(key.month)
+        .bind(&key.department_code)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    /// 予約ブロックに含まれる番号を連番順に読み込む
+    async fn load_reserved_numbers(
+        &self,
+        reservation: &mut DocumentNumberReservation,
+    ) -> Result<(), RepositoryError> {
+        reservation.numbers = sqlx::query_as::<_, ReservedDocumentNumber>(
+            r#"
+            SELECT document_number, sequence_number, status, document_id, updated_at
+            FROM document_number_reserved_numbers
+            WHERE reservation_id = ?
+            ORDER BY sequence_number
+            "#,
+        )
+        .bind(reservation.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        Ok(())
+    }
+
+    /// トランザクション内で文書を取得する（存在しない場合はNotFound）
+    async fn fetch_document_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: i32,
+    ) -> Result<Document, RepositoryError> {
+        let row = sqlx::query(&format!("{DOCUMENT_SELECT} WHERE d.id = ?"))
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(RepositoryError::Database)?
+            .ok_or_else(|| RepositoryError::NotFound { id: id.to_string() })?;
+
+        document_from_row(&row)
+    }
+
+    /// 文書の有効・無効を切り替え、削除または復元として変更履歴に残す
+    async fn change_state_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: i32,
+        is_active: bool,
+        request: &ChangeDocumentStateRequest,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "UPDATE documents SET is_active = ?, version = version + 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        )
+        .bind(is_active)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        sqlx::query(
+            "INSERT INTO document_changes (document_id, change_type, changed_by, reason) VALUES (?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(if is_active { "restored" } else { "deleted" })
+        .bind(request.changed_by)
+        .bind(&request.reason)
+        .execute(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        Ok(())
+    }
+
+    /// 連番カウンターから採番して文書を登録する（トランザクション内）
+    async fn allocate_and_insert_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        request: &CreateDocumentRequest,
+        sequence_key: &SequenceKey,
+        format_number: &DocumentNumberFormatter<'_>,
+        location: &DocumentLocation<'_>,
+    ) -> Result<(Document, i32), DocumentNumberGenerationError> {
+        // この連番カウンターの期限切れの予約を先に解放し、解放された番号から採番されるようにする
+        Self::expire_reservations_in_tx(tx, chrono::Local::now().date_naive(), Some(sequence_key))
+            .await?;
+
+        // 期限切れで解放された予約番号があれば、カウンターを進める前に再利用する
+        // カウンター導入前の文書や欠番・旧番号と衝突する番号は読み飛ばして次の連番を使う
+        let (document_number, sequence_number, reissued_id) = loop {
+            let (sequence_number, reissued_id) =
+                match Self::reissue_released_number_in_tx(tx, sequence_key).await? {
+                    Some((id, sequence_number)) => (sequence_number, Some(id)),
+                    None => (Self::next_sequence_in_tx(tx, sequence_key).await?, None),
+                };
+            let document_number = format_number(sequence_number)?;
+
+            let exists = sqlx::query_scalar::<_, bool>(NUMBER_TAKEN_QUERY)
+                .bind(&document_number)
+                .bind(&document_number)
+                .bind(&document_number)
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(RepositoryError::Database)?;
+
+            if !exists {
+                break (document_number, sequence_number, reissued_id);
+            }
+        };
+
+        let document = Self::insert_document_in_tx(tx, &document_number, request, location).await?;
+
+        if let Some(id) = reissued_id {
+            sqlx::query("UPDATE document_number_reserved_numbers SET document_id = ? WHERE id = ?")
+                .bind(document.id)
+                .bind(id)
+                .execute(&mut **tx)
+                .await
+                .map_err(RepositoryError::Database)?;
+        }
+
+        Ok((document, sequence_number))
+    }
+
+    /// 現在または将来有効なルールが、まだ採番していない連番でこの番号を生成し得ないことを確認する
+    async fn ensure_no_future_rule_collision_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        document_number: &str,
+        find_collision: &RuleCollisionFinder<'_>,
+    ) -> Result<(), RepositoryError> {
+        let rules = sqlx::query_as::<_, DocumentNumberGenerationRule>(&format!(
+            "SELECT {RULE_COLUMNS} FROM document_number_generation_rules \
+             WHERE effective_until IS NULL OR effective_until >= ?"
+        ))
+        .bind(chrono::Local::now().date_naive())
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        for rule in &rules {
+            let Some((key, sequence_number)) = find_collision(rule) else {
+                continue;
+            };
+            let last_sequence = sqlx::query_scalar::<_, i32>(
+                r#"
+                SELECT last_sequence FROM document_number_sequences
+                WHERE rule_id = ? AND year = ? AND month = ? AND department_code = ?
+                "#,
+            )
+            .bind(key.rule_id)
+            .bind(key.year)
+            .bind(key.month)
+            .bind(&key.department_code)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(RepositoryError::Database)?
+            .unwrap_or(0);
+
+            if sequence_number > last_sequence {
+                return Err(RepositoryError::Conflict(format!(
+                    "Document number {document_number} collides with future output of rule '{}'",
+                    rule.rule_name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 番号を確定した文書をトランザクション内で挿入し、挿入後のレコードを返す
+    async fn insert_document_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        document_number: &str,
+        request: &CreateDocumentRequest,
+        location: &DocumentLocation<'_>,
+    ) -> Result<Document, RepositoryError> {
+        let network_path = (location.format_path)(document_number);
+        let result = sqlx::query(
+            r#"
+            INSERT INTO documents (number, title, document_type_id, business_number, created_by, created_date, internal_external, importance_class, personal_info, notes, department_code, network_path, is_active)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(document_number)
+        .bind(&request.title)
+        .bind(request.document_type_id)
+        .bind(&request.business_number)
+        .bind(request.created_by)
+        .bind(request.created_date.format("%Y-%m-%d").to_string())
+        .bind(request.internal_external)
+        .bind(request.importance_class)
+        .bind(request.personal_info)
+        .bind(&request.notes)
+        .bind(location.department_code)
+        .bind(network_path)
+        .bind(true)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                RepositoryError::Conflict(format!("Document number {document_number} already exists"))
+            }
+            e => RepositoryError::Database(e),
+        })?;
+
+        let row = sqlx::query(&format!("{DOCUMENT_SELECT} WHERE d.id = ?"))
+            .bind(result.last_insert_rowid())
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        document_from_row(&row)
+    }
+
+    /// 検索条件に一致する文書を集計軸ごとにグループ化し、件数の多い順に返す
+    async fn fetch_facet(
+        &self,
+        facet: &FacetQuery,
+        filters: &DocumentSearchFilters,
+        full_text: Option<&FullTextQuery>,
+    ) -> Result<Vec<SqliteRow>, RepositoryError> {
+        let fts_join = if full_text.is_some() {
+            " JOIN documents_fts ON documents_fts.rowid = d.id"
+        } else {
+            ""
+        };
+        let mut query = format!(
+            "SELECT {}, COUNT(*) AS count FROM documents d{}{fts_join} WHERE 1=1{}",
+            facet.columns, facet.joins, facet.condition
+        );
+        push_search_conditions(&mut query, filters, full_text);
+        query.push_str(&format!(
+            " GROUP BY {group_by} ORDER BY count DESC, {group_by}",
+            group_by = facet.group_by
+        ));
+        if let Some(limit) = facet.limit {
+            query.push_str(&format!(" LIMIT {limit}"));
+        }
+
+        bind_search_filters(sqlx::query(&query), filters, full_text)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)
+    }
+}
+
+/// 文書の閲覧に必要な機密レベル（`Document::required_clearance`と同じ判定）
+const REQUIRED_CLEARANCE_SQL: &str = "CASE \
+     WHEN d.importance_class = 'class1' THEN 2 \
+     WHEN d.internal_external = 'internal' THEN 1 \
+     ELSE 0 END";
+
+/// 文書の作成日に社員が所属していた部署の文書か（社員IDを1回バインドする）
+///
+/// 文書の部署コードは作成時点のコードのため、所属部署の作成日時点の記録と照合する
+const AFFILIATED_DEPARTMENT_SQL: &str = "EXISTS (\
+     SELECT 1 FROM department_assignments a \
+     JOIN department_versions v ON v.department_id = a.department_id \
+     WHERE a.employee_id = ? \
+       AND a.start_date <= d.created_date \
+       AND (a.end_date IS NULL OR a.end_date >= d.created_date) \
+       AND v.code = d.department_code AND v.kind = 'department' \
+       AND v.valid_from <= d.created_date \
+       AND (v.valid_to IS NULL OR v.valid_to > d.created_date))";
+
+const DOCUMENT_SELECT: &str = "SELECT d.id, d.number, d.title, d.document_type_id, d.business_number, d.created_by, e.name as created_by_name, d.created_date, d.internal_external, d.importance_class, d.personal_info, d.notes, d.network_path, d.is_active, d.version, d.created_at, d.updated_at FROM documents d LEFT JOIN employees e ON d.created_by = e.id";
+
+/// 採番した番号が登録済みの文書・欠番・旧番号と衝突するか（番号を3回バインドする）
+const NUMBER_TAKEN_QUERY: &str = "SELECT EXISTS(SELECT 1 FROM documents WHERE number = ?) \
+     OR EXISTS(SELECT 1 FROM document_number_voids WHERE document_number = ?) \
+     OR EXISTS(SELECT 1 FROM document_number_aliases WHERE alias_number = ?)";
+
+/// 文書検索の集計軸
+struct FacetQuery {
+    columns: &'static str,
+    joins: &'static str,
+    /// 集計対象を絞り込む追加条件
+    condition: &'static str,
+    group_by: &'static str,
+    limit: Option<i64>,
+}
+
+const DOCUMENT_TYPE_FACET: FacetQuery = FacetQuery {
+    columns: "d.document_type_id, dt.prefix AS document_type_code, dt.name AS document_type_name",
+    joins: " LEFT JOIN document_types dt ON dt.id = d.document_type_id",
+    condition: "",
+    group_by: "d.document_type_id",
+    limit: None,
+};
+
+const DEPARTMENT_FACET: FacetQuery = FacetQuery {
+    columns: "d.department_code, MAX(dep.name) AS department_name",
+    joins: " LEFT JOIN departments dep ON dep.code = d.department_code",
+    condition: "",
+    group_by: "d.department_code",
+    limit: None,
+};
+
+const YEAR_FACET: FacetQuery = FacetQuery {
+    columns: "CAST(strftime('%Y', d.created_date) AS INTEGER) AS year",
+    joins: "",
+    condition: "",
+    group_by: "year",
+    limit: None,
+};
+
+const IMPORTANCE_CLASS_FACET: FacetQuery = FacetQuery {
+    columns: "d.importance_class",
+    joins: "",
+    condition: "",
+    group_by: "d.importance_class",
+    limit: None,
+};
+
+const INTERNAL_EXTERNAL_FACET: FacetQuery = FacetQuery {
+    columns: "d.internal_external",
+    joins: "",
+    condition: "",
+    group_by: "d.internal_external",
+    limit: None,
+};
+
+/// 業務番号は件数の多い上位のみ返す
+const BUSINESS_NUMBER_FACET: FacetQuery = FacetQuery {
+    columns: "d.business_number",
+    joins: "",
+    condition: " AND d.business_number IS NOT NULL",
+    group_by: "d.business_number",
+    limit: Some(20),
+};
+
+/// 全文検索の関連度（bm25、小さいほど関連が高い）。タイトル・業務番号の一致を重く評価する
+const FULL_TEXT_RANK_SQL: &str = "bm25(documents_fts, 10.0, 1.0, 5.0, 2.0)";
+
+/// 一致箇所を<mark>で囲んだ抜粋
+const FULL_TEXT_SNIPPET_SQL: &str = "snippet(documents_fts, -1, '<mark>', '</mark>', '…', 16)";
+
+/// trigramトークナイザーで索引検索できる最短の語の長さ
+const TRIGRAM_MIN_CHARS: usize = 3;
+
+/// 全文検索キーワードの検索方法
+enum FullTextQuery {
+    /// FTS5のMATCH式（全ての語が3文字以上の場合）
+    Match(String),
+    /// 索引を使えない短い語を含む場合の部分一致（LIKEパターン）
+    Like(Vec<String>),
+}
+
+impl FullTextQuery {
+    /// 空白区切りの語をすべて含む文書を探す検索方法を決める（語が無い場合はNone）
+    fn parse(query: &str) -> Option<Self> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return None;
+        }
+
+        if terms
+            .iter()
+            .all(|term| term.chars().count() >= TRIGRAM_MIN_CHARS)
+        {
+            // 各語をフレーズとして引用し、FTS5の演算子として解釈させない
+            let expression = terms
+                .iter()
+                .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+                .collect::<Vec<_>>()
+                .join(" ");
+            Some(FullTextQuery::Match(expression))
+        } else {
+            Some(FullTextQuery::Like(
+                terms
+                    .iter()
+                    .map(|term| {
+                        let escaped = term
+                            .replace('\\', "\\\\")
+                            .replace('%', "\\%")
+                            .replace('_', "\\_");
+                        format!("%{escaped}%")
+                    })
+                    .collect(),
+            ))
+        }
+    }
+}
+
+/// 検索条件をWHERE句に追加する（`bind_search_filters`と同じ順序でプレースホルダーを並べる）
+fn push_search_conditions(
+    sql: &mut String,
+    filters: &DocumentSearchFilters,
+    full_text: Option<&FullTextQuery>,
+) {
+    // ゴミ箱の文書・取り下げた文書は検索しない
+    sql.push_str(" AND d.is_active = 1");
+
+    match full_text {
+        Some(FullTextQuery::Match(_)) => sql.push_str(" AND documents_fts MATCH ?"),
+        Some(FullTextQuery::Like(patterns)) => {
+            for _ in patterns {
+                sql.push_str(
+                    " AND (documents_fts.title LIKE ? ESCAPE '\\' \
+                     OR documents_fts.notes LIKE ? ESCAPE '\\' \
+                     OR documents_fts.business_number LIKE ? ESCAPE '\\' \
+                     OR documents_fts.creator_name LIKE ? ESCAPE '\\')",
+                );
+            }
+        }
+        None => {}
+    }
+
+    if filters.title.is_some() {
+        sql.push_str(" AND d.title LIKE ?");
+    }
+
+    if filters.document_type_id.is_some() {
+        sql.push_str(" AND d.document_type_id = ?");
+    }
+
+    if filters.created_by.is_some() {
+        sql.push_str(" AND d.created_by = ?");
+    }
+
+    if filters.department_code.is_some() {
+        if affiliation_employee_id(filters).is_some() {
+            sql.push_str(&format!(
+                " AND (d.department_code = ? OR {AFFILIATED_DEPARTMENT_SQL})"
+            ));
+        } else {
+            sql.push_str(" AND d.department_code = ?");
+        }
+    }
+
+    if filters.year.is_some() {
+        sql.push_str(" AND CAST(strftime('%Y', d.created_date) AS INTEGER) = ?");
+    }
+
+    if filters.importance_class.is_some() {
+        sql.push_str(" AND d.importance_class = ?");
+    }
+
+    if filters.internal_external.is_some() {
+        sql.push_str(" AND d.internal_external = ?");
+    }
+
+    if filters.business_number.is_some() {
+        sql.push_str(" AND d.business_number = ?");
+    }
+
+    if filters.max_clearance.is_some() {
+        sql.push_str(&format!(" AND {REQUIRED_CLEARANCE_SQL} <= ?"));
+    }
+}
+
+/// 期限切れの予約を選ぶ条件に基準日と連番カウンターのキーをバインドする
+fn bind_reservation_scope<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    as_of: NaiveDate,
+    key: Option<&'q SequenceKey>,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    let query = query.bind(as_of);
+    match key {
+        Some(key) => query
+            .bind(key.rule_id)
+            .bind(key.year)
+            .bind(key.month)
+            .bind(&key.department_code),
+        None => query,
+    }
+}
+
+/// 部署の絞り込みを所属履歴まで広げる場合の社員（部署を指定しない場合は広げるものがないため使わない）
+fn affiliation_employee_id(filters: &DocumentSearchFilters) -> Option<i32> {
+    filters
+        .affiliation_employee_id
+        .filter(|_| filters.include_historical_affiliations && filters.department_code.is_some())
+}
+
+/// `push_search_conditions`で追加したプレースホルダーに値を割り当てる
+fn bind_search_filters<'q>(
+    mut stmt: Query<'q, Sqlite, SqliteArguments<'q>>,
+    filters: &DocumentSearchFilters,
+    full_text: Option<&FullTextQuery>,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    match full_text {
+        Some(FullTextQuery::Match(expression)) => stmt = stmt.bind(expression.clone()),
+        Some(FullTextQuery::Like(patterns)) => {
+            for pattern in patterns {
+                for _ in 0..4 {
+                    stmt = stmt.bind(pattern.clone());
+                }
+            }
+        }
+        None => {}
+    }
+    if let Some(ref title) = filters.title {
+        stmt = stmt.bind(format!("%{title}%"));
+    }
+    if let Some(document_type_id) = filters.document_type_id {
+        stmt = stmt.bind(document_type_id);
+    }
+    if let Some(created_by) = filters.created_by {
+        stmt = stmt.bind(created_by);
+    }
+    if let Some(ref department_code) = filters.department_code {
+        stmt = stmt.bind(department_code.clone());
+    }
+    if let Some(employee_id) = affiliation_employee_id(filters) {
+        stmt = stmt.bind(employee_id);
+    }
+    if let Some(year) = filters.year {
+        stmt = stmt.bind(year);
+    }
+    if let Some(importance_class) = filters.importance_class {
+        stmt = stmt.bind(importance_class);
+    }
+    if let Some(internal_external) = filters.internal_external {
+        stmt = stmt.bind(internal_external);
+    }
+    if let Some(ref business_number) = filters.business_number {
+        stmt = stmt.bind(business_number.clone());
+    }
+    if let Some(max_clearance) = filters.max_clearance {
+        stmt = stmt.bind(max_clearance);
+    }
+    stmt
+}
+
+/// Idempotency-Keyの行から、作成した文書の採番結果を読み出す
+fn idempotent_numbered_document(
+    row: &SqliteRow,
+    document_id: i32,
+) -> Result<IdempotentNumberedDocument, RepositoryError> {
+    let rule_id: Option<i32> = row.get("rule_id");
+    let year: Option<i32> = row.get("sequence_year");
+    let month: Option<i32> = row.get("sequence_month");
+    let department_code: Option<String> = row.get("sequence_department_code");
+    let sequence_number: Option<i32> = row.get("sequence_number");
+    match (rule_id, year, month, department_code, sequence_number) {
+        (Some(rule_id), Some(year), Some(month), Some(department_code), Some(sequence_number)) => {
+            Ok(IdempotentNumberedDocument {
+                document_id,
+                sequence_key: SequenceKey {
+                    rule_id,
+                    year,
+                    month,
+                    department_code,
+                },
+                sequence_number,
+            })
+        }
+        // 採番結果を記録する前に作成された文書は、作成結果を組み立て直せない
+        _ => Err(RepositoryError::Conflict(format!(
+            "Numbering of document {document_id} was not recorded for this idempotency key"
+        ))),
+    }
+}
+
+/// 文書テーブルの行をモデルに変換する
+fn document_from_row(row: &SqliteRow) -> Result<Document, RepositoryError> {
+    Ok(Document {
+        id: row.get("id"),
+        number: row.get("number"),
+        title: row.get("title"),
+        document_type_id: row.get("document_type_id"),
+        business_number: row.get("business_number"),
+        created_by: row.get("created_by"),
+        created_by_name: row.get("created_by_name"),
+        created_date: NaiveDate::parse_from_str(&row.get::<String, _>("created_date"), "%Y-%m-%d")
+            .map_err(|e| RepositoryError::Validation(format!("Invalid date format: {e}")))?,
+        internal_external: row.get("internal_external"),
+        importance_class: row.get("importance_class"),
+        personal_info: row.get("personal_info"),
+        notes: row.get("notes"),
+        network_path: row.get("network_path"),
+        is_active: row.get("is_active"),
+        version: row.get("version"),
+        created_at: parse_timestamp(&row.get::<String, _>("created_at"))?,
+        updated_at: parse_timestamp(&row.get::<String, _>("updated_at"))?,
+    })
+}
+
+/// 文書の項目の変更（変更履歴の1行）
+struct FieldChange {
+    field: &'static str,
+    old_value: Option<String>,
+    new_value: Option<String>,
+}
+
+/// 値が変わる場合だけ項目を書き換え、変更を記録する
+fn apply_change<T: PartialEq>(
+    changes: &mut Vec<FieldChange>,
+    field: &'static str,
+    current: &mut T,
+    new: T,
+    to_text: fn(&T) -> Option<String>,
+) {
+    if *current != new {
+        changes.push(FieldChange {
+            field,
+            old_value: to_text(current),
+            new_value: to_text(&new),
+        });
+        *current = new;
+    }
+}
+
+/// 区分値を変更履歴に残す表記（APIと同じ値）にする
+fn classification_text<T: serde::Serialize>(value: &Option<T>) -> Option<String> {
+    value
+        .as_ref()
+        .and_then(|value| serde_json::to_value(value).ok())
+        .and_then(|value| value.as_str().map(str::to_string))
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, RepositoryError> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .map(|naive| DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc))
+        .map_err(|e| RepositoryError::Validation(format!("Invalid datetime format: {e}")))
 }
 
 #[async_trait]
@@ -99,9 +1026,9 @@ impl DocumentRepository for SqliteDocumentRepository {
         .bind(&request.business_number)
         .bind(request.created_by)
         .bind(request.created_date.format("%Y-%m-%d").to_string())
-        .bind(&request.internal_external)
-        .bind(&request.importance_class)
-        .bind(&request.personal_info)
+        .bind(request.internal_external)
+        .bind(request.importance_class)
+        .bind(request.personal_info)
         .bind(&request.notes)
         .bind(&request.number) // network_pathとして使用
         .bind(true)
@@ -112,231 +1039,822 @@ impl DocumentRepository for SqliteDocumentRepository {
         let id = result.last_insert_rowid() as i32;
 
         // 挿入されたレコードを取得
-        let row = sqlx::query(
-            "SELECT d.id, d.number, d.title, d.document_type_id, d.business_number, d.created_by, e.name as created_by_name, d.created_date, d.internal_external, d.importance_class, d.personal_info, d.notes, d.network_path, d.is_active, d.created_at, d.updated_at FROM documents d LEFT JOIN employees e ON d.created_by = e.id WHERE d.id = ?"
-        )
-        .bind(id)
-        .fetch_one(&self.pool)
-        .await
-        .map_err(RepositoryError::Database)?;
-
-        // レスポンス構築
-        use chrono::{NaiveDate, NaiveDateTime};
-
-        let document = Document {
-            id: row.get("id"),
-            number: row.get("number"),
-            title: row.get("title"),
-            document_type_id: row.get("document_type_id"),
-            business_number: row.get("business_number"),
-            created_by: row.get("created_by"),
-            created_by_name: row.get("created_by_name"),
-            created_date: NaiveDate::parse_from_str(
-                &row.get::<String, _>("created_date"),
-                "%Y-%m-%d",
-            )
-            .map_err(|e| RepositoryError::Validation(format!("Invalid date format: {e}")))?,
-            internal_external: row.get("internal_external"),
-            importance_class: row.get("importance_class"),
-            personal_info: row.get("personal_info"),
-            notes: row.get("notes"),
-            network_path: row.get("network_path"),
-            is_active: row.get("is_active"),
-            created_at: DateTime::<Utc>::from_naive_utc_and_offset(
-                NaiveDateTime::parse_from_str(
-                    &row.get::<String, _>("created_at"),
-                    "%Y-%m-%d %H:%M:%S",
-                )
-                .map_err(|e| {
-                    RepositoryError::Validation(format!("Invalid datetime format: {e}"))
-                })?,
-                Utc,
-            ),
-            updated_at: DateTime::<Utc>::from_naive_utc_and_offset(
-                NaiveDateTime::parse_from_str(
-                    &row.get::<String, _>("updated_at"),
-                    "%Y-%m-%d %H:%M:%S",
-                )
-                .map_err(|e| {
-                    RepositoryError::Validation(format!("Invalid datetime format: {e}"))
-                })?,
-                Utc,
-            ),
-        };
+        let row = sqlx::query(&format!("{DOCUMENT_SELECT} WHERE d.id = ?"))
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
 
-        Ok(document)
+        document_from_row(&row)
     }
 
-    async fn get_by_id(&self, id: i32) -> Result<Option<Document>, RepositoryError> {
-        let row = sqlx::query(
-            "SELECT d.id, d.number, d.title, d.document_type_id, d.business_number, d.created_by, e.name as created_by_name, d.created_date, d.internal_external, d.importance_class, d.personal_info, d.notes, d.network_path, d.is_active, d.created_at, d.updated_at FROM documents d LEFT JOIN employees e ON d.created_by = e.id WHERE d.id = ?"
+    async fn create_with_allocated_number(
+        &self,
+        request: CreateDocumentRequest,
+        sequence_key: &SequenceKey,
+        format_number: &DocumentNumberFormatter<'_>,
+        location: &DocumentLocation<'_>,
+        idempotency_key: Option<&str>,
+    ) -> Result<(Document, i32), DocumentNumberGenerationError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+        let (document, sequence_number) = Self::allocate_and_insert_in_tx(
+            &mut tx,
+            &request,
+            sequence_key,
+            format_number,
+            location,
         )
-        .bind(id)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(RepositoryError::Database)?;
+        .await?;
 
-        if let Some(row) = row {
-            use chrono::{NaiveDate, NaiveDateTime};
+        // 作成結果の保存前に中断しても再送で重複作成しないよう、キーと文書を同じトランザクションで対応付ける
+        if let Some(key) = idempotency_key {
+            let bound = sqlx::query(
+                r#"
+                UPDATE document_idempotency_keys
+                SET document_id = ?, rule_id = ?, sequence_year = ?, sequence_month = ?,
+                    sequence_department_code = ?, sequence_number = ?
+                WHERE idempotency_key = ? AND document_id IS NULL
+                "#,
+            )
+            .bind(document.id)
+            .bind(sequence_key.rule_id)
+            .bind(sequence_key.year)
+            .bind(sequence_key.month)
+            .bind(&sequence_key.department_code)
+            .bind(sequence_number)
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(RepositoryError::Database)?;
 
-            let document = Document {
-                id: row.get("id"),
-                number: row.get("number"),
-                title: row.get("title"),
-                document_type_id: row.get("document_type_id"),
-                business_number: row.get("business_number"),
-                created_by: row.get("created_by"),
-                created_by_name: row.get("created_by_name"),
-                created_date: NaiveDate::parse_from_str(
-                    &row.get::<String, _>("created_date"),
-                    "%Y-%m-%d",
-                )
-                .map_err(|e| RepositoryError::Validation(format!("Invalid date format: {e}")))?,
-                internal_external: row.get("internal_external"),
-                importance_class: row.get("importance_class"),
-                personal_info: row.get("personal_info"),
-                notes: row.get("notes"),
-                network_path: row.get("network_path"),
-                is_active: row.get("is_active"),
-                created_at: DateTime::<Utc>::from_naive_utc_and_offset(
-                    NaiveDateTime::parse_from_str(
-                        &row.get::<String, _>("created_at"),
-                        "%Y-%m-%d %H:%M:%S",
-                    )
-                    .map_err(|e| {
-                        RepositoryError::Validation(format!("Invalid datetime format: {e}"))
-                    })?,
-                    Utc,
-                ),
-                updated_at: DateTime::<Utc>::from_naive_utc_and_offset(
-                    NaiveDateTime::parse_from_str(
-                        &row.get::<String, _>("updated_at"),
-                        "%Y-%m-%d %H:%M:%S",
-                    )
-                    .map_err(|e| {
-                        RepositoryError::Validation(format!("Invalid datetime format: {e}"))
-                    })?,
-                    Utc,
-                ),
-            };
-            Ok(Some(document))
-        } else {
-            Ok(None)
+            // 確保の期限切れで他のリクエストに引き継がれた、または期限切れで削除されたキーでは作成しない
+            if bound.rows_affected() == 0 {
+                return Err(RepositoryError::Conflict(format!(
+                    "Idempotency key {key} is no longer held by this request"
+                ))
+                .into());
+            }
         }
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok((document, sequence_number))
     }
 
-    async fn search(
+    async fn create_many_with_allocated_numbers(
         &self,
-        filters: DocumentSearchFilters,
-    ) -> Result<(Vec<Document>, i64), RepositoryError> {
-        let mut query = "SELECT d.id, d.number, d.title, d.document_type_id, d.business_number, d.created_by, e.name as created_by_name, d.created_date, d.internal_external, d.importance_class, d.personal_info, d.notes, d.network_path, d.is_active, d.created_at, d.updated_at FROM documents d LEFT JOIN employees e ON d.created_by = e.id WHERE 1=1".to_string();
-        let mut count_query = "SELECT COUNT(*) as count FROM documents d WHERE 1=1".to_string();
+        drafts: Vec<AllocatedDocumentDraft<'_>>,
+        all_or_nothing: bool,
+    ) -> Result<Vec<Result<(Document, i32), DocumentNumberGenerationError>>, RepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+        let mut results = Vec::with_capacity(drafts.len());
 
-        // フィルター条件を構築
-        if let Some(ref _title) = filters.title {
-            query.push_str(" AND d.title LIKE ?");
-            count_query.push_str(" AND d.title LIKE ?");
+        for draft in &drafts {
+            // 行ごとのセーブポイントで、失敗した行の採番と登録だけを取り消せるようにする
+            let mut savepoint = tx.begin().await.map_err(RepositoryError::Database)?;
+            let result = match draft.request.validate() {
+                Ok(()) => {
+                    Self::allocate_and_insert_in_tx(
+                        &mut savepoint,
+                        &draft.request,
+                        &draft.sequence_key,
+                        draft.format_number,
+                        &draft.location,
+                    )
+                    .await
+                }
+                Err(e) => Err(RepositoryError::Validation(e.to_string()).into()),
+            };
+
+            match result {
+                Ok(created) => {
+                    savepoint
+                        .commit()
+                        .await
+                        .map_err(RepositoryError::Database)?;
+                    results.push(Ok(created));
+                }
+                Err(e) => {
+                    savepoint
+                        .rollback()
+                        .await
+                        .map_err(RepositoryError::Database)?;
+                    results.push(Err(e));
+                    if all_or_nothing {
+                        tx.rollback().await.map_err(RepositoryError::Database)?;
+                        return Ok(results);
+                    }
+                }
+            }
         }
 
-        if let Some(_document_type_id) = filters.document_type_id {
-            query.push_str(" AND d.document_type_id = ?");
-            count_query.push_str(" AND d.document_type_id = ?");
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(results)
+    }
+
+    async fn create_with_manual_number(
+        &self,
+        request: CreateDocumentRequest,
+        location: &DocumentLocation<'_>,
+        assigned_by: i32,
+        reason: &str,
+        find_collision: &RuleCollisionFinder<'_>,
+    ) -> Result<(Document, DocumentNumberOverride), RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let document_number = request.number.clone().ok_or_else(|| {
+            RepositoryError::Validation("Document number is required".to_string())
+        })?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        // 欠番にした番号は再利用しない
+        let voided = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM document_number_voids WHERE document_number = ?)",
+        )
+        .bind(&document_number)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+        if voided {
+            return Err(RepositoryError::Conflict(format!(
+                "Document number {document_number} has been voided"
+            )));
         }
 
-        if let Some(_created_by) = filters.created_by {
-            query.push_str(" AND d.created_by = ?");
-            count_query.push_str(" AND d.created_by = ?");
+        // 他の文書の旧番号として使われている番号も使わない
+        let alias_of = sqlx::query_scalar::<_, i32>(
+            "SELECT document_id FROM document_number_aliases WHERE alias_number = ?",
+        )
+        .bind(&document_number)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+        if let Some(document_id) = alias_of {
+            return Err(RepositoryError::Conflict(format!(
+                "Document number {document_number} is a former number of document {document_id}"
+            )));
         }
 
-        // LIMIT/OFFSET追加
-        query.push_str(" ORDER BY d.created_at DESC LIMIT ? OFFSET ?");
+        // 文書番号の重複は一意制約で検出する
+        let document =
+            Self::insert_document_in_tx(&mut tx, &document_number, &request, location).await?;
 
-        // カウントクエリ実行
-        let mut count_stmt = sqlx::query(&count_query);
-        if let Some(ref title) = filters.title {
-            count_stmt = count_stmt.bind(format!("%{title}%"));
+        // 改訂版・ドラフト版の番号とも重複させない
+        let used_by_revision = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM document_revisions WHERE number = ?)",
+        )
+        .bind(&document_number)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+        if used_by_revision {
+            return Err(RepositoryError::Conflict(format!(
+                "Document number {document_number} is already used by a revision"
+            )));
+        }
+
+        // 挿入で書き込みロックを取得した後に確認し、並行するルールの作成や採番と競合させない
+        Self::ensure_no_future_rule_collision_in_tx(&mut tx, &document_number, find_collision)
+            .await?;
+
+        let number_override = sqlx::query_as::<_, DocumentNumberOverride>(
+            r#"
+            INSERT INTO document_number_overrides (document_id, document_number, assigned_by, reason)
+            VALUES (?, ?, ?, ?)
+            RETURNING id, document_id, document_number, assigned_by, reason, created_at
+            "#,
+        )
+        .bind(document.id)
+        .bind(&document_number)
+        .bind(assigned_by)
+        .bind(reason)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok((document, number_override))
+    }
+
+    async fn get_by_id(&self, id: i32) -> Result<Option<Document>, RepositoryError> {
+        let row = sqlx::query(&format!("{DOCUMENT_SELECT} WHERE d.id = ?"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        row.as_ref().map(document_from_row).transpose()
+    }
+
+    async fn update(
+        &self,
+        id: i32,
+        request: UpdateDocumentRequest,
+        expected_version: i32,
+    ) -> Result<Document, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let current = Self::fetch_document_in_tx(&mut tx, id).await?;
+        if current.version != expected_version {
+            return Err(RepositoryError::VersionMismatch {
+                expected: expected_version,
+                current: current.version,
+            });
         }
-        if let Some(document_type_id) = filters.document_type_id {
-            count_stmt = count_stmt.bind(document_type_id);
+        if !current.is_active {
+            return Err(RepositoryError::Conflict(format!(
+                "Document {id} is deleted and must be restored before updating"
+            )));
         }
-        if let Some(created_by) = filters.created_by {
-            count_stmt = count_stmt.bind(created_by);
+
+        if let Some(document_type_id) = request.document_type_id {
+            let exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM document_types WHERE id = ?)",
+            )
+            .bind(document_type_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(RepositoryError::Database)?;
+            if !exists {
+                return Err(RepositoryError::Validation(format!(
+                    "Document type {document_type_id} does not exist"
+                )));
+            }
         }
 
-        let count_row = count_stmt
-            .fetch_one(&self.pool)
+        let mut document = current;
+        let mut changes = Vec::new();
+        if let Some(title) = request.title {
+            apply_change(
+                &mut changes,
+                "title",
+                &mut document.title,
+                title.trim().to_string(),
+                |v| Some(v.clone()),
+            );
+        }
+        if let Some(document_type_id) = request.document_type_id {
+            apply_change(
+                &mut changes,
+                "document_type_id",
+                &mut document.document_type_id,
+                document_type_id,
+                |v| Some(v.to_string()),
+            );
+        }
+        if let Some(business_number) = request.business_number {
+            apply_change(
+                &mut changes,
+                "business_number",
+                &mut document.business_number,
+                business_number.map(|v| v.trim().to_string()),
+                Clone::clone,
+            );
+        }
+        if let Some(internal_external) = request.internal_external {
+            apply_change(
+                &mut changes,
+                "internal_external",
+                &mut document.internal_external,
+                internal_external,
+                classification_text,
+            );
+        }
+        if let Some(importance_class) = request.importance_class {
+            apply_change(
+                &mut changes,
+                "importance_class",
+                &mut document.importance_class,
+                importance_class,
+                classification_text,
+            );
+        }
+        if let Some(personal_info) = request.personal_info {
+            apply_change(
+                &mut changes,
+                "personal_info",
+                &mut document.personal_info,
+                personal_info,
+                classification_text,
+            );
+        }
+        if let Some(notes) = request.notes {
+            apply_change(
+                &mut changes,
+                "notes",
+                &mut document.notes,
+                notes,
+                Clone::clone,
+            );
+        }
+        if let Some(network_path) = request.network_path {
+            apply_change(
+                &mut changes,
+                "network_path",
+                &mut document.network_path,
+                network_path.map(|v| v.trim().to_string()),
+                Clone::clone,
+            );
+        }
+
+        // 値が変わらない更新は記録しない
+        if changes.is_empty() {
+            return Ok(document);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET title = ?, document_type_id = ?, business_number = ?, internal_external = ?,
+                importance_class = ?, personal_info = ?, notes = ?, network_path = ?,
+                version = version + 1, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND version = ?
+            "#,
+        )
+        .bind(&document.title)
+        .bind(document.document_type_id)
+        .bind(&document.business_number)
+        .bind(document.internal_external)
+        .bind(document.importance_class)
+        .bind(document.personal_info)
+        .bind(&document.notes)
+        .bind(&document.network_path)
+        .bind(id)
+        .bind(expected_version)
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        for change in &changes {
+            sqlx::query(
+                r#"
+                INSERT INTO document_changes (document_id, change_type, field_name, old_value, new_value, changed_by, reason)
+                VALUES (?, 'updated', ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(id)
+            .bind(change.field)
+            .bind(&change.old_value)
+            .bind(&change.new_value)
+            .bind(request.updated_by)
+            .bind(&request.reason)
+            .execute(&mut *tx)
             .await
             .map_err(RepositoryError::Database)?;
-        let total: i64 = count_row.get("count");
+        }
 
-        // メインクエリ実行
-        let mut stmt = sqlx::query(&query);
-        if let Some(ref title) = filters.title {
-            stmt = stmt.bind(format!("%{title}%"));
+        let document = Self::fetch_document_in_tx(&mut tx, id).await?;
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(document)
+    }
+
+    async fn soft_delete(
+        &self,
+        id: i32,
+        request: ChangeDocumentStateRequest,
+    ) -> Result<Document, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        if !Self::fetch_document_in_tx(&mut tx, id).await?.is_active {
+            return Err(RepositoryError::Conflict(format!(
+                "Document {id} is already deleted"
+            )));
+        }
+
+        Self::change_state_in_tx(&mut tx, id, false, &request).await?;
+
+        let document = Self::fetch_document_in_tx(&mut tx, id).await?;
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(document)
+    }
+
+    async fn restore(
+        &self,
+        id: i32,
+        request: ChangeDocumentStateRequest,
+    ) -> Result<Document, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let current = Self::fetch_document_in_tx(&mut tx, id).await?;
+        if current.is_active {
+            return Err(RepositoryError::Conflict(format!(
+                "Document {id} is not deleted"
+            )));
         }
-        if let Some(document_type_id) = filters.document_type_id {
-            stmt = stmt.bind(document_type_id);
+
+        // 欠番にした番号の文書（取り下げ済み）は復元しない
+        let voided = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM document_number_voids WHERE document_number = ?)",
+        )
+        .bind(&current.number)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+        if voided {
+            return Err(RepositoryError::Conflict(format!(
+                "Document number {} has been voided and cannot be restored",
+                current.number
+            )));
         }
-        if let Some(created_by) = filters.created_by {
-            stmt = stmt.bind(created_by);
+
+        Self::change_state_in_tx(&mut tx, id, true, &request).await?;
+
+        let document = Self::fetch_document_in_tx(&mut tx, id).await?;
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(document)
+    }
+
+    async fn list_deleted(
+        &self,
+        limit: i64,
+        offset: i64,
+        max_clearance: ClearanceLevel,
+    ) -> Result<(Vec<DeletedDocument>, i64), RepositoryError> {
+        // 削除の記録がある無効な文書がゴミ箱の対象（欠番にした文書は除く）
+        let deleted_from = format!(
+            r#"
+            FROM documents d
+            JOIN document_changes c ON c.id = (
+                SELECT MAX(id) FROM document_changes
+                WHERE document_id = d.id AND change_type = 'deleted'
+            )
+            WHERE d.is_active = 0
+              AND NOT EXISTS (SELECT 1 FROM document_number_voids v WHERE v.document_number = d.number)
+              AND {REQUIRED_CLEARANCE_SQL} <= ?
+            "#
+        );
+
+        let total = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) {deleted_from}"))
+            .bind(max_clearance)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        let rows = sqlx::query_as::<_, (i32, i32, NaiveDateTime, Option<String>)>(&format!(
+            "SELECT d.id, c.changed_by, c.changed_at, c.reason {deleted_from} \
+             ORDER BY c.id DESC LIMIT ? OFFSET ?"
+        ))
+        .bind(max_clearance)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        let mut documents = Vec::with_capacity(rows.len());
+        for (id, deleted_by, deleted_at, reason) in rows {
+            let document = self
+                .get_by_id(id)
+                .await?
+                .ok_or_else(|| RepositoryError::NotFound { id: id.to_string() })?;
+            documents.push(DeletedDocument {
+                document,
+                deleted_by,
+                deleted_at,
+                reason,
+            });
         }
-        stmt = stmt.bind(filters.limit).bind(filters.offset);
 
-        let rows = stmt
+        Ok((documents, total))
+    }
+
+    async fn list_changes(&self, document_id: i32) -> Result<Vec<DocumentChange>, RepositoryError> {
+        sqlx::query_as::<_, DocumentChange>(
+            "SELECT * FROM document_changes WHERE document_id = ? ORDER BY id",
+        )
+        .bind(document_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        request_fingerprint: &str,
+        expires_at: NaiveDateTime,
+        claimed_until: NaiveDateTime,
+    ) -> Result<IdempotencyKeyClaim, RepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+        let now = Utc::now().naive_utc();
+
+        sqlx::query("DELETE FROM document_idempotency_keys WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO document_idempotency_keys
+                (idempotency_key, request_fingerprint, expires_at, claimed_until)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (idempotency_key) DO NOTHING
+            "#,
+        )
+        .bind(key)
+        .bind(request_fingerprint)
+        .bind(expires_at)
+        .bind(claimed_until)
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        let claim = if inserted.rows_affected() == 1 {
+            IdempotencyKeyClaim::Claimed
+        } else {
+            let row = sqlx::query(
+                r#"
+                SELECT request_fingerprint, response, document_id, claimed_until, rule_id,
+                       sequence_year, sequence_month, sequence_department_code, sequence_number
+                FROM document_idempotency_keys
+                WHERE idempotency_key = ?
+                "#,
+            )
+            .bind(key)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(RepositoryError::Database)?;
+            let fingerprint: String = row.get("request_fingerprint");
+            let response: Option<String> = row.get("response");
+            let document_id: Option<i32> = row.get("document_id");
+            let held_until: Option<NaiveDateTime> = row.get("claimed_until");
+
+            if fingerprint != request_fingerprint {
+                return Err(RepositoryError::Conflict(format!(
+                    "Idempotency key {key} was already used with a different request"
+                )));
+            }
+            match (response, document_id) {
+                (Some(response), _) => IdempotencyKeyClaim::Completed(response),
+                (None, Some(document_id)) => {
+                    IdempotencyKeyClaim::Created(idempotent_numbered_document(&row, document_id)?)
+                }
+                // 文書を作成しないまま確保の期限が過ぎた（処理中に中断した）キーは引き継ぐ
+                (None, None) if held_until.is_none_or(|until| until <= now) => {
+                    sqlx::query(
+                        "UPDATE document_idempotency_keys SET claimed_until = ? WHERE idempotency_key = ?",
+                    )
+                    .bind(claimed_until)
+                    .bind(key)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(RepositoryError::Database)?;
+                    IdempotencyKeyClaim::Claimed
+                }
+                (None, None) => {
+                    return Err(RepositoryError::Conflict(format!(
+                        "A request with idempotency key {key} is still being processed"
+                    )));
+                }
+            }
+        };
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+        Ok(claim)
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        document_id: i32,
+        response: &str,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "UPDATE document_idempotency_keys SET document_id = ?, response = ? WHERE idempotency_key = ?",
+        )
+        .bind(document_id)
+        .bind(response)
+        .bind(key)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        Ok(())
+    }
+
+    async fn release_idempotency_key(&self, key: &str) -> Result<(), RepositoryError> {
+        sqlx::query(
+            "DELETE FROM document_idempotency_keys WHERE idempotency_key = ? AND response IS NULL AND document_id IS NULL",
+        )
+        .bind(key)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        filters: DocumentSearchFilters,
+    ) -> Result<(Vec<Document>, i64), RepositoryError> {
+        let full_text = filters.query.as_deref().and_then(FullTextQuery::parse);
+        let from = if full_text.is_some() {
+            " JOIN documents_fts ON documents_fts.rowid = d.id WHERE 1=1"
+        } else {
+            " WHERE 1=1"
+        };
+
+        let mut query = format!("{DOCUMENT_SELECT}{from}");
+        let mut count_query = format!("SELECT COUNT(*) as count FROM documents d{from}");
+
+        // フィルター条件を構築
+        push_search_conditions(&mut query, &filters, full_text.as_ref());
+        push_search_conditions(&mut count_query, &filters, full_text.as_ref());
+
+        // LIMIT/OFFSET追加
+        query.push_str(" ORDER BY d.created_at DESC LIMIT ? OFFSET ?");
+
+        // カウントクエリ実行
+        let count_row =
+            bind_search_filters(sqlx::query(&count_query), &filters, full_text.as_ref())
+                .fetch_one(&self.pool)
+                .await
+                .map_err(RepositoryError::Database)?;
+        let total: i64 = count_row.get("count");
+
+        // メインクエリ実行
+        let rows = bind_search_filters(sqlx::query(&query), &filters, full_text.as_ref())
+            .bind(filters.limit)
+            .bind(filters.offset)
             .fetch_all(&self.pool)
             .await
             .map_err(RepositoryError::Database)?;
 
-        use chrono::{NaiveDate, NaiveDateTime};
-        let documents: Result<Vec<Document>, RepositoryError> = rows
-            .into_iter()
+        let documents: Result<Vec<Document>, RepositoryError> =
+            rows.iter().map(document_from_row).collect();
+
+        Ok((documents?, total))
+    }
+
+    async fn full_text_search(
+        &self,
+        filters: DocumentSearchFilters,
+    ) -> Result<(Vec<DocumentSearchHit>, i64), RepositoryError> {
+        let Some(full_text) = filters.query.as_deref().and_then(FullTextQuery::parse) else {
+            return Err(RepositoryError::Validation(
+                "Full-text query cannot be empty".to_string(),
+            ));
+        };
+
+        // bm25()とsnippet()はMATCHを使う検索でしか使えないため、部分一致の場合は作成日時順で抜粋なし
+        let (rank_column, order_by) = match full_text {
+            FullTextQuery::Match(_) => (
+                format!("{FULL_TEXT_RANK_SQL} AS rank, {FULL_TEXT_SNIPPET_SQL} AS snippet"),
+                "rank ASC, d.created_at DESC",
+            ),
+            FullTextQuery::Like(_) => (
+                "NULL AS rank, NULL AS snippet".to_string(),
+                "d.created_at DESC",
+            ),
+        };
+        let from = " JOIN documents_fts ON documents_fts.rowid = d.id WHERE 1=1";
+
+        let mut query = format!(
+            "{}{from}",
+            DOCUMENT_SELECT.replacen("SELECT ", &format!("SELECT {rank_column}, "), 1)
+        );
+        let mut count_query = format!("SELECT COUNT(*) as count FROM documents d{from}");
+        push_search_conditions(&mut query, &filters, Some(&full_text));
+        push_search_conditions(&mut count_query, &filters, Some(&full_text));
+        query.push_str(&format!(" ORDER BY {order_by} LIMIT ? OFFSET ?"));
+
+        let count_row = bind_search_filters(sqlx::query(&count_query), &filters, Some(&full_text))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+        let total: i64 = count_row.get("count");
+
+        let rows = bind_search_filters(sqlx::query(&query), &filters, Some(&full_text))
+            .bind(filters.limit)
+            .bind(filters.offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        let hits = rows
+            .iter()
             .map(|row| {
-                Ok(Document {
-                    id: row.get("id"),
-                    number: row.get("number"),
-                    title: row.get("title"),
-                    document_type_id: row.get("document_type_id"),
-                    business_number: row.get("business_number"),
-                    created_by: row.get("created_by"),
-                    created_by_name: row.get("created_by_name"),
-                    created_date: NaiveDate::parse_from_str(
-                        &row.get::<String, _>("created_date"),
-                        "%Y-%m-%d",
-                    )
-                    .map_err(|e| {
-                        RepositoryError::Validation(format!("Invalid date format: {e}"))
-                    })?,
-                    internal_external: row.get("internal_external"),
-                    importance_class: row.get("importance_class"),
-                    personal_info: row.get("personal_info"),
-                    notes: row.get("notes"),
-                    network_path: row.get("network_path"),
-                    is_active: row.get("is_active"),
-                    created_at: DateTime::<Utc>::from_naive_utc_and_offset(
-                        NaiveDateTime::parse_from_str(
-                            &row.get::<String, _>("created_at"),
-                            "%Y-%m-%d %H:%M:%S",
-                        )
-                        .map_err(|e| {
-                            RepositoryError::Validation(format!("Invalid datetime format: {e}"))
-                        })?,
-                        Utc,
-                    ),
-                    updated_at: DateTime::<Utc>::from_naive_utc_and_offset(
-                        NaiveDateTime::parse_from_str(
-                            &row.get::<String, _>("updated_at"),
-                            "%Y-%m-%d %H:%M:%S",
-                        )
-                        .map_err(|e| {
-                            RepositoryError::Validation(format!("Invalid datetime format: {e}"))
-                        })?,
-                        Utc,
-                    ),
+                Ok(DocumentSearchHit {
+                    document: document_from_row(row)?,
+                    rank: row.get("rank"),
+                    snippet: row.get("snippet"),
                 })
             })
+            .collect::<Result<Vec<_>, RepositoryError>>()?;
+
+        Ok((hits, total))
+    }
+
+    async fn search_aggregations(
+        &self,
+        filters: &DocumentSearchFilters,
+    ) -> Result<DocumentSearchAggregations, RepositoryError> {
+        let full_text = filters.query.as_deref().and_then(FullTextQuery::parse);
+        let full_text = full_text.as_ref();
+
+        // 文書種別別集計
+        let document_type_counts = self
+            .fetch_facet(&DOCUMENT_TYPE_FACET, filters, full_text)
+            .await?
+            .iter()
+            .map(|row| DocumentTypeCount {
+                document_type_id: row.get("document_type_id"),
+                document_type_code: row.get("document_type_code"),
+                document_type_name: row.get("document_type_name"),
+                count: row.get("count"),
+            })
             .collect();
 
-        Ok((documents?, total))
+        // 部署別集計
+        let department_counts = self
+            .fetch_facet(&DEPARTMENT_FACET, filters, full_text)
+            .await?
+            .iter()
+            .map(|row| DocumentDepartmentCount {
+                department_code: row.get("department_code"),
+                department_name: row.get("department_name"),
+                count: row.get("count"),
+            })
+            .collect();
+
+        // 作成年別集計
+        let year_counts = self
+            .fetch_facet(&YEAR_FACET, filters, full_text)
+            .await?
+            .iter()
+            .map(|row| YearCount {
+                year: row.get("year"),
+                count: row.get("count"),
+            })
+            .collect();
+
+        // 重要度別集計
+        let importance_class_counts = self
+            .fetch_facet(&IMPORTANCE_CLASS_FACET, filters, full_text)
+            .await?
+            .iter()
+            .map(|row| ImportanceClassCount {
+                importance_class: row.get("importance_class"),
+                count: row.get("count"),
+            })
+            .collect();
+
+        // 社内外区分別集計
+        let internal_external_counts = self
+            .fetch_facet(&INTERNAL_EXTERNAL_FACET, filters, full_text)
+            .await?
+            .iter()
+            .map(|row| InternalExternalCount {
+                internal_external: row.get("internal_external"),
+                count: row.get("count"),
+            })
+            .collect();
+
+        // 業務番号別集計
+        let business_number_counts = self
+            .fetch_facet(&BUSINESS_NUMBER_FACET, filters, full_text)
+            .await?
+            .iter()
+            .map(|row| BusinessNumberCount {
+                business_number: row.get("business_number"),
+                count: row.get("count"),
+            })
+            .collect();
+
+        Ok(DocumentSearchAggregations {
+            document_type_counts,
+            department_counts,
+            year_counts,
+            importance_class_counts,
+            internal_external_counts,
+            business_number_counts,
+        })
     }
 
     async fn get_document_type_id_by_code(
@@ -371,4 +1889,476 @@ impl DocumentRepository for SqliteDocumentRepository {
             )))
         }
     }
+
+    async fn reserve_numbers(
+        &self,
+        request: &CreateDocumentNumberReservationRequest,
+        sequence_key: &SequenceKey,
+        format_number: &DocumentNumberFormatter<'_>,
+    ) -> Result<DocumentNumberReservation, DocumentNumberGenerationError> {
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        // 先に連番を確保してから予約ブロックを記録する（最初の書き込みで他の採番を待たせる）
+        let mut numbers = Vec::with_capacity(request.count as usize);
+        while numbers.len() < request.count as usize {
+            let sequence_number = Self::next_sequence_in_tx(&mut tx, sequence_key).await?;
+            let document_number = format_number(sequence_number)?;
+
+            let exists = sqlx::query_scalar::<_, bool>(NUMBER_TAKEN_QUERY)
+                .bind(&document_number)
+                .bind(&document_number)
+                .bind(&document_number)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(RepositoryError::Database)?;
+
+            if !exists {
+                numbers.push((document_number, sequence_number));
+            }
+        }
+
+        let reservation_id = sqlx::query_scalar::<_, i32>(
+            r#"
+            INSERT INTO document_number_reservations (
+                rule_id, year, month, department_code, document_type_code, section_code,
+                created_date, reserved_by, expires_on, expiry_action, purpose
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+        )
+        .bind(sequence_key.rule_id)
+        .bind(sequence_key.year)
+        .bind(sequence_key.month)
+        .bind(&sequence_key.department_code)
+        .bind(request.document_type_code.trim())
+        .bind(
+            request
+                .section_code
+                .as_deref()
+                .map(str::trim)
+                .filter(|code| !code.is_empty()),
+        )
+        .bind(request.created_date)
+        .bind(request.reserved_by)
+        .bind(request.expires_on)
+        .bind(request.expiry_action)
+        .bind(&request.purpose)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        for (document_number, sequence_number) in &numbers {
+            sqlx::query(
+                r#"
+                INSERT INTO document_number_reserved_numbers (reservation_id, document_number, sequence_number)
+                VALUES (?, ?, ?)
+                "#,
+            )
+            .bind(reservation_id)
+            .bind(document_number)
+            .bind(sequence_number)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                    RepositoryError::Conflict(format!(
+                        "Document number {document_number} is already reserved"
+                    ))
+                }
+                e => RepositoryError::Database(e),
+            })?;
+        }
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(self
+            .get_number_reservation(reservation_id)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound {
+                id: reservation_id.to_string(),
+            })?)
+    }
+
+    async fn get_number_reservation(
+        &self,
+        id: i32,
+    ) -> Result<Option<DocumentNumberReservation>, RepositoryError> {
+        let reservation = sqlx::query_as::<_, DocumentNumberReservation>(
+            "SELECT * FROM document_number_reservations WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        let Some(mut reservation) = reservation else {
+            return Ok(None);
+        };
+        self.load_reserved_numbers(&mut reservation).await?;
+
+        Ok(Some(reservation))
+    }
+
+    async fn search_number_reservations(
+        &self,
+        rule_id: Option<i32>,
+        department_code: Option<&str>,
+        include_expired: bool,
+    ) -> Result<Vec<DocumentNumberReservation>, RepositoryError> {
+        let mut reservations = sqlx::query_as::<_, DocumentNumberReservation>(
+            r#"
+            SELECT * FROM document_number_reservations
+            WHERE (? IS NULL OR rule_id = ?)
+              AND (? IS NULL OR department_code = ?)
+              AND (? OR (expired_at IS NULL AND expires_on >= date('now', 'localtime')))
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(rule_id)
+        .bind(rule_id)
+        .bind(department_code)
+        .bind(department_code)
+        .bind(include_expired)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        for reservation in &mut reservations {
+            self.load_reserved_numbers(reservation).await?;
+        }
+
+        Ok(reservations)
+    }
+
+    async fn claim_reserved_number(
+        &self,
+        reservation_id: i32,
+        request: CreateDocumentRequest,
+        location: &DocumentLocation<'_>,
+    ) -> Result<Document, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let document_number = request.number.clone().ok_or_else(|| {
+            RepositoryError::Validation("Document number is required".to_string())
+        })?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let reserved_id = sqlx::query_scalar::<_, i32>(
+            r#"
+            UPDATE document_number_reserved_numbers
+            SET status = 'claimed', updated_at = CURRENT_TIMESTAMP
+            WHERE reservation_id = ? AND document_number = ? AND status = 'reserved'
+            RETURNING id
+            "#,
+        )
+        .bind(reservation_id)
+        .bind(&document_number)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?
+        .ok_or_else(|| {
+            RepositoryError::Conflict(format!(
+                "Document number {document_number} is not available in reservation {reservation_id}"
+            ))
+        })?;
+
+        let document =
+            Self::insert_document_in_tx(&mut tx, &document_number, &request, location).await?;
+
+        sqlx::query("UPDATE document_number_reserved_numbers SET document_id = ? WHERE id = ?")
+            .bind(document.id)
+            .bind(reserved_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(document)
+    }
+
+    async fn expire_number_reservations(
+        &self,
+        as_of: NaiveDate,
+    ) -> Result<ReservationExpiryResult, RepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+        let result = Self::expire_reservations_in_tx(&mut tx, as_of, None).await?;
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(result)
+    }
+
+    async fn find_issued_number(
+        &self,
+        document_number: &str,
+    ) -> Result<Option<IssuedDocumentNumber>, RepositoryError> {
+        sqlx::query_as::<_, IssuedDocumentNumber>(
+            "SELECT id AS document_id, number, department_code, is_active FROM documents WHERE number = ?",
+        )
+        .bind(document_number)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    async fn find_reservation_by_number(
+        &self,
+        document_number: &str,
+    ) -> Result<Option<DocumentNumberReservation>, RepositoryError> {
+        let reservation_id = sqlx::query_scalar::<_, i32>(
+            "SELECT reservation_id FROM document_number_reserved_numbers WHERE document_number = ?",
+        )
+        .bind(document_number)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        match reservation_id {
+            Some(id) => self.get_number_reservation(id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn void_document_number(
+        &self,
+        voided: NewVoidedDocumentNumber,
+    ) -> Result<VoidedDocumentNumber, RepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let key = voided.sequence_key.as_ref();
+        let record = sqlx::query_as::<_, VoidedDocumentNumber>(
+            r#"
+            INSERT INTO document_number_voids (
+                document_number, rule_id, year, month, department_code, sequence_number,
+                document_id, reason, voided_by
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(&voided.document_number)
+        .bind(key.map(|key| key.rule_id))
+        .bind(key.map(|key| key.year))
+        .bind(key.map(|key| key.month))
+        .bind(key.map(|key| key.department_code.as_str()))
+        .bind(voided.sequence_number)
+        .bind(voided.document_id)
+        .bind(voided.reason.trim())
+        .bind(voided.voided_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                RepositoryError::Conflict(format!(
+                    "Document number {} is already voided",
+                    voided.document_number
+                ))
+            }
+            e => RepositoryError::Database(e),
+        })?;
+
+        if let Some(document_id) = voided.document_id {
+            sqlx::query(
+                "UPDATE documents SET is_active = 0, version = version + 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            )
+            .bind(document_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(RepositoryError::Database)?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE document_number_reserved_numbers
+            SET status = 'voided', updated_at = CURRENT_TIMESTAMP
+            WHERE document_number = ? AND status IN ('reserved', 'released')
+            "#,
+        )
+        .bind(&voided.document_number)
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(record)
+    }
+
+    async fn list_voided_numbers(
+        &self,
+        filters: &NumberGapFilters,
+    ) -> Result<Vec<VoidedDocumentNumber>, RepositoryError> {
+        sqlx::query_as::<_, VoidedDocumentNumber>(
+            r#"
+            SELECT * FROM document_number_voids
+            WHERE (? IS NULL OR rule_id = ?)
+              AND (? IS NULL OR year = ?)
+              AND (? IS NULL OR month = ?)
+              AND (? IS NULL OR department_code = ?)
+            ORDER BY id
+            "#,
+        )
+        .bind(filters.rule_id)
+        .bind(filters.rule_id)
+        .bind(filters.year)
+        .bind(filters.year)
+        .bind(filters.month)
+        .bind(filters.month)
+        .bind(&filters.department_code)
+        .bind(&filters.department_code)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    async fn list_sequence_counters(
+        &self,
+        filters: &NumberGapFilters,
+    ) -> Result<Vec<SequenceCounter>, RepositoryError> {
+        sqlx::query_as::<_, SequenceCounter>(
+            r#"
+            SELECT rule_id, year, month, department_code, last_sequence
+            FROM document_number_sequences
+            WHERE (? IS NULL OR rule_id = ?)
+              AND (? IS NULL OR year = ?)
+              AND (? IS NULL OR month = ?)
+              AND (? IS NULL OR department_code = ?)
+            ORDER BY rule_id, year, month, department_code
+            "#,
+        )
+        .bind(filters.rule_id)
+        .bind(filters.rule_id)
+        .bind(filters.year)
+        .bind(filters.year)
+        .bind(filters.month)
+        .bind(filters.month)
+        .bind(&filters.department_code)
+        .bind(&filters.department_code)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    async fn list_issued_numbers(
+        &self,
+        department_code: Option<&str>,
+    ) -> Result<Vec<IssuedDocumentNumber>, RepositoryError> {
+        sqlx::query_as::<_, IssuedDocumentNumber>(
+            r#"
+            SELECT id AS document_id, number, department_code, is_active
+            FROM documents
+            WHERE ? IS NULL OR department_code IS NULL OR department_code = ?
+            ORDER BY id
+            "#,
+        )
+        .bind(department_code)
+        .bind(department_code)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    async fn get_by_number(
+        &self,
+        document_number: &str,
+    ) -> Result<Option<Document>, RepositoryError> {
+        let row = sqlx::query(&format!(
+            "{DOCUMENT_SELECT} WHERE d.number = ? \
+             OR d.id = (SELECT document_id FROM document_number_aliases WHERE alias_number = ?) \
+             ORDER BY d.number = ? DESC LIMIT 1"
+        ))
+        .bind(document_number)
+        .bind(document_number)
+        .bind(document_number)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        row.as_ref().map(document_from_row).transpose()
+    }
+
+    async fn add_number_alias(
+        &self,
+        document_id: i32,
+        request: CreateDocumentNumberAliasRequest,
+    ) -> Result<DocumentNumberAlias, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+        let alias_number = request.alias_number.trim();
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let document_exists =
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM documents WHERE id = ?)")
+                .bind(document_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(RepositoryError::Database)?;
+        if !document_exists {
+            return Err(RepositoryError::NotFound {
+                id: document_id.to_string(),
+            });
+        }
+
+        let taken = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM documents WHERE number = ?)
+                OR EXISTS(SELECT 1 FROM document_number_voids WHERE document_number = ?)
+                OR EXISTS(SELECT 1 FROM document_number_reserved_numbers WHERE document_number = ?)
+                OR EXISTS(SELECT 1 FROM document_revisions WHERE number = ?)
+            "#,
+        )
+        .bind(alias_number)
+        .bind(alias_number)
+        .bind(alias_number)
+        .bind(alias_number)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+        if taken {
+            return Err(RepositoryError::Conflict(format!(
+                "Document number {alias_number} is already in use"
+            )));
+        }
+
+        let alias = sqlx::query_as::<_, DocumentNumberAlias>(
+            r#"
+            INSERT INTO document_number_aliases (document_id, alias_number, reason, created_by)
+            VALUES (?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(document_id)
+        .bind(alias_number)
+        .bind(&request.reason)
+        .bind(request.created_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => RepositoryError::Conflict(
+                format!("Document number {alias_number} is already registered as a former number"),
+            ),
+            e => RepositoryError::Database(e),
+        })?;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(alias)
+    }
+
+    async fn list_number_aliases(
+        &self,
+        document_id: i32,
+    ) -> Result<Vec<DocumentNumberAlias>, RepositoryError> {
+        sqlx::query_as::<_, DocumentNumberAlias>(
+            "SELECT * FROM document_number_aliases WHERE document_id = ? ORDER BY created_at, id",
+        )
+        .bind(document_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
 }
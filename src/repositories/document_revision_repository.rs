@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+use super::RepositoryError;
+use crate::models::{
+    CreateDocumentRevisionRequest, DocumentRevision, PromoteDraftRequest, RevisionStatus,
+    RevisionType,
+};
+
+#[async_trait]
+pub trait DocumentRevisionRepository: Send + Sync {
+    /// 次の版番号を採番して改訂版・ドラフト版を作成
+    async fn create_revision(
+        &self,
+        document_id: i32,
+        request: CreateDocumentRevisionRequest,
+    ) -> Result<DocumentRevision, RepositoryError>;
+
+    /// ドラフト版を次の改訂版として発行
+    async fn promote_draft(
+        &self,
+        document_id: i32,
+        draft_id: i32,
+        request: PromoteDraftRequest,
+    ) -> Result<DocumentRevision, RepositoryError>;
+
+    /// 文書の版履歴を作成順に取得
+    async fn list_revisions(
+        &self,
+        document_id: i32,
+    ) -> Result<Vec<DocumentRevision>, RepositoryError>;
+}
+
+const REVISION_COLUMNS: &str = "id, document_id, revision_type, version_number, number, status, \
+     notes, promoted_revision_id, created_by, created_at, updated_at";
+
+pub struct SqliteDocumentRevisionRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteDocumentRevisionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 親文書の番号に次の版番号の接尾辞を付けて版を挿入する
+    ///
+    /// 採番と挿入を1文で行うため、同時実行されても同じ版番号は発行されない
+    async fn insert_next_revision(
+        tx: &mut Transaction<'_, Sqlite>,
+        document_id: i32,
+        revision_type: RevisionType,
+        notes: Option<&str>,
+        created_by: i32,
+    ) -> Result<DocumentRevision, RepositoryError> {
+        let revision = sqlx::query_as::<_, DocumentRevision>(&format!(
+            r#"
+            INSERT INTO document_revisions (document_id, revision_type, version_number, number, status, notes, created_by)
+            SELECT d.id, ?, n.next_version, d.number || ? || n.next_version, ?, ?, ?
+            FROM documents d,
+                 (SELECT COALESCE(MAX(version_number), 0) + 1 AS next_version
+                  FROM document_revisions WHERE document_id = ? AND revision_type = ?) n
+            WHERE d.id = ? AND d.is_active = 1
+            RETURNING {REVISION_COLUMNS}
+            "#
+        ))
+        .bind(revision_type)
+        .bind(revision_type.suffix())
+        .bind(revision_type.initial_status())
+        .bind(notes)
+        .bind(created_by)
+        .bind(document_id)
+        .bind(revision_type)
+        .bind(document_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        revision.ok_or_else(|| RepositoryError::NotFound {
+            id: document_id.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl DocumentRevisionRepository for SqliteDocumentRevisionRepository {
+    async fn create_revision(
+        &self,
+        document_id: i32,
+        request: CreateDocumentRevisionRequest,
+    ) -> Result<DocumentRevision, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+        let revision = Self::insert_next_revision(
+            &mut tx,
+            document_id,
+            request.revision_type,
+            request.notes.as_deref(),
+            request.created_by,
+        )
+        .await?;
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(revision)
+    }
+
+    async fn promote_draft(
+        &self,
+        document_id: i32,
+        draft_id: i32,
+        request: PromoteDraftRequest,
+    ) -> Result<DocumentRevision, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        // 先にドラフトを発行済みにして書き込みロックを取得し、二重発行を防ぐ
+        let draft_notes = sqlx::query_scalar::<_, Option<String>>(
+            r#"
+            UPDATE document_revisions
+            SET status = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND document_id = ? AND revision_type = ? AND status = ?
+            RETURNING notes
+            "#,
+        )
+        .bind(RevisionStatus::Promoted)
+        .bind(draft_id)
+        .bind(document_id)
+        .bind(RevisionType::Draft)
+        .bind(RevisionStatus::Draft)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        let Some(draft_notes) = draft_notes else {
+            let status = sqlx::query_scalar::<_, RevisionStatus>(
+                "SELECT status FROM document_revisions WHERE id = ? AND document_id = ? AND revision_type = ?",
+            )
+            .bind(draft_id)
+            .bind(document_id)
+            .bind(RevisionType::Draft)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+            return Err(match status {
+                Some(_) => {
+                    RepositoryError::Conflict(format!("Draft {draft_id} has already been promoted"))
+                }
+                None => RepositoryError::NotFound {
+                    id: draft_id.to_string(),
+                },
+            });
+        };
+
+        let notes = request.notes.or(draft_notes);
+        let revision = Self::insert_next_revision(
+            &mut tx,
+            document_id,
+            RevisionType::Revision,
+            notes.as_deref(),
+            request.promoted_by,
+        )
+        .await?;
+
+        sqlx::query("UPDATE document_revisions SET promoted_revision_id = ? WHERE id = ?")
+            .bind(revision.id)
+            .bind(draft_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(revision)
+    }
+
+    async fn list_revisions(
+        &self,
+        document_id: i32,
+    ) -> Result<Vec<DocumentRevision>, RepositoryError> {
+        let document_exists =
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM documents WHERE id = ?)")
+                .bind(document_id)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(RepositoryError::Database)?;
+
+        if !document_exists {
+            return Err(RepositoryError::NotFound {
+                id: document_id.to_string(),
+            });
+        }
+
+        sqlx::query_as::<_, DocumentRevision>(&format!(
+            "SELECT {REVISION_COLUMNS} FROM document_revisions WHERE document_id = ? ORDER BY id"
+        ))
+        .bind(document_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+}
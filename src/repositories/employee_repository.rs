@@ -0,0 +1,445 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+
+use super::RepositoryError;
+use crate::models::{
+    AssignmentType, ClearanceLevel, Employee, EmployeeAssignment, EmployeeAssignmentRequest,
+    EmployeeClearance, EmployeeRole, OrganizationUnitKind, RoleClearance,
+};
+
+#[async_trait]
+pub trait EmployeeRepository: Send + Sync {
+    /// 在籍中の社員の権限ロールを取得（存在しない・退職済みの場合はNone）
+    async fn get_role(&self, employee_id: i32) -> Result<Option<EmployeeRole>, RepositoryError>;
+
+    /// 在籍中の社員の機密レベル設定を取得（存在しない・退職済みの場合はNone）
+    async fn get_clearance(
+        &self,
+        employee_id: i32,
+    ) -> Result<Option<EmployeeClearance>, RepositoryError>;
+
+    /// 社員個別の機密レベルを設定する（Noneでロールの既定値に戻す）
+    async fn set_employee_clearance(
+        &self,
+        employee_id: i32,
+        clearance_level: Option<ClearanceLevel>,
+    ) -> Result<Option<EmployeeClearance>, RepositoryError>;
+
+    /// ロールの機密レベルを設定する
+    async fn set_role_clearance(
+        &self,
+        role: EmployeeRole,
+        clearance_level: ClearanceLevel,
+    ) -> Result<RoleClearance, RepositoryError>;
+
+    /// 社員を取得する（所属は指定日に有効な主務から求める）
+    async fn get_employee(
+        &self,
+        employee_id: i32,
+        as_of: NaiveDate,
+    ) -> Result<Option<Employee>, RepositoryError>;
+
+    /// 社員の所属履歴を開始日順に取得（指定日があればその日に有効な所属だけ）
+    async fn list_assignments(
+        &self,
+        employee_id: i32,
+        as_of: Option<NaiveDate>,
+    ) -> Result<Vec<EmployeeAssignment>, RepositoryError>;
+
+    /// IDで所属を取得
+    async fn get_assignment(&self, id: i32) -> Result<Option<EmployeeAssignment>, RepositoryError>;
+
+    /// 所属を登録する
+    ///
+    /// 部署・課が開始日に存在しない場合は Validation、主務の期間が重なる場合は Conflict を返す
+    async fn create_assignment(
+        &self,
+        employee_id: i32,
+        request: &EmployeeAssignmentRequest,
+    ) -> Result<EmployeeAssignment, RepositoryError>;
+
+    /// 所属を置き換える（存在しない場合はNone）
+    async fn update_assignment(
+        &self,
+        id: i32,
+        request: &EmployeeAssignmentRequest,
+    ) -> Result<Option<EmployeeAssignment>, RepositoryError>;
+
+    /// 所属を削除する（誤登録の取り消し用。異動・退任は最終日を設定する）
+    async fn delete_assignment(&self, id: i32) -> Result<bool, RepositoryError>;
+}
+
+const ASSIGNMENT_SELECT: &str = r#"
+    SELECT a.id, a.employee_id, a.department_id, d.code as department_code,
+           d.name as department_name, a.section_id, s.code as section_code,
+           s.name as section_name, a.position, a.assignment_type, a.start_date, a.end_date,
+           a.notes, a.created_at, a.updated_at
+    FROM department_assignments a
+    JOIN departments d ON d.id = a.department_id
+    LEFT JOIN departments s ON s.id = a.section_id
+"#;
+
+pub struct SqliteEmployeeRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteEmployeeRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl EmployeeRepository for SqliteEmployeeRepository {
+    async fn get_role(&self, employee_id: i32) -> Result<Option<EmployeeRole>, RepositoryError> {
+        sqlx::query_scalar::<_, EmployeeRole>(
+            "SELECT role FROM employees WHERE id = ? AND is_active = 1",
+        )
+        .bind(employee_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    async fn get_clearance(
+        &self,
+        employee_id: i32,
+    ) -> Result<Option<EmployeeClearance>, RepositoryError> {
+        // ロールの既定値が未登録の場合は最も低いレベルとして扱う
+        let row =
+            sqlx::query_as::<_, (EmployeeRole, Option<ClearanceLevel>, Option<ClearanceLevel>)>(
+                r#"
+            SELECT e.role, r.clearance_level, e.clearance_level
+            FROM employees e
+            LEFT JOIN role_clearance_levels r ON r.role = e.role
+            WHERE e.id = ? AND e.is_active = 1
+            "#,
+            )
+            .bind(employee_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        Ok(row.map(|(role, role_level, employee_level)| {
+            let role_clearance_level = role_level.unwrap_or_default();
+            EmployeeClearance {
+                employee_id,
+                role,
+                role_clearance_level,
+                employee_clearance_level: employee_level,
+                effective_clearance_level: employee_level.unwrap_or(role_clearance_level),
+            }
+        }))
+    }
+
+    async fn set_employee_clearance(
+        &self,
+        employee_id: i32,
+        clearance_level: Option<ClearanceLevel>,
+    ) -> Result<Option<EmployeeClearance>, RepositoryError> {
+        let result = sqlx::query(
+            "UPDATE employees SET clearance_level = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? AND is_active = 1",
+        )
+        .bind(clearance_level)
+        .bind(employee_id)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        self.get_clearance(employee_id).await
+    }
+
+    async fn set_role_clearance(
+        &self,
+        role: EmployeeRole,
+        clearance_level: ClearanceLevel,
+    ) -> Result<RoleClearance, RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO role_clearance_levels (role, clearance_level) VALUES (?, ?)
+            ON CONFLICT (role) DO UPDATE SET
+                clearance_level = excluded.clearance_level,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(role)
+        .bind(clearance_level)
+        .execute(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        Ok(RoleClearance {
+            role,
+            clearance_level,
+        })
+    }
+
+    async fn get_employee(
+        &self,
+        employee_id: i32,
+        as_of: NaiveDate,
+    ) -> Result<Option<Employee>, RepositoryError> {
+        let row = sqlx::query(
+            r#"
+            SELECT e.id, e.employee_number, e.name, e.email, e.is_active, e.created_at,
+                   e.updated_at, a.department_id, a.section_id, a.position
+            FROM employees e
+            LEFT JOIN department_assignments a
+                ON a.id = (
+                    SELECT id FROM department_assignments
+                    WHERE employee_id = e.id AND assignment_type = 'primary'
+                      AND start_date <= ?1 AND (end_date IS NULL OR end_date >= ?1)
+                    ORDER BY start_date DESC
+                    LIMIT 1
+                )
+            WHERE e.id = ?2
+            "#,
+        )
+        .bind(as_of)
+        .bind(employee_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        Ok(row.map(|row| Employee {
+            id: row.get("id"),
+            employee_number: row.get("employee_number"),
+            name: row.get("name"),
+            email: row.get("email"),
+            ad_username: None,
+            department_id: row.get("department_id"),
+            section_id: row.get("section_id"),
+            position: row.get("position"),
+            is_active: row.get::<i32, _>("is_active") == 1,
+            created_at: utc(row.get("created_at")),
+            updated_at: utc(row.get("updated_at")),
+        }))
+    }
+
+    async fn list_assignments(
+        &self,
+        employee_id: i32,
+        as_of: Option<NaiveDate>,
+    ) -> Result<Vec<EmployeeAssignment>, RepositoryError> {
+        let query = format!(
+            r#"{ASSIGNMENT_SELECT}
+            WHERE a.employee_id = ?1
+              AND (?2 IS NULL OR (a.start_date <= ?2 AND (a.end_date IS NULL OR a.end_date >= ?2)))
+            ORDER BY a.start_date, a.assignment_type = 'primary' DESC, a.id"#
+        );
+        sqlx::query_as::<_, EmployeeAssignment>(&query)
+            .bind(employee_id)
+            .bind(as_of)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)
+    }
+
+    async fn get_assignment(&self, id: i32) -> Result<Option<EmployeeAssignment>, RepositoryError> {
+        let query = format!("{ASSIGNMENT_SELECT} WHERE a.id = ?");
+        sqlx::query_as::<_, EmployeeAssignment>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)
+    }
+
+    async fn create_assignment(
+        &self,
+        employee_id: i32,
+        request: &EmployeeAssignmentRequest,
+    ) -> Result<EmployeeAssignment, RepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let employee_exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM employees WHERE id = ?)")
+                .bind(employee_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(RepositoryError::Database)?;
+        if !employee_exists {
+            return Err(RepositoryError::NotFound {
+                id: employee_id.to_string(),
+            });
+        }
+        check_assignment(&mut tx, employee_id, None, request).await?;
+
+        let id = sqlx::query(
+            r#"
+            INSERT INTO department_assignments
+                (employee_id, department_id, section_id, position, assignment_type, start_date,
+                 end_date, notes)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(employee_id)
+        .bind(request.department_id)
+        .bind(request.section_id)
+        .bind(trimmed(&request.position))
+        .bind(request.assignment_type)
+        .bind(request.start_date)
+        .bind(request.end_date)
+        .bind(trimmed(&request.notes))
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?
+        .last_insert_rowid() as i32;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        self.get_assignment(id)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound { id: id.to_string() })
+    }
+
+    async fn update_assignment(
+        &self,
+        id: i32,
+        request: &EmployeeAssignmentRequest,
+    ) -> Result<Option<EmployeeAssignment>, RepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let Some(employee_id) = sqlx::query_scalar::<_, i32>(
+            "SELECT employee_id FROM department_assignments WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?
+        else {
+            return Ok(None);
+        };
+        check_assignment(&mut tx, employee_id, Some(id), request).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE department_assignments
+            SET department_id = ?, section_id = ?, position = ?, assignment_type = ?,
+                start_date = ?, end_date = ?, notes = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+        )
+        .bind(request.department_id)
+        .bind(request.section_id)
+        .bind(trimmed(&request.position))
+        .bind(request.assignment_type)
+        .bind(request.start_date)
+        .bind(request.end_date)
+        .bind(trimmed(&request.notes))
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        self.get_assignment(id).await
+    }
+
+    async fn delete_assignment(&self, id: i32) -> Result<bool, RepositoryError> {
+        let result = sqlx::query("DELETE FROM department_assignments WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// 部署・課の整合性と主務の重複を確認する（`assignment_id`は変更する所属自身）
+async fn check_assignment(
+    tx: &mut Transaction<'_, Sqlite>,
+    employee_id: i32,
+    assignment_id: Option<i32>,
+    request: &EmployeeAssignmentRequest,
+) -> Result<(), RepositoryError> {
+    request.validate().map_err(RepositoryError::Validation)?;
+
+    let unit_exists = |id: i32, kind: OrganizationUnitKind, parent_id: Option<i32>| {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM department_versions
+                WHERE department_id = ? AND kind = ? AND (? IS NULL OR parent_id = ?)
+                  AND valid_from <= ? AND (valid_to IS NULL OR valid_to > ?)
+            )
+            "#,
+        )
+        .bind(id)
+        .bind(kind)
+        .bind(parent_id)
+        .bind(parent_id)
+        .bind(request.start_date)
+        .bind(request.start_date)
+    };
+
+    if !unit_exists(
+        request.department_id,
+        OrganizationUnitKind::Department,
+        None,
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(RepositoryError::Database)?
+    {
+        return Err(RepositoryError::Validation(format!(
+            "Department {} did not exist on {}",
+            request.department_id, request.start_date
+        )));
+    }
+    if let Some(section_id) = request.section_id
+        && !unit_exists(
+            section_id,
+            OrganizationUnitKind::Section,
+            Some(request.department_id),
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)?
+    {
+        return Err(RepositoryError::Validation(format!(
+            "Section {section_id} did not belong to department {} on {}",
+            request.department_id, request.start_date
+        )));
+    }
+
+    if request.assignment_type == AssignmentType::Primary {
+        let overlapping: Option<i32> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM department_assignments
+            WHERE employee_id = ?1 AND assignment_type = 'primary' AND id != ?2
+              AND start_date <= COALESCE(?4, '9999-12-31')
+              AND (end_date IS NULL OR end_date >= ?3)
+            LIMIT 1
+            "#,
+        )
+        .bind(employee_id)
+        .bind(assignment_id.unwrap_or(0))
+        .bind(request.start_date)
+        .bind(request.end_date)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+        if let Some(overlapping) = overlapping {
+            return Err(RepositoryError::Conflict(format!(
+                "Primary assignment overlaps assignment {overlapping}; set its end_date first"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn trimmed(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn utc(value: NaiveDateTime) -> DateTime<Utc> {
+    value.and_utc()
+}
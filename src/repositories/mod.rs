@@ -7,6 +7,9 @@ pub mod circulation_repository;
 pub mod department_repository;
 pub mod document_number_rule_repository;
 pub mod document_repository;
+pub mod document_revision_repository;
+pub mod employee_repository;
+pub mod path_generation_rule_repository;
 
 // Re-export all repositories
 pub use advanced_search_repository::*;
@@ -16,3 +19,6 @@ pub use circulation_repository::*;
 pub use department_repository::*;
 pub use document_number_rule_repository::*;
 pub use document_repository::*;
+pub use document_revision_repository::*;
+pub use employee_repository::*;
+pub use path_generation_rule_repository::*;
@@ -0,0 +1,567 @@
+use async_trait::async_trait;
+use chrono::{Datelike, Days, NaiveDate};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+use super::RepositoryError;
+use crate::models::{
+    CreatePathGenerationRuleRequest, DocumentPathContext, PathGenerationRule,
+    PathGenerationRuleVersion, PathRuleChange, PathRuleChangeType, StoredDocumentPath,
+};
+
+#[async_trait]
+pub trait PathGenerationRuleRepository: Send + Sync {
+    /// 指定時点で効力を持っていたルールの版から、文書の部署・作成日に適用可能なものを検索（優先度順）
+    async fn find_applicable_rule(
+        &self,
+        department_code: &str,
+        document_date: NaiveDate,
+        as_of: NaiveDate,
+    ) -> Result<Option<PathGenerationRuleVersion>, RepositoryError>;
+
+    /// パス生成ルールを作成
+    async fn create_rule(
+        &self,
+        request: CreatePathGenerationRuleRequest,
+    ) -> Result<PathGenerationRule, RepositoryError>;
+
+    /// IDでルールを取得
+    async fn get_rule_by_id(&self, id: i32) -> Result<Option<PathGenerationRule>, RepositoryError>;
+
+    /// ルールを検索
+    async fn search_rules(
+        &self,
+        department_code: Option<String>,
+        active_on_date: Option<NaiveDate>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<PathGenerationRule>, i64), RepositoryError>;
+
+    /// ルールを変更し、指定日から効力を持つ版として履歴に記録する
+    async fn update_rule(
+        &self,
+        id: i32,
+        request: CreatePathGenerationRuleRequest,
+        apply_from: NaiveDate,
+    ) -> Result<Option<PathGenerationRule>, RepositoryError>;
+
+    /// ルールの有効期間終了日を設定して廃止する
+    async fn retire_rule(
+        &self,
+        id: i32,
+        effective_until: NaiveDate,
+        change: &PathRuleChange,
+    ) -> Result<Option<PathGenerationRule>, RepositoryError>;
+
+    /// ルールの変更履歴を版番号順に取得
+    async fn list_versions(
+        &self,
+        rule_id: i32,
+    ) -> Result<Vec<PathGenerationRuleVersion>, RepositoryError>;
+
+    /// 登録済みの文書番号からパス生成に必要な属性を取得
+    async fn find_document_context(
+        &self,
+        document_number: &str,
+    ) -> Result<Option<DocumentPathContext>, RepositoryError>;
+
+    /// 有効な文書の保存済みパスと解決に必要な属性をID順に取得
+    async fn list_stored_paths(
+        &self,
+        department_code: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<StoredDocumentPath>, RepositoryError>;
+
+    /// 作成する文書の部署・文書種別からパス生成に必要な属性を組み立てる
+    async fn build_document_context(
+        &self,
+        document_number: &str,
+        department_code: &str,
+        document_type_id: i32,
+        created_date: NaiveDate,
+    ) -> Result<DocumentPathContext, RepositoryError>;
+}
+
+const RULE_COLUMNS: &str = "id, rule_name, path_template, department_code, year_from, year_to, \
+     effective_from, effective_until, priority, created_at, updated_at";
+
+const VERSION_COLUMNS: &str = "id, rule_id, version_number, rule_name, path_template, \
+     department_code, year_from, year_to, effective_from, effective_until, priority, change_type, \
+     changed_by, change_reason, valid_from, valid_until, created_at";
+
+const DOCUMENT_CONTEXT_SELECT: &str = r#"
+    SELECT d.id, d.network_path, d.number, COALESCE(d.department_code, current.department_code) AS department_code,
+           dep.name AS department_name, dt.prefix, dt.name AS document_type_name, d.created_date
+    FROM documents d
+    JOIN document_types dt ON dt.id = d.document_type_id
+    LEFT JOIN employee_current_departments current ON current.employee_id = d.created_by
+    LEFT JOIN departments dep ON dep.code = COALESCE(d.department_code, current.department_code)
+"#;
+
+type DocumentContextRow = (
+    i32,
+    Option<String>,
+    String,
+    String,
+    Option<String>,
+    String,
+    String,
+    NaiveDate,
+);
+
+fn stored_path_from_row(row: DocumentContextRow) -> StoredDocumentPath {
+    let (
+        document_id,
+        network_path,
+        document_number,
+        department_code,
+        department_name,
+        document_type_code,
+        document_type_name,
+        created_date,
+    ) = row;
+
+    StoredDocumentPath {
+        document_id,
+        network_path,
+        context: DocumentPathContext {
+            document_number,
+            department_code,
+            department_name,
+            document_type_code,
+            document_type_name,
+            created_date,
+        },
+    }
+}
+
+// SQLite実装
+
+pub struct SqlitePathGenerationRuleRepository {
+    pool: SqlitePool,
+}
+
+impl SqlitePathGenerationRuleRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// ルールの現在の内容を、valid_fromから効力を持つ新しい版として記録する
+    ///
+    /// valid_from以降にも効力を持つ既存の版はその前日までに打ち切る
+    async fn record_version_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        rule: &PathGenerationRule,
+        change_type: PathRuleChangeType,
+        change: &PathRuleChange,
+        valid_from: NaiveDate,
+    ) -> Result<PathGenerationRuleVersion, RepositoryError> {
+        sqlx::query(
+            r#"
+            UPDATE path_generation_rule_versions
+            SET valid_until = CASE WHEN valid_from >= ? THEN valid_from ELSE ? END
+            WHERE rule_id = ? AND (valid_until IS NULL OR valid_until > ?)
+            "#,
+        )
+        .bind(valid_from)
+        .bind(valid_from)
+        .bind(rule.id)
+        .bind(valid_from)
+        .execute(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        sqlx::query_as::<_, PathGenerationRuleVersion>(&format!(
+            r#"
+            INSERT INTO path_generation_rule_versions
+                (rule_id, version_number, rule_name, path_template, department_code, year_from,
+                 year_to, effective_from, effective_until, priority, change_type, changed_by,
+                 change_reason, valid_from)
+            SELECT ?, COALESCE(MAX(version_number), 0) + 1, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+            FROM path_generation_rule_versions WHERE rule_id = ?
+            RETURNING {VERSION_COLUMNS}
+            "#
+        ))
+        .bind(rule.id)
+        .bind(&rule.rule_name)
+        .bind(&rule.path_template)
+        .bind(&rule.department_code)
+        .bind(rule.year_from)
+        .bind(rule.year_to)
+        .bind(rule.effective_from)
+        .bind(rule.effective_until)
+        .bind(rule.priority)
+        .bind(change_type)
+        .bind(change.changed_by)
+        .bind(&change.change_reason)
+        .bind(valid_from)
+        .bind(rule.id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+}
+
+#[async_trait]
+impl PathGenerationRuleRepository for SqlitePathGenerationRuleRepository {
+    async fn find_applicable_rule(
+        &self,
+        department_code: &str,
+        document_date: NaiveDate,
+        as_of: NaiveDate,
+    ) -> Result<Option<PathGenerationRuleVersion>, RepositoryError> {
+        // 指定時点で効力を持っていた版のうち、部署固有ルールを汎用ルール（department_code = NULL）より優先し、
+        // 同じ区分内では優先度・有効開始日の新しい順で選択する
+        let query = format!(
+            r#"
+            SELECT {VERSION_COLUMNS}
+            FROM path_generation_rule_versions
+            WHERE valid_from <= ?
+              AND (valid_until IS NULL OR valid_until > ?)
+              AND (department_code = ? OR department_code IS NULL)
+              AND (year_from IS NULL OR year_from <= ?)
+              AND (year_to IS NULL OR year_to >= ?)
+              AND effective_from <= ?
+              AND (effective_until IS NULL OR effective_until >= ?)
+            ORDER BY department_code IS NULL, priority ASC, effective_from DESC, rule_id ASC
+            LIMIT 1
+            "#
+        );
+
+        sqlx::query_as::<_, PathGenerationRuleVersion>(&query)
+            .bind(as_of)
+            .bind(as_of)
+            .bind(department_code)
+            .bind(document_date.year())
+            .bind(document_date.year())
+            .bind(as_of)
+            .bind(as_of)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)
+    }
+
+    async fn create_rule(
+        &self,
+        request: CreatePathGenerationRuleRequest,
+    ) -> Result<PathGenerationRule, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let rule = sqlx::query_as::<_, PathGenerationRule>(&format!(
+            r#"
+            INSERT INTO path_generation_rules
+                (rule_name, path_template, department_code, year_from, year_to,
+                 effective_from, effective_until, priority)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING {RULE_COLUMNS}
+            "#
+        ))
+        .bind(request.rule_name.trim())
+        .bind(&request.path_template)
+        .bind(normalize_department_code(
+            request.department_code.as_deref(),
+        ))
+        .bind(request.year_from)
+        .bind(request.year_to)
+        .bind(request.effective_from)
+        .bind(request.effective_until)
+        .bind(request.priority)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        // 初版は有効期間の開始日から効力を持つ
+        Self::record_version_in_tx(
+            &mut tx,
+            &rule,
+            PathRuleChangeType::Created,
+            &request.change,
+            rule.effective_from,
+        )
+        .await?;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(rule)
+    }
+
+    async fn get_rule_by_id(&self, id: i32) -> Result<Option<PathGenerationRule>, RepositoryError> {
+        let query = format!("SELECT {RULE_COLUMNS} FROM path_generation_rules WHERE id = ?");
+
+        sqlx::query_as::<_, PathGenerationRule>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)
+    }
+
+    async fn search_rules(
+        &self,
+        department_code: Option<String>,
+        active_on_date: Option<NaiveDate>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<PathGenerationRule>, i64), RepositoryError> {
+        let mut conditions = String::from(" WHERE 1=1");
+
+        if department_code.is_some() {
+            conditions.push_str(" AND department_code = ?");
+        }
+
+        if active_on_date.is_some() {
+            conditions.push_str(
+                " AND effective_from <= ? AND (effective_until IS NULL OR effective_until >= ?)",
+            );
+        }
+
+        // カウントクエリ実行
+        let count_query = format!("SELECT COUNT(*) FROM path_generation_rules{conditions}");
+        let mut count_stmt = sqlx::query_scalar::<_, i64>(&count_query);
+        if let Some(ref code) = department_code {
+            count_stmt = count_stmt.bind(code);
+        }
+        if let Some(date) = active_on_date {
+            count_stmt = count_stmt.bind(date).bind(date);
+        }
+
+        let total = count_stmt
+            .fetch_one(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        // メインクエリ実行
+        let query = format!(
+            "SELECT {RULE_COLUMNS} FROM path_generation_rules{conditions} \
+             ORDER BY priority ASC, effective_from DESC, id ASC LIMIT ? OFFSET ?"
+        );
+        let mut stmt = sqlx::query_as::<_, PathGenerationRule>(&query);
+        if let Some(ref code) = department_code {
+            stmt = stmt.bind(code);
+        }
+        if let Some(date) = active_on_date {
+            stmt = stmt.bind(date).bind(date);
+        }
+
+        let rules = stmt
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        Ok((rules, total))
+    }
+
+    async fn update_rule(
+        &self,
+        id: i32,
+        request: CreatePathGenerationRuleRequest,
+        apply_from: NaiveDate,
+    ) -> Result<Option<PathGenerationRule>, RepositoryError> {
+        request
+            .validate()
+            .map_err(|e| RepositoryError::Validation(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let rule = sqlx::query_as::<_, PathGenerationRule>(&format!(
+            r#"
+            UPDATE path_generation_rules
+            SET rule_name = ?, path_template = ?, department_code = ?, year_from = ?, year_to = ?,
+                effective_from = ?, effective_until = ?, priority = ?,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            RETURNING {RULE_COLUMNS}
+            "#
+        ))
+        .bind(request.rule_name.trim())
+        .bind(&request.path_template)
+        .bind(normalize_department_code(
+            request.department_code.as_deref(),
+        ))
+        .bind(request.year_from)
+        .bind(request.year_to)
+        .bind(request.effective_from)
+        .bind(request.effective_until)
+        .bind(request.priority)
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        let Some(rule) = rule else {
+            return Ok(None);
+        };
+
+        Self::record_version_in_tx(
+            &mut tx,
+            &rule,
+            PathRuleChangeType::Updated,
+            &request.change,
+            apply_from,
+        )
+        .await?;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(Some(rule))
+    }
+
+    async fn retire_rule(
+        &self,
+        id: i32,
+        effective_until: NaiveDate,
+        change: &PathRuleChange,
+    ) -> Result<Option<PathGenerationRule>, RepositoryError> {
+        let Some(rule) = self.get_rule_by_id(id).await? else {
+            return Ok(None);
+        };
+
+        if effective_until <= rule.effective_from {
+            return Err(RepositoryError::Validation(
+                "Effective until date must be after effective from date".to_string(),
+            ));
+        }
+
+        let mut tx = self.pool.begin().await.map_err(RepositoryError::Database)?;
+
+        let rule = sqlx::query_as::<_, PathGenerationRule>(&format!(
+            r#"
+            UPDATE path_generation_rules
+            SET effective_until = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            RETURNING {RULE_COLUMNS}
+            "#
+        ))
+        .bind(effective_until)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        // 終了日までは従来の版と同じ内容のため、翌日から効力を持つ版として記録する
+        let valid_from = effective_until
+            .checked_add_days(Days::new(1))
+            .unwrap_or(effective_until);
+        Self::record_version_in_tx(
+            &mut tx,
+            &rule,
+            PathRuleChangeType::Retired,
+            change,
+            valid_from,
+        )
+        .await?;
+
+        tx.commit().await.map_err(RepositoryError::Database)?;
+
+        Ok(Some(rule))
+    }
+
+    async fn list_versions(
+        &self,
+        rule_id: i32,
+    ) -> Result<Vec<PathGenerationRuleVersion>, RepositoryError> {
+        if self.get_rule_by_id(rule_id).await?.is_none() {
+            return Err(RepositoryError::NotFound {
+                id: rule_id.to_string(),
+            });
+        }
+
+        sqlx::query_as::<_, PathGenerationRuleVersion>(&format!(
+            "SELECT {VERSION_COLUMNS} FROM path_generation_rule_versions WHERE rule_id = ? ORDER BY version_number"
+        ))
+        .bind(rule_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)
+    }
+
+    async fn find_document_context(
+        &self,
+        document_number: &str,
+    ) -> Result<Option<DocumentPathContext>, RepositoryError> {
+        // 部署が記録されていない文書は作成者の所属部署で解決する
+        let row = sqlx::query_as::<_, DocumentContextRow>(&format!(
+            "{DOCUMENT_CONTEXT_SELECT} WHERE d.number = ?"
+        ))
+        .bind(document_number)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?;
+
+        Ok(row.map(|row| stored_path_from_row(row).context))
+    }
+
+    async fn list_stored_paths(
+        &self,
+        department_code: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<StoredDocumentPath>, RepositoryError> {
+        let mut query = format!("{DOCUMENT_CONTEXT_SELECT} WHERE d.is_active = 1");
+        if department_code.is_some() {
+            query.push_str(" AND COALESCE(d.department_code, current.department_code) = ?");
+        }
+        query.push_str(" ORDER BY d.id LIMIT ? OFFSET ?");
+
+        let mut stmt = sqlx::query_as::<_, DocumentContextRow>(&query);
+        if let Some(code) = department_code {
+            stmt = stmt.bind(code);
+        }
+
+        let rows = stmt
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::Database)?;
+
+        Ok(rows.into_iter().map(stored_path_from_row).collect())
+    }
+
+    async fn build_document_context(
+        &self,
+        document_number: &str,
+        department_code: &str,
+        document_type_id: i32,
+        created_date: NaiveDate,
+    ) -> Result<DocumentPathContext, RepositoryError> {
+        let (document_type_code, document_type_name) = sqlx::query_as::<_, (String, String)>(
+            "SELECT prefix, name FROM document_types WHERE id = ?",
+        )
+        .bind(document_type_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(RepositoryError::Database)?
+        .ok_or_else(|| RepositoryError::NotFound {
+            id: document_type_id.to_string(),
+        })?;
+
+        let department_name =
+            sqlx::query_scalar::<_, String>("SELECT name FROM departments WHERE code = ?")
+                .bind(department_code)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(RepositoryError::Database)?;
+
+        Ok(DocumentPathContext {
+            document_number: document_number.to_string(),
+            department_code: department_code.to_string(),
+            department_name,
+            document_type_code,
+            document_type_name,
+            created_date,
+        })
+    }
+}
+
+/// 空文字の部署コードは「全部署共通」として保存する
+fn normalize_department_code(department_code: Option<&str>) -> Option<&str> {
+    department_code
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+}
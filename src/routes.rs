@@ -1,12 +1,47 @@
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{get, post, put},
 };
 
 use crate::AppState;
+use crate::handlers::business_management::{generate_business_number, get_business_number_usage};
+use crate::handlers::clearance::{
+    get_employee_clearance_handler, update_employee_clearance_handler,
+    update_role_clearance_handler,
+};
+use crate::handlers::document_number_aliases::{
+    create_number_alias_handler, list_number_aliases_handler,
+};
+use crate::handlers::document_number_reservations::{
+    claim_reserved_number_handler, create_number_reservation_handler,
+    expire_number_reservations_handler, get_number_reservation_handler,
+    search_number_reservations_handler,
+};
+use crate::handlers::document_number_rules::{
+    create_number_rule_handler, decompose_document_number_handler, get_number_rule_handler,
+    number_gaps_handler, number_rule_conflicts_handler, retire_number_rule_handler,
+    search_number_rules_handler, simulate_number_rules_handler, update_number_rule_handler,
+    void_document_number_handler,
+};
+use crate::handlers::document_revisions::{
+    create_revision_handler, list_revisions_handler, promote_draft_handler,
+};
+use crate::handlers::employee_assignments::{
+    create_employee_assignment_handler, delete_employee_assignment_handler,
+    get_employee_assignment_handler, get_employee_handler, list_employee_assignments_handler,
+    update_employee_assignment_handler,
+};
 use crate::handlers::graphql::{graphql_handler, graphql_playground};
 use crate::handlers::http::{
-    create_document_handler, get_document_handler, health_check_handler, search_documents_handler,
+    create_document_handler, create_document_with_manual_number_handler,
+    create_documents_bulk_handler, delete_document_handler, get_document_handler,
+    health_check_handler, list_deleted_documents_handler, list_document_changes_handler,
+    restore_document_handler, search_documents_handler, update_document_handler,
+};
+use crate::handlers::path_generation_rules::{
+    create_path_rule_handler, get_path_rule_handler, list_path_rule_versions_handler,
+    resolve_network_path_handler, retire_path_rule_handler, search_path_rules_handler,
+    update_path_rule_handler, verify_network_paths_handler,
 };
 
 /// APIルーターの設定
@@ -16,8 +51,142 @@ pub fn create_routes() -> Router<AppState> {
         .route("/health", get(health_check_handler))
         // Document API
         .route("/api/documents", post(create_document_handler))
-        .route("/api/documents/{id}", get(get_document_handler))
+        .route(
+            "/api/documents/{id}",
+            get(get_document_handler)
+                .patch(update_document_handler)
+                .delete(delete_document_handler),
+        )
+        .route("/api/documents/trash", get(list_deleted_documents_handler))
+        .route(
+            "/api/documents/{id}/restore",
+            post(restore_document_handler),
+        )
+        .route(
+            "/api/documents/{id}/changes",
+            get(list_document_changes_handler),
+        )
         .route("/api/documents", get(search_documents_handler))
+        .route("/api/documents/bulk", post(create_documents_bulk_handler))
+        .route(
+            "/api/documents/manual-number",
+            post(create_document_with_manual_number_handler),
+        )
+        .route(
+            "/api/documents/{id}/aliases",
+            get(list_number_aliases_handler).post(create_number_alias_handler),
+        )
+        // Document Revision API
+        .route(
+            "/api/documents/{id}/revisions",
+            get(list_revisions_handler).post(create_revision_handler),
+        )
+        .route(
+            "/api/documents/{id}/revisions/{revision_id}/promote",
+            post(promote_draft_handler),
+        )
+        // Document Number Rule API
+        .route(
+            "/api/document-number-rules",
+            get(search_number_rules_handler).post(create_number_rule_handler),
+        )
+        .route(
+            "/api/document-number-rules/simulate",
+            post(simulate_number_rules_handler),
+        )
+        .route(
+            "/api/document-number-rules/conflicts",
+            get(number_rule_conflicts_handler),
+        )
+        .route(
+            "/api/document-numbers/decompose",
+            get(decompose_document_number_handler),
+        )
+        .route(
+            "/api/document-numbers/void",
+            post(void_document_number_handler),
+        )
+        .route("/api/document-numbers/gaps", get(number_gaps_handler))
+        .route(
+            "/api/document-number-rules/{id}",
+            get(get_number_rule_handler).put(update_number_rule_handler),
+        )
+        .route(
+            "/api/document-number-rules/{id}/retire",
+            post(retire_number_rule_handler),
+        )
+        // Document Number Reservation API
+        .route(
+            "/api/document-number-reservations",
+            get(search_number_reservations_handler).post(create_number_reservation_handler),
+        )
+        .route(
+            "/api/document-number-reservations/expire",
+            post(expire_number_reservations_handler),
+        )
+        .route(
+            "/api/document-number-reservations/{id}",
+            get(get_number_reservation_handler),
+        )
+        .route(
+            "/api/document-number-reservations/{id}/claim",
+            post(claim_reserved_number_handler),
+        )
+        // Employee Assignment API
+        .route("/api/employees/{id}", get(get_employee_handler))
+        .route(
+            "/api/employees/{id}/assignments",
+            get(list_employee_assignments_handler).post(create_employee_assignment_handler),
+        )
+        .route(
+            "/api/employee-assignments/{id}",
+            get(get_employee_assignment_handler)
+                .put(update_employee_assignment_handler)
+                .delete(delete_employee_assignment_handler),
+        )
+        // Clearance API
+        .route(
+            "/api/employees/{id}/clearance",
+            get(get_employee_clearance_handler).put(update_employee_clearance_handler),
+        )
+        .route(
+            "/api/roles/{role}/clearance",
+            put(update_role_clearance_handler),
+        )
+        // Path Generation Rule API
+        .route(
+            "/api/path-generation-rules",
+            get(search_path_rules_handler).post(create_path_rule_handler),
+        )
+        .route(
+            "/api/path-generation-rules/{id}",
+            get(get_path_rule_handler).put(update_path_rule_handler),
+        )
+        .route(
+            "/api/path-generation-rules/{id}/retire",
+            post(retire_path_rule_handler),
+        )
+        .route(
+            "/api/path-generation-rules/{id}/versions",
+            get(list_path_rule_versions_handler),
+        )
+        .route(
+            "/api/network-paths/verify",
+            post(verify_network_paths_handler),
+        )
+        .route(
+            "/api/network-paths/{document_number}",
+            get(resolve_network_path_handler),
+        )
+        // Business Number API
+        .route(
+            "/api/businesses/generate-number",
+            post(generate_business_number),
+        )
+        .route(
+            "/api/businesses/numbers/{business_number}/usage",
+            get(get_business_number_usage),
+        )
         // GraphQL エンドポイント（Playground付き）
         .route("/graphql", get(graphql_playground).post(graphql_handler))
 }
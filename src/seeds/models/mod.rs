@@ -2,11 +2,13 @@ pub mod seed_department;
 pub mod seed_document_number_rule;
 pub mod seed_document_type;
 pub mod seed_employee;
+pub mod seed_path_generation_rule;
 
 pub use seed_department::SeedDepartment;
 pub use seed_document_number_rule::SeedDocumentNumberRule;
 pub use seed_document_type::SeedDocumentType;
 pub use seed_employee::SeedEmployee;
+pub use seed_path_generation_rule::SeedPathGenerationRule;
 
 use serde::{Deserialize, Serialize};
 
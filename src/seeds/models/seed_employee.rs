@@ -1,6 +1,8 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+use crate::models::EmployeeRole;
+
 /// Seedデータ用の従業員構造体
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SeedEmployee {
@@ -11,6 +13,8 @@ pub struct SeedEmployee {
     pub position: String,
     pub email: String,
     pub hire_date: String, // JSON内では文字列で管理
+    #[serde(default)]
+    pub role: EmployeeRole,
 }
 
 impl SeedEmployee {
@@ -40,6 +44,7 @@ mod tests {
         assert_eq!(employee.id, 1);
         assert_eq!(employee.name, "山田太郎");
         assert_eq!(employee.department, "DEV");
+        assert_eq!(employee.role, EmployeeRole::User);
 
         // hire_dateの解析テスト
         let parsed_date = employee.hire_date_parsed().unwrap();
@@ -0,0 +1,63 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Seedデータ用のパス生成ルール構造体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeedPathGenerationRule {
+    pub id: i32,
+    pub rule_name: String,
+    pub path_template: String,
+    pub department_code: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    pub effective_from: String, // JSON内では文字列で管理
+    pub effective_until: Option<String>,
+    pub priority: i32,
+}
+
+impl SeedPathGenerationRule {
+    /// effective_fromをNaiveDateに変換
+    pub fn effective_from_parsed(&self) -> Result<NaiveDate, chrono::ParseError> {
+        NaiveDate::parse_from_str(&self.effective_from, "%Y-%m-%d")
+    }
+
+    /// effective_untilをNaiveDateに変換（存在する場合）
+    pub fn effective_until_parsed(&self) -> Result<Option<NaiveDate>, chrono::ParseError> {
+        self.effective_until
+            .as_deref()
+            .map(|date_str| NaiveDate::parse_from_str(date_str, "%Y-%m-%d"))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_path_generation_rule_deserialization() {
+        let json = r#"{
+            "id": 1,
+            "rule_name": "標準パス",
+            "path_template": "\\\\server01\\docs\\{年}\\{部署名}\\{文書種別名}",
+            "department_code": null,
+            "year_from": 2024,
+            "year_to": null,
+            "effective_from": "2024-01-01",
+            "effective_until": null,
+            "priority": 9
+        }"#;
+
+        let rule: SeedPathGenerationRule = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            rule.path_template,
+            r"\\server01\docs\{年}\{部署名}\{文書種別名}"
+        );
+        assert_eq!(rule.year_from, Some(2024));
+        assert_eq!(
+            rule.effective_from_parsed().unwrap().to_string(),
+            "2024-01-01"
+        );
+        assert_eq!(rule.effective_until_parsed().unwrap(), None);
+    }
+}
@@ -120,6 +120,13 @@ impl Seeder {
                 self.seed_document_number_rules(&seed_file.data, dry_run)
                     .await?
             }
+            "path_generation_rules" => {
+                let seed_file = self
+                    .loader
+                    .load_seed_file::<SeedPathGenerationRule>(env, table_name)?;
+                self.seed_path_generation_rules(&seed_file.data, dry_run)
+                    .await?
+            }
             _ => {
                 return Err(SeedError::InvalidData(format!(
                     "Unknown table: {}",
@@ -165,6 +172,15 @@ impl Seeder {
                     .execute(&self.pool)
                     .await?
             }
+            "path_generation_rules" => {
+                // 変更履歴がルールを参照しているため先に削除する
+                sqlx::query("DELETE FROM path_generation_rule_versions")
+                    .execute(&self.pool)
+                    .await?;
+                sqlx::query("DELETE FROM path_generation_rules")
+                    .execute(&self.pool)
+                    .await?
+            }
             _ => {
                 return Err(SeedError::InvalidData(format!(
                     "Unknown table: {}",
@@ -198,6 +214,7 @@ impl Seeder {
                     "departments".to_string(),
                     "document_types".to_string(),
                 ],
+                "path_generation_rules" => vec!["departments".to_string()],
                 _ => vec![],
             };
             dependencies.insert(table.clone(), deps);
@@ -271,8 +288,8 @@ impl Seeder {
 
             sqlx::query(r#"
                 INSERT OR REPLACE INTO employees 
-                (id, employee_number, name, department, position, email, hire_date, created_at, updated_at)
-                VALUES (?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+                (id, employee_number, name, department, position, email, hire_date, role, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
             "#)
             .bind(employee.id)
             .bind(&employee.employee_number)
@@ -281,11 +298,13 @@ impl Seeder {
             .bind(&employee.position)
             .bind(&employee.email)
             .bind(hire_date)
+            .bind(employee.role)
             .execute(&self.pool)
             .await?;
 
             count += 1;
         }
+        self.seed_primary_assignments().await?;
 
         Ok(count)
     }
@@ -333,9 +352,40 @@ impl Seeder {
             count += 1;
         }
 
+        // 履歴のない部署は作成日（不明な場合は最初期）から現在まで有効な記録を作る
+        sqlx::query(
+            r#"
+            INSERT INTO department_versions (department_id, code, name, kind, parent_id, level, valid_from)
+            SELECT id, code, name, kind, parent_id, COALESCE(level, 0), COALESCE(created_date, '1900-01-01')
+            FROM departments d
+            WHERE NOT EXISTS (SELECT 1 FROM department_versions v WHERE v.department_id = d.id)
+        "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        self.seed_primary_assignments().await?;
+
         Ok(count)
     }
 
+    /// 所属のない社員に、部署コードが一致する部署の主務を入社日（部署の新設より前なら新設日）から登録する
+    async fn seed_primary_assignments(&self) -> Result<(), SeedError> {
+        sqlx::query(
+            r#"
+            INSERT INTO department_assignments (employee_id, department_id, position, assignment_type, start_date)
+            SELECT e.id, d.id, e.position, 'primary',
+                   COALESCE(MAX(e.hire_date, d.created_date), e.hire_date, d.created_date, '1900-01-01')
+            FROM employees e
+            JOIN departments d ON d.code = e.department AND d.kind = 'department'
+            WHERE NOT EXISTS (SELECT 1 FROM department_assignments a WHERE a.employee_id = e.id)
+        "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     async fn seed_document_types(
         &self,
         document_types: &[SeedDocumentType],
@@ -427,4 +477,77 @@ impl Seeder {
 
         Ok(count)
     }
+
+    async fn seed_path_generation_rules(
+        &self,
+        rules: &[SeedPathGenerationRule],
+        dry_run: bool,
+    ) -> Result<usize, SeedError> {
+        if dry_run {
+            println!("  Would insert {} path generation rules", rules.len());
+            return Ok(rules.len());
+        }
+
+        let mut count = 0;
+        for rule in rules {
+            let effective_from = rule
+                .effective_from_parsed()
+                .map_err(|e| SeedError::InvalidData(format!("Invalid effective_from: {}", e)))?;
+
+            let effective_until = rule
+                .effective_until_parsed()
+                .map_err(|e| SeedError::InvalidData(format!("Invalid effective_until: {}", e)))?;
+
+            // シードの内容を初版とするため、既存の変更履歴は置き換える
+            sqlx::query("DELETE FROM path_generation_rule_versions WHERE rule_id = ?")
+                .bind(rule.id)
+                .execute(&self.pool)
+                .await?;
+
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO path_generation_rules
+                (id, rule_name, path_template, department_code, year_from, year_to,
+                 effective_from, effective_until, priority, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+            "#,
+            )
+            .bind(rule.id)
+            .bind(&rule.rule_name)
+            .bind(&rule.path_template)
+            .bind(&rule.department_code)
+            .bind(rule.year_from)
+            .bind(rule.year_to)
+            .bind(effective_from)
+            .bind(effective_until)
+            .bind(rule.priority)
+            .execute(&self.pool)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO path_generation_rule_versions
+                (rule_id, version_number, rule_name, path_template, department_code, year_from,
+                 year_to, effective_from, effective_until, priority, change_type, valid_from)
+                VALUES (?, 1, ?, ?, ?, ?, ?, ?, ?, ?, 'created', ?)
+            "#,
+            )
+            .bind(rule.id)
+            .bind(&rule.rule_name)
+            .bind(&rule.path_template)
+            .bind(&rule.department_code)
+            .bind(rule.year_from)
+            .bind(rule.year_to)
+            .bind(effective_from)
+            .bind(effective_until)
+            .bind(rule.priority)
+            .bind(effective_from)
+            .execute(&self.pool)
+            .await?;
+
+            count += 1;
+        }
+
+        Ok(count)
+    }
 }
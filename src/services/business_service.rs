@@ -1,8 +1,8 @@
 use crate::error::BusinessError;
 use crate::models::{
-    Business, BusinessMember, BusinessSearchFilters, CirculationCandidate, CirculationCandidates,
-    CreateBusinessMemberRequest, CreateBusinessRequest, UpdateBusinessMemberRequest,
-    UpdateBusinessRequest,
+    Business, BusinessMember, BusinessNumberAllocation, BusinessNumberUsage, BusinessSearchFilters,
+    CirculationCandidate, CirculationCandidates, CreateBusinessMemberRequest,
+    CreateBusinessRequest, UpdateBusinessMemberRequest, UpdateBusinessRequest,
 };
 use crate::repositories::BusinessRepository;
 use std::sync::Arc;
@@ -81,6 +81,7 @@ impl BusinessService {
         &self,
         business_id: i32,
         request: UpdateBusinessRequest,
+        expected_version: i32,
         user_permissions: &UserPermissions,
     ) -> Result<Business, BusinessError> {
         // 権限チェック
@@ -91,7 +92,9 @@ impl BusinessService {
             return Err(BusinessError::PermissionDenied);
         }
 
-        self.repository.update(business_id, request).await
+        self.repository
+            .update(business_id, request, expected_version)
+            .await
     }
 
     /// 業務を削除する
@@ -256,10 +259,23 @@ impl BusinessService {
     }
 
     /// 業務番号を生成する
-    pub async fn generate_business_number(&self) -> Result<String, BusinessError> {
+    pub async fn generate_business_number(
+        &self,
+    ) -> Result<BusinessNumberAllocation, BusinessError> {
         self.repository.generate_business_number().await
     }
 
+    /// 業務番号の使用状況を取得する
+    pub async fn get_business_number_usage(
+        &self,
+        business_number: &str,
+    ) -> Result<BusinessNumberUsage, BusinessError> {
+        self.repository
+            .get_business_number_usage(business_number)
+            .await?
+            .ok_or(BusinessError::NotFound)
+    }
+
     // プライベートメソッド
 
     /// ユーザーが業務に関与しているかチェック
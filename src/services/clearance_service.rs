@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use crate::models::{
+    EmployeeClearance, EmployeeRole, RoleClearance, UpdateEmployeeClearanceRequest,
+    UpdateRoleClearanceRequest,
+};
+use crate::repositories::{EmployeeRepository, RepositoryError};
+use crate::services::{DocumentServiceError, EmployeeService};
+
+/// 社員・ロールの機密レベル設定を扱うサービス（設定の変更は管理者のみ）
+#[derive(Clone)]
+pub struct ClearanceService {
+    employee_repository: Arc<dyn EmployeeRepository>,
+    /// 設定を変更する社員が管理者であることを確かめる
+    employee_service: EmployeeService,
+}
+
+impl ClearanceService {
+    pub fn new(
+        employee_repository: impl EmployeeRepository + 'static,
+        employee_service: EmployeeService,
+    ) -> Self {
+        Self {
+            employee_repository: Arc::new(employee_repository),
+            employee_service,
+        }
+    }
+
+    /// 社員の機密レベル設定を取得する
+    pub async fn get_employee_clearance(
+        &self,
+        employee_id: i32,
+    ) -> Result<EmployeeClearance, DocumentServiceError> {
+        self.employee_repository
+            .get_clearance(employee_id)
+            .await?
+            .ok_or_else(|| not_found(employee_id))
+    }
+
+    /// 社員個別の機密レベルを設定する（管理者のみ）
+    pub async fn update_employee_clearance(
+        &self,
+        actor_id: Option<i32>,
+        employee_id: i32,
+        request: UpdateEmployeeClearanceRequest,
+    ) -> Result<EmployeeClearance, DocumentServiceError> {
+        self.employee_service.ensure_admin(actor_id).await?;
+
+        self.employee_repository
+            .set_employee_clearance(employee_id, request.clearance_level)
+            .await?
+            .ok_or_else(|| not_found(employee_id))
+    }
+
+    /// ロールの機密レベルを設定する（管理者のみ）
+    pub async fn update_role_clearance(
+        &self,
+        actor_id: Option<i32>,
+        role: EmployeeRole,
+        request: UpdateRoleClearanceRequest,
+    ) -> Result<RoleClearance, DocumentServiceError> {
+        self.employee_service.ensure_admin(actor_id).await?;
+
+        self.employee_repository
+            .set_role_clearance(role, request.clearance_level)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+}
+
+fn not_found(employee_id: i32) -> DocumentServiceError {
+    DocumentServiceError::RepositoryError(RepositoryError::NotFound {
+        id: employee_id.to_string(),
+    })
+}
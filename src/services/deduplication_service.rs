@@ -230,6 +230,8 @@ impl DeduplicationServiceImpl {
                 email: Some("yamada@company.com".to_string()),
                 ad_username: Some("yamada.taro".to_string()),
                 department_id: Some(1),
+                section_id: None,
+                position: None,
                 is_active: true,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
@@ -241,6 +243,8 @@ impl DeduplicationServiceImpl {
                 email: Some("yamada.taro@company.com".to_string()),
                 ad_username: Some("yamada_taro".to_string()),
                 department_id: Some(1),
+                section_id: None,
+                position: None,
                 is_active: true,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
@@ -252,6 +256,8 @@ impl DeduplicationServiceImpl {
                 email: Some("sato@company.com".to_string()),
                 ad_username: Some("sato.hanako".to_string()),
                 department_id: Some(2),
+                section_id: None,
+                position: None,
                 is_active: true,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
@@ -263,6 +269,8 @@ impl DeduplicationServiceImpl {
                 email: Some("hanako.sato@company.com".to_string()),
                 ad_username: Some("h.sato".to_string()),
                 department_id: Some(2),
+                section_id: None,
+                position: None,
                 is_active: true,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
@@ -1,21 +1,16 @@
-use chrono::Datelike;
 use std::sync::Arc;
 
 use crate::models::{
-    DocumentNumberGenerationError, DocumentNumberRequest, GeneratedDocumentNumber,
+    DocumentNumberGenerationError, DocumentNumberGenerationRule, DocumentNumberRequest,
+    GeneratedDocumentNumber, RuleConflictReport, RulePriorityOverlap, RuleSimulationRequest,
+    RuleSimulationResult, RuleTemplateCollision, SequenceKey, TemplateCollisionKind,
 };
 use crate::repositories::DocumentNumberRuleRepository;
-
-/// テンプレート適用用のパラメータ構造体
-struct TemplateParams<'a> {
-    template: &'a str,
-    department_code: &'a str,
-    document_type_code: &'a str,
-    year: i32,
-    month: i32,
-    sequence_number: i32,
-    sequence_digits: i32,
-}
+use crate::services::document_number_template::{TemplateToken, template_uses};
+use crate::services::{
+    NumberTemplateContext, find_colliding_number, find_shared_counter_collision,
+    render_document_number,
+};
 
 /// 文書番号生成サービス
 #[derive(Clone)]
@@ -25,60 +20,37 @@ pub struct DocumentNumberGenerator {
 
 impl DocumentNumberGenerator {
     pub fn new(rule_repository: impl DocumentNumberRuleRepository + 'static) -> Self {
-        Self {
-            rule_repository: Arc::new(rule_repository),
-        }
+        Self::with_repository(Arc::new(rule_repository))
+    }
+
+    /// 共有済みのルールリポジトリから生成サービスを作成する
+    pub fn with_repository(rule_repository: Arc<dyn DocumentNumberRuleRepository>) -> Self {
+        Self { rule_repository }
     }
 
-    /// 文書番号を生成する
+    /// 文書番号を生成する（採番は行わないプレビュー）
+    ///
+    /// 実際の採番は文書作成時に連番カウンターを進めて行うため、
+    /// ここで返す番号は同時に作成された他の文書に使われる可能性がある
     pub async fn generate_document_number(
         &self,
         request: DocumentNumberRequest,
     ) -> Result<GeneratedDocumentNumber, DocumentNumberGenerationError> {
-        // リクエストのバリデーション
-        request
-            .validate()
-            .map_err(|_| DocumentNumberGenerationError::NoApplicableRule)?;
+        let request = self.resolve_historical_codes(&request).await?;
+        let rule = self.resolve_rule(&request).await?;
+        let key = Self::sequence_key(&rule, &request);
+        let context = self.template_context(&rule, &request).await?;
 
-        // 適用可能なルールを検索
-        let rule = self
+        // 次の連番を取得
+        let mut sequence_number = self
             .rule_repository
-            .find_applicable_rule(
-                &request.document_type_code,
-                &request.department_code,
-                request.created_date,
-            )
+            .get_next_sequence_number(key.rule_id, key.year, key.month, &key.department_code)
             .await?;
 
-        let rule = match rule {
-            Some(rule) => rule,
-            None => return Err(DocumentNumberGenerationError::NoApplicableRule),
-        };
+        // 既存番号と重複する場合は連番上限まで読み飛ばす
+        loop {
+            let document_number = self.format_number(&rule, &context, sequence_number)?;
 
-        // 年月の情報を取得
-        let year = request.created_date.year();
-        let month = request.created_date.month() as i32;
-
-        // 最大10回まで重複回避を試行
-        for _attempt in 0..10 {
-            // 次の連番を取得
-            let sequence_number = self
-                .rule_repository
-                .get_next_sequence_number(rule.id, year, month, &request.department_code)
-                .await?;
-
-            // テンプレートから文書番号を生成
-            let document_number = self.apply_template(TemplateParams {
-                template: &rule.template,
-                department_code: &request.department_code,
-                document_type_code: &request.document_type_code,
-                year,
-                month,
-                sequence_number,
-                sequence_digits: rule.sequence_digits,
-            })?;
-
-            // 重複チェック
             let exists = self
                 .rule_repository
                 .is_document_number_exists(&document_number)
@@ -92,48 +64,259 @@ impl DocumentNumberGenerator {
                     template_used: rule.template,
                 });
             }
+
+            sequence_number += 1;
         }
+    }
 
-        Err(DocumentNumberGenerationError::SequenceExhausted)
+    /// 部署コード・課コードを文書の作成日時点の組織のコードに読み替えたリクエストを返す
+    ///
+    /// 組織改編の前のコードで新しい文書を、改編後のコードで過去の日付の文書を作成しても、
+    /// 作成日に有効だったコードでルールを選択し番号を組み立てる
+    pub async fn resolve_historical_codes(
+        &self,
+        request: &DocumentNumberRequest,
+    ) -> Result<DocumentNumberRequest, DocumentNumberGenerationError> {
+        let (department_code, section_code) = self
+            .rule_repository
+            .resolve_organization_codes(
+                &request.department_code,
+                request.section_code.clone(),
+                request.created_date,
+            )
+            .await?;
+
+        Ok(DocumentNumberRequest {
+            department_code,
+            section_code,
+            ..request.clone()
+        })
     }
 
-    /// テンプレートを適用して文書番号を生成
-    fn apply_template(
+    /// リクエストに適用する文書番号生成ルールを決定する
+    pub async fn resolve_rule(
         &self,
-        params: TemplateParams,
+        request: &DocumentNumberRequest,
+    ) -> Result<DocumentNumberGenerationRule, DocumentNumberGenerationError> {
+        // リクエストのバリデーション
+        request
+            .validate()
+            .map_err(|_| DocumentNumberGenerationError::NoApplicableRule)?;
+
+        // 適用可能なルールを検索
+        self.rule_repository
+            .find_applicable_rule(
+                &request.document_type_code,
+                &request.department_code,
+                request.created_date,
+            )
+            .await?
+            .ok_or(DocumentNumberGenerationError::NoApplicableRule)
+    }
+
+    /// ルールとリクエストから連番カウンターのキーを作成する（期間はルールのリセット単位に従う）
+    pub fn sequence_key(
+        rule: &DocumentNumberGenerationRule,
+        request: &DocumentNumberRequest,
+    ) -> SequenceKey {
+        let (year, month) = rule.reset_scope.period(request.created_date);
+        SequenceKey {
+            rule_id: rule.id,
+            year,
+            month,
+            department_code: request.department_code.clone(),
+        }
+    }
+
+    /// テンプレートに埋め込む文書の属性を用意する
+    ///
+    /// 文書種別名はテンプレートが`{文書種別名}`を含む場合だけ取得する
+    pub async fn template_context(
+        &self,
+        rule: &DocumentNumberGenerationRule,
+        request: &DocumentNumberRequest,
+    ) -> Result<NumberTemplateContext, DocumentNumberGenerationError> {
+        let document_type_name = if template_uses(&rule.template, &TemplateToken::DocumentTypeName)
+        {
+            self.rule_repository
+                .find_document_type_name(&request.document_type_code)
+                .await?
+        } else {
+            None
+        };
+
+        Ok(NumberTemplateContext {
+            document_type_code: request.document_type_code.clone(),
+            document_type_name,
+            department_code: request.department_code.clone(),
+            section_code: request
+                .section_code
+                .as_deref()
+                .map(str::trim)
+                .filter(|code| !code.is_empty())
+                .map(str::to_string),
+            created_date: request.created_date,
+        })
+    }
+
+    /// 連番を埋め込んで文書番号を組み立てる
+    ///
+    /// 連番がルールの桁数に収まらない場合は SequenceExhausted を返す
+    pub fn format_number(
+        &self,
+        rule: &DocumentNumberGenerationRule,
+        context: &NumberTemplateContext,
+        sequence_number: i32,
     ) -> Result<String, DocumentNumberGenerationError> {
-        let mut result = params.template.to_string();
-
-        // 文書種別コード
-        result = result.replace("{文書種別コード}", params.document_type_code);
-
-        // 部署コード
-        result = result.replace("{部署コード}", params.department_code);
-
-        // 年（下2桁）
-        let year_short = params.year % 100;
-        result = result.replace("{年下2桁}", &format!("{year_short:02}"));
-
-        // 月（2桁ゼロ埋め）
-        result = result.replace("{月:2桁}", &format!("{:02}", params.month));
-
-        // 連番（指定桁数でゼロ埋め）
-        let sequence_format = format!("{{連番:{}桁}}", params.sequence_digits);
-        let sequence_value = format!(
-            "{:0width$}",
-            params.sequence_number,
-            width = params.sequence_digits as usize
-        );
-        result = result.replace(&sequence_format, &sequence_value);
-
-        // 未処理のプレースホルダーがないかチェック
-        if result.contains('{') && result.contains('}') {
-            return Err(DocumentNumberGenerationError::TemplateError(format!(
-                "Unresolved placeholders in template: {}",
-                params.template
-            )));
+        let max_sequence = 10_i64
+            .checked_pow(rule.sequence_digits as u32)
+            .map_or(i64::MAX, |limit| limit - 1);
+        if i64::from(sequence_number) > max_sequence {
+            return Err(DocumentNumberGenerationError::SequenceExhausted);
+        }
+
+        render_document_number(&rule.template, context, sequence_number)
+    }
+
+    /// ルールの適用をシミュレーションする（採番は行わない）
+    ///
+    /// 適用候補のルールを選択される順に並べ、選択されるルールが次に採番する番号を求める
+    pub async fn simulate(
+        &self,
+        request: &RuleSimulationRequest,
+    ) -> Result<RuleSimulationResult, DocumentNumberGenerationError> {
+        let number_request = self
+            .resolve_historical_codes(&request.number_request())
+            .await?;
+        let candidates = self
+            .rule_repository
+            .find_candidate_rules(
+                &number_request.document_type_code,
+                &number_request.department_code,
+                number_request.created_date,
+            )
+            .await?;
+
+        let mut result = RuleSimulationResult {
+            document_type_code: number_request.document_type_code.clone(),
+            department_code: number_request.department_code.clone(),
+            created_date: number_request.created_date,
+            candidates: Vec::new(),
+            selected_rule_id: candidates.first().map(|rule| rule.id),
+            next_numbers: Vec::new(),
+            error: None,
+        };
+
+        if let Some(rule) = candidates.first() {
+            let key = Self::sequence_key(rule, &number_request);
+            let context = self.template_context(rule, &number_request).await?;
+            let mut sequence_number = self
+                .rule_repository
+                .get_next_sequence_number(key.rule_id, key.year, key.month, &key.department_code)
+                .await?;
+
+            while result.next_numbers.len() < request.count() as usize {
+                let document_number = match self.format_number(rule, &context, sequence_number) {
+                    Ok(number) => number,
+                    Err(e) => {
+                        result.error = Some(e.to_string());
+                        break;
+                    }
+                };
+                if !self
+                    .rule_repository
+                    .is_document_number_exists(&document_number)
+                    .await?
+                {
+                    result.next_numbers.push(document_number);
+                }
+                sequence_number += 1;
+            }
+        } else {
+            result.error = Some(DocumentNumberGenerationError::NoApplicableRule.to_string());
         }
 
+        result.candidates = candidates;
         Ok(result)
     }
+
+    /// すべてのルールを走査し、同じ優先度での適用範囲の重なりと文書番号の衝突を報告する
+    pub async fn find_rule_conflicts(
+        &self,
+    ) -> Result<RuleConflictReport, DocumentNumberGenerationError> {
+        let (mut rules, _) = self
+            .rule_repository
+            .search_rules(None, None, i64::MAX, 0)
+            .await?;
+        // 報告する組はIDの小さいルールを先にする
+        rules.sort_by_key(|rule| rule.id);
+
+        let mut report = RuleConflictReport {
+            checked_rules: rules.len() as i64,
+            priority_overlaps: Vec::new(),
+            template_collisions: Vec::new(),
+        };
+
+        for (index, rule) in rules.iter().enumerate() {
+            if let Some(kind) = find_shared_counter_collision(rule) {
+                report.template_collisions.push(RuleTemplateCollision {
+                    kind,
+                    rule_id: rule.id,
+                    rule_name: rule.rule_name.clone(),
+                    template: rule.template.clone(),
+                    other_rule_id: rule.id,
+                    other_rule_name: rule.rule_name.clone(),
+                    other_template: rule.template.clone(),
+                    example_number: None,
+                    periods_overlap: true,
+                });
+            }
+
+            for other in &rules[index + 1..] {
+                let overlap = rule.overlapping_period(other);
+
+                // 部署固有ルールは汎用ルールより常に優先されるため、同じ区分同士だけを比べる
+                if let Some((overlap_from, overlap_until)) = overlap
+                    && rule.priority == other.priority
+                    && rule.department_code == other.department_code
+                {
+                    let other_codes = other.parsed_document_type_codes();
+                    let shared_codes: Vec<String> = rule
+                        .parsed_document_type_codes()
+                        .into_iter()
+                        .filter(|code| other_codes.contains(code))
+                        .collect();
+                    if !shared_codes.is_empty() {
+                        report.priority_overlaps.push(RulePriorityOverlap {
+                            rule_id: rule.id,
+                            rule_name: rule.rule_name.clone(),
+                            other_rule_id: other.id,
+                            other_rule_name: other.rule_name.clone(),
+                            priority: rule.priority,
+                            department_code: rule.department_code.clone(),
+                            document_type_codes: shared_codes,
+                            overlap_from,
+                            overlap_until,
+                        });
+                    }
+                }
+
+                if let Some(example_number) = find_colliding_number(rule, other) {
+                    report.template_collisions.push(RuleTemplateCollision {
+                        kind: TemplateCollisionKind::SharedPattern,
+                        rule_id: rule.id,
+                        rule_name: rule.rule_name.clone(),
+                        template: rule.template.clone(),
+                        other_rule_id: other.id,
+                        other_rule_name: other.rule_name.clone(),
+                        other_template: other.template.clone(),
+                        example_number: Some(example_number),
+                        periods_overlap: overlap.is_some(),
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
 }
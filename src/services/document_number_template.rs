@@ -0,0 +1,724 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::models::{
+    DecomposedDocumentNumber, DocumentNumberGenerationError, DocumentNumberGenerationRule,
+    ParsedDocumentNumber, SequenceKey, SequenceResetScope, TemplateCollisionKind,
+};
+
+/// テンプレートの構成要素
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TemplateToken {
+    Literal(String),
+    DocumentTypeCode,
+    DocumentTypeName,
+    DepartmentCode,
+    SectionCode,
+    Year,
+    YearShort,
+    Month,
+    FiscalYear,
+    FiscalYearShort,
+    EraName,
+    EraInitial,
+    EraYear,
+    EraYearPadded,
+    Sequence(usize),
+}
+
+/// 和暦の元号
+pub(crate) struct Era {
+    pub name: &'static str,
+    pub initial: &'static str,
+    pub start: NaiveDate,
+}
+
+/// 元号の一覧（新しい順）
+pub(crate) const ERAS: [Era; 5] = [
+    Era {
+        name: "令和",
+        initial: "R",
+        start: NaiveDate::from_ymd_opt(2019, 5, 1).unwrap(),
+    },
+    Era {
+        name: "平成",
+        initial: "H",
+        start: NaiveDate::from_ymd_opt(1989, 1, 8).unwrap(),
+    },
+    Era {
+        name: "昭和",
+        initial: "S",
+        start: NaiveDate::from_ymd_opt(1926, 12, 25).unwrap(),
+    },
+    Era {
+        name: "大正",
+        initial: "T",
+        start: NaiveDate::from_ymd_opt(1912, 7, 30).unwrap(),
+    },
+    Era {
+        name: "明治",
+        initial: "M",
+        start: NaiveDate::from_ymd_opt(1868, 10, 23).unwrap(),
+    },
+];
+
+/// 日付が属する元号と和暦年を求める
+pub(crate) fn era_of(date: NaiveDate) -> Option<(&'static Era, i32)> {
+    ERAS.iter()
+        .find(|era| date >= era.start)
+        .map(|era| (era, date.year() - era.start.year() + 1))
+}
+
+/// 日付が属する年度（4月始まり）
+pub(crate) fn fiscal_year_of(date: NaiveDate) -> i32 {
+    if date.month() >= 4 {
+        date.year()
+    } else {
+        date.year() - 1
+    }
+}
+
+/// テンプレート文字列を構成要素に分解する
+///
+/// `{{`と`}}`はそれぞれ波括弧そのものとして扱う
+pub(crate) fn tokenize(
+    template: &str,
+) -> Result<Vec<TemplateToken>, DocumentNumberGenerationError> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        literal.push_str(&rest[..start]);
+        let brace = &rest[start..];
+
+        if let Some(after) = brace
+            .strip_prefix("{{")
+            .or_else(|| brace.strip_prefix("}}"))
+        {
+            literal.push_str(&brace[..1]);
+            rest = after;
+            continue;
+        }
+        if brace.starts_with('}') {
+            return Err(DocumentNumberGenerationError::TemplateError(format!(
+                "Unmatched '}}' in template (use '}}}}' for a literal brace): {template}"
+            )));
+        }
+
+        let after = &brace[1..];
+        let end = after.find('}').ok_or_else(|| {
+            DocumentNumberGenerationError::TemplateError(format!(
+                "Unclosed placeholder in template: {template}"
+            ))
+        })?;
+
+        let token = match &after[..end] {
+            "文書種別コード" => TemplateToken::DocumentTypeCode,
+            "文書種別名" => TemplateToken::DocumentTypeName,
+            "部署コード" => TemplateToken::DepartmentCode,
+            "課コード" => TemplateToken::SectionCode,
+            "年" | "年:4桁" => TemplateToken::Year,
+            "年下2桁" => TemplateToken::YearShort,
+            "月:2桁" => TemplateToken::Month,
+            "年度" | "年度:4桁" => TemplateToken::FiscalYear,
+            "年度下2桁" => TemplateToken::FiscalYearShort,
+            "元号" => TemplateToken::EraName,
+            "元号略" => TemplateToken::EraInitial,
+            "和暦年" => TemplateToken::EraYear,
+            "和暦年:2桁" => TemplateToken::EraYearPadded,
+            name => match name
+                .strip_prefix("連番:")
+                .and_then(|digits| digits.strip_suffix('桁'))
+                .and_then(|digits| digits.parse::<usize>().ok())
+            {
+                Some(digits) if digits > 0 => TemplateToken::Sequence(digits),
+                _ => {
+                    return Err(DocumentNumberGenerationError::TemplateError(format!(
+                        "Unknown placeholder {{{name}}} in template: {template}"
+                    )));
+                }
+            },
+        };
+
+        if !literal.is_empty() {
+            tokens.push(TemplateToken::Literal(std::mem::take(&mut literal)));
+        }
+        tokens.push(token);
+        rest = &after[end + 1..];
+    }
+
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        tokens.push(TemplateToken::Literal(literal));
+    }
+
+    Ok(tokens)
+}
+
+/// ルール保存時にテンプレートを検証する
+///
+/// 未知のプレースホルダー・閉じ忘れ・連番がちょうど1つでないもの・連番の桁数とルールの桁数の不一致を拒否する
+pub fn validate_number_template(
+    template: &str,
+    sequence_digits: i32,
+) -> Result<(), DocumentNumberGenerationError> {
+    let mut sequences = 0;
+    for token in tokenize(template)? {
+        if let TemplateToken::Sequence(digits) = token {
+            sequences += 1;
+            if digits as i64 != i64::from(sequence_digits) {
+                return Err(DocumentNumberGenerationError::TemplateError(format!(
+                    "{{連番:{digits}桁}} does not match sequence_digits {sequence_digits}: {template}"
+                )));
+            }
+        }
+    }
+    if sequences != 1 {
+        return Err(DocumentNumberGenerationError::TemplateError(format!(
+            "Template must contain exactly one {{連番:N桁}}: {template}"
+        )));
+    }
+    Ok(())
+}
+
+/// テンプレートが指定の構成要素を含むか
+pub(crate) fn template_uses(template: &str, token: &TemplateToken) -> bool {
+    tokenize(template).is_ok_and(|tokens| tokens.contains(token))
+}
+
+/// テンプレートに埋め込む文書の属性
+#[derive(Debug, Clone)]
+pub struct NumberTemplateContext {
+    pub document_type_code: String,
+    pub document_type_name: Option<String>,
+    pub department_code: String,
+    pub section_code: Option<String>,
+    pub created_date: NaiveDate,
+}
+
+/// テンプレートに文書の属性と連番を埋め込んで文書番号を組み立てる
+pub fn render_document_number(
+    template: &str,
+    context: &NumberTemplateContext,
+    sequence_number: i32,
+) -> Result<String, DocumentNumberGenerationError> {
+    let date = context.created_date;
+    let era = || {
+        era_of(date).ok_or_else(|| {
+            DocumentNumberGenerationError::TemplateError(format!(
+                "No Japanese era defined for {date}"
+            ))
+        })
+    };
+    let required = |value: &Option<String>, name: &str| {
+        value.clone().ok_or_else(|| {
+            DocumentNumberGenerationError::TemplateError(format!(
+                "{name} is required by template: {template}"
+            ))
+        })
+    };
+
+    let mut number = String::new();
+    for token in tokenize(template)? {
+        let part = match token {
+            TemplateToken::Literal(text) => text,
+            TemplateToken::DocumentTypeCode => context.document_type_code.clone(),
+            TemplateToken::DocumentTypeName => {
+                required(&context.document_type_name, "Document type name")?
+            }
+            TemplateToken::DepartmentCode => context.department_code.clone(),
+            TemplateToken::SectionCode => required(&context.section_code, "Section code")?,
+            TemplateToken::Year => format!("{:04}", date.year()),
+            TemplateToken::YearShort => format!("{:02}", date.year() % 100),
+            TemplateToken::Month => format!("{:02}", date.month()),
+            TemplateToken::FiscalYear => format!("{:04}", fiscal_year_of(date)),
+            TemplateToken::FiscalYearShort => format!("{:02}", fiscal_year_of(date) % 100),
+            TemplateToken::EraName => era()?.0.name.to_string(),
+            TemplateToken::EraInitial => era()?.0.initial.to_string(),
+            TemplateToken::EraYear => era()?.1.to_string(),
+            TemplateToken::EraYearPadded => format!("{:02}", era()?.1),
+            TemplateToken::Sequence(digits) => format!("{sequence_number:0digits$}"),
+        };
+        number.push_str(&part);
+    }
+
+    Ok(number)
+}
+
+/// ルールのテンプレートで生成され得る番号であれば構成要素に分解する
+pub fn parse_document_number(
+    rule: &DocumentNumberGenerationRule,
+    document_number: &str,
+) -> Option<ParsedDocumentNumber> {
+    let tokens = tokenize(&rule.template).ok()?;
+    let mut parsed = ParsedDocumentNumber {
+        rule_id: rule.id,
+        document_type_code: None,
+        document_type_name: None,
+        department_code: None,
+        section_code: None,
+        year: None,
+        month: None,
+        fiscal_year: None,
+        era: None,
+        era_year: None,
+        sequence_number: 0,
+    };
+
+    let mut document_type_codes = rule.parsed_document_type_codes();
+    // 長いコードから試し、"DEV-TEC" が "DEV" に誤って一致しないようにする
+    document_type_codes.sort_by_key(|code| std::cmp::Reverse(code.len()));
+
+    let context = MatchContext {
+        document_type_codes: &document_type_codes,
+        department_code: rule.department_code.as_deref(),
+    };
+
+    if !match_tokens(&tokens, document_number, &context, &mut parsed) {
+        return None;
+    }
+
+    // 西暦年がテンプレートに無い場合は和暦・年度から補う
+    if parsed.year.is_none() {
+        parsed.year = match (&parsed.era, parsed.era_year, parsed.fiscal_year) {
+            (Some(name), Some(era_year), _) => ERAS
+                .iter()
+                .find(|era| era.name == name)
+                .map(|era| era.start.year() + era_year - 1),
+            (_, _, Some(fiscal_year)) => parsed.month.map(|month| {
+                if month >= 4 {
+                    fiscal_year
+                } else {
+                    fiscal_year + 1
+                }
+            }),
+            _ => None,
+        };
+    }
+
+    Some(parsed)
+}
+
+struct MatchContext<'a> {
+    document_type_codes: &'a [String],
+    department_code: Option<&'a str>,
+}
+
+/// 構成要素を先頭から順に照合する（部署コード等の可変長要素はバックトラックする）
+fn match_tokens(
+    tokens: &[TemplateToken],
+    input: &str,
+    context: &MatchContext,
+    parsed: &mut ParsedDocumentNumber,
+) -> bool {
+    let Some((token, remaining_tokens)) = tokens.split_first() else {
+        return input.is_empty();
+    };
+
+    match token {
+        TemplateToken::Literal(text) => input
+            .strip_prefix(text.as_str())
+            .is_some_and(|rest| match_tokens(remaining_tokens, rest, context, parsed)),
+        TemplateToken::DocumentTypeCode => context.document_type_codes.iter().any(|code| {
+            input.strip_prefix(code.as_str()).is_some_and(|rest| {
+                parsed.document_type_code = Some(code.clone());
+                match_tokens(remaining_tokens, rest, context, parsed)
+            })
+        }),
+        TemplateToken::DepartmentCode => {
+            if let Some(code) = context.department_code {
+                return input.strip_prefix(code).is_some_and(|rest| {
+                    parsed.department_code = Some(code.to_string());
+                    match_tokens(remaining_tokens, rest, context, parsed)
+                });
+            }
+
+            let max_len = input
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .count()
+                .min(10);
+            (1..=max_len).rev().any(|len| {
+                parsed.department_code = Some(input[..len].to_string());
+                match_tokens(remaining_tokens, &input[len..], context, parsed)
+            })
+        }
+        TemplateToken::DocumentTypeName => {
+            // 文書種別名はルールに保持していないため、任意の文字列として読み飛ばす
+            let boundaries: Vec<usize> = input
+                .char_indices()
+                .map(|(index, _)| index)
+                .skip(1)
+                .chain((!input.is_empty()).then_some(input.len()))
+                .collect();
+            boundaries.into_iter().rev().any(|len| {
+                parsed.document_type_name = Some(input[..len].to_string());
+                match_tokens(remaining_tokens, &input[len..], context, parsed)
+            })
+        }
+        TemplateToken::SectionCode => {
+            let max_len = input
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .count()
+                .min(10);
+            (1..=max_len).rev().any(|len| {
+                parsed.section_code = Some(input[..len].to_string());
+                match_tokens(remaining_tokens, &input[len..], context, parsed)
+            })
+        }
+        TemplateToken::Year => take_digits(input, 4).is_some_and(|(value, rest)| {
+            parsed.year = Some(value);
+            match_tokens(remaining_tokens, rest, context, parsed)
+        }),
+        TemplateToken::FiscalYear => take_digits(input, 4).is_some_and(|(value, rest)| {
+            parsed.fiscal_year = Some(value);
+            match_tokens(remaining_tokens, rest, context, parsed)
+        }),
+        TemplateToken::FiscalYearShort => take_digits(input, 2).is_some_and(|(value, rest)| {
+            parsed.fiscal_year = Some(expand_short_year(value));
+            match_tokens(remaining_tokens, rest, context, parsed)
+        }),
+        TemplateToken::EraName => ERAS.iter().any(|era| {
+            input.strip_prefix(era.name).is_some_and(|rest| {
+                parsed.era = Some(era.name.to_string());
+                match_tokens(remaining_tokens, rest, context, parsed)
+            })
+        }),
+        TemplateToken::EraInitial => ERAS.iter().any(|era| {
+            input.strip_prefix(era.initial).is_some_and(|rest| {
+                parsed.era = Some(era.name.to_string());
+                match_tokens(remaining_tokens, rest, context, parsed)
+            })
+        }),
+        TemplateToken::EraYear => [2, 1].into_iter().any(|digits| {
+            take_digits(input, digits).is_some_and(|(value, rest)| {
+                value > 0 && !input.starts_with('0') && {
+                    parsed.era_year = Some(value);
+                    match_tokens(remaining_tokens, rest, context, parsed)
+                }
+            })
+        }),
+        TemplateToken::EraYearPadded => take_digits(input, 2).is_some_and(|(value, rest)| {
+            value > 0 && {
+                parsed.era_year = Some(value);
+                match_tokens(remaining_tokens, rest, context, parsed)
+            }
+        }),
+        TemplateToken::YearShort => take_digits(input, 2).is_some_and(|(value, rest)| {
+            parsed.year = Some(expand_short_year(value));
+            match_tokens(remaining_tokens, rest, context, parsed)
+        }),
+        TemplateToken::Month => take_digits(input, 2).is_some_and(|(value, rest)| {
+            (1..=12).contains(&value) && {
+                parsed.month = Some(value);
+                match_tokens(remaining_tokens, rest, context, parsed)
+            }
+        }),
+        TemplateToken::Sequence(digits) => {
+            take_digits(input, *digits).is_some_and(|(value, rest)| {
+                value > 0 && {
+                    parsed.sequence_number = value;
+                    match_tokens(remaining_tokens, rest, context, parsed)
+                }
+            })
+        }
+    }
+}
+
+/// 下2桁の年を展開するとき、今年より先として扱う年数の上限
+const SHORT_YEAR_FUTURE_LIMIT: i32 = 20;
+
+/// 下2桁の年を西暦に展開する
+///
+/// 今年から`SHORT_YEAR_FUTURE_LIMIT`年先までを上限とする100年の範囲に収める（例: 2026年なら95は1995年、30は2030年）
+fn expand_short_year(value: i32) -> i32 {
+    let latest = chrono::Local::now().year() + SHORT_YEAR_FUTURE_LIMIT;
+    latest - (latest - value).rem_euclid(100)
+}
+
+/// 先頭から指定桁数の数字を取り出す
+fn take_digits(input: &str, digits: usize) -> Option<(i32, &str)> {
+    let head = input.get(..digits)?;
+    if !head.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((head.parse().ok()?, &input[digits..]))
+}
+
+/// 既存の文書番号をルールのテンプレートで逆引きし、一致したルールごとの構成要素を返す
+///
+/// テンプレートに無い部署・文書種別はルールで1つに決まる場合に補い、
+/// 部署が決まらない全部署共通ルールでは`department_code`を連番カウンターの部署として使う。
+/// 番号の時点で有効だったルールを先にし、その中では採番時に選択される順に並べる
+pub fn decompose_document_number(
+    rules: &[DocumentNumberGenerationRule],
+    document_number: &str,
+    department_code: Option<&str>,
+) -> Vec<DecomposedDocumentNumber> {
+    let mut matches: Vec<DecomposedDocumentNumber> = rules
+        .iter()
+        .filter_map(|rule| {
+            let mut components = parse_document_number(rule, document_number)?;
+            if components.department_code.is_none() {
+                components.department_code = rule.department_code.clone();
+            }
+            if components.document_type_code.is_none()
+                && let [code] = rule.parsed_document_type_codes().as_slice()
+            {
+                components.document_type_code = Some(code.clone());
+            }
+
+            let effective = components.date_range().map(|(start, end)| {
+                rule.effective_from <= end
+                    && rule.effective_until.is_none_or(|until| start <= until)
+            });
+            let sequence_key =
+                sequence_period(rule.reset_scope, &components).and_then(|(year, month)| {
+                    let department_code =
+                        components.department_code.as_deref().or(department_code)?;
+                    Some(SequenceKey {
+                        rule_id: rule.id,
+                        year,
+                        month,
+                        department_code: department_code.to_string(),
+                    })
+                });
+
+            Some(DecomposedDocumentNumber {
+                rule_id: rule.id,
+                rule_name: rule.rule_name.clone(),
+                template: rule.template.clone(),
+                priority: rule.priority,
+                components,
+                effective,
+                sequence_key,
+                next_sequence: None,
+            })
+        })
+        .collect();
+
+    let rule_of = |id: i32| rules.iter().find(|rule| rule.id == id);
+    matches.sort_by_key(|decomposed| {
+        let rule = rule_of(decomposed.rule_id);
+        (
+            decomposed.effective == Some(false),
+            rule.is_some_and(|rule| rule.department_code.is_none()),
+            decomposed.priority,
+            std::cmp::Reverse(rule.map(|rule| rule.effective_from)),
+            decomposed.rule_id,
+        )
+    });
+    matches
+}
+
+/// 文書番号がどのルールの連番カウンターから採番されたものかを推定し、カウンターのキーと連番を返す
+///
+/// 逆引きの結果のうち番号の時点で有効で、部署が`department_code`と矛盾しない最初のルールを採る
+pub fn attribute_document_number(
+    rules: &[DocumentNumberGenerationRule],
+    document_number: &str,
+    department_code: Option<&str>,
+) -> Option<(SequenceKey, i32)> {
+    decompose_document_number(rules, document_number, department_code)
+        .into_iter()
+        .filter(|decomposed| decomposed.effective != Some(false))
+        .filter(|decomposed| {
+            match (
+                decomposed.components.department_code.as_deref(),
+                department_code,
+            ) {
+                (Some(parsed), Some(department_code)) => parsed == department_code,
+                _ => true,
+            }
+        })
+        .find_map(|decomposed| {
+            decomposed
+                .sequence_key
+                .map(|key| (key, decomposed.components.sequence_number))
+        })
+}
+
+/// 番号の構成要素から、リセット単位に応じた連番カウンターの期間を求める
+fn sequence_period(scope: SequenceResetScope, parsed: &ParsedDocumentNumber) -> Option<(i32, i32)> {
+    match scope {
+        SequenceResetScope::Monthly => Some((parsed.year?, parsed.month?)),
+        SequenceResetScope::Yearly => Some((parsed.year?, 0)),
+        SequenceResetScope::FiscalYearly => {
+            let fiscal_year = parsed.fiscal_year.or_else(|| {
+                let date = NaiveDate::from_ymd_opt(parsed.year?, parsed.month? as u32, 1)?;
+                Some(fiscal_year_of(date))
+            })?;
+            Some((fiscal_year, 0))
+        }
+        SequenceResetScope::Never => Some((0, 0)),
+    }
+}
+
+/// 衝突検出に使う、生成され得る番号の1文字分のパターン
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatternAtom {
+    Char(char),
+    Digit,
+    Any,
+    /// 0文字以上の数字
+    DigitStar,
+    /// 0文字以上の任意の文字
+    AnyStar,
+}
+
+impl PatternAtom {
+    fn is_star(self) -> bool {
+        matches!(self, PatternAtom::DigitStar | PatternAtom::AnyStar)
+    }
+
+    /// 両方のパターンに一致する文字を1つ返す
+    fn common_char(self, other: PatternAtom) -> Option<char> {
+        use PatternAtom::*;
+        match (self, other) {
+            (Char(a), Char(b)) => (a == b).then_some(a),
+            (Char(c), Digit | DigitStar) | (Digit | DigitStar, Char(c)) => {
+                c.is_ascii_digit().then_some(c)
+            }
+            (Char(c), Any | AnyStar) | (Any | AnyStar, Char(c)) => Some(c),
+            (Digit | DigitStar, _) | (_, Digit | DigitStar) => Some('0'),
+            (Any | AnyStar, Any | AnyStar) => Some('X'),
+        }
+    }
+}
+
+/// テンプレートから生成され得る番号のパターンを作る
+///
+/// 部署コードはルールの部署が決まっていればその値、文書種別コードは指定の値とし、
+/// 日付や課コードなど作成時に決まる要素は取り得る文字の並びで近似する
+fn number_pattern(
+    tokens: &[TemplateToken],
+    department_code: Option<&str>,
+    document_type_code: &str,
+) -> Vec<PatternAtom> {
+    use PatternAtom::*;
+
+    let mut atoms = Vec::new();
+    let push_text = |atoms: &mut Vec<PatternAtom>, text: &str| atoms.extend(text.chars().map(Char));
+    for token in tokens {
+        match token {
+            TemplateToken::Literal(text) => push_text(&mut atoms, text),
+            TemplateToken::DocumentTypeCode => push_text(&mut atoms, document_type_code),
+            TemplateToken::DepartmentCode => match department_code {
+                Some(code) => push_text(&mut atoms, code),
+                None => atoms.extend([Any, AnyStar]),
+            },
+            TemplateToken::DocumentTypeName
+            | TemplateToken::SectionCode
+            | TemplateToken::EraName => atoms.extend([Any, AnyStar]),
+            TemplateToken::EraInitial => atoms.push(Any),
+            TemplateToken::Year | TemplateToken::FiscalYear => atoms.extend([Digit; 4]),
+            TemplateToken::YearShort
+            | TemplateToken::Month
+            | TemplateToken::FiscalYearShort
+            | TemplateToken::EraYearPadded => atoms.extend([Digit; 2]),
+            TemplateToken::EraYear => atoms.extend([Digit, DigitStar]),
+            TemplateToken::Sequence(digits) => atoms.extend(std::iter::repeat_n(Digit, *digits)),
+        }
+    }
+    atoms
+}
+
+/// 2つのパターンの両方に一致する文字列があれば、その例を1つ返す
+///
+/// 両方のパターンを何文字目まで読んだかの組を状態として幅優先で探索する
+fn common_match(a: &[PatternAtom], b: &[PatternAtom]) -> Option<String> {
+    let width = b.len() + 1;
+    let state = |i: usize, j: usize| i * width + j;
+    let mut parent: Vec<Option<(usize, Option<char>)>> = vec![None; (a.len() + 1) * width];
+    let mut queue = std::collections::VecDeque::from([(0, 0)]);
+    parent[0] = Some((0, None));
+
+    while let Some((i, j)) = queue.pop_front() {
+        if i == a.len() && j == b.len() {
+            let mut example = Vec::new();
+            let mut current = state(i, j);
+            while current != 0 {
+                let (previous, c) = parent[current]?;
+                example.extend(c);
+                current = previous;
+            }
+            return Some(example.into_iter().rev().collect());
+        }
+
+        let mut next = Vec::new();
+        // 0文字以上のパターンは読み飛ばせる
+        if a.get(i).is_some_and(|atom| atom.is_star()) {
+            next.push((i + 1, j, None));
+        }
+        if b.get(j).is_some_and(|atom| atom.is_star()) {
+            next.push((i, j + 1, None));
+        }
+        if let (Some(&x), Some(&y)) = (a.get(i), b.get(j))
+            && let Some(c) = x.common_char(y)
+        {
+            let next_i = if x.is_star() { i } else { i + 1 };
+            let next_j = if y.is_star() { j } else { j + 1 };
+            next.push((next_i, next_j, Some(c)));
+        }
+
+        for (next_i, next_j, c) in next {
+            let id = state(next_i, next_j);
+            if parent[id].is_none() {
+                parent[id] = Some((state(i, j), c));
+                queue.push_back((next_i, next_j));
+            }
+        }
+    }
+
+    None
+}
+
+/// 2つのルールが同じ文書番号を生成し得る場合、その番号の例を返す
+///
+/// 例の数字部分は0、任意の文字列部分はXで表す
+pub fn find_colliding_number(
+    rule: &DocumentNumberGenerationRule,
+    other: &DocumentNumberGenerationRule,
+) -> Option<String> {
+    let tokens = tokenize(&rule.template).ok()?;
+    let other_tokens = tokenize(&other.template).ok()?;
+    let other_codes = other.parsed_document_type_codes();
+
+    rule.parsed_document_type_codes().iter().find_map(|code| {
+        let pattern = number_pattern(&tokens, rule.department_code.as_deref(), code);
+        other_codes.iter().find_map(|other_code| {
+            let other_pattern =
+                number_pattern(&other_tokens, other.department_code.as_deref(), other_code);
+            common_match(&pattern, &other_pattern)
+        })
+    })
+}
+
+/// 単独のルールが連番カウンターの違いを番号で区別できず、同じ番号を生成し得る理由を返す
+pub fn find_shared_counter_collision(
+    rule: &DocumentNumberGenerationRule,
+) -> Option<TemplateCollisionKind> {
+    let tokens = tokenize(&rule.template).ok()?;
+    let uses = |candidates: &[TemplateToken]| candidates.iter().any(|t| tokens.contains(t));
+
+    if rule.department_code.is_none() && !uses(&[TemplateToken::DepartmentCode]) {
+        return Some(TemplateCollisionKind::DepartmentCounter);
+    }
+
+    let has_year = uses(&[
+        TemplateToken::Year,
+        TemplateToken::YearShort,
+        TemplateToken::EraYear,
+        TemplateToken::EraYearPadded,
+    ]);
+    let has_fiscal_year = uses(&[TemplateToken::FiscalYear, TemplateToken::FiscalYearShort]);
+    let has_month = uses(&[TemplateToken::Month]);
+    let distinguishes_period = match rule.reset_scope {
+        SequenceResetScope::Monthly => has_month && (has_year || has_fiscal_year),
+        SequenceResetScope::Yearly => has_year,
+        SequenceResetScope::FiscalYearly => has_fiscal_year || (has_year && has_month),
+        SequenceResetScope::Never => true,
+    };
+    (!distinguishes_period).then_some(TemplateCollisionKind::ResetPeriod)
+}
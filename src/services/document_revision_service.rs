@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use crate::models::{CreateDocumentRevisionRequest, DocumentRevision, PromoteDraftRequest};
+use crate::repositories::DocumentRevisionRepository;
+use crate::services::{DocumentService, DocumentServiceError};
+
+/// 文書の版（改訂版・ドラフト版）管理サービス
+#[derive(Clone)]
+pub struct DocumentRevisionService {
+    revision_repository: Arc<dyn DocumentRevisionRepository>,
+    /// 版履歴を見せる前に文書の閲覧可否を確かめる
+    document_service: DocumentService,
+}
+
+impl DocumentRevisionService {
+    pub fn new(
+        revision_repository: impl DocumentRevisionRepository + 'static,
+        document_service: DocumentService,
+    ) -> Self {
+        Self {
+            revision_repository: Arc::new(revision_repository),
+            document_service,
+        }
+    }
+
+    /// 改訂版またはドラフト版を作成する
+    pub async fn create_revision(
+        &self,
+        document_id: i32,
+        request: CreateDocumentRevisionRequest,
+    ) -> Result<DocumentRevision, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        self.revision_repository
+            .create_revision(document_id, request)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// ドラフト版を改訂版として発行する
+    pub async fn promote_draft(
+        &self,
+        document_id: i32,
+        draft_id: i32,
+        request: PromoteDraftRequest,
+    ) -> Result<DocumentRevision, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        self.revision_repository
+            .promote_draft(document_id, draft_id, request)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 閲覧者の機密レベルで見られる文書の版履歴を取得する
+    pub async fn list_revisions(
+        &self,
+        document_id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<DocumentRevision>, DocumentServiceError> {
+        self.document_service
+            .ensure_document_visible(document_id, viewer_id)
+            .await?;
+
+        self.revision_repository
+            .list_revisions(document_id)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+}
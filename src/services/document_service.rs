@@ -1,17 +1,49 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use chrono::{Datelike, NaiveDate};
+
 use crate::models::{
-    CreateDocumentRequest, CreateDocumentWithNumberRequest, CreatedDocumentWithNumber,
-    DocumentNumberGenerationError, DocumentNumberRequest, DocumentValidationError,
+    AllocatedDocumentDraft, BulkCreateDocumentRowResult, BulkCreateDocumentsRequest,
+    BulkCreateDocumentsResult, BulkCreateMode, ChangeDocumentStateRequest,
+    ClaimReservedNumberRequest, ClearanceLevel, CreateDocumentNumberAliasRequest,
+    CreateDocumentNumberGenerationRuleRequest, CreateDocumentNumberReservationRequest,
+    CreateDocumentRequest, CreateDocumentWithManualNumberRequest, CreateDocumentWithNumberRequest,
+    CreatedDocumentWithManualNumber, CreatedDocumentWithNumber, DEFAULT_IDEMPOTENCY_KEY_TTL_HOURS,
+    DeletedDocument, Document, DocumentChange, DocumentLocation, DocumentNumberAlias,
+    DocumentNumberDecomposition, DocumentNumberFormatter, DocumentNumberGapReport,
+    DocumentNumberGenerationError, DocumentNumberGenerationRule, DocumentNumberRequest,
+    DocumentNumberReservation, DocumentSearchAggregations, DocumentSearchFilters,
+    DocumentSearchHit, DocumentValidationError, GeneratedDocumentNumber,
+    IDEMPOTENCY_CLAIM_LEASE_SECONDS, IdempotencyKeyClaim, IdempotentNumberedDocument,
+    NewVoidedDocumentNumber, NumberGapFilters, PathGenerationError, ReservationExpiryResult,
+    ReservedNumberStatus, RuleConflictReport, RuleSimulationRequest, RuleSimulationResult,
+    SequenceGap, SequenceGapKind, SequenceGapSummary, SequenceKey,
+    UpdateDocumentNumberGenerationRuleRequest, UpdateDocumentRequest, VoidDocumentNumberRequest,
+    VoidedDocumentNumber, validate_idempotency_key,
+};
+use crate::repositories::{
+    DocumentNumberRuleRepository, DocumentRepository, EmployeeRepository, RepositoryError,
+};
+use crate::services::document_number_template::{
+    NumberTemplateContext, TemplateToken, template_uses,
+};
+use crate::services::{
+    DocumentNumberGenerator, EmployeeService, NewDocumentPath, PathGenerationService,
+    attribute_document_number, decompose_document_number, parse_document_number,
+    validate_number_template,
 };
-use crate::repositories::{DocumentNumberRuleRepository, DocumentRepository};
-use crate::services::DocumentNumberGenerator;
 
 /// 文書管理ビジネスロジックサービス
 #[derive(Clone)]
 pub struct DocumentService {
     document_repository: Arc<dyn DocumentRepository>,
+    rule_repository: Arc<dyn DocumentNumberRuleRepository>,
     number_generator: DocumentNumberGenerator,
+    employee_repository: Option<Arc<dyn EmployeeRepository>>,
+    employee_service: Option<EmployeeService>,
+    path_service: Option<PathGenerationService>,
+    idempotency_key_ttl: chrono::Duration,
 }
 
 impl DocumentService {
@@ -20,19 +52,293 @@ impl DocumentService {
         rule_repository: impl DocumentNumberRuleRepository + 'static,
     ) -> Self {
         let document_repository = Arc::new(document_repository);
-        let number_generator = DocumentNumberGenerator::new(rule_repository);
+        let rule_repository: Arc<dyn DocumentNumberRuleRepository> = Arc::new(rule_repository);
+        let number_generator = DocumentNumberGenerator::with_repository(rule_repository.clone());
 
         Self {
             document_repository,
+            rule_repository,
             number_generator,
+            employee_repository: None,
+            employee_service: None,
+            path_service: None,
+            idempotency_key_ttl: chrono::Duration::hours(DEFAULT_IDEMPOTENCY_KEY_TTL_HOURS as i64),
         }
     }
 
+    /// 閲覧者の機密レベルを求める社員リポジトリを設定する
+    pub fn with_employee_repository(
+        mut self,
+        employee_repository: impl EmployeeRepository + 'static,
+    ) -> Self {
+        self.employee_repository = Some(Arc::new(employee_repository));
+        self
+    }
+
+    /// 手動採番で操作者が管理者であることを確かめる社員サービスを設定する
+    pub fn with_employee_service(mut self, employee_service: EmployeeService) -> Self {
+        self.employee_service = Some(employee_service);
+        self
+    }
+
+    /// 文書作成時にネットワークパスを解決するサービスを設定する
+    pub fn with_path_service(mut self, path_service: PathGenerationService) -> Self {
+        self.path_service = Some(path_service);
+        self
+    }
+
+    /// Idempotency-Keyと作成結果を保持する期間を設定する
+    pub fn with_idempotency_key_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.idempotency_key_ttl = ttl;
+        self
+    }
+
+    /// Idempotency-Key付きで文書番号を自動生成して文書を作成する
+    ///
+    /// 保持期間内に同じキーで同じ内容のリクエストが再送された場合は、新しい番号を採番せずに
+    /// 最初の作成結果を返す。戻り値の2番目は再送に対する応答かどうか
+    pub async fn create_document_idempotent(
+        &self,
+        request: CreateDocumentWithNumberRequest,
+        idempotency_key: &str,
+    ) -> Result<(CreatedDocumentWithNumber, bool), DocumentServiceError> {
+        validate_idempotency_key(idempotency_key)?;
+
+        let fingerprint = serde_json::to_string(&request)
+            .map_err(|e| RepositoryError::Database(sqlx::Error::Encode(Box::new(e))))?;
+        let now = chrono::Utc::now().naive_utc();
+        let expires_at = now + self.idempotency_key_ttl;
+        let claimed_until = now + chrono::Duration::seconds(IDEMPOTENCY_CLAIM_LEASE_SECONDS);
+
+        let (created, replayed) = match self
+            .document_repository
+            .claim_idempotency_key(idempotency_key, &fingerprint, expires_at, claimed_until)
+            .await?
+        {
+            IdempotencyKeyClaim::Completed(response) => {
+                let created = serde_json::from_str(&response)
+                    .map_err(|e| RepositoryError::Database(sqlx::Error::Decode(Box::new(e))))?;
+                return Ok((created, true));
+            }
+            IdempotencyKeyClaim::Created(numbered) => {
+                (self.recover_numbered_document(numbered).await?, true)
+            }
+            IdempotencyKeyClaim::Claimed => {
+                match self
+                    .create_numbered_document(request, Some(idempotency_key))
+                    .await
+                {
+                    Ok(created) => (created, false),
+                    Err(err) => {
+                        // 失敗したリクエストは同じキーで再送して作成し直せるようにする
+                        // （文書を作成済みのキーは解放されず、再送時に作成済みの文書を返す）
+                        self.document_repository
+                            .release_idempotency_key(idempotency_key)
+                            .await?;
+                        return Err(err);
+                    }
+                }
+            }
+        };
+
+        let response = serde_json::to_string(&created)
+            .map_err(|e| RepositoryError::Database(sqlx::Error::Encode(Box::new(e))))?;
+        self.document_repository
+            .complete_idempotency_key(idempotency_key, created.document.id, &response)
+            .await?;
+
+        Ok((created, replayed))
+    }
+
+    /// Idempotency-Keyで作成済みだが作成結果を保存する前に中断した文書の作成結果を組み立て直す
+    ///
+    /// 現在のルールから採番計画を立て直さず、作成時にキーへ記録したルールと連番を使う
+    async fn recover_numbered_document(
+        &self,
+        numbered: IdempotentNumberedDocument,
+    ) -> Result<CreatedDocumentWithNumber, DocumentServiceError> {
+        let document = self
+            .document_repository
+            .get_by_id(numbered.document_id)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound {
+                id: numbered.document_id.to_string(),
+            })?;
+        let rule_id = numbered.sequence_key.rule_id;
+        let rule = self
+            .rule_repository
+            .get_rule_by_id(rule_id)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound {
+                id: rule_id.to_string(),
+            })?;
+
+        let generated_number = GeneratedDocumentNumber {
+            document_number: document.number.clone(),
+            rule_id,
+            sequence_number: numbered.sequence_number,
+            template_used: rule.template,
+        };
+        Ok(CreatedDocumentWithNumber {
+            document_number: generated_number.document_number.clone(),
+            document,
+            generated_number,
+        })
+    }
+
     /// 文書番号を自動生成して文書を作成する
     pub async fn create_document_with_number(
         &self,
         request: CreateDocumentWithNumberRequest,
     ) -> Result<CreatedDocumentWithNumber, DocumentServiceError> {
+        self.create_numbered_document(request, None).await
+    }
+
+    /// 文書番号を自動生成して文書を作成する（指定したIdempotency-Keyは作成した文書に対応付ける）
+    async fn create_numbered_document(
+        &self,
+        request: CreateDocumentWithNumberRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<CreatedDocumentWithNumber, DocumentServiceError> {
+        let plan = self.plan_numbered_document(request).await?;
+
+        // 連番カウンターから採番し、同一トランザクションで文書を所在とともに作成
+        let format_number = |sequence_number| {
+            self.number_generator
+                .format_number(&plan.rule, &plan.template_context, sequence_number)
+        };
+        let format_path = new_document_path_formatter(plan.path.as_ref());
+        let location = DocumentLocation {
+            department_code: &plan.department_code,
+            format_path: &format_path,
+        };
+        let (document, sequence_number) = self
+            .document_repository
+            .create_with_allocated_number(
+                plan.request.clone(),
+                &plan.sequence_key,
+                &format_number,
+                &location,
+                idempotency_key,
+            )
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)?;
+
+        Ok(numbered_document_result(&plan, document, sequence_number))
+    }
+
+    /// 複数の文書を1つのトランザクションでリクエストの順に採番して作成する
+    ///
+    /// 全行を検証してから採番する。`AllOrNothing`では1行でも失敗すれば何も登録せず、
+    /// `BestEffort`では失敗した行を除いて登録する。どちらも失敗した行のエラーを行ごとに返し、
+    /// `AllOrNothing`で登録しなかった残りの行には失敗した行を示すエラーを返す
+    pub async fn create_documents_bulk(
+        &self,
+        request: BulkCreateDocumentsRequest,
+    ) -> Result<BulkCreateDocumentsResult, DocumentServiceError> {
+        request.validate()?;
+        let mode = request.mode;
+        let all_or_nothing = mode == BulkCreateMode::AllOrNothing;
+
+        let mut rows: Vec<BulkCreateDocumentRowResult> = (0..request.documents.len())
+            .map(|index| BulkCreateDocumentRowResult {
+                index,
+                created: None,
+                error: None,
+            })
+            .collect();
+        let mut plans = Vec::with_capacity(request.documents.len());
+        for (index, document) in request.documents.into_iter().enumerate() {
+            match self.plan_numbered_document(document).await {
+                Ok(plan) => plans.push((index, plan)),
+                Err(e) => rows[index].error = Some(e.to_string()),
+            }
+        }
+
+        // 全件一括の場合は、検証で1行でも失敗すれば採番しない
+        let validation_failed = plans.len() < rows.len();
+        let skip_allocation = plans.is_empty() || (all_or_nothing && validation_failed);
+        if !skip_allocation {
+            let formatters: Vec<Box<DocumentNumberFormatter<'_>>> = plans
+                .iter()
+                .map(|(_, plan)| {
+                    Box::new(move |sequence_number| {
+                        self.number_generator.format_number(
+                            &plan.rule,
+                            &plan.template_context,
+                            sequence_number,
+                        )
+                    }) as Box<DocumentNumberFormatter<'_>>
+                })
+                .collect();
+            let path_formatters: Vec<_> = plans
+                .iter()
+                .map(|(_, plan)| new_document_path_formatter(plan.path.as_ref()))
+                .collect();
+            let drafts = plans
+                .iter()
+                .zip(formatters.iter().zip(&path_formatters))
+                .map(
+                    |((_, plan), (format_number, format_path))| AllocatedDocumentDraft {
+                        request: plan.request.clone(),
+                        sequence_key: plan.sequence_key.clone(),
+                        format_number: format_number.as_ref(),
+                        location: DocumentLocation {
+                            department_code: &plan.department_code,
+                            format_path,
+                        },
+                    },
+                )
+                .collect();
+
+            let outcomes = self
+                .document_repository
+                .create_many_with_allocated_numbers(drafts, all_or_nothing)
+                .await?;
+            let rolled_back = all_or_nothing && outcomes.iter().any(Result::is_err);
+
+            for ((index, plan), outcome) in plans.iter().zip(outcomes) {
+                match outcome {
+                    Ok(_) if rolled_back => {}
+                    Ok((document, sequence_number)) => {
+                        rows[*index].created =
+                            Some(numbered_document_result(plan, document, sequence_number));
+                    }
+                    Err(e) => {
+                        rows[*index].error =
+                            Some(DocumentServiceError::NumberGenerationError(e).to_string());
+                    }
+                }
+            }
+        }
+
+        let created_count = rows.iter().filter(|row| row.created.is_some()).count();
+
+        // 全件一括で失敗した場合は、失敗していない行にも登録されなかった理由を返す
+        if all_or_nothing
+            && created_count == 0
+            && let Some(failed) = rows.iter().position(|row| row.error.is_some())
+        {
+            for row in rows.iter_mut().filter(|row| row.error.is_none()) {
+                row.error = Some(format!("Rolled back because row {failed} failed"));
+            }
+        }
+        let failed_count = rows.iter().filter(|row| row.error.is_some()).count();
+
+        Ok(BulkCreateDocumentsResult {
+            mode,
+            committed: created_count > 0,
+            created_count,
+            failed_count,
+            results: rows,
+        })
+    }
+
+    /// 文書番号付き作成リクエストを検証し、適用するルールと連番カウンターを決める
+    async fn plan_numbered_document(
+        &self,
+        request: CreateDocumentWithNumberRequest,
+    ) -> Result<NumberedDocumentPlan, DocumentServiceError> {
         // リクエストのバリデーション
         request
             .validate()
@@ -42,16 +348,30 @@ impl DocumentService {
         let number_request = DocumentNumberRequest {
             document_type_code: request.document_type_code.clone(),
             department_code: request.department_code.clone(),
+            section_code: request.section_code.clone(),
             created_date: request.created_date,
             created_by: request.created_by,
         };
+        let number_request = self
+            .number_generator
+            .resolve_historical_codes(&number_request)
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)?;
 
-        // 文書番号を生成
-        let generated_number = self
+        // 適用するルールを決定
+        let rule = self
             .number_generator
-            .generate_document_number(number_request)
+            .resolve_rule(&number_request)
             .await
             .map_err(DocumentServiceError::NumberGenerationError)?;
+        if template_uses(&rule.template, &TemplateToken::SectionCode)
+            && request
+                .section_code
+                .as_deref()
+                .is_none_or(|code| code.trim().is_empty())
+        {
+            return Err(DocumentValidationError::MissingSectionCode.into());
+        }
 
         // document_type_codeからdocument_type_idを解決
         let document_type_id = self
@@ -59,31 +379,103 @@ impl DocumentService {
             .await
             .map_err(DocumentServiceError::RepositoryError)?;
 
+        let sequence_key = DocumentNumberGenerator::sequence_key(&rule, &number_request);
+        let template_context = self
+            .number_generator
+            .template_context(&rule, &number_request)
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)?;
+
         // 文書作成リクエストを作成
         let doc_request = CreateDocumentRequest {
-            number: None, // 生成される
+            number: None, // 採番される
             title: request.title,
             document_type_id,
             business_number: None,
             created_by: request.created_by,
             created_date: request.created_date,
-            internal_external: None,
-            importance_class: None,
-            personal_info: None,
+            internal_external: request.internal_external,
+            importance_class: request.importance_class,
+            personal_info: request.personal_info,
             notes: None,
         };
 
-        // 文書を作成
-        let document = self
+        let path = self
+            .plan_location(&number_request.department_code, &doc_request)
+            .await?;
+
+        Ok(NumberedDocumentPlan {
+            rule,
+            template_context,
+            sequence_key,
+            request: doc_request,
+            department_code: number_request.department_code,
+            path,
+        })
+    }
+
+    /// 管理者が指定した文書番号で文書を作成する（採番ルールを使わない例外処理）
+    ///
+    /// `actor_id`は操作する社員で、管理者でなければ拒否し、監査記録の割当者として残す
+    pub async fn create_document_with_manual_number(
+        &self,
+        actor_id: Option<i32>,
+        request: CreateDocumentWithManualNumberRequest,
+    ) -> Result<CreatedDocumentWithManualNumber, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        let Some(employee_service) = &self.employee_service else {
+            return Err(DocumentServiceError::PermissionDenied(
+                "Employee directory is not configured".to_string(),
+            ));
+        };
+        let assigned_by = employee_service.ensure_admin(actor_id).await?;
+
+        let document_type_id = self
+            .resolve_document_type_id(&request.document.document_type_code)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+
+        let number = request.number;
+        let doc_request = CreateDocumentRequest {
+            number: Some(number.clone()),
+            title: request.document.title,
+            document_type_id,
+            business_number: None,
+            created_by: request.document.created_by,
+            created_date: request.document.created_date,
+            internal_external: request.document.internal_external,
+            importance_class: request.document.importance_class,
+            personal_info: request.document.personal_info,
+            notes: None,
+        };
+
+        let path = self
+            .plan_location(&request.document.department_code, &doc_request)
+            .await?;
+        let format_path = new_document_path_formatter(path.as_ref());
+        let location = DocumentLocation {
+            department_code: &request.document.department_code,
+            format_path: &format_path,
+        };
+        let (document, number_override) = self
             .document_repository
-            .create(doc_request)
+            .create_with_manual_number(
+                doc_request,
+                &location,
+                assigned_by,
+                &request.reason,
+                &future_rule_collision(&number, &request.document.department_code),
+            )
             .await
             .map_err(DocumentServiceError::RepositoryError)?;
 
-        Ok(CreatedDocumentWithNumber {
-            document_number: generated_number.document_number.clone(),
+        Ok(CreatedDocumentWithManualNumber {
+            document_number: document.number.clone(),
             document,
-            generated_number,
+            number_override,
         })
     }
 
@@ -98,6 +490,111 @@ impl DocumentService {
             .map_err(DocumentServiceError::RepositoryError)
     }
 
+    /// 文書のメタデータを更新する（変更した項目は変更履歴に残る）
+    ///
+    /// 取得時のバージョン`expected_version`から更新されていた場合は更新しない
+    pub async fn update_document(
+        &self,
+        id: i32,
+        request: UpdateDocumentRequest,
+        expected_version: i32,
+    ) -> Result<Document, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        self.document_repository
+            .update(id, request, expected_version)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 文書をゴミ箱へ移動する（論理削除）
+    pub async fn delete_document(
+        &self,
+        id: i32,
+        request: ChangeDocumentStateRequest,
+    ) -> Result<Document, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        self.document_repository
+            .soft_delete(id, request)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// ゴミ箱の文書を復元する
+    pub async fn restore_document(
+        &self,
+        id: i32,
+        request: ChangeDocumentStateRequest,
+    ) -> Result<Document, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        self.document_repository
+            .restore(id, request)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 閲覧者の機密レベルで見られるゴミ箱の文書を一覧する
+    pub async fn list_deleted_documents(
+        &self,
+        limit: i64,
+        offset: i64,
+        viewer_id: Option<i32>,
+    ) -> Result<(Vec<DeletedDocument>, i64), DocumentServiceError> {
+        let clearance = self.resolve_clearance(viewer_id).await?;
+
+        let (documents, total) = self
+            .document_repository
+            .list_deleted(limit, offset, clearance)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+        let documents = documents
+            .into_iter()
+            .map(|deleted| DeletedDocument {
+                document: deleted.document.redact_for(clearance),
+                ..deleted
+            })
+            .collect();
+
+        Ok((documents, total))
+    }
+
+    /// 閲覧者の機密レベルで見られる文書の変更履歴を古い順に取得する
+    ///
+    /// 閲覧できない文書は存在しないものとして扱い、保存場所を見られない場合はネットワークパスの変更前後の値を伏せる
+    pub async fn list_document_changes(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<DocumentChange>, DocumentServiceError> {
+        let clearance = self.resolve_clearance(viewer_id).await?;
+        let document = self.get_visible_document(id, clearance).await?;
+
+        let mut changes = self
+            .document_repository
+            .list_changes(id)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+        if clearance < document.path_clearance() {
+            for change in changes
+                .iter_mut()
+                .filter(|change| change.field_name.as_deref() == Some("network_path"))
+            {
+                change.old_value = None;
+                change.new_value = None;
+            }
+        }
+
+        Ok(changes)
+    }
+
     /// 文書を検索する
     pub async fn search_documents(
         &self,
@@ -109,17 +606,985 @@ impl DocumentService {
             .map_err(DocumentServiceError::RepositoryError)
     }
 
+    /// 閲覧者が文書を見られることを確認する（閲覧できない文書は存在しないものとして扱う）
+    pub async fn ensure_document_visible(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<(), DocumentServiceError> {
+        let clearance = self.resolve_clearance(viewer_id).await?;
+        self.get_visible_document(id, clearance).await?;
+        Ok(())
+    }
+
+    /// 機密レベルで見られる文書をIDで取得する（見られない場合はNotFound）
+    async fn get_visible_document(
+        &self,
+        id: i32,
+        clearance: ClearanceLevel,
+    ) -> Result<Document, DocumentServiceError> {
+        self.get_document_by_id(id)
+            .await?
+            .filter(|document| document.required_clearance() <= clearance)
+            .ok_or_else(|| {
+                DocumentServiceError::RepositoryError(RepositoryError::NotFound {
+                    id: id.to_string(),
+                })
+            })
+    }
+
+    /// 閲覧者の機密レベルで見られる文書をIDで取得する
+    ///
+    /// 閲覧できない文書は存在しないものとして扱い、保存場所を見られない場合はネットワークパスを伏せる
+    pub async fn get_document_for_viewer(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Option<Document>, DocumentServiceError> {
+        let clearance = self.resolve_clearance(viewer_id).await?;
+
+        Ok(self
+            .get_document_by_id(id)
+            .await?
+            .filter(|document| document.required_clearance() <= clearance)
+            .map(|document| document.redact_for(clearance)))
+    }
+
+    /// 閲覧者が文書のネットワークパス（保存場所）を見られることを確認する
+    ///
+    /// 閲覧者の指定が必須。文書自体を閲覧できない場合は存在しないものとして扱う
+    pub async fn ensure_network_path_visible(
+        &self,
+        document_number: &str,
+        viewer_id: Option<i32>,
+    ) -> Result<(), DocumentServiceError> {
+        if viewer_id.is_none() {
+            return Err(DocumentServiceError::PermissionDenied(
+                "A viewer is required to resolve network paths".to_string(),
+            ));
+        }
+        let clearance = self.resolve_clearance(viewer_id).await?;
+
+        let document = self
+            .get_document_by_number(document_number)
+            .await?
+            .filter(|document| document.required_clearance() <= clearance)
+            .ok_or_else(|| {
+                DocumentServiceError::RepositoryError(RepositoryError::NotFound {
+                    id: document_number.to_string(),
+                })
+            })?;
+        if clearance < document.path_clearance() {
+            return Err(DocumentServiceError::PermissionDenied(format!(
+                "Clearance level is too low to see the storage path of {document_number}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// 文書番号または旧番号で文書を取得する（旧番号の場合は現在の文書を返す）
+    pub async fn get_document_by_number(
+        &self,
+        document_number: &str,
+    ) -> Result<Option<Document>, DocumentServiceError> {
+        self.document_repository
+            .get_by_number(document_number.trim())
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 閲覧者の機密レベルで見られる文書を文書番号または旧番号で取得する
+    pub async fn get_document_by_number_for_viewer(
+        &self,
+        document_number: &str,
+        viewer_id: Option<i32>,
+    ) -> Result<Option<Document>, DocumentServiceError> {
+        let clearance = self.resolve_clearance(viewer_id).await?;
+
+        Ok(self
+            .get_document_by_number(document_number)
+            .await?
+            .filter(|document| document.required_clearance() <= clearance)
+            .map(|document| document.redact_for(clearance)))
+    }
+
+    /// 文書に旧番号を登録する
+    pub async fn add_number_alias(
+        &self,
+        document_id: i32,
+        request: CreateDocumentNumberAliasRequest,
+    ) -> Result<DocumentNumberAlias, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        self.document_repository
+            .add_number_alias(document_id, request)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 閲覧者の機密レベルで見られる文書の旧番号を登録順に取得する
+    pub async fn list_number_aliases(
+        &self,
+        document_id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<DocumentNumberAlias>, DocumentServiceError> {
+        self.ensure_document_visible(document_id, viewer_id).await?;
+
+        self.document_repository
+            .list_number_aliases(document_id)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 閲覧者の機密レベルで見られる文書に限って検索する
+    pub async fn search_documents_for_viewer(
+        &self,
+        mut filters: DocumentSearchFilters,
+        viewer_id: Option<i32>,
+    ) -> Result<(Vec<Document>, i64), DocumentServiceError> {
+        let clearance = self.resolve_clearance(viewer_id).await?;
+        filters.max_clearance = Some(clearance);
+        scope_affiliations(&mut filters, viewer_id)?;
+
+        let (documents, total) = self.search_documents(filters).await?;
+        Ok((
+            documents
+                .into_iter()
+                .map(|document| document.redact_for(clearance))
+                .collect(),
+            total,
+        ))
+    }
+
+    /// 閲覧者の機密レベルで見られる文書に限って全文検索する（関連度順）
+    pub async fn full_text_search_for_viewer(
+        &self,
+        mut filters: DocumentSearchFilters,
+        viewer_id: Option<i32>,
+    ) -> Result<(Vec<DocumentSearchHit>, i64), DocumentServiceError> {
+        let clearance = self.resolve_clearance(viewer_id).await?;
+        filters.max_clearance = Some(clearance);
+        scope_affiliations(&mut filters, viewer_id)?;
+
+        let (hits, total) = self
+            .document_repository
+            .full_text_search(filters)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+        Ok((
+            hits.into_iter()
+                .map(|hit| DocumentSearchHit {
+                    document: hit.document.redact_for(clearance),
+                    ..hit
+                })
+                .collect(),
+            total,
+        ))
+    }
+
+    /// 閲覧者の機密レベルで見られる文書に限って検索結果の内訳を集計する
+    pub async fn search_aggregations_for_viewer(
+        &self,
+        mut filters: DocumentSearchFilters,
+        viewer_id: Option<i32>,
+    ) -> Result<DocumentSearchAggregations, DocumentServiceError> {
+        filters.max_clearance = Some(self.resolve_clearance(viewer_id).await?);
+        scope_affiliations(&mut filters, viewer_id)?;
+
+        self.document_repository
+            .search_aggregations(&filters)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 閲覧者の機密レベルを求める（閲覧者不明・未登録の場合は一般レベル）
+    pub async fn resolve_clearance(
+        &self,
+        viewer_id: Option<i32>,
+    ) -> Result<ClearanceLevel, DocumentServiceError> {
+        let (Some(viewer_id), Some(employee_repository)) = (viewer_id, &self.employee_repository)
+        else {
+            return Ok(ClearanceLevel::Public);
+        };
+
+        Ok(employee_repository
+            .get_clearance(viewer_id)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?
+            .map(|clearance| clearance.effective_clearance_level)
+            .unwrap_or_default())
+    }
+
+    /// 文書番号生成ルールを作成する
+    pub async fn create_number_rule(
+        &self,
+        request: CreateDocumentNumberGenerationRuleRequest,
+    ) -> Result<DocumentNumberGenerationRule, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+        validate_number_template(&request.template, request.sequence_digits)
+            .map_err(|e| DocumentValidationError::InvalidNumberTemplate(e.to_string()))?;
+
+        self.rule_repository
+            .create_rule(request)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// IDで文書番号生成ルールを取得する
+    pub async fn get_number_rule(
+        &self,
+        id: i32,
+    ) -> Result<Option<DocumentNumberGenerationRule>, DocumentServiceError> {
+        self.rule_repository
+            .get_rule_by_id(id)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 文書番号生成ルールを検索する
+    pub async fn search_number_rules(
+        &self,
+        department_code: Option<String>,
+        active_on_date: Option<NaiveDate>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<DocumentNumberGenerationRule>, i64), DocumentServiceError> {
+        self.rule_repository
+            .search_rules(department_code, active_on_date, limit, offset)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 文書番号生成ルールを更新する
+    pub async fn update_number_rule(
+        &self,
+        id: i32,
+        request: UpdateDocumentNumberGenerationRuleRequest,
+    ) -> Result<DocumentNumberGenerationRule, DocumentServiceError> {
+        let current = self
+            .get_number_rule(id)
+            .await?
+            .ok_or_else(|| rule_not_found(id))?;
+
+        let merged = request
+            .apply_to(&current)
+            .map_err(DocumentServiceError::ValidationError)?;
+        validate_number_template(&merged.template, merged.sequence_digits)
+            .map_err(|e| DocumentValidationError::InvalidNumberTemplate(e.to_string()))?;
+
+        self.rule_repository
+            .update_rule(id, merged)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?
+            .ok_or_else(|| rule_not_found(id))
+    }
+
+    /// 文書番号生成ルールを廃止する（有効期間終了日を設定）
+    pub async fn retire_number_rule(
+        &self,
+        id: i32,
+        effective_until: NaiveDate,
+    ) -> Result<DocumentNumberGenerationRule, DocumentServiceError> {
+        self.rule_repository
+            .retire_rule(id, effective_until)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?
+            .ok_or_else(|| rule_not_found(id))
+    }
+
+    /// 文書番号生成ルールの適用をシミュレーションする（採番は行わない）
+    pub async fn simulate_number_rules(
+        &self,
+        request: RuleSimulationRequest,
+    ) -> Result<RuleSimulationResult, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        self.number_generator
+            .simulate(&request)
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)
+    }
+
+    /// 文書番号生成ルールの優先度の重複と番号の衝突を検査する
+    pub async fn find_number_rule_conflicts(
+        &self,
+    ) -> Result<RuleConflictReport, DocumentServiceError> {
+        self.number_generator
+            .find_rule_conflicts()
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)
+    }
+
+    /// 既存の文書番号をルールのテンプレートで逆引きする
+    ///
+    /// 一致したルールごとに連番カウンターの次の連番を添え、移行時の番号検証とカウンター調整に使えるようにする
+    pub async fn decompose_document_number(
+        &self,
+        document_number: &str,
+        department_code: Option<&str>,
+    ) -> Result<DocumentNumberDecomposition, DocumentServiceError> {
+        let document_number = document_number.trim();
+        if document_number.is_empty() || document_number.contains(char::is_whitespace) {
+            return Err(DocumentValidationError::InvalidDocumentNumber.into());
+        }
+
+        let rules = self.all_number_rules().await?;
+
+        let mut matches = decompose_document_number(
+            &rules,
+            document_number,
+            department_code
+                .map(str::trim)
+                .filter(|code| !code.is_empty()),
+        );
+        for decomposed in &mut matches {
+            if let Some(key) = &decomposed.sequence_key {
+                decomposed.next_sequence = Some(
+                    self.rule_repository
+                        .get_next_sequence_number(
+                            key.rule_id,
+                            key.year,
+                            key.month,
+                            &key.department_code,
+                        )
+                        .await
+                        .map_err(DocumentServiceError::RepositoryError)?,
+                );
+            }
+        }
+
+        let registered = self
+            .rule_repository
+            .is_document_number_exists(document_number)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+
+        Ok(DocumentNumberDecomposition {
+            document_number: document_number.to_string(),
+            registered,
+            matches,
+        })
+    }
+
+    /// ルールの連番を期限付きでまとめて予約する
+    pub async fn reserve_document_numbers(
+        &self,
+        request: CreateDocumentNumberReservationRequest,
+    ) -> Result<DocumentNumberReservation, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        let number_request = self
+            .number_generator
+            .resolve_historical_codes(&request.number_request())
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)?;
+        let rule = self
+            .number_generator
+            .resolve_rule(&number_request)
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)?;
+        if template_uses(&rule.template, &TemplateToken::SectionCode)
+            && request
+                .section_code
+                .as_deref()
+                .is_none_or(|code| code.trim().is_empty())
+        {
+            return Err(DocumentValidationError::MissingSectionCode.into());
+        }
+
+        let sequence_key = DocumentNumberGenerator::sequence_key(&rule, &number_request);
+        let template_context = self
+            .number_generator
+            .template_context(&rule, &number_request)
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)?;
+        let format_number = |sequence_number| {
+            self.number_generator
+                .format_number(&rule, &template_context, sequence_number)
+        };
+
+        // 予約には番号を組み立てた（作成日時点の）部署・課コードを記録する
+        let request = CreateDocumentNumberReservationRequest {
+            department_code: number_request.department_code.clone(),
+            section_code: number_request.section_code.clone(),
+            ..request
+        };
+        self.document_repository
+            .reserve_numbers(&request, &sequence_key, &format_number)
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)
+    }
+
+    /// IDで番号の予約を取得する
+    pub async fn get_number_reservation(
+        &self,
+        id: i32,
+    ) -> Result<Option<DocumentNumberReservation>, DocumentServiceError> {
+        self.document_repository
+            .get_number_reservation(id)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 番号の予約を検索する（`include_expired`が偽なら期限を過ぎた予約を除く）
+    pub async fn search_number_reservations(
+        &self,
+        rule_id: Option<i32>,
+        department_code: Option<&str>,
+        include_expired: bool,
+    ) -> Result<Vec<DocumentNumberReservation>, DocumentServiceError> {
+        self.document_repository
+            .search_number_reservations(rule_id, department_code, include_expired)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 予約番号で文書を登録する
+    ///
+    /// 番号は予約時の文書種別・部署で組み立てているため、登録する文書も同じでなければならない
+    pub async fn claim_reserved_number(
+        &self,
+        reservation_id: i32,
+        request: ClaimReservedNumberRequest,
+    ) -> Result<CreatedDocumentWithNumber, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        let reservation = self
+            .get_number_reservation(reservation_id)
+            .await?
+            .ok_or_else(|| {
+                DocumentServiceError::RepositoryError(RepositoryError::NotFound {
+                    id: reservation_id.to_string(),
+                })
+            })?;
+        // 期限切れ処理がまだ行われていない予約も、期限を過ぎていれば使えない
+        if reservation.expired_at.is_some()
+            || reservation.expires_on < chrono::Local::now().date_naive()
+        {
+            return Err(DocumentServiceError::RepositoryError(
+                RepositoryError::Conflict(format!(
+                    "Reservation {reservation_id} expired on {}",
+                    reservation.expires_on
+                )),
+            ));
+        }
+        let claim_codes = self
+            .number_generator
+            .resolve_historical_codes(&DocumentNumberRequest {
+                document_type_code: request.document.document_type_code.trim().to_string(),
+                department_code: request.document.department_code.trim().to_string(),
+                section_code: request.document.section_code.clone(),
+                created_date: request.document.created_date,
+                created_by: request.document.created_by,
+            })
+            .await
+            .map_err(DocumentServiceError::NumberGenerationError)?;
+        if reservation.document_type_code != claim_codes.document_type_code
+            || reservation.department_code != claim_codes.department_code
+        {
+            return Err(DocumentServiceError::RepositoryError(
+                RepositoryError::Conflict(format!(
+                    "Reservation {reservation_id} is for document type {} in department {}",
+                    reservation.document_type_code, reservation.department_code
+                )),
+            ));
+        }
+        let reserved = reservation
+            .numbers
+            .iter()
+            .find(|reserved| reserved.document_number == request.number)
+            .ok_or_else(|| {
+                DocumentServiceError::RepositoryError(RepositoryError::Conflict(format!(
+                    "Document number {} is not part of reservation {reservation_id}",
+                    request.number
+                )))
+            })?;
+        let sequence_number = reserved.sequence_number;
+
+        let document_type_id = self
+            .resolve_document_type_id(&request.document.document_type_code)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+
+        let number = request.number;
+        let doc_request = CreateDocumentRequest {
+            number: Some(number.clone()),
+            title: request.document.title,
+            document_type_id,
+            business_number: None,
+            created_by: request.document.created_by,
+            created_date: request.document.created_date,
+            internal_external: request.document.internal_external,
+            importance_class: request.document.importance_class,
+            personal_info: request.document.personal_info,
+            notes: None,
+        };
+
+        let path = self
+            .plan_location(&reservation.department_code, &doc_request)
+            .await?;
+        let format_path = new_document_path_formatter(path.as_ref());
+        let location = DocumentLocation {
+            department_code: &reservation.department_code,
+            format_path: &format_path,
+        };
+        let document = self
+            .document_repository
+            .claim_reserved_number(reservation_id, doc_request, &location)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+
+        let rule = self
+            .get_number_rule(reservation.rule_id)
+            .await?
+            .ok_or_else(|| rule_not_found(reservation.rule_id))?;
+        let generated_number = GeneratedDocumentNumber {
+            document_number: document.number.clone(),
+            rule_id: rule.id,
+            sequence_number,
+            template_used: rule.template,
+        };
+
+        Ok(CreatedDocumentWithNumber {
+            document_number: generated_number.document_number.clone(),
+            document,
+            generated_number,
+        })
+    }
+
+    /// 期限を過ぎた予約の未使用番号を解放または欠番にする
+    pub async fn expire_number_reservations(
+        &self,
+        as_of: NaiveDate,
+    ) -> Result<ReservationExpiryResult, DocumentServiceError> {
+        self.document_repository
+            .expire_number_reservations(as_of)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 文書番号を欠番にする
+    ///
+    /// 登録済みの文書は取り下げ（無効化）、予約番号は予約から外し、いずれも番号を再利用しない。
+    /// 文書も予約もない番号は、連番カウンターが既に通過した（採番で読み飛ばされた）ものだけを受け付ける
+    pub async fn void_document_number(
+        &self,
+        request: VoidDocumentNumberRequest,
+    ) -> Result<VoidedDocumentNumber, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+
+        self.expire_number_reservations(chrono::Local::now().date_naive())
+            .await?;
+
+        let document_number = request.document_number.trim();
+        let issued = self
+            .document_repository
+            .find_issued_number(document_number)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+        let reservation = self
+            .document_repository
+            .find_reservation_by_number(document_number)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+
+        // 予約番号は予約ブロックのカウンター、それ以外はテンプレートの逆引きで連番カウンターを決める
+        let attribution = match &reservation {
+            Some(reservation) => reservation
+                .numbers
+                .iter()
+                .find(|reserved| reserved.document_number == document_number)
+                .map(|reserved| {
+                    (
+                        SequenceKey {
+                            rule_id: reservation.rule_id,
+                            year: reservation.year,
+                            month: reservation.month,
+                            department_code: reservation.department_code.clone(),
+                        },
+                        reserved.sequence_number,
+                    )
+                }),
+            None => {
+                let rules = self.all_number_rules().await?;
+                let department_code = issued
+                    .as_ref()
+                    .and_then(|issued| issued.department_code.as_deref())
+                    .or(request.department_code.as_deref())
+                    .map(str::trim)
+                    .filter(|code| !code.is_empty());
+                attribute_document_number(&rules, document_number, department_code)
+            }
+        };
+
+        if issued.is_none() && reservation.is_none() {
+            let skipped = match &attribution {
+                Some((key, sequence_number)) => {
+                    let next_sequence = self
+                        .rule_repository
+                        .get_next_sequence_number(
+                            key.rule_id,
+                            key.year,
+                            key.month,
+                            &key.department_code,
+                        )
+                        .await
+                        .map_err(DocumentServiceError::RepositoryError)?;
+                    *sequence_number < next_sequence
+                }
+                None => false,
+            };
+            if !skipped {
+                return Err(DocumentServiceError::RepositoryError(
+                    RepositoryError::NotFound {
+                        id: document_number.to_string(),
+                    },
+                ));
+            }
+        }
+
+        let (sequence_key, sequence_number) = attribution.unzip();
+        self.document_repository
+            .void_document_number(NewVoidedDocumentNumber {
+                document_number: document_number.to_string(),
+                sequence_key,
+                sequence_number,
+                document_id: issued.map(|issued| issued.document_id),
+                reason: request.reason,
+                voided_by: request.voided_by,
+            })
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 連番カウンターごとに、採番済みの範囲で文書が登録されていない連番を一覧にする
+    ///
+    /// 欠けは欠番の記録があれば欠番、予約中・解放済みの予約番号なら予約、どちらもなければ原因不明に分類する
+    pub async fn find_number_gaps(
+        &self,
+        filters: NumberGapFilters,
+    ) -> Result<DocumentNumberGapReport, DocumentServiceError> {
+        self.expire_number_reservations(chrono::Local::now().date_naive())
+            .await?;
+
+        let department_code = filters.department_code.as_deref();
+        let counters = self
+            .document_repository
+            .list_sequence_counters(&filters)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+        if counters.is_empty() {
+            return Ok(DocumentNumberGapReport::default());
+        }
+
+        let rules = self.all_number_rules().await?;
+        let voided = self
+            .document_repository
+            .list_voided_numbers(&filters)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+        let voided_numbers: HashSet<&str> = voided
+            .iter()
+            .map(|voided| voided.document_number.as_str())
+            .collect();
+
+        // 欠番にしていない文書が使っている連番
+        let mut issued: HashSet<(SequenceKey, i32)> = HashSet::new();
+        for document in self
+            .document_repository
+            .list_issued_numbers(department_code)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?
+        {
+            if voided_numbers.contains(document.number.as_str()) {
+                continue;
+            }
+            if let Some(attribution) = attribute_document_number(
+                &rules,
+                &document.number,
+                document.department_code.as_deref(),
+            ) {
+                issued.insert(attribution);
+            }
+        }
+
+        let mut explained: HashMap<(SequenceKey, i32), SequenceGap> = HashMap::new();
+        for reservation in self
+            .document_repository
+            .search_number_reservations(filters.rule_id, department_code, true)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?
+        {
+            let key = SequenceKey {
+                rule_id: reservation.rule_id,
+                year: reservation.year,
+                month: reservation.month,
+                department_code: reservation.department_code.clone(),
+            };
+            for reserved in &reservation.numbers {
+                let kind = match reserved.status {
+                    ReservedNumberStatus::Reserved | ReservedNumberStatus::Released => {
+                        SequenceGapKind::Reserved
+                    }
+                    ReservedNumberStatus::Voided => SequenceGapKind::Voided,
+                    ReservedNumberStatus::Claimed | ReservedNumberStatus::Reissued => continue,
+                };
+                explained.insert(
+                    (key.clone(), reserved.sequence_number),
+                    SequenceGap {
+                        sequence_number: reserved.sequence_number,
+                        kind,
+                        document_number: Some(reserved.document_number.clone()),
+                        reason: None,
+                        voided_by: None,
+                        reservation_id: Some(reservation.id),
+                        reservation_status: Some(reserved.status),
+                    },
+                );
+            }
+        }
+        // 欠番の記録は予約の状態より優先する
+        for voided in &voided {
+            let (Some(rule_id), Some(year), Some(month), Some(department_code), Some(sequence)) = (
+                voided.rule_id,
+                voided.year,
+                voided.month,
+                voided.department_code.clone(),
+                voided.sequence_number,
+            ) else {
+                continue;
+            };
+            let key = SequenceKey {
+                rule_id,
+                year,
+                month,
+                department_code,
+            };
+            let gap = explained
+                .entry((key, sequence))
+                .or_insert_with(|| SequenceGap {
+                    sequence_number: sequence,
+                    kind: SequenceGapKind::Voided,
+                    document_number: None,
+                    reason: None,
+                    voided_by: None,
+                    reservation_id: None,
+                    reservation_status: None,
+                });
+            gap.kind = SequenceGapKind::Voided;
+            gap.document_number = Some(voided.document_number.clone());
+            gap.reason = Some(voided.reason.clone());
+            gap.voided_by = Some(voided.voided_by);
+        }
+
+        let mut report = DocumentNumberGapReport::default();
+        for counter in counters {
+            let key = counter.key();
+            let mut issued_count = 0;
+            let mut gaps = Vec::new();
+            for sequence_number in 1..=counter.last_sequence {
+                let position = (key.clone(), sequence_number);
+                if issued.contains(&position) {
+                    issued_count += 1;
+                    continue;
+                }
+                let gap = explained.remove(&position).unwrap_or(SequenceGap {
+                    sequence_number,
+                    kind: SequenceGapKind::Unexplained,
+                    document_number: None,
+                    reason: None,
+                    voided_by: None,
+                    reservation_id: None,
+                    reservation_status: None,
+                });
+                match gap.kind {
+                    SequenceGapKind::Voided => report.voided_gaps += 1,
+                    SequenceGapKind::Reserved => report.reserved_gaps += 1,
+                    SequenceGapKind::Unexplained => report.unexplained_gaps += 1,
+                }
+                gaps.push(gap);
+            }
+            report.total_gaps += gaps.len() as i64;
+
+            report.sequences.push(SequenceGapSummary {
+                rule_id: counter.rule_id,
+                rule_name: rules
+                    .iter()
+                    .find(|rule| rule.id == counter.rule_id)
+                    .map(|rule| rule.rule_name.clone())
+                    .unwrap_or_default(),
+                year: counter.year,
+                month: counter.month,
+                department_code: counter.department_code,
+                last_sequence: counter.last_sequence,
+                issued_count,
+                gaps,
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// 登録済みの文書番号生成ルールをすべて取得する（失効済みを含む）
+    async fn all_number_rules(
+        &self,
+    ) -> Result<Vec<DocumentNumberGenerationRule>, DocumentServiceError> {
+        let (rules, _) = self
+            .rule_repository
+            .search_rules(None, None, i64::MAX, 0)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)?;
+        Ok(rules)
+    }
+
+    /// 作成する文書に適用するパス生成ルールを解決する
+    ///
+    /// 適用できるパス生成ルールがない場合はパスを未設定のまま登録し、文書作成自体は成功させる
+    async fn plan_location(
+        &self,
+        department_code: &str,
+        request: &CreateDocumentRequest,
+    ) -> Result<Option<NewDocumentPath>, DocumentServiceError> {
+        let Some(path_service) = &self.path_service else {
+            return Ok(None);
+        };
+
+        match path_service
+            .prepare_for_new_document(
+                department_code,
+                request.document_type_id,
+                request.created_date,
+            )
+            .await
+        {
+            Ok(path) => Ok(Some(path)),
+            Err(DocumentServiceError::PathGenerationError(e)) => {
+                tracing::warn!(
+                    "Network path for a new document in {department_code} was not resolved: {e}"
+                );
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     /// document_type_codeからdocument_type_idを解決する（データベースから）
     async fn resolve_document_type_id(
         &self,
         document_type_code: &str,
-    ) -> Result<i32, crate::repositories::RepositoryError> {
+    ) -> Result<i32, RepositoryError> {
         self.document_repository
             .get_document_type_id_by_code(document_type_code)
             .await
     }
 }
 
+/// 検証と採番ルールの解決を済ませた、採番前の文書
+struct NumberedDocumentPlan {
+    rule: DocumentNumberGenerationRule,
+    template_context: NumberTemplateContext,
+    sequence_key: SequenceKey,
+    request: CreateDocumentRequest,
+    department_code: String,
+    path: Option<NewDocumentPath>,
+}
+
+/// 解決したパス生成ルールで、確定した文書番号のネットワークパスを生成する関数を作る
+fn new_document_path_formatter(
+    path: Option<&NewDocumentPath>,
+) -> impl Fn(&str) -> Option<String> + Send + Sync + '_ {
+    move |document_number| match path?.render(document_number) {
+        Ok(network_path) => Some(network_path),
+        Err(e) => {
+            tracing::warn!("Network path for document {document_number} was not resolved: {e}");
+            None
+        }
+    }
+}
+
+/// 採番して作成した文書の作成結果を組み立てる
+fn numbered_document_result(
+    plan: &NumberedDocumentPlan,
+    document: Document,
+    sequence_number: i32,
+) -> CreatedDocumentWithNumber {
+    let generated_number = GeneratedDocumentNumber {
+        document_number: document.number.clone(),
+        rule_id: plan.rule.id,
+        sequence_number,
+        template_used: plan.rule.template.clone(),
+    };
+
+    CreatedDocumentWithNumber {
+        document_number: generated_number.document_number.clone(),
+        document,
+        generated_number,
+    }
+}
+
+/// 手動採番する番号が、ルールのテンプレートで将来採番され得るかを判定する関数を作る
+///
+/// テンプレートに一致した場合はその連番カウンターのキーと連番を返す。
+/// テンプレートに含まれない要素は作成対象の部署・当月の連番として扱う
+fn future_rule_collision<'a>(
+    document_number: &'a str,
+    department_code: &'a str,
+) -> impl Fn(&DocumentNumberGenerationRule) -> Option<(SequenceKey, i32)> + Send + Sync + 'a {
+    move |rule| {
+        let parsed = parse_document_number(rule, document_number)?;
+        let today = chrono::Local::now().date_naive();
+        let date = match (parsed.year, parsed.fiscal_year) {
+            (None, Some(fiscal_year)) => NaiveDate::from_ymd_opt(fiscal_year, 4, 1),
+            _ => NaiveDate::from_ymd_opt(
+                parsed.year.unwrap_or(today.year()),
+                parsed.month.map_or(today.month(), |month| month as u32),
+                1,
+            ),
+        }
+        .unwrap_or(today);
+        let (year, month) = rule.reset_scope.period(date);
+        let key = SequenceKey {
+            rule_id: rule.id,
+            year,
+            month,
+            department_code: parsed
+                .department_code
+                .unwrap_or_else(|| department_code.to_string()),
+        };
+        Some((key, parsed.sequence_number))
+    }
+}
+
+/// 部署の絞り込みを過去の所属部署まで広げる検索では閲覧者の所属履歴と照合する
+fn scope_affiliations(
+    filters: &mut DocumentSearchFilters,
+    viewer_id: Option<i32>,
+) -> Result<(), DocumentServiceError> {
+    if filters.include_historical_affiliations && filters.department_code.is_some() {
+        filters.affiliation_employee_id =
+            Some(viewer_id.ok_or(DocumentValidationError::MissingAffiliationViewer)?);
+    }
+
+    Ok(())
+}
+
+fn rule_not_found(id: i32) -> DocumentServiceError {
+    DocumentServiceError::RepositoryError(RepositoryError::NotFound { id: id.to_string() })
+}
+
 /// DocumentServiceのエラー型
 #[derive(Debug, thiserror::Error)]
 pub enum DocumentServiceError {
@@ -128,7 +1593,11 @@ pub enum DocumentServiceError {
     #[error("Document number generation error: {0}")]
     NumberGenerationError(#[from] DocumentNumberGenerationError),
     #[error("Repository error: {0}")]
-    RepositoryError(#[from] crate::repositories::RepositoryError),
+    RepositoryError(#[from] RepositoryError),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    #[error("Network path generation error: {0}")]
+    PathGenerationError(#[from] PathGenerationError),
 }
 
 #[cfg(test)]
@@ -142,8 +1611,12 @@ mod tests {
             title: "テスト文書".to_string(),
             document_type_code: "A".to_string(),
             department_code: "T".to_string(),
+            section_code: None,
             created_by: 1,
             created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
+            internal_external: None,
+            importance_class: None,
+            personal_info: None,
         };
 
         let result = request.validate();
@@ -156,8 +1629,12 @@ mod tests {
             title: "".to_string(),
             document_type_code: "A".to_string(),
             department_code: "T".to_string(),
+            section_code: None,
             created_by: 1,
             created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
+            internal_external: None,
+            importance_class: None,
+            personal_info: None,
         };
 
         let result = request.validate();
@@ -174,8 +1651,12 @@ mod tests {
             title: "テスト文書".to_string(),
             document_type_code: "A".to_string(),
             department_code: "".to_string(), // 空文字（無効）
+            section_code: None,
             created_by: 1,
             created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
+            internal_external: None,
+            importance_class: None,
+            personal_info: None,
         };
 
         let result = request.validate();
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use crate::models::{
+    Employee, EmployeeAssignment, EmployeeAssignmentQuery, EmployeeAssignmentRequest, EmployeeRole,
+};
+use crate::repositories::{EmployeeRepository, RepositoryError};
+use crate::services::DocumentServiceError;
+
+/// 社員と所属履歴を扱うサービス（所属の登録・変更・削除は管理者のみ）
+#[derive(Clone)]
+pub struct EmployeeService {
+    employee_repository: Arc<dyn EmployeeRepository>,
+}
+
+impl EmployeeService {
+    pub fn new(employee_repository: impl EmployeeRepository + 'static) -> Self {
+        Self {
+            employee_repository: Arc::new(employee_repository),
+        }
+    }
+
+    /// 社員を取得する（部署・課・役職は今日有効な主務から求める）
+    pub async fn get_employee(&self, employee_id: i32) -> Result<Employee, DocumentServiceError> {
+        self.employee_repository
+            .get_employee(employee_id, chrono::Local::now().date_naive())
+            .await?
+            .ok_or_else(|| not_found(employee_id))
+    }
+
+    /// 社員の所属履歴を開始日順に取得する
+    pub async fn list_assignments(
+        &self,
+        employee_id: i32,
+        query: EmployeeAssignmentQuery,
+    ) -> Result<Vec<EmployeeAssignment>, DocumentServiceError> {
+        self.get_employee(employee_id).await?;
+
+        self.employee_repository
+            .list_assignments(employee_id, query.as_of)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// IDで所属を取得する
+    pub async fn get_assignment(
+        &self,
+        id: i32,
+    ) -> Result<EmployeeAssignment, DocumentServiceError> {
+        self.employee_repository
+            .get_assignment(id)
+            .await?
+            .ok_or_else(|| not_found(id))
+    }
+
+    /// 社員の所属を登録する（管理者のみ）
+    pub async fn create_assignment(
+        &self,
+        actor_id: Option<i32>,
+        employee_id: i32,
+        request: EmployeeAssignmentRequest,
+    ) -> Result<EmployeeAssignment, DocumentServiceError> {
+        self.ensure_admin(actor_id).await?;
+
+        self.employee_repository
+            .create_assignment(employee_id, &request)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 所属を置き換える（管理者のみ。異動・退任は最終日を設定する）
+    pub async fn update_assignment(
+        &self,
+        actor_id: Option<i32>,
+        id: i32,
+        request: EmployeeAssignmentRequest,
+    ) -> Result<EmployeeAssignment, DocumentServiceError> {
+        self.ensure_admin(actor_id).await?;
+
+        self.employee_repository
+            .update_assignment(id, &request)
+            .await?
+            .ok_or_else(|| not_found(id))
+    }
+
+    /// 誤って登録した所属を削除する（管理者のみ）
+    pub async fn delete_assignment(
+        &self,
+        actor_id: Option<i32>,
+        id: i32,
+    ) -> Result<(), DocumentServiceError> {
+        self.ensure_admin(actor_id).await?;
+
+        if self.employee_repository.delete_assignment(id).await? {
+            Ok(())
+        } else {
+            Err(not_found(id))
+        }
+    }
+
+    /// 操作する社員が管理者権限を持つことを確認し、その社員IDを返す
+    pub async fn ensure_admin(&self, actor_id: Option<i32>) -> Result<i32, DocumentServiceError> {
+        let Some(actor_id) = actor_id else {
+            return Err(DocumentServiceError::PermissionDenied(
+                "An administrator user_id is required".to_string(),
+            ));
+        };
+
+        match self.employee_repository.get_role(actor_id).await? {
+            Some(EmployeeRole::Admin) => Ok(actor_id),
+            _ => Err(DocumentServiceError::PermissionDenied(format!(
+                "Employee {actor_id} is not an administrator"
+            ))),
+        }
+    }
+}
+
+fn not_found(id: i32) -> DocumentServiceError {
+    DocumentServiceError::RepositoryError(RepositoryError::NotFound { id: id.to_string() })
+}
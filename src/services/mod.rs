@@ -6,13 +6,19 @@ pub mod business_search_service;
 pub mod business_service;
 pub mod cache_service;
 pub mod circulation_service;
+pub mod clearance_service;
 pub mod database_optimizer;
 pub mod deduplication_service;
 pub mod document_number_generator;
+pub mod document_number_template;
+pub mod document_revision_service;
 pub mod document_service;
+pub mod employee_service;
 pub mod metrics_service;
 pub mod migration_service;
+pub mod network_path_template;
 pub mod notification_service;
+pub mod path_generation_service;
 pub mod report_service;
 pub mod search_history_service;
 pub mod validation_service;
@@ -24,13 +30,19 @@ pub use business_search_service::*;
 pub use business_service::*;
 pub use cache_service::*;
 pub use circulation_service::*;
+pub use clearance_service::*;
 pub use database_optimizer::*;
 pub use deduplication_service::*;
 pub use document_number_generator::*;
+pub use document_number_template::*;
+pub use document_revision_service::*;
 pub use document_service::*;
+pub use employee_service::*;
 pub use metrics_service::*;
 pub use migration_service::*;
+pub use network_path_template::*;
 pub use notification_service::*;
+pub use path_generation_service::*;
 pub use report_service::*;
 pub use search_history_service::*;
 pub use validation_service::*;
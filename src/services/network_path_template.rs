@@ -0,0 +1,112 @@
+use chrono::Datelike;
+
+use crate::models::{DocumentPathContext, PathGenerationError};
+
+/// パステンプレートの構成要素
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathToken {
+    Literal(String),
+    Year,
+    YearShort,
+    Month,
+    DepartmentCode,
+    DepartmentName,
+    DocumentTypeCode,
+    DocumentTypeName,
+    DocumentNumber,
+}
+
+/// パステンプレートを構成要素に分解する
+fn tokenize(template: &str) -> Result<Vec<PathToken>, PathGenerationError> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        literal.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after.find('}').ok_or_else(|| {
+            PathGenerationError::TemplateError(format!(
+                "Unclosed placeholder in template: {template}"
+            ))
+        })?;
+
+        let token = match &after[..end] {
+            "年" => PathToken::Year,
+            "年下2桁" => PathToken::YearShort,
+            "月" => PathToken::Month,
+            "部署コード" => PathToken::DepartmentCode,
+            "部署名" => PathToken::DepartmentName,
+            "文書種別コード" => PathToken::DocumentTypeCode,
+            "文書種別名" => PathToken::DocumentTypeName,
+            "文書番号" => PathToken::DocumentNumber,
+            name => {
+                return Err(PathGenerationError::TemplateError(format!(
+                    "Unknown placeholder {{{name}}} in template: {template}"
+                )));
+            }
+        };
+
+        if !literal.is_empty() {
+            tokens.push(PathToken::Literal(std::mem::take(&mut literal)));
+        }
+        tokens.push(token);
+        rest = &after[end + 1..];
+    }
+
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        tokens.push(PathToken::Literal(literal));
+    }
+
+    Ok(tokens)
+}
+
+/// パステンプレートに未知のプレースホルダーや閉じ忘れがないことを確認する
+pub fn validate_path_template(template: &str) -> Result<(), PathGenerationError> {
+    tokenize(template).map(|_| ())
+}
+
+/// パステンプレートに文書の属性を埋め込んでネットワークパスを生成する
+///
+/// テンプレートに`{文書番号}`が含まれない場合は、生成したフォルダの直下に文書番号のフォルダを置く
+pub fn render_network_path(
+    template: &str,
+    context: &DocumentPathContext,
+) -> Result<String, PathGenerationError> {
+    let tokens = tokenize(template)?;
+    let mut path = String::new();
+
+    for token in &tokens {
+        match token {
+            PathToken::Literal(text) => path.push_str(text),
+            PathToken::Year => path.push_str(&context.created_date.year().to_string()),
+            PathToken::YearShort => {
+                path.push_str(&format!("{:02}", context.created_date.year() % 100))
+            }
+            PathToken::Month => path.push_str(&format!("{:02}", context.created_date.month())),
+            PathToken::DepartmentCode => path.push_str(&context.department_code),
+            PathToken::DepartmentName => {
+                let name = context.department_name.as_deref().ok_or_else(|| {
+                    PathGenerationError::TemplateError(format!(
+                        "Department name not found for {}",
+                        context.department_code
+                    ))
+                })?;
+                path.push_str(name);
+            }
+            PathToken::DocumentTypeCode => path.push_str(&context.document_type_code),
+            PathToken::DocumentTypeName => path.push_str(&context.document_type_name),
+            PathToken::DocumentNumber => path.push_str(&context.document_number),
+        }
+    }
+
+    if !tokens.contains(&PathToken::DocumentNumber) {
+        let folder_len = path.trim_end_matches('\\').len();
+        path.truncate(folder_len);
+        path.push('\\');
+        path.push_str(&context.document_number);
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use chrono::{Local, NaiveDate};
+
+use crate::models::{
+    CreatePathGenerationRuleRequest, DocumentPathContext, DocumentValidationError,
+    NetworkPathMismatch, NetworkPathVerificationReport, NetworkPathVerificationRequest,
+    PathGenerationError, PathGenerationRule, PathGenerationRuleVersion, ResolvedNetworkPath,
+    RetirePathGenerationRuleRequest, UpdatePathGenerationRuleRequest,
+};
+use crate::repositories::{PathGenerationRuleRepository, RepositoryError};
+use crate::services::{DocumentServiceError, render_network_path, validate_path_template};
+
+/// 保存済みパスの一括再解決で一度に読み込む文書数
+const VERIFICATION_BATCH_SIZE: i64 = 500;
+
+/// ネットワークパス生成ルールの管理とパス解決を行うサービス
+#[derive(Clone)]
+pub struct PathGenerationService {
+    rule_repository: Arc<dyn PathGenerationRuleRepository>,
+}
+
+impl PathGenerationService {
+    pub fn new(rule_repository: impl PathGenerationRuleRepository + 'static) -> Self {
+        Self {
+            rule_repository: Arc::new(rule_repository),
+        }
+    }
+
+    /// パス生成ルールを作成する
+    pub async fn create_rule(
+        &self,
+        request: CreatePathGenerationRuleRequest,
+    ) -> Result<PathGenerationRule, DocumentServiceError> {
+        request
+            .validate()
+            .map_err(DocumentServiceError::ValidationError)?;
+        validate_path_template(&request.path_template)
+            .map_err(|e| DocumentValidationError::InvalidPathTemplate(e.to_string()))?;
+
+        self.rule_repository
+            .create_rule(request)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// IDでパス生成ルールを取得する
+    pub async fn get_rule(&self, id: i32) -> Result<PathGenerationRule, DocumentServiceError> {
+        self.rule_repository
+            .get_rule_by_id(id)
+            .await?
+            .ok_or_else(|| rule_not_found(id))
+    }
+
+    /// パス生成ルールを検索する
+    pub async fn search_rules(
+        &self,
+        department_code: Option<String>,
+        active_on_date: Option<NaiveDate>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<PathGenerationRule>, i64), DocumentServiceError> {
+        self.rule_repository
+            .search_rules(department_code, active_on_date, limit, offset)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// パス生成ルールを変更する
+    ///
+    /// 変更前の内容は版として残り、`apply_from`より前の時点の解決には引き続き使われる
+    pub async fn update_rule(
+        &self,
+        id: i32,
+        request: UpdatePathGenerationRuleRequest,
+    ) -> Result<PathGenerationRule, DocumentServiceError> {
+        let current = self.get_rule(id).await?;
+        let merged = request.apply_to(&current)?;
+        validate_path_template(&merged.path_template)
+            .map_err(|e| DocumentValidationError::InvalidPathTemplate(e.to_string()))?;
+
+        let apply_from = request
+            .apply_from
+            .unwrap_or_else(|| Local::now().date_naive());
+
+        self.rule_repository
+            .update_rule(id, merged, apply_from)
+            .await?
+            .ok_or_else(|| rule_not_found(id))
+    }
+
+    /// パス生成ルールを廃止する（有効期間終了日を設定）
+    pub async fn retire_rule(
+        &self,
+        id: i32,
+        request: RetirePathGenerationRuleRequest,
+    ) -> Result<PathGenerationRule, DocumentServiceError> {
+        self.rule_repository
+            .retire_rule(id, request.effective_until, &request.change)
+            .await?
+            .ok_or_else(|| rule_not_found(id))
+    }
+
+    /// パス生成ルールの変更履歴を取得する
+    pub async fn list_versions(
+        &self,
+        rule_id: i32,
+    ) -> Result<Vec<PathGenerationRuleVersion>, DocumentServiceError> {
+        self.rule_repository
+            .list_versions(rule_id)
+            .await
+            .map_err(DocumentServiceError::RepositoryError)
+    }
+
+    /// 登録済みの文書番号からネットワークパスを解決する
+    ///
+    /// `as_of`を省略した場合は文書の作成日時点で有効だったルールを使う
+    pub async fn resolve_for_document_number(
+        &self,
+        document_number: &str,
+        as_of: Option<NaiveDate>,
+    ) -> Result<ResolvedNetworkPath, DocumentServiceError> {
+        let context = self
+            .rule_repository
+            .find_document_context(document_number)
+            .await?
+            .ok_or_else(|| {
+                DocumentServiceError::RepositoryError(RepositoryError::NotFound {
+                    id: document_number.to_string(),
+                })
+            })?;
+
+        self.resolve(&context, as_of.unwrap_or(context.created_date))
+            .await
+    }
+
+    /// 作成する文書の部署・文書種別・作成日に適用するパス生成ルールを解決する
+    ///
+    /// 文書番号は採番と同じトランザクションで確定するため、パスは`NewDocumentPath::render`で生成する
+    pub async fn prepare_for_new_document(
+        &self,
+        department_code: &str,
+        document_type_id: i32,
+        created_date: NaiveDate,
+    ) -> Result<NewDocumentPath, DocumentServiceError> {
+        let context = self
+            .rule_repository
+            .build_document_context("", department_code, document_type_id, created_date)
+            .await?;
+        let rule = self
+            .rule_repository
+            .find_applicable_rule(department_code, created_date, created_date)
+            .await?
+            .ok_or_else(|| PathGenerationError::NoApplicableRule {
+                department_code: department_code.to_string(),
+                date: created_date,
+            })?;
+
+        Ok(NewDocumentPath {
+            path_template: rule.path_template,
+            context,
+        })
+    }
+
+    /// 保存済みのネットワークパスをルールで再解決し、一致しない文書を報告する
+    pub async fn verify_stored_paths(
+        &self,
+        request: NetworkPathVerificationRequest,
+    ) -> Result<NetworkPathVerificationReport, DocumentServiceError> {
+        let department_code = request
+            .department_code
+            .as_deref()
+            .map(str::trim)
+            .filter(|code| !code.is_empty());
+
+        let mut report = NetworkPathVerificationReport {
+            as_of: request.as_of,
+            checked_documents: 0,
+            matched_documents: 0,
+            mismatches: Vec::new(),
+        };
+        let mut offset = 0;
+
+        loop {
+            let batch = self
+                .rule_repository
+                .list_stored_paths(department_code, VERIFICATION_BATCH_SIZE, offset)
+                .await?;
+            let batch_len = batch.len() as i64;
+
+            for stored in batch {
+                report.checked_documents += 1;
+                let as_of = request.as_of.unwrap_or(stored.context.created_date);
+
+                let mismatch = match self.resolve(&stored.context, as_of).await {
+                    Ok(resolved)
+                        if stored.network_path.as_deref() == Some(&resolved.network_path) =>
+                    {
+                        report.matched_documents += 1;
+                        continue;
+                    }
+                    Ok(resolved) => NetworkPathMismatch {
+                        document_id: stored.document_id,
+                        document_number: resolved.document_number,
+                        stored_path: stored.network_path,
+                        resolved_path: Some(resolved.network_path),
+                        rule_id: Some(resolved.rule_id),
+                        rule_version: Some(resolved.rule_version),
+                        error: None,
+                    },
+                    Err(DocumentServiceError::PathGenerationError(e)) => NetworkPathMismatch {
+                        document_id: stored.document_id,
+                        document_number: stored.context.document_number,
+                        stored_path: stored.network_path,
+                        resolved_path: None,
+                        rule_id: None,
+                        rule_version: None,
+                        error: Some(e.to_string()),
+                    },
+                    Err(e) => return Err(e),
+                };
+                report.mismatches.push(mismatch);
+            }
+
+            if batch_len < VERIFICATION_BATCH_SIZE {
+                break;
+            }
+            offset += batch_len;
+        }
+
+        Ok(report)
+    }
+
+    async fn resolve(
+        &self,
+        context: &DocumentPathContext,
+        as_of: NaiveDate,
+    ) -> Result<ResolvedNetworkPath, DocumentServiceError> {
+        let rule = self
+            .rule_repository
+            .find_applicable_rule(&context.department_code, context.created_date, as_of)
+            .await?
+            .ok_or_else(|| PathGenerationError::NoApplicableRule {
+                department_code: context.department_code.clone(),
+                date: as_of,
+            })?;
+
+        let network_path = render_network_path(&rule.path_template, context)?;
+
+        Ok(ResolvedNetworkPath {
+            document_number: context.document_number.clone(),
+            network_path,
+            rule_id: rule.rule_id,
+            rule_name: rule.rule_name,
+            rule_version: rule.version_number,
+            as_of,
+        })
+    }
+}
+
+/// 作成する文書に適用するパス生成ルールと、番号以外の解決に必要な属性
+#[derive(Debug, Clone)]
+pub struct NewDocumentPath {
+    path_template: String,
+    context: DocumentPathContext,
+}
+
+impl NewDocumentPath {
+    /// 確定した文書番号でネットワークパスを生成する
+    pub fn render(&self, document_number: &str) -> Result<String, PathGenerationError> {
+        let context = DocumentPathContext {
+            document_number: document_number.to_string(),
+            ..self.context.clone()
+        };
+        render_network_path(&self.path_template, &context)
+    }
+}
+
+fn rule_not_found(id: i32) -> DocumentServiceError {
+    DocumentServiceError::RepositoryError(RepositoryError::NotFound { id: id.to_string() })
+}
@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use doc_man_db::handlers::{DocumentHandlers, HealthHandler};
-use doc_man_db::models::{CreateDocumentWithNumberRequest, Document};
+use doc_man_db::models::{
+    CreateDocumentWithNumberRequest, Document, DocumentNumberFormatter,
+    DocumentNumberGenerationError, SequenceKey,
+};
 use doc_man_db::repositories::{DocumentNumberRuleRepository, DocumentRepository, RepositoryError};
 use doc_man_db::services::DocumentService;
 
@@ -30,11 +33,46 @@ impl DocumentRepository for MockDocumentRepository {
             notes: request.notes,
             network_path: None,
             is_active: true,
+            version: 1,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         })
     }
 
+    async fn create_with_allocated_number(
+        &self,
+        request: doc_man_db::models::CreateDocumentRequest,
+        _sequence_key: &SequenceKey,
+        format_number: &DocumentNumberFormatter<'_>,
+        location: &doc_man_db::models::DocumentLocation<'_>,
+        _idempotency_key: Option<&str>,
+    ) -> Result<(Document, i32), DocumentNumberGenerationError> {
+        let number = format_number(1)?;
+        let mut document = self.create(request).await?;
+        document.network_path = (location.format_path)(&number);
+        document.number = number;
+        Ok((document, 1))
+    }
+
+    async fn create_many_with_allocated_numbers(
+        &self,
+        _drafts: Vec<doc_man_db::models::AllocatedDocumentDraft<'_>>,
+        _all_or_nothing: bool,
+    ) -> Result<Vec<Result<(Document, i32), DocumentNumberGenerationError>>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn create_with_manual_number(
+        &self,
+        _request: doc_man_db::models::CreateDocumentRequest,
+        _location: &doc_man_db::models::DocumentLocation<'_>,
+        _assigned_by: i32,
+        _reason: &str,
+        _find_collision: &doc_man_db::models::RuleCollisionFinder<'_>,
+    ) -> Result<(Document, doc_man_db::models::DocumentNumberOverride), RepositoryError> {
+        unimplemented!()
+    }
+
     async fn get_by_id(&self, id: i32) -> Result<Option<Document>, RepositoryError> {
         if id == 1 {
             Ok(Some(Document {
@@ -52,6 +90,7 @@ impl DocumentRepository for MockDocumentRepository {
                 notes: None,
                 network_path: None,
                 is_active: true,
+                version: 1,
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             }))
@@ -79,12 +118,27 @@ impl DocumentRepository for MockDocumentRepository {
             notes: None,
             network_path: None,
             is_active: true,
+            version: 1,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }];
         Ok((documents, 1))
     }
 
+    async fn full_text_search(
+        &self,
+        _filters: doc_man_db::models::DocumentSearchFilters,
+    ) -> Result<(Vec<doc_man_db::models::DocumentSearchHit>, i64), RepositoryError> {
+        Ok((vec![], 0))
+    }
+
+    async fn search_aggregations(
+        &self,
+        _filters: &doc_man_db::models::DocumentSearchFilters,
+    ) -> Result<doc_man_db::models::DocumentSearchAggregations, RepositoryError> {
+        Ok(Default::default())
+    }
+
     async fn get_document_type_id_by_code(
         &self,
         document_type_code: &str,
@@ -97,6 +151,175 @@ impl DocumentRepository for MockDocumentRepository {
             _ => Ok(1),
         }
     }
+
+    async fn reserve_numbers(
+        &self,
+        _request: &doc_man_db::models::CreateDocumentNumberReservationRequest,
+        _sequence_key: &SequenceKey,
+        _format_number: &DocumentNumberFormatter<'_>,
+    ) -> Result<doc_man_db::models::DocumentNumberReservation, DocumentNumberGenerationError> {
+        unimplemented!()
+    }
+
+    async fn get_number_reservation(
+        &self,
+        _id: i32,
+    ) -> Result<Option<doc_man_db::models::DocumentNumberReservation>, RepositoryError> {
+        Ok(None)
+    }
+
+    async fn search_number_reservations(
+        &self,
+        _rule_id: Option<i32>,
+        _department_code: Option<&str>,
+        _include_expired: bool,
+    ) -> Result<Vec<doc_man_db::models::DocumentNumberReservation>, RepositoryError> {
+        Ok(vec![])
+    }
+
+    async fn claim_reserved_number(
+        &self,
+        _reservation_id: i32,
+        _request: doc_man_db::models::CreateDocumentRequest,
+        _location: &doc_man_db::models::DocumentLocation<'_>,
+    ) -> Result<Document, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn expire_number_reservations(
+        &self,
+        _as_of: NaiveDate,
+    ) -> Result<doc_man_db::models::ReservationExpiryResult, RepositoryError> {
+        Ok(Default::default())
+    }
+
+    async fn find_issued_number(
+        &self,
+        _document_number: &str,
+    ) -> Result<Option<doc_man_db::models::IssuedDocumentNumber>, RepositoryError> {
+        Ok(None)
+    }
+
+    async fn find_reservation_by_number(
+        &self,
+        _document_number: &str,
+    ) -> Result<Option<doc_man_db::models::DocumentNumberReservation>, RepositoryError> {
+        Ok(None)
+    }
+
+    async fn void_document_number(
+        &self,
+        _voided: doc_man_db::models::NewVoidedDocumentNumber,
+    ) -> Result<doc_man_db::models::VoidedDocumentNumber, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn list_voided_numbers(
+        &self,
+        _filters: &doc_man_db::models::NumberGapFilters,
+    ) -> Result<Vec<doc_man_db::models::VoidedDocumentNumber>, RepositoryError> {
+        Ok(vec![])
+    }
+
+    async fn list_sequence_counters(
+        &self,
+        _filters: &doc_man_db::models::NumberGapFilters,
+    ) -> Result<Vec<doc_man_db::models::SequenceCounter>, RepositoryError> {
+        Ok(vec![])
+    }
+
+    async fn list_issued_numbers(
+        &self,
+        _department_code: Option<&str>,
+    ) -> Result<Vec<doc_man_db::models::IssuedDocumentNumber>, RepositoryError> {
+        Ok(vec![])
+    }
+
+    async fn get_by_number(
+        &self,
+        _document_number: &str,
+    ) -> Result<Option<Document>, RepositoryError> {
+        Ok(None)
+    }
+
+    async fn add_number_alias(
+        &self,
+        _document_id: i32,
+        _request: doc_man_db::models::CreateDocumentNumberAliasRequest,
+    ) -> Result<doc_man_db::models::DocumentNumberAlias, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn list_number_aliases(
+        &self,
+        _document_id: i32,
+    ) -> Result<Vec<doc_man_db::models::DocumentNumberAlias>, RepositoryError> {
+        Ok(vec![])
+    }
+
+    async fn update(
+        &self,
+        _id: i32,
+        _request: doc_man_db::models::UpdateDocumentRequest,
+        _expected_version: i32,
+    ) -> Result<Document, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn soft_delete(
+        &self,
+        _id: i32,
+        _request: doc_man_db::models::ChangeDocumentStateRequest,
+    ) -> Result<Document, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn restore(
+        &self,
+        _id: i32,
+        _request: doc_man_db::models::ChangeDocumentStateRequest,
+    ) -> Result<Document, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn list_deleted(
+        &self,
+        _limit: i64,
+        _offset: i64,
+        _max_clearance: doc_man_db::models::ClearanceLevel,
+    ) -> Result<(Vec<doc_man_db::models::DeletedDocument>, i64), RepositoryError> {
+        Ok((vec![], 0))
+    }
+
+    async fn list_changes(
+        &self,
+        _document_id: i32,
+    ) -> Result<Vec<doc_man_db::models::DocumentChange>, RepositoryError> {
+        Ok(vec![])
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        _key: &str,
+        _request_fingerprint: &str,
+        _expires_at: chrono::NaiveDateTime,
+        _claimed_until: chrono::NaiveDateTime,
+    ) -> Result<doc_man_db::models::IdempotencyKeyClaim, RepositoryError> {
+        Ok(doc_man_db::models::IdempotencyKeyClaim::Claimed)
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        _key: &str,
+        _document_id: i32,
+        _response: &str,
+    ) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    async fn release_idempotency_key(&self, _key: &str) -> Result<(), RepositoryError> {
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -117,11 +340,21 @@ impl DocumentNumberRuleRepository for MockDocumentNumberRuleRepository {
             effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
             effective_until: None,
             priority: 1,
+            reset_scope: Default::default(),
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
         }))
     }
 
+    async fn find_candidate_rules(
+        &self,
+        _document_type_code: &str,
+        _department_code: &str,
+        _date: NaiveDate,
+    ) -> Result<Vec<doc_man_db::models::DocumentNumberGenerationRule>, RepositoryError> {
+        unimplemented!()
+    }
+
     async fn get_next_sequence_number(
         &self,
         _rule_id: i32,
@@ -162,6 +395,38 @@ impl DocumentNumberRuleRepository for MockDocumentNumberRuleRepository {
     ) -> Result<(Vec<doc_man_db::models::DocumentNumberGenerationRule>, i64), RepositoryError> {
         unimplemented!()
     }
+
+    async fn update_rule(
+        &self,
+        _id: i32,
+        _request: doc_man_db::models::CreateDocumentNumberGenerationRuleRequest,
+    ) -> Result<Option<doc_man_db::models::DocumentNumberGenerationRule>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn retire_rule(
+        &self,
+        _id: i32,
+        _effective_until: NaiveDate,
+    ) -> Result<Option<doc_man_db::models::DocumentNumberGenerationRule>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn find_document_type_name(
+        &self,
+        _document_type_code: &str,
+    ) -> Result<Option<String>, RepositoryError> {
+        Ok(Some("技術文書".to_string()))
+    }
+
+    async fn resolve_organization_codes(
+        &self,
+        department_code: &str,
+        section_code: Option<String>,
+        _date: NaiveDate,
+    ) -> Result<(String, Option<String>), RepositoryError> {
+        Ok((department_code.to_string(), section_code))
+    }
 }
 
 #[tokio::test]
@@ -188,8 +453,12 @@ async fn test_create_document_handler() {
         title: "ハンドラーテスト文書".to_string(),
         document_type_code: "A".to_string(),
         department_code: "T".to_string(),
+        section_code: None,
         created_by: 1,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     // When: 文書作成ハンドラー呼び出し
@@ -244,11 +513,20 @@ async fn test_search_documents_handler() {
     let handlers = DocumentHandlers::new(service);
 
     let filters = doc_man_db::models::DocumentSearchFilters {
+        query: None,
         title: Some("テスト".to_string()),
         document_type_id: None,
         created_by: None,
         created_date_from: None,
         created_date_to: None,
+        department_code: None,
+        include_historical_affiliations: false,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
+        max_clearance: None,
+        affiliation_employee_id: None,
         limit: 10,
         offset: 0,
     };
@@ -276,8 +554,12 @@ async fn test_create_document_handler_validation_error() {
         title: "".to_string(), // 空のタイトル（バリデーションエラー）
         document_type_code: "A".to_string(),
         department_code: "T".to_string(),
+        section_code: None,
         created_by: 1,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     // When: 無効なリクエストで文書作成ハンドラー呼び出し
@@ -2,7 +2,7 @@ use axum::http::StatusCode;
 use reqwest::Client;
 use serde_json::json;
 
-use super::helpers::spawn_app;
+use super::helpers::{spawn_app, spawn_app_with_pool};
 
 #[tokio::test]
 async fn test_graphql_introspection() {
@@ -212,6 +212,319 @@ async fn test_graphql_query_document_by_id() {
     // (ファイル削除は不要)
 }
 
+#[tokio::test]
+async fn test_graphql_query_document_by_former_number() {
+    // Given: 旧番号を登録した文書がある
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let created: serde_json::Value = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "旧番号のある文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let document_id = created["document"]["id"].as_i64().unwrap();
+    client
+        .post(format!("http://{addr}/api/documents/{document_id}/aliases"))
+        .json(&json!({ "alias_number": "OLD-0001", "created_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+
+    // When: 旧番号で文書を取得
+    let response = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                query GetDocument($number: String!) {
+                    document(number: $number) { id number }
+                }
+            "#,
+            "variables": { "number": "OLD-0001" }
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 現在の番号の文書が返される
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["errors"].is_null());
+    assert_eq!(body["data"]["document"]["id"], document_id);
+    assert_eq!(
+        body["data"]["document"]["number"],
+        created["document_number"]
+    );
+}
+
+#[tokio::test]
+async fn test_graphql_update_and_delete_document() {
+    // Given: 備考付きの文書がある
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let created: serde_json::Value = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "GraphQL更新前",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let document_id = created["document"]["id"].as_i64().unwrap();
+    client
+        .patch(format!("http://{addr}/api/documents/{document_id}"))
+        .header("If-Match", "\"1\"")
+        .json(&json!({ "notes": "消す備考", "updated_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+
+    // When: タイトルを変更し、備考をnullで消去してから削除
+    let response = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                mutation Update($id: Int!, $input: UpdateDocumentInput!) {
+                    updateDocument(id: $id, version: 2, input: $input) { title notes isActive }
+                    deleteDocument(id: $id, deletedBy: 1, reason: "不要") { isActive }
+                }
+            "#,
+            "variables": {
+                "id": document_id,
+                "input": { "title": "GraphQL更新後", "notes": null, "updatedBy": 1 }
+            }
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 更新・削除され、ゴミ箱と変更履歴に反映される
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["errors"].is_null());
+    assert_eq!(body["data"]["updateDocument"]["title"], "GraphQL更新後");
+    assert!(body["data"]["updateDocument"]["notes"].is_null());
+    assert_eq!(body["data"]["deleteDocument"]["isActive"], false);
+
+    let response = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                query Trash($id: Int!) {
+                    deletedDocuments { total documents { document { id } reason } }
+                    documentChanges(documentId: $id) { changeType fieldName }
+                }
+            "#,
+            "variables": { "id": document_id }
+        }))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["errors"].is_null());
+    assert_eq!(body["data"]["deletedDocuments"]["total"], 1);
+    assert_eq!(
+        body["data"]["deletedDocuments"]["documents"][0]["document"]["id"],
+        document_id
+    );
+    let changes = body["data"]["documentChanges"].as_array().unwrap();
+    assert_eq!(changes.len(), 4);
+    assert!(changes.iter().any(|c| c["changeType"] == "DELETED"));
+}
+
+#[tokio::test]
+async fn test_graphql_trash_and_changes_respect_clearance() {
+    // Given: 個人情報有の社内文書のネットワークパスを変更してから削除してある
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let created: serde_json::Value = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "GraphQLゴミ箱機密テスト",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let document_id = created["document"]["id"].as_i64().unwrap();
+    sqlx::query("UPDATE documents SET internal_external = 'internal', personal_info = 'present' WHERE id = ?")
+        .bind(document_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    client
+        .patch(format!("http://{addr}/api/documents/{document_id}"))
+        .header("If-Match", "\"1\"")
+        .json(&json!({ "network_path": r"\\server01\restricted\graphql", "updated_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+    client
+        .delete(format!(
+            "http://{addr}/api/documents/{document_id}?changed_by=1"
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    let query = r#"
+        query Trash($id: Int!, $userId: Int) {
+            deletedDocuments(userId: $userId) { total documents { document { id networkPath } } }
+            documentChanges(documentId: $id, userId: $userId) { fieldName oldValue newValue }
+        }
+    "#;
+    let fetch = |user_id: Option<i32>| {
+        client
+            .post(format!("http://{addr}/graphql"))
+            .json(&json!({
+                "query": query,
+                "variables": { "id": document_id, "userId": user_id }
+            }))
+            .send()
+    };
+    let path_change = |body: &serde_json::Value| {
+        body["data"]["documentChanges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["fieldName"] == "network_path")
+            .cloned()
+            .unwrap()
+    };
+
+    // When/Then: 閲覧者不明の場合はゴミ箱に現れず、変更履歴も取得できない
+    let body: serde_json::Value = fetch(None).await.unwrap().json().await.unwrap();
+    assert!(body["errors"][0]["message"].is_string());
+    assert!(body["data"].is_null());
+
+    // When/Then: 一般社員には文書は見えるが、保存場所は伏せられる
+    let body: serde_json::Value = fetch(Some(2)).await.unwrap().json().await.unwrap();
+    assert!(body["errors"].is_null(), "{body}");
+    assert_eq!(body["data"]["deletedDocuments"]["total"], 1);
+    assert!(body["data"]["deletedDocuments"]["documents"][0]["document"]["networkPath"].is_null());
+    let change = path_change(&body);
+    assert!(change["oldValue"].is_null());
+    assert!(change["newValue"].is_null());
+
+    // When/Then: 管理者には保存場所も表示される
+    let body: serde_json::Value = fetch(Some(1)).await.unwrap().json().await.unwrap();
+    assert_eq!(
+        body["data"]["deletedDocuments"]["documents"][0]["document"]["networkPath"],
+        r"\\server01\restricted\graphql"
+    );
+    assert_eq!(
+        path_change(&body)["newValue"],
+        r"\\server01\restricted\graphql"
+    );
+}
+
+#[tokio::test]
+async fn test_graphql_update_with_stale_version_is_rejected() {
+    // Given: 文書と部署があり、どちらも一度更新されている
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let created: serde_json::Value = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "GraphQL同時編集",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let document_id = created["document"]["id"].as_i64().unwrap();
+    let graphql = |query: &str, variables: serde_json::Value| {
+        client
+            .post(format!("http://{addr}/graphql"))
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+    };
+    let body: serde_json::Value = graphql(
+        r#"
+            mutation ($id: Int!) {
+                updateDocument(id: $id, version: 1, input: { title: "先の更新", updatedBy: 1 }) { version }
+                updateDepartment(id: 1, version: 1, input: { name: "先の部署名" }) { version }
+            }
+        "#,
+        json!({ "id": document_id }),
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert!(body["errors"].is_null());
+    assert_eq!(body["data"]["updateDocument"]["version"], 2);
+    assert_eq!(body["data"]["updateDepartment"]["version"], 2);
+
+    // When: 取得時の古いバージョンで文書を更新
+    let body: serde_json::Value = graphql(
+        r#"
+            mutation ($id: Int!) {
+                updateDocument(id: $id, version: 1, input: { title: "後の更新", updatedBy: 1 }) { version }
+            }
+        "#,
+        json!({ "id": document_id }),
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+
+    // Then: 競合エラーになり、拡張情報に最新の文書が含まれる
+    let error = &body["errors"][0];
+    assert_eq!(error["extensions"]["code"], "VERSION_CONFLICT");
+    assert_eq!(error["extensions"]["current"]["title"], "先の更新");
+    assert_eq!(error["extensions"]["current"]["version"], 2);
+
+    // When/Then: 部署も古いバージョンでは更新できない
+    let body: serde_json::Value = graphql(
+        r#"
+            mutation {
+                updateDepartment(id: 1, version: 1, input: { name: "後の部署名" }) { version }
+            }
+        "#,
+        json!({}),
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(body["errors"][0]["extensions"]["code"], "VERSION_CONFLICT");
+    assert_eq!(
+        body["errors"][0]["extensions"]["current"]["name"],
+        "先の部署名"
+    );
+}
+
 #[tokio::test]
 async fn test_graphql_search_documents() {
     // Given: テストサーバーを起動し、複数の文書を作成
@@ -371,3 +684,1052 @@ async fn test_graphql_playground_endpoint() {
     // Cleanup: インメモリデータベースは自動的にクリーンアップされる
     // (ファイル削除は不要)
 }
+
+#[tokio::test]
+async fn test_graphql_document_revisions() {
+    // Given: テストサーバーと親文書
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    let response = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "GraphQL版管理テスト文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap();
+    let created: serde_json::Value = response.json().await.unwrap();
+    let document_id = created["document"]["id"].as_i64().unwrap();
+
+    // When: ドラフト版を作成して履歴を取得
+    let mutation = json!({
+        "query": r#"
+            mutation CreateRevision($documentId: Int!, $input: CreateDocumentRevisionInput!) {
+                createDocumentRevision(documentId: $documentId, input: $input) {
+                    number
+                    revisionType
+                    status
+                }
+            }
+        "#,
+        "variables": {
+            "documentId": document_id,
+            "input": { "revisionType": "DRAFT", "createdBy": 1 }
+        }
+    });
+    let response = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&mutation)
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    let revision = &body["data"]["createDocumentRevision"];
+
+    let query = json!({
+        "query": r#"
+            query Revisions($documentId: Int!) {
+                documentRevisions(documentId: $documentId) { number status }
+            }
+        "#,
+        "variables": { "documentId": document_id }
+    });
+    let response = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&query)
+        .send()
+        .await
+        .unwrap();
+    let history: serde_json::Value = response.json().await.unwrap();
+
+    // Then: d1が採番され、履歴に含まれる
+    let base_number = created["document_number"].as_str().unwrap();
+    assert_eq!(revision["number"], format!("{base_number}d1"));
+    assert_eq!(revision["revisionType"], "DRAFT");
+    assert_eq!(revision["status"], "DRAFT");
+    assert_eq!(
+        history["data"]["documentRevisions"][0]["number"],
+        format!("{base_number}d1")
+    );
+}
+
+#[tokio::test]
+async fn test_graphql_document_clearance() {
+    // Given: 社内文書を1件用意
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+
+    let create_body: serde_json::Value = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                mutation CreateDocument($input: CreateDocumentInput!) {
+                    createDocument(input: $input) { document { id number } }
+                }
+            "#,
+            "variables": {
+                "input": {
+                    "title": "GraphQL機密レベルテスト文書",
+                    "documentTypeCode": "BUS",
+                    "departmentCode": "DEV",
+                    "createdBy": 1,
+                    "createdDate": "2025-08-17"
+                }
+            }
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let document_id = create_body["data"]["createDocument"]["document"]["id"]
+        .as_i64()
+        .unwrap();
+    sqlx::query("UPDATE documents SET internal_external = 'internal' WHERE id = ?")
+        .bind(document_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let query = r#"
+        query Visible($id: Int!, $userId: Int) {
+            document(id: $id, userId: $userId) { id }
+            searchDocuments(filters: { title: "GraphQL機密レベルテスト文書" }, userId: $userId) {
+                total
+            }
+        }
+    "#;
+
+    // When: 閲覧者不明と一般社員で取得
+    for (user_id, expected_total) in [(None, 0), (Some(2), 1)] {
+        let body: serde_json::Value = client
+            .post(format!("http://{addr}/graphql"))
+            .json(&json!({
+                "query": query,
+                "variables": { "id": document_id, "userId": user_id }
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // Then: 機密レベルが足りない場合は取得・検索のどちらにも現れない
+        assert!(body["errors"].is_null());
+        assert_eq!(body["data"]["searchDocuments"]["total"], expected_total);
+        assert_eq!(body["data"]["document"].is_null(), expected_total == 0);
+    }
+
+    // When: 個人情報有に変更し、文書番号からネットワークパスを解決
+    let document_number = create_body["data"]["createDocument"]["document"]["number"]
+        .as_str()
+        .unwrap();
+    sqlx::query("UPDATE documents SET personal_info = 'present' WHERE id = ?")
+        .bind(document_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let resolve_query = r#"
+        query Resolve($number: String!, $userId: Int) {
+            resolveNetworkPath(documentNumber: $number, userId: $userId) { networkPath }
+        }
+    "#;
+    for (user_id, allowed) in [(None, false), (Some(2), false), (Some(1), true)] {
+        let body: serde_json::Value = client
+            .post(format!("http://{addr}/graphql"))
+            .json(&json!({
+                "query": resolve_query,
+                "variables": { "number": document_number, "userId": user_id }
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // Then: 閲覧者不明や保存場所を見られない社員にはエラーになる
+        assert_eq!(body["errors"].is_null(), allowed, "{body}");
+        assert_eq!(
+            body["data"]["resolveNetworkPath"]["networkPath"].is_string(),
+            allowed
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_graphql_document_full_text_search() {
+    // Given: 文書を1件作成
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                mutation CreateDocument($input: CreateDocumentInput!) {
+                    createDocument(input: $input) { document { id } }
+                }
+            "#,
+            "variables": {
+                "input": {
+                    "title": "GraphQL全文検索 年次報告書",
+                    "documentTypeCode": "BUS",
+                    "departmentCode": "DEV",
+                    "createdBy": 1,
+                    "createdDate": "2025-08-17"
+                }
+            }
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // When: 全文検索キーワードで検索
+    let body: serde_json::Value = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                query Search($filters: DocumentSearchFilters!) {
+                    searchDocuments(filters: $filters, userId: 1) {
+                        total
+                        documents { title }
+                        hits { document { title } rank snippet }
+                    }
+                }
+            "#,
+            "variables": { "filters": { "query": "年次報告" } }
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Then: 関連度とハイライト付きの抜粋が返される
+    assert!(body["errors"].is_null());
+    let result = &body["data"]["searchDocuments"];
+    assert_eq!(result["total"], 1);
+    assert_eq!(
+        result["documents"][0]["title"],
+        "GraphQL全文検索 年次報告書"
+    );
+    assert!(result["hits"][0]["rank"].as_f64().is_some());
+    assert_eq!(
+        result["hits"][0]["snippet"],
+        "GraphQL全文検索 <mark>年次報告</mark>書"
+    );
+}
+
+#[tokio::test]
+async fn test_graphql_document_search_facets() {
+    // Given: 文書種別の異なる文書を2件作成
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    for document_type_code in ["TEC", "BUS"] {
+        client
+            .post(format!("http://{addr}/graphql"))
+            .json(&json!({
+                "query": r#"
+                    mutation CreateDocument($input: CreateDocumentInput!) {
+                        createDocument(input: $input) { document { id } }
+                    }
+                "#,
+                "variables": {
+                    "input": {
+                        "title": "GraphQL集計テスト文書",
+                        "documentTypeCode": document_type_code,
+                        "departmentCode": "DEV",
+                        "createdBy": 1,
+                        "createdDate": "2025-08-17",
+                        "importanceClass": "CLASS2"
+                    }
+                }
+            }))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    // When: 重要度で絞り込んで集計を取得
+    let body: serde_json::Value = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                query Search($filters: DocumentSearchFilters!) {
+                    searchDocuments(filters: $filters, userId: 1) {
+                        total
+                        aggregations {
+                            documentTypeCounts { documentTypeCode count }
+                            yearCounts { year count }
+                            importanceClassCounts { importanceClass count }
+                        }
+                    }
+                }
+            "#,
+            "variables": {
+                "filters": {
+                    "title": "GraphQL集計テスト文書",
+                    "importanceClass": "CLASS2",
+                    "year": 2025
+                }
+            }
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Then: 絞り込み後の件数と内訳が返される
+    assert!(body["errors"].is_null());
+    let result = &body["data"]["searchDocuments"];
+    assert_eq!(result["total"], 2);
+    assert_eq!(
+        result["aggregations"]["documentTypeCounts"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        result["aggregations"]["yearCounts"],
+        json!([{ "year": 2025, "count": 2 }])
+    );
+    assert_eq!(
+        result["aggregations"]["importanceClassCounts"],
+        json!([{ "importanceClass": "CLASS2", "count": 2 }])
+    );
+}
+
+#[tokio::test]
+async fn test_graphql_document_classification() {
+    // Given: テストサーバーを起動
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    // When: 分類を指定して文書を作成
+    let body: serde_json::Value = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                mutation CreateDocument($input: CreateDocumentInput!) {
+                    createDocument(input: $input) {
+                        document {
+                            internalExternal
+                            internalExternalLabel
+                            importanceClass
+                            importanceClassLabel
+                            personalInfo
+                            personalInfoLabel
+                        }
+                    }
+                }
+            "#,
+            "variables": {
+                "input": {
+                    "title": "GraphQL分類テスト文書",
+                    "documentTypeCode": "BUS",
+                    "departmentCode": "DEV",
+                    "createdBy": 1,
+                    "createdDate": "2025-08-17",
+                    "internalExternal": "EXTERNAL",
+                    "importanceClass": "CLASS2",
+                    "personalInfo": "NONE"
+                }
+            }
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Then: 列挙値と日本語の表示名が返される
+    assert!(body["errors"].is_null(), "{body}");
+    let document = &body["data"]["createDocument"]["document"];
+    assert_eq!(document["internalExternal"], "EXTERNAL");
+    assert_eq!(document["internalExternalLabel"], "社外");
+    assert_eq!(document["importanceClass"], "CLASS2");
+    assert_eq!(document["importanceClassLabel"], "情報クラスⅡ");
+    assert_eq!(document["personalInfo"], "NONE");
+    assert_eq!(document["personalInfoLabel"], "無");
+}
+
+#[tokio::test]
+async fn test_graphql_document_number_rule_simulation() {
+    // Given: テストサーバーを起動
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    let query = json!({
+        "query": r#"
+            query {
+                simulateDocumentNumberRule(input: {
+                    documentTypeCode: "BUS",
+                    departmentCode: "HR",
+                    createdDate: "2025-08-17",
+                    count: 2
+                }) {
+                    candidates { id priority }
+                    selectedRuleId
+                    nextNumbers
+                }
+                documentNumberRuleConflicts {
+                    checkedRules
+                    priorityOverlaps { ruleId }
+                    templateCollisions { kind ruleId otherRuleId exampleNumber }
+                }
+            }
+        "#
+    });
+
+    // When: シミュレーションと衝突検査を実行
+    let response = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&query)
+        .send()
+        .await
+        .unwrap();
+
+    // Then: HR部署では汎用ルールが選ばれ、衝突が報告される
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["errors"].is_null(), "{body}");
+    let simulation = &body["data"]["simulateDocumentNumberRule"];
+    assert_eq!(
+        simulation["candidates"],
+        json!([{ "id": 3, "priority": 9 }])
+    );
+    assert_eq!(simulation["selectedRuleId"], 3);
+    assert_eq!(
+        simulation["nextNumbers"],
+        json!(["BUS-2508001", "BUS-2508002"])
+    );
+
+    let conflicts = &body["data"]["documentNumberRuleConflicts"];
+    assert_eq!(conflicts["checkedRules"], 3);
+    assert_eq!(conflicts["priorityOverlaps"], json!([]));
+    assert!(
+        conflicts["templateCollisions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|c| c["kind"] == "DEPARTMENT_COUNTER" && c["ruleId"] == 3)
+    );
+}
+
+#[tokio::test]
+async fn test_graphql_decompose_document_number() {
+    // Given: テストサーバーを起動
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    let query = json!({
+        "query": r#"
+            query {
+                decomposeDocumentNumber(documentNumber: "BUS-2508007") {
+                    documentNumber
+                    registered
+                    matches {
+                        ruleId
+                        effective
+                        components { documentTypeCode departmentCode year month sequenceNumber }
+                        sequenceKey { year month departmentCode }
+                        nextSequence
+                    }
+                }
+            }
+        "#
+    });
+
+    // When: 文書番号を逆引き
+    let response = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&query)
+        .send()
+        .await
+        .unwrap();
+
+    // Then: DEV部署の業務文書ルールと汎用ルールに一致する
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["errors"].is_null(), "{body}");
+    let decomposition = &body["data"]["decomposeDocumentNumber"];
+    assert_eq!(decomposition["registered"], false);
+    let matches = decomposition["matches"].as_array().unwrap();
+    assert_eq!(matches[0]["ruleId"], 2);
+    assert_eq!(
+        matches[0]["components"],
+        json!({
+            "documentTypeCode": "BUS",
+            "departmentCode": "DEV",
+            "year": 2025,
+            "month": 8,
+            "sequenceNumber": 7
+        })
+    );
+    assert_eq!(
+        matches[0]["sequenceKey"],
+        json!({ "year": 2025, "month": 8, "departmentCode": "DEV" })
+    );
+    assert_eq!(matches[0]["nextSequence"], 1);
+    assert_eq!(matches[1]["ruleId"], 3);
+}
+
+#[tokio::test]
+async fn test_graphql_create_document_with_idempotency_key() {
+    // Given: idempotencyKey付きの文書作成ミューテーション
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let mutation = json!({
+        "query": r#"
+            mutation ($input: CreateDocumentInput!, $key: String) {
+                createDocument(input: $input, idempotencyKey: $key) {
+                    document { id }
+                    documentNumber
+                }
+            }
+        "#,
+        "variables": {
+            "input": {
+                "title": "GraphQL再送文書",
+                "documentTypeCode": "TEC",
+                "departmentCode": "DEV",
+                "createdBy": 1,
+                "createdDate": "2025-08-17"
+            },
+            "key": "graphql-create-001"
+        }
+    });
+
+    // When: 同じキーで2回実行
+    let mut results = Vec::new();
+    for _ in 0..2 {
+        let body: serde_json::Value = client
+            .post(format!("http://{addr}/graphql"))
+            .json(&mutation)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(body["errors"].is_null(), "{body}");
+        results.push(body["data"]["createDocument"].clone());
+    }
+
+    // Then: 2回目は最初の作成結果が返る
+    assert_eq!(results[0], results[1]);
+    assert_eq!(results[0]["documentNumber"], "TEC-2508001");
+}
+
+#[tokio::test]
+async fn test_graphql_create_documents_bulk() {
+    // Given: 不正な行を含む一括登録
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let mutation = |mode: &str| {
+        json!({
+            "query": format!(r#"
+                mutation ($documents: [CreateDocumentInput!]!) {{
+                    createDocumentsBulk(input: {{ mode: {mode}, documents: $documents }}) {{
+                        committed
+                        createdCount
+                        failedCount
+                        results {{ index created {{ documentNumber }} error }}
+                    }}
+                }}
+            "#),
+            "variables": {
+                "documents": [
+                    {
+                        "title": "GraphQL一括1",
+                        "documentTypeCode": "TEC",
+                        "departmentCode": "DEV",
+                        "createdBy": 1,
+                        "createdDate": "2025-08-17"
+                    },
+                    {
+                        "title": " ",
+                        "documentTypeCode": "TEC",
+                        "departmentCode": "DEV",
+                        "createdBy": 1,
+                        "createdDate": "2025-08-17"
+                    }
+                ]
+            }
+        })
+    };
+
+    // When: 全件一括モードとベストエフォートモードで実行
+    let mut results = Vec::new();
+    for mode in ["ALL_OR_NOTHING", "BEST_EFFORT"] {
+        let body: serde_json::Value = client
+            .post(format!("http://{addr}/graphql"))
+            .json(&mutation(mode))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(body["errors"].is_null(), "{body}");
+        results.push(body["data"]["createDocumentsBulk"].clone());
+    }
+
+    // Then: 全件一括は何も登録せず、ベストエフォートは正しい行だけ登録する
+    assert_eq!(results[0]["committed"], false);
+    assert_eq!(results[0]["createdCount"], 0);
+    assert!(results[0]["results"][1]["error"].is_string());
+    assert_eq!(results[1]["committed"], true);
+    assert_eq!(results[1]["createdCount"], 1);
+    assert_eq!(results[1]["failedCount"], 1);
+    assert_eq!(
+        results[1]["results"][0]["created"]["documentNumber"],
+        "TEC-2508001"
+    );
+}
+
+#[tokio::test]
+async fn test_graphql_department_restructure_history() {
+    // Given: 開発部（DEV）と営業部（SALES）がある
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let graphql = |query: &str| {
+        client
+            .post(format!("http://{addr}/graphql"))
+            .json(&json!({ "query": query }))
+            .send()
+    };
+
+    // When: 2025-04-01付けで開発部をシステム部（SYS）に名称変更し、
+    // 2025-06-01付けで営業部を人事部に統合
+    let body: serde_json::Value = graphql(
+        r#"
+            mutation {
+                rename: restructureDepartments(input: {
+                    eventType: RENAME, effectiveDate: "2025-04-01", departmentId: 1,
+                    code: "SYS", name: "システム部", reason: "組織改編"
+                }) { id eventType sourceIds targetIds }
+                merge: restructureDepartments(input: {
+                    eventType: MERGE, effectiveDate: "2025-06-01", sourceIds: [2], targetId: 3
+                }) { eventType sourceIds targetIds }
+            }
+        "#,
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert!(body["errors"].is_null(), "{body}");
+    assert_eq!(body["data"]["rename"]["eventType"], "RENAME");
+    assert_eq!(body["data"]["merge"]["sourceIds"], json!([2]));
+    assert_eq!(body["data"]["merge"]["targetIds"], json!([3]));
+
+    // Then: 時点を指定すると当時の組織が返される
+    let body: serde_json::Value = graphql(
+        r#"
+            query {
+                before: department(id: 1, asOf: "2025-03-31") { code name kind }
+                after: department(id: 1, asOf: "2025-04-01") { code name }
+                current: department(id: 1) { code version }
+                salesBeforeMerge: department(id: 2, asOf: "2025-05-31") { code isActive }
+                salesAfterMerge: department(id: 2, asOf: "2025-06-01") { code }
+                departments(asOf: "2025-06-01") { code }
+                departmentHistory(id: 1) { code validFrom validTo eventId }
+                organizationEvents(departmentId: 1) { eventType effectiveDate reason }
+            }
+        "#,
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert!(body["errors"].is_null(), "{body}");
+    let data = &body["data"];
+    assert_eq!(data["before"]["code"], "DEV");
+    assert_eq!(data["before"]["kind"], "DEPARTMENT");
+    assert_eq!(data["after"]["code"], "SYS");
+    assert_eq!(data["after"]["name"], "システム部");
+    assert_eq!(data["current"]["code"], "SYS");
+    assert_eq!(data["current"]["version"], 2);
+    assert_eq!(data["salesBeforeMerge"]["code"], "SALES");
+    assert_eq!(data["salesBeforeMerge"]["isActive"], true);
+    assert!(data["salesAfterMerge"].is_null());
+    let codes: Vec<&str> = data["departments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["code"].as_str().unwrap())
+        .collect();
+    assert!(codes.contains(&"SYS"));
+    assert!(!codes.contains(&"SALES"));
+    let history = data["departmentHistory"].as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["code"], "DEV");
+    assert_eq!(history[0]["validTo"], "2025-04-01");
+    assert_eq!(history[1]["code"], "SYS");
+    assert!(history[1]["validTo"].is_null());
+    assert_eq!(data["organizationEvents"].as_array().unwrap().len(), 1);
+    assert_eq!(data["organizationEvents"][0]["reason"], "組織改編");
+
+    // When/Then: 改編より前の日付には遡って記録できない
+    let body: serde_json::Value = graphql(
+        r#"
+            mutation {
+                restructureDepartments(input: {
+                    eventType: RENAME, effectiveDate: "2025-03-01", departmentId: 1, name: "遡及"
+                }) { id }
+            }
+        "#,
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert!(body["errors"][0]["message"].is_string());
+}
+
+#[tokio::test]
+async fn test_graphql_department_employee_count_as_of() {
+    // Given: 社員11が2024-04-01付けで開発部（部署1）から営業部（部署2）へ異動した
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let timeline: serde_json::Value = client
+        .get(format!("http://{addr}/api/employees/11/assignments"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let mut dev_assignment = timeline["assignments"][0].clone();
+    dev_assignment["end_date"] = json!("2024-03-31");
+    let response = client
+        .put(format!(
+            "http://{addr}/api/employee-assignments/{}?user_id=1",
+            dev_assignment["id"]
+        ))
+        .json(&dev_assignment)
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let response = client
+        .post(format!(
+            "http://{addr}/api/employees/11/assignments?user_id=1"
+        ))
+        .json(&json!({ "department_id": 2, "start_date": "2024-04-01" }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    // When: 異動の前日と当日の部署を取得
+    let body: serde_json::Value = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                {
+                    devBefore: department(id: 1, asOf: "2024-03-31") { employeeCount }
+                    devAfter: department(id: 1, asOf: "2024-04-01") { employeeCount }
+                    salesBefore: department(id: 2, asOf: "2024-03-31") { employeeCount }
+                    salesAfter: department(id: 2, asOf: "2024-04-01") { employeeCount }
+                    departments(asOf: "2024-04-01") { id employeeCount }
+                }
+            "#
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Then: 人数はその日に有効だった主務から数えられる
+    assert!(body["errors"].is_null(), "{body}");
+    let count = |alias: &str| body["data"][alias]["employeeCount"].as_i64().unwrap();
+    assert_eq!(count("devAfter"), count("devBefore") - 1);
+    assert_eq!(count("salesAfter"), count("salesBefore") + 1);
+    let sales = body["data"]["departments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|department| department["id"] == 2)
+        .unwrap();
+    assert_eq!(
+        sales["employeeCount"].as_i64().unwrap(),
+        count("salesAfter")
+    );
+}
+
+#[tokio::test]
+async fn test_graphql_numbering_resolves_historical_department_code() {
+    // Given: 2025-04-01付けで開発部（DEV）がシステム部（SYS）に名称変更され、
+    // 部署コードを含む採番ルールが両方のコードにある
+    // （APIでは1文字の部署コードしか登録できないため、seedと同じくDBへ直接登録する）
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let body: serde_json::Value = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                mutation {
+                    restructureDepartments(input: {
+                        eventType: RENAME, effectiveDate: "2025-04-01", departmentId: 1, code: "SYS"
+                    }) { id }
+                }
+            "#
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(body["errors"].is_null(), "{body}");
+    for department_code in ["DEV", "SYS"] {
+        sqlx::query(
+            "INSERT INTO document_number_generation_rules
+                 (rule_name, template, sequence_digits, department_code, document_type_codes,
+                  effective_from, effective_until, priority)
+             VALUES (? || 'ルール', '{部署コード}-{年下2桁}{連番:3桁}', 3, ?, '[\"TEC\"]',
+                     '2025-01-01', NULL, 1)",
+        )
+        .bind(department_code)
+        .bind(department_code)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    // When: 改編前の日付を新コードで、改編後の日付を旧コードで作成
+    let mut numbers = Vec::new();
+    for (department_code, created_date) in [("SYS", "2025-03-15"), ("DEV", "2025-08-17")] {
+        let created: serde_json::Value = client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": "改編をまたぐ文書",
+                "document_type_code": "TEC",
+                "department_code": department_code,
+                "created_by": 1,
+                "created_date": created_date
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        numbers.push(created["document_number"].as_str().unwrap().to_string());
+    }
+
+    // Then: 作成日に有効だった部署コードで採番される
+    assert_eq!(numbers[0], "DEV-25001");
+    assert_eq!(numbers[1], "SYS-25001");
+
+    // When: 改編前の日付の番号を新コードで予約し、新コードのまま登録
+    let reservation: serde_json::Value = client
+        .post(format!("http://{addr}/api/document-number-reservations"))
+        .json(&json!({
+            "document_type_code": "TEC",
+            "department_code": "SYS",
+            "created_date": "2025-03-15",
+            "count": 1,
+            "expires_on": "2099-12-31",
+            "reserved_by": 1
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let response = client
+        .post(format!(
+            "http://{addr}/api/document-number-reservations/{}/claim",
+            reservation["id"]
+        ))
+        .json(&json!({
+            "number": "DEV-25002",
+            "title": "改編をまたぐ予約",
+            "document_type_code": "TEC",
+            "department_code": "SYS",
+            "created_by": 1,
+            "created_date": "2025-03-15"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 予約も作成日に有効だった部署コードで採番・記録され、その番号で登録できる
+    assert_eq!(reservation["department_code"], "DEV");
+    assert_eq!(reservation["numbers"][0]["document_number"], "DEV-25002");
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_graphql_search_includes_historical_affiliations() {
+    // Given: 社員11は2024-03-31まで開発部、2024-04-01から営業部に所属し、
+    // 各部署で所属期間の内外に作成された文書がある
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let timeline: serde_json::Value = client
+        .get(format!("http://{addr}/api/employees/11/assignments"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let mut dev_assignment = timeline["assignments"][0].clone();
+    let dev_assignment_id = dev_assignment["id"].as_i64().unwrap();
+    dev_assignment["end_date"] = json!("2024-03-31");
+    let response = client
+        .put(format!(
+            "http://{addr}/api/employee-assignments/{dev_assignment_id}?user_id=1"
+        ))
+        .json(&dev_assignment)
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let response = client
+        .post(format!(
+            "http://{addr}/api/employees/11/assignments?user_id=1"
+        ))
+        .json(&json!({ "department_id": 2, "start_date": "2024-04-01" }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    // 部署ごとに番号が重ならないよう部署コードを含む採番ルールを登録する
+    // （APIでは1文字の部署コードしか登録できないため、seedと同じくDBへ直接登録する）
+    for department_code in ["DEV", "SALES", "HR"] {
+        sqlx::query(
+            "INSERT INTO document_number_generation_rules
+                 (rule_name, template, sequence_digits, department_code, document_type_codes,
+                  effective_from, effective_until, priority)
+             VALUES (? || 'ルール', '{部署コード}-{年下2桁}{連番:3桁}', 3, ?, '[\"TEC\"]',
+                     '2020-01-01', NULL, 1)",
+        )
+        .bind(department_code)
+        .bind(department_code)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    for (department_code, created_date) in [
+        ("DEV", "2023-05-01"),
+        ("DEV", "2025-08-17"),
+        ("SALES", "2025-08-17"),
+        ("HR", "2025-08-17"),
+    ] {
+        let response = client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": format!("所属履歴検索 {department_code} {created_date}"),
+                "document_type_code": "TEC",
+                "department_code": department_code,
+                "created_by": 1,
+                "created_date": created_date
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+    let search = |filters: serde_json::Value, user_id: Option<i32>| {
+        client
+            .post(format!("http://{addr}/graphql"))
+            .json(&json!({
+                "query": r#"
+                    query ($filters: DocumentSearchFilters!, $userId: Int) {
+                        searchDocuments(filters: $filters, userId: $userId) { total documents { title } }
+                    }
+                "#,
+                "variables": { "filters": filters, "userId": user_id }
+            }))
+            .send()
+    };
+
+    // When: 部署コードと併用して過去の所属を含めて検索
+    let body: serde_json::Value = search(
+        json!({
+            "title": "所属履歴検索",
+            "departmentCode": "HR",
+            "includeHistoricalAffiliations": true
+        }),
+        Some(11),
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+
+    // Then: 指定した部署の文書に、所属していた期間に所属部署で作成された文書が加わる
+    assert!(body["errors"].is_null(), "{body}");
+    let mut titles: Vec<&str> = body["data"]["searchDocuments"]["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["title"].as_str().unwrap())
+        .collect();
+    titles.sort_unstable();
+    assert_eq!(
+        titles,
+        vec![
+            "所属履歴検索 DEV 2023-05-01",
+            "所属履歴検索 HR 2025-08-17",
+            "所属履歴検索 SALES 2025-08-17"
+        ]
+    );
+
+    // When/Then: 部署コードを指定しない場合は絞り込まれず、すべての文書が返される
+    for user_id in [Some(11), None] {
+        let body: serde_json::Value = search(
+            json!({ "title": "所属履歴検索", "includeHistoricalAffiliations": true }),
+            user_id,
+        )
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+        assert!(body["errors"].is_null(), "{body}");
+        assert_eq!(body["data"]["searchDocuments"]["total"], 4);
+    }
+
+    // When/Then: 部署コードと併用して呼び出し元が不明な場合はエラー
+    let body: serde_json::Value = search(
+        json!({
+            "title": "所属履歴検索",
+            "departmentCode": "HR",
+            "includeHistoricalAffiliations": true
+        }),
+        None,
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert!(body["errors"][0]["message"].is_string());
+}
@@ -2,7 +2,10 @@
 
 use chrono::{NaiveDate, Utc};
 use doc_man_db::graphql::types::*;
-use doc_man_db::models::{CreateDocumentWithNumberRequest, Document as ModelDocument};
+use doc_man_db::models::{
+    CreateDocumentWithNumberRequest, Document as ModelDocument, ImportanceClass, InternalExternal,
+    PersonalInfo,
+};
 
 #[test]
 fn test_document_conversion() {
@@ -15,12 +18,13 @@ fn test_document_conversion() {
         created_by: 1,
         created_by_name: Some("テストユーザー".to_string()),
         created_date: NaiveDate::from_ymd_opt(2024, 8, 19).unwrap(),
-        internal_external: Some("internal".to_string()),
-        importance_class: Some("normal".to_string()),
-        personal_info: Some("none".to_string()),
+        internal_external: Some(InternalExternal::Internal),
+        importance_class: Some(ImportanceClass::Class2),
+        personal_info: Some(PersonalInfo::None),
         notes: Some("テストノート".to_string()),
         network_path: Some("/test/path.pdf".to_string()),
         is_active: true,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -42,8 +46,12 @@ fn test_create_document_input_conversion() {
         title: "新規文書".to_string(),
         document_type_code: "技術".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 1,
         created_date: "2024-08-19".to_string(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     let request: CreateDocumentWithNumberRequest = input.into();
@@ -61,11 +69,18 @@ fn test_create_document_input_conversion() {
 #[test]
 fn test_document_search_filters_conversion() {
     let filters = DocumentSearchFilters {
+        query: None,
         title: Some("テスト".to_string()),
         document_type_id: Some(1),
         created_by: Some(1),
         created_date_from: Some("2024-01-01".to_string()),
         created_date_to: Some("2024-12-31".to_string()),
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: Some(20),
         offset: Some(10),
     };
@@ -90,11 +105,18 @@ fn test_document_search_filters_conversion() {
 #[test]
 fn test_document_search_filters_defaults() {
     let filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: None,
         created_date_to: None,
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: None,
         offset: None,
     };
@@ -136,12 +158,13 @@ fn test_created_document_with_number_conversion() {
         created_by: 1,
         created_by_name: Some("テストユーザー".to_string()),
         created_date: NaiveDate::from_ymd_opt(2024, 8, 19).unwrap(),
-        internal_external: Some("internal".to_string()),
-        importance_class: Some("normal".to_string()),
-        personal_info: Some("none".to_string()),
+        internal_external: Some(InternalExternal::Internal),
+        importance_class: Some(ImportanceClass::Class2),
+        personal_info: Some(PersonalInfo::None),
         notes: Some("技術文書のノート".to_string()),
         network_path: Some("/tech/doc.pdf".to_string()),
         is_active: true,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -179,12 +202,13 @@ fn test_search_documents_result_creation() {
             created_by: 1,
             created_by_name: Some("テストユーザー1".to_string()),
             created_date: NaiveDate::from_ymd_opt(2024, 8, 19).unwrap(),
-            internal_external: Some("internal".to_string()),
-            importance_class: Some("normal".to_string()),
-            personal_info: Some("none".to_string()),
+            internal_external: Some(InternalExternal::Internal),
+            importance_class: Some(ImportanceClass::Class2),
+            personal_info: Some(PersonalInfo::None),
             notes: None,
             network_path: None,
             is_active: true,
+            version: 1,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         },
@@ -197,12 +221,13 @@ fn test_search_documents_result_creation() {
             created_by: 2,
             created_by_name: Some("テストユーザー2".to_string()),
             created_date: NaiveDate::from_ymd_opt(2024, 8, 19).unwrap(),
-            internal_external: Some("internal".to_string()),
-            importance_class: Some("normal".to_string()),
-            personal_info: Some("none".to_string()),
+            internal_external: Some(InternalExternal::Internal),
+            importance_class: Some(ImportanceClass::Class2),
+            personal_info: Some(PersonalInfo::None),
             notes: None,
             network_path: None,
             is_active: true,
+            version: 1,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         },
@@ -214,6 +239,8 @@ fn test_search_documents_result_creation() {
     let result = SearchDocumentsResult {
         documents: graphql_documents,
         total: 2,
+        hits: Vec::new(),
+        aggregations: None,
     };
 
     assert_eq!(result.documents.len(), 2);
@@ -228,8 +255,12 @@ fn test_invalid_date_format_handling() {
         title: "テスト文書".to_string(),
         document_type_code: "技術".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 1,
         created_date: "invalid-date".to_string(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     // 無効な日付形式の場合は現在日付が使用される
@@ -247,11 +278,18 @@ fn test_invalid_date_format_handling() {
 #[test]
 fn test_document_search_filters_with_invalid_dates() {
     let filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: Some("invalid-date".to_string()),
         created_date_to: Some("2024-12-31".to_string()),
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: None,
         offset: None,
     };
@@ -3,6 +3,11 @@ use tokio::net::TcpListener;
 
 /// テスト用のアプリケーションサーバー起動ヘルパー
 pub async fn spawn_app() -> SocketAddr {
+    spawn_app_with_pool().await.0
+}
+
+/// テスト用のアプリケーションサーバーを起動し、直接データを準備するための接続プールも返す
+pub async fn spawn_app_with_pool() -> (SocketAddr, sqlx::SqlitePool) {
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
 
@@ -47,5 +52,5 @@ pub async fn spawn_app() -> SocketAddr {
     // サーバーが起動するまで少し待機
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-    addr
+    (addr, pool)
 }
@@ -1,9 +1,11 @@
 use axum::http::StatusCode;
-use doc_man_db::models::CreatedDocumentWithNumber;
+use doc_man_db::models::{CreateBusinessRequest, CreatedDocumentWithNumber};
+use doc_man_db::repositories::{BusinessRepository, SqliteBusinessRepository};
 use reqwest::Client;
 use serde_json::json;
+use std::sync::Arc;
 
-use super::helpers::spawn_app;
+use super::helpers::{spawn_app, spawn_app_with_pool};
 
 #[tokio::test]
 async fn test_health_check_endpoint() {
@@ -71,6 +73,50 @@ async fn test_create_document_with_number_api() {
     assert_eq!(created_document.generated_number.rule_id, 1);
 }
 
+#[tokio::test]
+async fn test_create_documents_concurrently_allocates_unique_numbers() {
+    // Given: テストサーバーを起動
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    // When: 同じルール・年月・部署で並列に文書を作成
+    let mut handles = vec![];
+    for i in 0..20 {
+        let client = client.clone();
+        handles.push(tokio::spawn(async move {
+            client
+                .post(format!("http://{addr}/api/documents"))
+                .json(&json!({
+                    "title": format!("並列作成文書{i}"),
+                    "document_type_code": "TEC",
+                    "department_code": "DEV",
+                    "created_by": 1,
+                    "created_date": "2025-09-01"
+                }))
+                .send()
+                .await
+                .expect("Failed to execute request")
+        }));
+    }
+
+    // Then: すべて成功し、文書番号と連番が重複しない
+    let mut numbers = Vec::new();
+    let mut sequences = Vec::new();
+    for handle in handles {
+        let response = handle.await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created: CreatedDocumentWithNumber = response.json().await.unwrap();
+        assert_eq!(created.document.number, created.document_number);
+        numbers.push(created.document_number);
+        sequences.push(created.generated_number.sequence_number);
+    }
+    numbers.sort();
+    numbers.dedup();
+    assert_eq!(numbers.len(), 20);
+    sequences.sort();
+    assert_eq!(sequences, (1..=20).collect::<Vec<_>>());
+}
+
 #[tokio::test]
 async fn test_get_document_by_id_api() {
     // Given: テストサーバーを起動し、文書を作成
@@ -251,3 +297,3291 @@ async fn test_cors_headers() {
             .contains_key("access-control-allow-methods")
     );
 }
+
+#[tokio::test]
+async fn test_document_number_rule_crud_api() {
+    // Given: テストサーバーを起動
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    // When: 新しい部署向けのルールを作成
+    let response = client
+        .post(format!("http://{addr}/api/document-number-rules"))
+        .json(&json!({
+            "rule_name": "品質保証部ルール",
+            "template": "{部署コード}-{年下2桁}{連番:3桁}",
+            "sequence_digits": 3,
+            "department_code": "Q",
+            "document_type_codes": ["TEC"],
+            "effective_from": "2025-01-01",
+            "effective_until": null,
+            "priority": 1
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    // Then: 201 Createdが返される
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let rule: serde_json::Value = response.json().await.unwrap();
+    let rule_id = rule["id"].as_i64().unwrap();
+
+    // コード変更なしで新ルールが文書番号生成に使われる
+    let response = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "品質保証部の技術文書",
+            "document_type_code": "TEC",
+            "department_code": "Q",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: CreatedDocumentWithNumber = response.json().await.unwrap();
+    assert_eq!(created.generated_number.rule_id as i64, rule_id);
+    assert!(created.document_number.starts_with("Q-25"));
+
+    // ルールを更新
+    let response = client
+        .put(format!("http://{addr}/api/document-number-rules/{rule_id}"))
+        .json(&json!({ "priority": 3 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(updated["priority"], 3);
+    assert_eq!(updated["rule_name"], "品質保証部ルール");
+
+    // ルールを廃止
+    let response = client
+        .post(format!(
+            "http://{addr}/api/document-number-rules/{rule_id}/retire"
+        ))
+        .json(&json!({ "effective_until": "2025-12-31" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let retired: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(retired["effective_until"], "2025-12-31");
+
+    // 部署で検索
+    let response = client
+        .get(format!(
+            "http://{addr}/api/document-number-rules?department_code=Q"
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["total"], 1);
+
+    // 存在しないルールは404
+    let response = client
+        .get(format!("http://{addr}/api/document-number-rules/999999"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_document_number_template_placeholders_api() {
+    // Given: テストサーバーを起動
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let rule_body = |template: &str, sequence_digits: i32| {
+        json!({
+            "rule_name": "和暦ルール",
+            "template": template,
+            "sequence_digits": sequence_digits,
+            "department_code": "Q",
+            "document_type_codes": ["TEC"],
+            "effective_from": "2025-01-01",
+            "effective_until": null,
+            "priority": 1
+        })
+    };
+
+    // When/Then: 未知のプレースホルダーや連番桁数の不一致は保存時に拒否される
+    for (template, sequence_digits) in [
+        ("{部署コード}-{係コード}{連番:3桁}", 3),
+        ("{部署コード}-{連番:3桁}", 4),
+        ("{部署コード}-{年{連番:3桁}", 3),
+    ] {
+        let response = client
+            .post(format!("http://{addr}/api/document-number-rules"))
+            .json(&rule_body(template, sequence_digits))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{template}");
+    }
+
+    // When: 和暦・年度・課コード・文書種別名・波括弧を使うルールを作成
+    let response = client
+        .post(format!("http://{addr}/api/document-number-rules"))
+        .json(&rule_body(
+            "{元号略}{和暦年:2桁}{{{課コード}}}{年度}-{文書種別名}-{連番:3桁}",
+            3,
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let rule: serde_json::Value = response.json().await.unwrap();
+    let rule_id = rule["id"].as_i64().unwrap();
+
+    // 更新でも未知のプレースホルダーは拒否される
+    let response = client
+        .put(format!("http://{addr}/api/document-number-rules/{rule_id}"))
+        .json(&json!({ "template": "{年月}{連番:3桁}" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let create_document = |section_code: Option<&'static str>| {
+        let client = client.clone();
+        async move {
+            client
+                .post(format!("http://{addr}/api/documents"))
+                .json(&json!({
+                    "title": "和暦テンプレート文書",
+                    "document_type_code": "TEC",
+                    "department_code": "Q",
+                    "section_code": section_code,
+                    "created_by": 1,
+                    "created_date": "2026-02-10"
+                }))
+                .send()
+                .await
+                .unwrap()
+        }
+    };
+
+    // Then: 課コードが無い場合は400
+    let response = create_document(None).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Then: テンプレートどおりに採番される
+    let response = create_document(Some("Q1")).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: CreatedDocumentWithNumber = response.json().await.unwrap();
+    assert_eq!(created.document_number, "R08{Q1}2025-技術文書-001");
+}
+
+#[tokio::test]
+async fn test_document_number_sequence_reset_scope_api() {
+    // Given: テストサーバーを起動し、リセット単位の異なるルールを部署ごとに作成
+    let addr = spawn_app().await;
+    let client = Client::new();
+    for (department_code, template, sequence_digits, reset_scope) in [
+        ("M", "{部署コード}-{年}-{連番:3桁}", 3, "yearly"),
+        ("F", "{部署コード}-{年度}-{連番:3桁}", 3, "fiscal_yearly"),
+        ("N", "{部署コード}-{連番:4桁}", 4, "never"),
+    ] {
+        let response = client
+            .post(format!("http://{addr}/api/document-number-rules"))
+            .json(&json!({
+                "rule_name": format!("{reset_scope}ルール"),
+                "template": template,
+                "sequence_digits": sequence_digits,
+                "department_code": department_code,
+                "document_type_codes": ["TEC"],
+                "effective_from": "2024-01-01",
+                "effective_until": null,
+                "priority": 1,
+                "reset_scope": reset_scope
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let rule: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(rule["reset_scope"], reset_scope);
+    }
+
+    let create_document = |department_code: &'static str, created_date: &'static str| {
+        let client = client.clone();
+        async move {
+            let response = client
+                .post(format!("http://{addr}/api/documents"))
+                .json(&json!({
+                    "title": "リセット単位の確認",
+                    "document_type_code": "TEC",
+                    "department_code": department_code,
+                    "created_by": 1,
+                    "created_date": created_date
+                }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            let created: CreatedDocumentWithNumber = response.json().await.unwrap();
+            created.document_number
+        }
+    };
+
+    // When/Then: 暦年ごとのルールは月をまたいでも連番が続き、年が変わるとリセットされる
+    assert_eq!(create_document("M", "2025-03-10").await, "M-2025-001");
+    assert_eq!(create_document("M", "2025-11-20").await, "M-2025-002");
+    assert_eq!(create_document("M", "2026-01-05").await, "M-2026-001");
+
+    // 年度ごとのルールは3月までは同じ年度の連番が続き、4月にリセットされる
+    assert_eq!(create_document("F", "2025-04-01").await, "F-2025-001");
+    assert_eq!(create_document("F", "2026-03-31").await, "F-2025-002");
+    assert_eq!(create_document("F", "2026-04-01").await, "F-2026-001");
+
+    // リセットしないルールは年をまたいでも連番が続く
+    assert_eq!(create_document("N", "2024-12-31").await, "N-0001");
+    assert_eq!(create_document("N", "2026-06-01").await, "N-0002");
+
+    // リセット単位だけを変更できる
+    let response = client
+        .get(format!(
+            "http://{addr}/api/document-number-rules?department_code=N"
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    let rule_id = body["rules"][0]["id"].as_i64().unwrap();
+    let response = client
+        .put(format!("http://{addr}/api/document-number-rules/{rule_id}"))
+        .json(&json!({ "reset_scope": "monthly" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(updated["reset_scope"], "monthly");
+    assert_eq!(updated["template"], "{部署コード}-{連番:4桁}");
+}
+
+#[tokio::test]
+async fn test_document_number_rule_simulation_api() {
+    // Given: テストサーバーを起動（DEV部署の技術文書ルールと汎用ルールが登録済み）
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    // When: DEV部署の技術文書でルールの適用をシミュレーション
+    let response = client
+        .post(format!("http://{addr}/api/document-number-rules/simulate"))
+        .json(&json!({
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_date": "2025-08-17",
+            "count": 3
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 候補が選択される順に並び、部署固有ルールが選ばれて次の番号が示される
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let candidates: Vec<i64> = body["candidates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|rule| rule["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(candidates, vec![1, 3]);
+    assert_eq!(body["candidates"][0]["priority"], 1);
+    assert_eq!(body["selected_rule_id"], 1);
+    assert_eq!(
+        body["next_numbers"],
+        json!(["TEC-2508001", "TEC-2508002", "TEC-2508003"])
+    );
+    assert!(body["error"].is_null());
+
+    // シミュレーションでは採番されない
+    let response = client
+        .post(format!("http://{addr}/api/document-number-rules/simulate"))
+        .json(&json!({
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_date": "2025-08-17",
+            "count": 1
+        }))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["next_numbers"], json!(["TEC-2508001"]));
+
+    // 適用できるルールが無い場合は候補が空になる
+    let response = client
+        .post(format!("http://{addr}/api/document-number-rules/simulate"))
+        .json(&json!({
+            "document_type_code": "ZZZ",
+            "department_code": "DEV",
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["candidates"], json!([]));
+    assert!(body["selected_rule_id"].is_null());
+    assert!(body["error"].is_string());
+
+    // 表示件数が範囲外なら400
+    let response = client
+        .post(format!("http://{addr}/api/document-number-rules/simulate"))
+        .json(&json!({
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_date": "2025-08-17",
+            "count": 0
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_document_number_rule_conflicts_api() {
+    // Given: テストサーバーを起動し、既存ルールと同じ優先度・適用範囲のルールを追加
+    // （APIでは1文字の部署コードしか登録できないため、seedと同じくDBへ直接登録する）
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let added_id = sqlx::query(
+        "INSERT INTO document_number_generation_rules
+             (rule_name, template, sequence_digits, department_code, document_type_codes,
+              effective_from, effective_until, priority)
+         VALUES ('技術文書ルール（重複）', 'DEV-{文書種別コード}{年}{月:2桁}{連番:3桁}', 3, 'DEV',
+                 '[\"TEC\"]', '2025-04-01', '2025-12-31', 1)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    // When: ルール全体を検査
+    let response = client
+        .get(format!("http://{addr}/api/document-number-rules/conflicts"))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 同じ優先度で有効期間が重なる組が報告される
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["checked_rules"], 4);
+    let overlaps = body["priority_overlaps"].as_array().unwrap();
+    assert_eq!(overlaps.len(), 1);
+    assert_eq!(overlaps[0]["rule_id"], 1);
+    assert_eq!(overlaps[0]["other_rule_id"], added_id);
+    assert_eq!(overlaps[0]["document_type_codes"], json!(["TEC"]));
+    assert_eq!(overlaps[0]["overlap_from"], "2025-04-01");
+    assert_eq!(overlaps[0]["overlap_until"], "2025-12-31");
+
+    // 部署固有ルールと汎用ルールの同じテンプレートは衝突として報告される
+    let collisions = body["template_collisions"].as_array().unwrap();
+    let has_collision = |kind: &str, rule_id: i64, other_rule_id: i64| {
+        collisions.iter().any(|c| {
+            c["kind"] == kind && c["rule_id"] == rule_id && c["other_rule_id"] == other_rule_id
+        })
+    };
+    assert!(has_collision("shared_pattern", 1, 3));
+    assert!(has_collision("shared_pattern", 2, 3));
+    assert!(has_collision("department_counter", 3, 3));
+    let example = collisions
+        .iter()
+        .find(|c| c["rule_id"] == 1 && c["other_rule_id"] == 3)
+        .unwrap();
+    assert_eq!(example["example_number"], "TEC-0000000");
+    assert_eq!(example["periods_overlap"], true);
+
+    // 固定の接頭辞で区別されるテンプレートは衝突しない
+    assert!(!collisions.iter().any(|c| c["kind"] == "shared_pattern"
+        && (c["rule_id"] == added_id || c["other_rule_id"] == added_id)));
+}
+
+#[tokio::test]
+async fn test_decompose_document_number_api() {
+    // Given: テストサーバーを起動し、DEV部署の技術文書を1件作成
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let response = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "逆引き対象の文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: CreatedDocumentWithNumber = response.json().await.unwrap();
+    assert_eq!(created.document_number, "TEC-2508001");
+
+    // When: 登録済みの番号を逆引き
+    let response = client
+        .get(format!(
+            "http://{addr}/api/document-numbers/decompose?document_number=TEC-2508001"
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 部署固有ルールが先に並び、構成要素と連番カウンターの状態が返される
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["registered"], true);
+    let matches = body["matches"].as_array().unwrap();
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0]["rule_id"], 1);
+    assert_eq!(matches[0]["effective"], true);
+    assert_eq!(matches[0]["components"]["document_type_code"], "TEC");
+    assert_eq!(matches[0]["components"]["department_code"], "DEV");
+    assert_eq!(matches[0]["components"]["year"], 2025);
+    assert_eq!(matches[0]["components"]["month"], 8);
+    assert_eq!(matches[0]["components"]["sequence_number"], 1);
+    assert_eq!(
+        matches[0]["sequence_key"],
+        json!({ "rule_id": 1, "year": 2025, "month": 8, "department_code": "DEV" })
+    );
+    assert_eq!(matches[0]["next_sequence"], 2);
+
+    // 全部署共通ルールは部署が決まらないためカウンターを特定しない
+    assert_eq!(matches[1]["rule_id"], 3);
+    assert!(matches[1]["sequence_key"].is_null());
+    assert!(matches[1]["next_sequence"].is_null());
+
+    // 部署を指定すると全部署共通ルールのカウンターも求められる
+    let response = client
+        .get(format!(
+            "http://{addr}/api/document-numbers/decompose?document_number=BUS-2412015&department_code=HR"
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["registered"], false);
+    let generic = body["matches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["rule_id"] == 3)
+        .unwrap();
+    assert_eq!(generic["sequence_key"]["department_code"], "HR");
+    assert_eq!(generic["next_sequence"], 1);
+
+    // どのルールにも一致しない番号は一致なし
+    let response = client
+        .get(format!(
+            "http://{addr}/api/document-numbers/decompose?document_number=CTA-2508001"
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["matches"], json!([]));
+
+    // 番号の指定が無い場合は400
+    let response = client
+        .get(format!("http://{addr}/api/document-numbers/decompose"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_document_revision_api() {
+    // Given: テストサーバーと親文書
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    let response = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "版管理テスト文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: CreatedDocumentWithNumber = response.json().await.unwrap();
+    let document_id = created.document.id;
+    let base_number = created.document_number;
+
+    // When: 改訂版とドラフト版を作成
+    let mut revisions = Vec::new();
+    for revision_type in ["revision", "draft", "draft"] {
+        let response = client
+            .post(format!(
+                "http://{addr}/api/documents/{document_id}/revisions"
+            ))
+            .json(&json!({ "revision_type": revision_type, "created_by": 1 }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let revision: serde_json::Value = response.json().await.unwrap();
+        revisions.push(revision);
+    }
+
+    // Then: 種別ごとに接尾辞が採番される
+    assert_eq!(revisions[0]["number"], format!("{base_number}r1"));
+    assert_eq!(revisions[1]["number"], format!("{base_number}d1"));
+    assert_eq!(revisions[2]["number"], format!("{base_number}d2"));
+    assert_eq!(revisions[2]["status"], "draft");
+
+    // When: ドラフト版d2を発行
+    let draft_id = revisions[2]["id"].as_i64().unwrap();
+    let response = client
+        .post(format!(
+            "http://{addr}/api/documents/{document_id}/revisions/{draft_id}/promote"
+        ))
+        .json(&json!({ "promoted_by": 1, "notes": "承認済み" }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 次の改訂版r2が発行される
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let promoted: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(promoted["number"], format!("{base_number}r2"));
+    assert_eq!(promoted["status"], "issued");
+
+    // 同じドラフトの二重発行は409
+    let response = client
+        .post(format!(
+            "http://{addr}/api/documents/{document_id}/revisions/{draft_id}/promote"
+        ))
+        .json(&json!({ "promoted_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // 版履歴に発行元のドラフトとの対応が残る
+    let response = client
+        .get(format!(
+            "http://{addr}/api/documents/{document_id}/revisions"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let history = body["revisions"].as_array().unwrap();
+    assert_eq!(history.len(), 4);
+    assert_eq!(history[2]["status"], "promoted");
+    assert_eq!(history[2]["promoted_revision_id"], promoted["id"]);
+
+    // 存在しない文書は404
+    let response = client
+        .get(format!("http://{addr}/api/documents/999999/revisions"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_create_document_with_manual_number_api() {
+    // Given: テストサーバーを起動（社員1は管理者、社員2は一般ユーザー）
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let manual_request = |number: &str| {
+        json!({
+            "number": number,
+            "title": "旧システムから移行した文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17",
+            "reason": "旧システムの番号を継続使用"
+        })
+    };
+
+    // When: 管理者が番号を指定して作成
+    let response = client
+        .post(format!(
+            "http://{addr}/api/documents/manual-number?user_id=1"
+        ))
+        .json(&manual_request("LEGACY-0001"))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 指定した番号で作成され、監査記録が残る
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(created["document"]["number"], "LEGACY-0001");
+    assert_eq!(created["number_override"]["assigned_by"], 1);
+    assert_eq!(
+        created["number_override"]["reason"],
+        "旧システムの番号を継続使用"
+    );
+
+    // 一般ユーザーは403
+    let response = client
+        .post(format!(
+            "http://{addr}/api/documents/manual-number?user_id=2"
+        ))
+        .json(&manual_request("LEGACY-0002"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // 操作者不明は本文に割当者を書いても403
+    let mut request = manual_request("LEGACY-0002");
+    request["assigned_by"] = json!(1);
+    let response = client
+        .post(format!("http://{addr}/api/documents/manual-number"))
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // 既存番号との重複は409
+    let response = client
+        .post(format!(
+            "http://{addr}/api/documents/manual-number?user_id=1"
+        ))
+        .json(&manual_request("LEGACY-0001"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // ルールが今後採番する番号との衝突は409
+    let response = client
+        .post(format!(
+            "http://{addr}/api/documents/manual-number?user_id=1"
+        ))
+        .json(&manual_request("TEC-2508500"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("future output"));
+}
+
+#[tokio::test]
+async fn test_network_path_generation_api() {
+    // Given: テストサーバーを起動（開発部専用ルールと全部署共通ルールが登録済み）
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    // When: 開発部と営業部で文書を作成
+    let mut created = Vec::new();
+    for (document_type_code, department_code) in [("TEC", "DEV"), ("BUS", "SALES")] {
+        let response = client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": "パス生成テスト文書",
+                "document_type_code": document_type_code,
+                "department_code": department_code,
+                "created_by": 1,
+                "created_date": "2025-08-17"
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let document: CreatedDocumentWithNumber = response.json().await.unwrap();
+        created.push(document);
+    }
+
+    // Then: 部署ごとのルールでパスが生成され、文書番号のフォルダが付く
+    assert_eq!(
+        created[0].document.network_path.as_deref(),
+        Some(
+            format!(
+                r"\\server01\dev\2025\技術文書\{}",
+                created[0].document_number
+            )
+            .as_str()
+        )
+    );
+    assert_eq!(
+        created[1].document.network_path.as_deref(),
+        Some(
+            format!(
+                r"\\server01\docs\2025\営業部\業務文書\{}",
+                created[1].document_number
+            )
+            .as_str()
+        )
+    );
+
+    // 登録済みの文書番号からも同じパスが解決される
+    let response = client
+        .get(format!(
+            "http://{addr}/api/network-paths/{}?user_id=1",
+            created[1].document_number
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let resolved: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        resolved["network_path"].as_str(),
+        created[1].document.network_path.as_deref()
+    );
+    assert_eq!(resolved["rule_name"], "標準パス");
+
+    // 存在しない文書番号は404
+    let response = client
+        .get(format!(
+            "http://{addr}/api/network-paths/NOPE-0001?user_id=1"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_path_generation_rule_api() {
+    // Given: テストサーバーを起動
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    // When: 未知のプレースホルダーを含むルールを登録
+    let response = client
+        .post(format!("http://{addr}/api/path-generation-rules"))
+        .json(&json!({
+            "rule_name": "不正なルール",
+            "path_template": r"\\server01\docs\{年度}",
+            "department_code": "SALES",
+            "year_from": null,
+            "year_to": null,
+            "effective_from": "2024-01-01",
+            "effective_until": null,
+            "priority": 1
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 400エラー
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // When: 2026年以降の営業部向けルールを登録
+    let response = client
+        .post(format!("http://{addr}/api/path-generation-rules"))
+        .json(&json!({
+            "rule_name": "営業部2026",
+            "path_template": r"\\server02\sales\{年}\{月}\{文書番号}",
+            "department_code": "SALES",
+            "year_from": 2026,
+            "year_to": null,
+            "effective_from": "2024-01-01",
+            "effective_until": null,
+            "priority": 1
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let rule: serde_json::Value = response.json().await.unwrap();
+    let rule_id = rule["id"].as_i64().unwrap();
+
+    // Then: 対象年の文書にだけ適用される
+    for (created_date, expected_prefix) in [
+        ("2025-12-01", r"\\server01\docs\2025\営業部\業務文書\"),
+        ("2026-01-15", r"\\server02\sales\2026\01\"),
+    ] {
+        let response = client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": "年条件テスト文書",
+                "document_type_code": "BUS",
+                "department_code": "SALES",
+                "created_by": 2,
+                "created_date": created_date
+            }))
+            .send()
+            .await
+            .unwrap();
+        let document: CreatedDocumentWithNumber = response.json().await.unwrap();
+        assert_eq!(
+            document.document.network_path,
+            Some(format!("{expected_prefix}{}", document.document_number))
+        );
+    }
+
+    // 廃止すると期間外の文書には適用されない
+    let response = client
+        .post(format!(
+            "http://{addr}/api/path-generation-rules/{rule_id}/retire"
+        ))
+        .json(&json!({ "effective_until": "2026-01-31" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = client
+        .get(format!(
+            "http://{addr}/api/path-generation-rules?department_code=SALES&active_on=2026-02-01"
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["total"], 0);
+}
+
+#[tokio::test]
+async fn test_path_rule_versioning_api() {
+    // Given: 開発部の技術文書を作成（開発部標準パスの初版で生成）
+    let addr = spawn_app().await;
+    let client = Client::new();
+
+    let response = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "版管理テスト文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap();
+    let document: CreatedDocumentWithNumber = response.json().await.unwrap();
+    let old_path = format!(r"\\server01\dev\2025\技術文書\{}", document.document_number);
+    assert_eq!(
+        document.document.network_path.as_deref(),
+        Some(old_path.as_str())
+    );
+
+    // When: 2025-10-01からサーバーを移行するようにルールを変更
+    let response = client
+        .put(format!("http://{addr}/api/path-generation-rules/1"))
+        .json(&json!({
+            "path_template": r"\\nas01\dev\{年}\{文書種別コード}",
+            "apply_from": "2025-10-01",
+            "changed_by": 1,
+            "change_reason": "サーバー移行"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let new_path = format!(r"\\nas01\dev\2025\TEC\{}", document.document_number);
+
+    // Then: 作成日時点のルールでは従来のパスが再現される
+    let response = client
+        .get(format!(
+            "http://{addr}/api/network-paths/{}?user_id=1",
+            document.document_number
+        ))
+        .send()
+        .await
+        .unwrap();
+    let resolved: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(resolved["network_path"].as_str(), Some(old_path.as_str()));
+    assert_eq!(resolved["rule_version"], 1);
+    assert_eq!(resolved["as_of"], "2025-08-17");
+
+    // 変更後の時点を指定すると新しいルールで解決される
+    let response = client
+        .get(format!(
+            "http://{addr}/api/network-paths/{}?as_of=2025-10-01&user_id=1",
+            document.document_number
+        ))
+        .send()
+        .await
+        .unwrap();
+    let resolved: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(resolved["network_path"].as_str(), Some(new_path.as_str()));
+    assert_eq!(resolved["rule_version"], 2);
+
+    // 不正な日付は400
+    let response = client
+        .get(format!(
+            "http://{addr}/api/network-paths/{}?as_of=2025-13-01&user_id=1",
+            document.document_number
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // 変更履歴に変更者・理由と効力期間が残る
+    let response = client
+        .get(format!(
+            "http://{addr}/api/path-generation-rules/1/versions"
+        ))
+        .send()
+        .await
+        .unwrap();
+    let versions: serde_json::Value = response.json().await.unwrap();
+    let versions = versions.as_array().unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["valid_until"], "2025-10-01");
+    assert_eq!(versions[1]["change_type"], "updated");
+    assert_eq!(versions[1]["changed_by"], 1);
+    assert_eq!(versions[1]["change_reason"], "サーバー移行");
+    assert_eq!(versions[1]["valid_from"], "2025-10-01");
+
+    // 一括再解決: 作成日時点では一致し、変更後の時点では差分として報告される
+    let response = client
+        .post(format!("http://{addr}/api/network-paths/verify"))
+        .json(&json!({ "department_code": "DEV" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert!(report["checked_documents"].as_i64().unwrap() >= 1);
+    assert!(
+        !report["mismatches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|m| m["document_number"] == document.document_number.as_str())
+    );
+
+    let response = client
+        .post(format!("http://{addr}/api/network-paths/verify"))
+        .json(&json!({ "department_code": "DEV", "as_of": "2025-10-01" }))
+        .send()
+        .await
+        .unwrap();
+    let report: serde_json::Value = response.json().await.unwrap();
+    let mismatch = report["mismatches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["document_number"] == document.document_number.as_str())
+        .expect("mismatch for updated rule");
+    assert_eq!(mismatch["stored_path"].as_str(), Some(old_path.as_str()));
+    assert_eq!(mismatch["resolved_path"].as_str(), Some(new_path.as_str()));
+    assert_eq!(mismatch["rule_version"], 2);
+}
+
+#[tokio::test]
+async fn test_document_clearance_api() {
+    // Given: 機密区分の異なる文書を4件用意（一般・社内・情報クラスⅠ・社内かつ個人情報有）
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+
+    let mut ids = Vec::new();
+    let mut numbers = Vec::new();
+    for _ in 0..4 {
+        let response = client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": "機密レベルテスト文書",
+                "document_type_code": "TEC",
+                "department_code": "DEV",
+                "created_by": 1,
+                "created_date": "2025-08-17"
+            }))
+            .send()
+            .await
+            .unwrap();
+        let document: CreatedDocumentWithNumber = response.json().await.unwrap();
+        ids.push(document.document.id);
+        numbers.push(document.document_number);
+    }
+    for (id, internal_external, importance_class, personal_info) in [
+        (ids[1], Some("internal"), None, None),
+        (ids[2], Some("internal"), Some("class1"), None),
+        (ids[3], Some("internal"), None, Some("present")),
+    ] {
+        sqlx::query("UPDATE documents SET internal_external = ?, importance_class = ?, personal_info = ? WHERE id = ?")
+            .bind(internal_external)
+            .bind(importance_class)
+            .bind(personal_info)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let search_total = |user_id: Option<i32>| {
+        let client = client.clone();
+        async move {
+            let mut url =
+                format!("http://{addr}/api/documents?title=機密レベルテスト文書&limit=10");
+            if let Some(user_id) = user_id {
+                url.push_str(&format!("&user_id={user_id}"));
+            }
+            let body: serde_json::Value =
+                client.get(url).send().await.unwrap().json().await.unwrap();
+            body["total"].as_i64().unwrap()
+        }
+    };
+
+    // Then: 閲覧者不明は一般文書のみ、一般社員（社内）は機密文書以外、管理者は全件を検索できる
+    assert_eq!(search_total(None).await, 1);
+    assert_eq!(search_total(Some(2)).await, 3);
+    assert_eq!(search_total(Some(1)).await, 4);
+
+    // 閲覧できない文書の詳細は404
+    let response = client
+        .get(format!("http://{addr}/api/documents/{}", ids[1]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = client
+        .get(format!("http://{addr}/api/documents/{}?user_id=2", ids[2]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // 個人情報を含む文書は一般社員には保存場所が伏せられる
+    let response = client
+        .get(format!("http://{addr}/api/documents/{}?user_id=2", ids[3]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let document: serde_json::Value = response.json().await.unwrap();
+    assert!(document["network_path"].is_null());
+
+    let response = client
+        .get(format!("http://{addr}/api/documents/{}?user_id=1", ids[3]))
+        .send()
+        .await
+        .unwrap();
+    let document: serde_json::Value = response.json().await.unwrap();
+    assert!(document["network_path"].as_str().is_some());
+
+    // 文書番号からのネットワークパス解決も閲覧者の機密レベルで制限される
+    let resolve_status = |number: String, user_id: Option<i32>| {
+        let client = client.clone();
+        async move {
+            let mut url = format!("http://{addr}/api/network-paths/{number}");
+            if let Some(user_id) = user_id {
+                url.push_str(&format!("?user_id={user_id}"));
+            }
+            client.get(url).send().await.unwrap().status()
+        }
+    };
+    assert_eq!(
+        resolve_status(numbers[0].clone(), None).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        resolve_status(numbers[2].clone(), Some(2)).await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        resolve_status(numbers[3].clone(), Some(2)).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        resolve_status(numbers[1].clone(), Some(2)).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        resolve_status(numbers[3].clone(), Some(1)).await,
+        StatusCode::OK
+    );
+
+    // When: 管理者以外・操作者不明では機密レベルを変更できない（本文の値は操作者として扱わない）
+    for url in [
+        format!("http://{addr}/api/employees/2/clearance?user_id=2"),
+        format!("http://{addr}/api/employees/2/clearance"),
+    ] {
+        let response = client
+            .put(&url)
+            .json(&json!({ "clearance_level": "public", "updated_by": 1 }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    // When: 管理者が社員個別に一般レベルへ下げる
+    let response = client
+        .put(format!("http://{addr}/api/employees/2/clearance?user_id=1"))
+        .json(&json!({ "clearance_level": "public" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let clearance: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(clearance["role_clearance_level"], "internal");
+    assert_eq!(clearance["effective_clearance_level"], "public");
+
+    // Then: 社内文書も見えなくなる
+    assert_eq!(search_total(Some(2)).await, 1);
+
+    // When: 一般社員ロールの既定値を機密に引き上げる
+    let response = client
+        .put(format!("http://{addr}/api/roles/user/clearance?user_id=1"))
+        .json(&json!({ "clearance_level": "confidential" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Then: 個別設定のない社員には反映され、個別設定のある社員には反映されない
+    let response = client
+        .get(format!("http://{addr}/api/employees/3/clearance"))
+        .send()
+        .await
+        .unwrap();
+    let clearance: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(clearance["effective_clearance_level"], "confidential");
+    assert_eq!(search_total(Some(3)).await, 4);
+    assert_eq!(search_total(Some(2)).await, 1);
+}
+
+#[tokio::test]
+async fn test_document_full_text_search_api() {
+    // Given: タイトルの異なる文書を2件用意し、片方の備考に同じ語を記入
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+
+    let mut ids = Vec::new();
+    for title in ["全文検索テスト 設計仕様書", "全文検索テスト 議事録"] {
+        let response = client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": title,
+                "document_type_code": "TEC",
+                "department_code": "DEV",
+                "created_by": 1,
+                "created_date": "2025-08-17"
+            }))
+            .send()
+            .await
+            .unwrap();
+        let document: CreatedDocumentWithNumber = response.json().await.unwrap();
+        ids.push(document.document.id);
+    }
+    sqlx::query("UPDATE documents SET notes = '設計仕様の差分を記録した' WHERE id = ?")
+        .bind(ids[1])
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let search = |query: &'static str| {
+        let client = client.clone();
+        async move {
+            client
+                .get(format!("http://{addr}/api/documents"))
+                .query(&[("q", query), ("user_id", "1")])
+                .send()
+                .await
+                .unwrap()
+                .json::<serde_json::Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    // When: 3文字以上の語で検索
+    let body = search("設計仕様").await;
+
+    // Then: タイトル一致が備考一致より上位に並び、一致箇所がハイライトされる
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["documents"][0]["id"], ids[0]);
+    assert_eq!(body["hits"][0]["document_id"], ids[0]);
+    assert_eq!(body["hits"][1]["document_id"], ids[1]);
+    assert!(body["hits"][0]["rank"].as_f64().is_some());
+    assert!(
+        body["hits"][1]["snippet"]
+            .as_str()
+            .unwrap()
+            .contains("<mark>設計仕様</mark>")
+    );
+
+    // 作成者名と組み合わせた検索（全ての語を含む文書のみ）
+    let body = search("山田太郎 議事録").await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["hits"][0]["document_id"], ids[1]);
+
+    // 作成者名の変更は索引に反映される
+    sqlx::query("UPDATE employees SET name = '山田次郎' WHERE id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(search("山田次郎 議事録").await["total"], 1);
+    assert_eq!(search("山田太郎 議事録").await["total"], 0);
+
+    // 2文字以下の語は部分一致で探し、関連度は付かない
+    let body = search("議事").await;
+    assert_eq!(body["total"], 1);
+    assert!(body["hits"][0]["rank"].is_null());
+
+    // When: 片方を社内文書にして閲覧者不明で検索
+    sqlx::query("UPDATE documents SET internal_external = 'internal' WHERE id = ?")
+        .bind(ids[0])
+        .execute(&pool)
+        .await
+        .unwrap();
+    let body: serde_json::Value = client
+        .get(format!("http://{addr}/api/documents?q=設計仕様"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Then: 機密レベルが足りない文書は検索結果に含まれない
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["hits"][0]["document_id"], ids[1]);
+}
+
+#[tokio::test]
+async fn test_document_search_facets_api() {
+    // Given: 文書種別・作成年・分類・業務番号の異なる文書を3件用意
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+
+    let mut ids = Vec::new();
+    for (document_type_code, created_date, importance_class) in [
+        ("TEC", "2024-05-01", "class1"),
+        ("TEC", "2025-08-17", "class2"),
+        ("BUS", "2025-09-01", "class2"),
+    ] {
+        let response = client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": "集計テスト文書",
+                "document_type_code": document_type_code,
+                "department_code": "DEV",
+                "created_by": 1,
+                "created_date": created_date,
+                "internal_external": "external",
+                "importance_class": importance_class
+            }))
+            .send()
+            .await
+            .unwrap();
+        let document: CreatedDocumentWithNumber = response.json().await.unwrap();
+        ids.push(document.document.id);
+    }
+    sqlx::query("UPDATE documents SET business_number = 'B-1001' WHERE id IN (?, ?)")
+        .bind(ids[0])
+        .bind(ids[1])
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let search = |query: &'static str| {
+        let client = client.clone();
+        async move {
+            client
+                .get(format!(
+                    "http://{addr}/api/documents?title=集計テスト文書&user_id=1{query}"
+                ))
+                .send()
+                .await
+                .unwrap()
+                .json::<serde_json::Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    // When: 絞り込みなしで検索
+    let body = search("").await;
+
+    // Then: 各軸の件数が返される
+    let aggregations = &body["aggregations"];
+    assert_eq!(body["total"], 3);
+    assert_eq!(
+        aggregations["document_type_counts"][0]["document_type_code"],
+        "TEC"
+    );
+    assert_eq!(aggregations["document_type_counts"][0]["count"], 2);
+    assert_eq!(
+        aggregations["department_counts"][0]["department_code"],
+        "DEV"
+    );
+    assert_eq!(aggregations["department_counts"][0]["count"], 3);
+    assert_eq!(aggregations["year_counts"][0]["year"], 2025);
+    assert_eq!(aggregations["year_counts"][0]["count"], 2);
+    assert_eq!(
+        aggregations["importance_class_counts"][0]["importance_class"],
+        "class2"
+    );
+    assert_eq!(aggregations["internal_external_counts"][0]["count"], 3);
+    assert_eq!(
+        aggregations["business_number_counts"],
+        json!([{ "business_number": "B-1001", "count": 2 }])
+    );
+
+    // When: 同じ軸で絞り込む
+    let body = search("&year=2025&importance_class=class2&business_number=B-1001").await;
+
+    // Then: 検索結果と集計の両方に条件が反映される
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["documents"][0]["id"], ids[1]);
+    assert_eq!(body["aggregations"]["document_type_counts"][0]["count"], 1);
+
+    // 表示名での指定、部署・社内外区分での絞り込み
+    let body =
+        search("&importance_class=情報クラスⅠ&internal_external=社外&department_code=DEV").await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["documents"][0]["id"], ids[0]);
+
+    // 機密レベルが足りない文書は集計にも含まれない
+    let body: serde_json::Value = client
+        .get(format!("http://{addr}/api/documents?title=集計テスト文書"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["aggregations"]["year_counts"][0]["count"], 2);
+    assert_eq!(
+        body["aggregations"]["year_counts"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // 解釈できない分類は400
+    let response = client
+        .get(format!(
+            "http://{addr}/api/documents?importance_class=unknown"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_document_classification_api() {
+    // Given: テストサーバーを起動
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+
+    // When: 表示名で分類を指定して文書を作成
+    let response = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "分類テスト文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17",
+            "internal_external": "社内",
+            "importance_class": "情報クラスⅠ",
+            "personal_info": "有"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 保存値に正規化されて返される
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: serde_json::Value = response.json().await.unwrap();
+    let document = &created["document"];
+    assert_eq!(document["internal_external"], "internal");
+    assert_eq!(document["importance_class"], "class1");
+    assert_eq!(document["personal_info"], "present");
+
+    // 未知の値は受け付けない
+    let response = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "分類テスト文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17",
+            "importance_class": "極秘"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_client_error());
+
+    // データベースへの直接書き込みでも未知の値は拒否される
+    let result = sqlx::query("UPDATE documents SET internal_external = '社内' WHERE id = ?")
+        .bind(document["id"].as_i64().unwrap())
+        .execute(&pool)
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_business_number_allocation_api() {
+    // Given: テストサーバーを起動し、業務番号を指定しない業務を同時に作成
+    // （業務の登録APIは未公開のため、リポジトリから作成する）
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let repository = Arc::new(SqliteBusinessRepository::new(pool.clone()));
+    let mut handles = Vec::new();
+    for i in 0..5 {
+        let repository = repository.clone();
+        handles.push(tokio::spawn(async move {
+            repository
+                .create(CreateBusinessRequest {
+                    business_number: None,
+                    name: format!("採番テスト業務{i}"),
+                    description: None,
+                    customer_name: None,
+                    start_date: None,
+                    end_date: None,
+                    status: None,
+                    created_by: 1,
+                })
+                .await
+        }));
+    }
+
+    // Then: 番号の重複で失敗せず、採番範囲の下限から重複なく割り当てられる
+    let mut numbers = Vec::new();
+    for handle in handles {
+        let business = handle.await.unwrap().expect("Failed to create business");
+        numbers.push(business.business_number);
+    }
+    numbers.sort_unstable();
+    assert_eq!(numbers, vec!["10000", "10001", "10002", "10003", "10004"]);
+
+    // When: 次の番号と空き状況を確認
+    let response = client
+        .post(format!("http://{addr}/api/businesses/generate-number"))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 次の番号と残りの空き件数が返される
+    assert_eq!(response.status(), StatusCode::OK);
+    let allocation: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(allocation["business_number"], "10005");
+    assert_eq!(allocation["wrapped"], false);
+    assert_eq!(allocation["remaining_capacity"], 89985);
+
+    // 使用状況を取得できる
+    let held = repository.get_by_business_number("10000").await.unwrap();
+    let response = client
+        .get(format!("http://{addr}/api/businesses/numbers/10000/usage"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let usage: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(usage["use_count"], 1);
+    assert_eq!(usage["active_business_id"], held.unwrap().id);
+
+    let response = client
+        .get(format!("http://{addr}/api/businesses/numbers/55555/usage"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_document_number_reservation_api() {
+    // Given: テストサーバーを起動
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let create_document = |title: &str| {
+        client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": title,
+                "document_type_code": "TEC",
+                "department_code": "DEV",
+                "created_by": 1,
+                "created_date": "2025-08-17"
+            }))
+            .send()
+    };
+
+    // When: 期限付きで3件の番号を予約
+    let response = client
+        .post(format!("http://{addr}/api/document-number-reservations"))
+        .json(&json!({
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_date": "2025-08-17",
+            "count": 3,
+            "expires_on": "2099-12-31",
+            "reserved_by": 1,
+            "purpose": "現地調査"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 連番カウンターから番号が払い出され、通常の採番はその次から行われる
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let reservation: serde_json::Value = response.json().await.unwrap();
+    let reservation_id = reservation["id"].as_i64().unwrap();
+    assert_eq!(reservation["rule_id"], 1);
+    assert_eq!(reservation["expiry_action"], "release");
+    let numbers: Vec<&str> = reservation["numbers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["document_number"].as_str().unwrap())
+        .collect();
+    assert_eq!(numbers, vec!["TEC-2508001", "TEC-2508002", "TEC-2508003"]);
+    assert_eq!(reservation["numbers"][0]["status"], "reserved");
+
+    let created: CreatedDocumentWithNumber = create_document("通常文書")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(created.document_number, "TEC-2508004");
+
+    // When: 予約番号で文書を登録
+    let claim = json!({
+        "number": "TEC-2508002",
+        "title": "現地調査報告",
+        "document_type_code": "TEC",
+        "department_code": "DEV",
+        "created_by": 1,
+        "created_date": "2025-08-20"
+    });
+    let response = client
+        .post(format!(
+            "http://{addr}/api/document-number-reservations/{reservation_id}/claim"
+        ))
+        .json(&claim)
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 予約した番号で登録され、同じ番号は再度使えない
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let claimed: CreatedDocumentWithNumber = response.json().await.unwrap();
+    assert_eq!(claimed.document_number, "TEC-2508002");
+    assert_eq!(claimed.generated_number.sequence_number, 2);
+
+    let response = client
+        .post(format!(
+            "http://{addr}/api/document-number-reservations/{reservation_id}/claim"
+        ))
+        .json(&claim)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // 予約と異なる文書種別では登録できない
+    let mut other_type = claim.clone();
+    other_type["number"] = json!("TEC-2508001");
+    other_type["document_type_code"] = json!("BUS");
+    let response = client
+        .post(format!(
+            "http://{addr}/api/document-number-reservations/{reservation_id}/claim"
+        ))
+        .json(&other_type)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // 期限切れ時に欠番とする予約を追加
+    let response = client
+        .post(format!("http://{addr}/api/document-number-reservations"))
+        .json(&json!({
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_date": "2025-08-17",
+            "count": 2,
+            "expires_on": "2099-12-31",
+            "reserved_by": 1,
+            "expiry_action": "void"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = client
+        .get(format!(
+            "http://{addr}/api/document-number-reservations?department_code=DEV"
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["total"], 2);
+
+    // When: 期限切れ処理を実行
+    let response = client
+        .post(format!(
+            "http://{addr}/api/document-number-reservations/expire"
+        ))
+        .json(&json!({ "as_of": "2100-01-01" }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 未使用の番号が予約ごとの指定に従って解放・欠番になる
+    assert_eq!(response.status(), StatusCode::OK);
+    let result: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(result["expired_reservations"], 2);
+    assert_eq!(result["released_numbers"], 2);
+    assert_eq!(result["voided_numbers"], 2);
+
+    // 解放された番号は通常の採番で再利用され、欠番は使われない
+    let mut reissued = Vec::new();
+    for title in ["再利用1", "再利用2", "新規"] {
+        let created: CreatedDocumentWithNumber =
+            create_document(title).await.unwrap().json().await.unwrap();
+        reissued.push(created.document_number);
+    }
+    assert_eq!(reissued, vec!["TEC-2508001", "TEC-2508003", "TEC-2508007"]);
+
+    let response = client
+        .get(format!(
+            "http://{addr}/api/document-number-reservations/{reservation_id}"
+        ))
+        .send()
+        .await
+        .unwrap();
+    let reservation: serde_json::Value = response.json().await.unwrap();
+    let statuses: Vec<&str> = reservation["numbers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, vec!["reissued", "claimed", "reissued"]);
+    assert!(!reservation["expired_at"].is_null());
+
+    // 期限切れの予約は既定では一覧に含まれない
+    let response = client
+        .get(format!("http://{addr}/api/document-number-reservations"))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["total"], 0);
+
+    // 予約数が範囲外の場合はエラー
+    let response = client
+        .post(format!("http://{addr}/api/document-number-reservations"))
+        .json(&json!({
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_date": "2025-08-17",
+            "count": 0,
+            "expires_on": "2099-12-31",
+            "reserved_by": 1
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_document_creation_expires_reservations_of_its_counter() {
+    // Given: 8月分と9月分の番号を1件ずつ予約し、どちらも期限切れにしてある
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let mut reservation_ids = Vec::new();
+    for created_date in ["2025-08-17", "2025-09-17"] {
+        let reservation: serde_json::Value = client
+            .post(format!("http://{addr}/api/document-number-reservations"))
+            .json(&json!({
+                "document_type_code": "TEC",
+                "department_code": "DEV",
+                "created_date": created_date,
+                "count": 1,
+                "expires_on": "2099-12-31",
+                "reserved_by": 1
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        reservation_ids.push(reservation["id"].as_i64().unwrap());
+    }
+    sqlx::query("UPDATE document_number_reservations SET expires_on = '2000-01-01'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let expired = |id: i64| {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT expired_at IS NOT NULL FROM document_number_reservations WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+    };
+
+    // When/Then: 予約の取得・一覧は期限切れ処理を行わず、期限を過ぎた予約を一覧から除く
+    let response = client
+        .get(format!(
+            "http://{addr}/api/document-number-reservations/{}",
+            reservation_ids[0]
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = client
+        .get(format!("http://{addr}/api/document-number-reservations"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["total"], 0);
+    assert!(!expired(reservation_ids[0]).await.unwrap());
+
+    // 期限を過ぎた予約番号では登録できない
+    let response = client
+        .post(format!(
+            "http://{addr}/api/document-number-reservations/{}/claim",
+            reservation_ids[1]
+        ))
+        .json(&json!({
+            "number": "TEC-2509001",
+            "title": "期限切れ予約の登録",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-09-17"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // When: 期限切れ処理を実行せずに8月分の文書を作成
+    let created: CreatedDocumentWithNumber = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "期限切れ予約の再利用",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Then: 同じ連番カウンターの予約だけが期限切れになり、解放された番号が使われる
+    assert_eq!(created.document_number, "TEC-2508001");
+    assert!(expired(reservation_ids[0]).await.unwrap());
+    assert!(!expired(reservation_ids[1]).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_document_number_void_and_gap_report_api() {
+    // Given: 連番1・2の文書、3・4の予約、5の文書、読み飛ばされた6、7の文書がある
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let create_document = |title: &str| {
+        client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": title,
+                "document_type_code": "TEC",
+                "department_code": "DEV",
+                "created_by": 1,
+                "created_date": "2025-08-17"
+            }))
+            .send()
+    };
+
+    let withdrawn: CreatedDocumentWithNumber = create_document("取り下げる文書")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    create_document("文書2").await.unwrap();
+    let response = client
+        .post(format!("http://{addr}/api/document-number-reservations"))
+        .json(&json!({
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_date": "2025-08-17",
+            "count": 2,
+            "expires_on": "2099-12-31",
+            "reserved_by": 1
+        }))
+        .send()
+        .await
+        .unwrap();
+    let reservation: serde_json::Value = response.json().await.unwrap();
+    create_document("文書5").await.unwrap();
+    sqlx::query("UPDATE document_number_sequences SET last_sequence = last_sequence + 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let latest: CreatedDocumentWithNumber = create_document("文書7")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(withdrawn.document_number, "TEC-2508001");
+    assert_eq!(latest.document_number, "TEC-2508007");
+
+    // When: 登録済みの文書の番号を欠番にする
+    let void = |document_number: &str| {
+        client
+            .post(format!("http://{addr}/api/document-numbers/void"))
+            .json(&json!({
+                "document_number": document_number,
+                "reason": "誤登録のため取り下げ",
+                "voided_by": 1
+            }))
+            .send()
+    };
+    let response = void("TEC-2508001").await.unwrap();
+
+    // Then: 理由・実行者・連番カウンターとともに記録され、文書は無効になる
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let voided: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(voided["document_id"], withdrawn.document.id);
+    assert_eq!(voided["rule_id"], 1);
+    assert_eq!(voided["sequence_number"], 1);
+    assert_eq!(voided["reason"], "誤登録のため取り下げ");
+    assert_eq!(voided["voided_by"], 1);
+
+    let document: serde_json::Value = client
+        .get(format!(
+            "http://{addr}/api/documents/{}",
+            withdrawn.document.id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(document["is_active"], false);
+
+    // 同じ番号は二重に欠番にできず、採番されていない番号は欠番にできない
+    assert_eq!(
+        void("TEC-2508001").await.unwrap().status(),
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        void("TEC-2508099").await.unwrap().status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // 予約中の番号を欠番にすると予約からも外れる
+    assert_eq!(
+        void("TEC-2508004").await.unwrap().status(),
+        StatusCode::CREATED
+    );
+    let reservation: serde_json::Value = client
+        .get(format!(
+            "http://{addr}/api/document-number-reservations/{}",
+            reservation["id"]
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(reservation["numbers"][1]["status"], "voided");
+
+    // When: 欠番レポートを取得
+    let response = client
+        .get(format!(
+            "http://{addr}/api/document-numbers/gaps?rule_id=1&department_code=DEV"
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 取り下げ・予約・原因不明の欠けが連番順に分類される
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(report["total_gaps"], 4);
+    assert_eq!(report["voided_gaps"], 2);
+    assert_eq!(report["reserved_gaps"], 1);
+    assert_eq!(report["unexplained_gaps"], 1);
+
+    let sequence = &report["sequences"][0];
+    assert_eq!(sequence["year"], 2025);
+    assert_eq!(sequence["month"], 8);
+    assert_eq!(sequence["last_sequence"], 7);
+    assert_eq!(sequence["issued_count"], 3);
+    let gaps: Vec<(i64, &str)> = sequence["gaps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|gap| {
+            (
+                gap["sequence_number"].as_i64().unwrap(),
+                gap["kind"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        gaps,
+        vec![
+            (1, "voided"),
+            (3, "reserved"),
+            (4, "voided"),
+            (6, "unexplained")
+        ]
+    );
+    assert_eq!(sequence["gaps"][0]["reason"], "誤登録のため取り下げ");
+    assert_eq!(sequence["gaps"][1]["reservation_id"], reservation["id"]);
+
+    // 欠番にした番号は採番で再利用されない
+    let next: CreatedDocumentWithNumber = create_document("文書8")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(next.document_number, "TEC-2508008");
+}
+
+#[tokio::test]
+async fn test_document_number_alias_api() {
+    // Given: 番号を付け替えた文書と、別の文書がある
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let create_document = |title: &str| {
+        client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": title,
+                "document_type_code": "TEC",
+                "department_code": "DEV",
+                "created_by": 1,
+                "created_date": "2025-08-17"
+            }))
+            .send()
+    };
+    let renumbered: CreatedDocumentWithNumber = create_document("付け替えた文書")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let other: CreatedDocumentWithNumber = create_document("別の文書")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let add_alias = |document_id: i32, alias_number: &str| {
+        client
+            .post(format!("http://{addr}/api/documents/{document_id}/aliases"))
+            .json(&json!({
+                "alias_number": alias_number,
+                "reason": "組織変更による付け替え",
+                "created_by": 1
+            }))
+            .send()
+    };
+
+    // When: 旧番号を登録
+    let response = add_alias(renumbered.document.id, "DEV-T-0042")
+        .await
+        .unwrap();
+
+    // Then: 登録され、文書の旧番号一覧に含まれる
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let alias: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(alias["document_id"], renumbered.document.id);
+    assert_eq!(alias["alias_number"], "DEV-T-0042");
+
+    let aliases: serde_json::Value = client
+        .get(format!(
+            "http://{addr}/api/documents/{}/aliases",
+            renumbered.document.id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(aliases["aliases"].as_array().unwrap().len(), 1);
+
+    // When: 旧番号・現在の番号で文書を取得
+    let get_document = |key: String| {
+        client
+            .get(format!("http://{addr}/api/documents/{key}"))
+            .send()
+    };
+    let by_alias: serde_json::Value = get_document("DEV-T-0042".to_string())
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let by_number: serde_json::Value = get_document(other.document_number.clone())
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Then: 現在の文書が返される
+    assert_eq!(by_alias["id"], renumbered.document.id);
+    assert_eq!(by_alias["number"], renumbered.document_number.as_str());
+    assert_eq!(by_number["id"], other.document.id);
+    assert_eq!(
+        get_document("DEV-T-9999".to_string())
+            .await
+            .unwrap()
+            .status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // 旧番号・現在の文書番号と重複する番号は登録できない
+    assert_eq!(
+        add_alias(other.document.id, "DEV-T-0042")
+            .await
+            .unwrap()
+            .status(),
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        add_alias(renumbered.document.id, &other.document_number)
+            .await
+            .unwrap()
+            .status(),
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        add_alias(9999, "DEV-T-0043").await.unwrap().status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // 旧番号は登録済みの番号として扱われる
+    let decomposition: serde_json::Value = client
+        .get(format!(
+            "http://{addr}/api/document-numbers/decompose?document_number=DEV-T-0042"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(decomposition["registered"], true);
+}
+
+#[tokio::test]
+async fn test_document_update_delete_and_restore_api() {
+    // Given: 備考付きの文書がある
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let created: CreatedDocumentWithNumber = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "更新前の文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let id = created.document.id;
+    let document_url = format!("http://{addr}/api/documents/{id}");
+    client
+        .patch(&document_url)
+        .header("If-Match", "\"1\"")
+        .json(&json!({ "notes": "初回の備考", "updated_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+
+    // When: タイトルと業務番号を変更し、備考をnullで消去
+    let response = client
+        .patch(&document_url)
+        .header("If-Match", "\"2\"")
+        .json(&json!({
+            "title": "更新後の文書",
+            "business_number": "B-001",
+            "notes": null,
+            "updated_by": 1,
+            "reason": "記載誤りの訂正"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 指定した項目だけが変わり、項目ごとに変更履歴が残る
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(updated["title"], "更新後の文書");
+    assert_eq!(updated["business_number"], "B-001");
+    assert!(updated["notes"].is_null());
+    assert_eq!(updated["number"], created.document_number.as_str());
+
+    let history: serde_json::Value = client
+        .get(format!("{document_url}/changes"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let changes = history["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 4);
+    assert!(changes.iter().any(|c| c["field_name"] == "title"
+        && c["old_value"] == "更新前の文書"
+        && c["new_value"] == "更新後の文書"
+        && c["reason"] == "記載誤りの訂正"));
+    assert!(changes.iter().any(|c| c["field_name"] == "notes"
+        && c["old_value"] == "初回の備考"
+        && c["new_value"].is_null()));
+
+    // 変更項目のない更新は拒否される
+    let empty = client
+        .patch(&document_url)
+        .header("If-Match", "\"3\"")
+        .json(&json!({ "updated_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(empty.status(), StatusCode::BAD_REQUEST);
+
+    // When: 文書を削除
+    let response = client
+        .delete(format!("{document_url}?changed_by=1&reason=重複登録"))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 無効になり、検索から外れてゴミ箱に入る
+    assert_eq!(response.status(), StatusCode::OK);
+    let deleted: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(deleted["is_active"], false);
+
+    let search: serde_json::Value = client
+        .get(format!("http://{addr}/api/documents?title=更新後の文書"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(search["total"], 0);
+
+    let trash: serde_json::Value = client
+        .get(format!("http://{addr}/api/documents/trash"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(trash["total"], 1);
+    assert_eq!(trash["documents"][0]["document"]["id"], id);
+    assert_eq!(trash["documents"][0]["reason"], "重複登録");
+
+    // 削除済みの文書は再削除・更新できない
+    let again = client
+        .delete(format!("{document_url}?changed_by=1"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(again.status(), StatusCode::CONFLICT);
+    let patch_deleted = client
+        .patch(&document_url)
+        .header("If-Match", deleted["version"].to_string())
+        .json(&json!({ "title": "削除後の更新", "updated_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(patch_deleted.status(), StatusCode::CONFLICT);
+
+    // When: ゴミ箱から復元
+    let response = client
+        .post(format!("{document_url}/restore"))
+        .json(&json!({ "changed_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 有効に戻り、ゴミ箱から外れる
+    assert_eq!(response.status(), StatusCode::OK);
+    let restored: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(restored["is_active"], true);
+
+    let trash: serde_json::Value = client
+        .get(format!("http://{addr}/api/documents/trash"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(trash["total"], 0);
+}
+
+#[tokio::test]
+async fn test_revisions_and_aliases_clearance_api() {
+    // Given: 旧番号を登録した情報クラスⅠの文書がある
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let created: CreatedDocumentWithNumber = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "版履歴機密テスト",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let id = created.document.id;
+    let response = client
+        .post(format!("http://{addr}/api/documents/{id}/aliases"))
+        .json(&json!({ "alias_number": "OLD-CONF-001", "created_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    sqlx::query("UPDATE documents SET internal_external = 'internal', importance_class = 'class1' WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    for resource in ["revisions", "aliases"] {
+        let get = |query: &str| {
+            client
+                .get(format!(
+                    "http://{addr}/api/documents/{id}/{resource}{query}"
+                ))
+                .send()
+        };
+
+        // When/Then: 機密レベルの足りない閲覧者・閲覧者不明には存在しないものとして扱う
+        assert_eq!(
+            get("?user_id=2").await.unwrap().status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(get("").await.unwrap().status(), StatusCode::NOT_FOUND);
+
+        // When/Then: 機密レベルを満たす閲覧者には一覧を返す
+        assert_eq!(get("?user_id=1").await.unwrap().status(), StatusCode::OK);
+    }
+
+    // GraphQLの版履歴も同じく閲覧者で絞り込む
+    let revisions_for = |user_id: i32| {
+        client
+            .post(format!("http://{addr}/graphql"))
+            .json(&json!({
+                "query": format!(
+                    "{{ documentRevisions(documentId: {id}, userId: {user_id}) {{ number }} }}"
+                )
+            }))
+            .send()
+    };
+    let body: serde_json::Value = revisions_for(2).await.unwrap().json().await.unwrap();
+    assert!(body["errors"].is_array(), "{body}");
+    let body: serde_json::Value = revisions_for(1).await.unwrap().json().await.unwrap();
+    assert!(body["errors"].is_null(), "{body}");
+}
+
+#[tokio::test]
+async fn test_document_trash_and_changes_clearance_api() {
+    // Given: 情報クラスⅠの文書と、社内かつ個人情報有の文書がある
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let mut ids = Vec::new();
+    for title in ["ゴミ箱機密テスト 機密", "ゴミ箱機密テスト 個人情報"] {
+        let created: CreatedDocumentWithNumber = client
+            .post(format!("http://{addr}/api/documents"))
+            .json(&json!({
+                "title": title,
+                "document_type_code": "TEC",
+                "department_code": "DEV",
+                "created_by": 1,
+                "created_date": "2025-08-17"
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        ids.push(created.document.id);
+    }
+    let (confidential_id, personal_id) = (ids[0], ids[1]);
+    sqlx::query("UPDATE documents SET internal_external = 'internal', importance_class = 'class1' WHERE id = ?")
+        .bind(confidential_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE documents SET internal_external = 'internal', personal_info = 'present' WHERE id = ?")
+        .bind(personal_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let personal_url = format!("http://{addr}/api/documents/{personal_id}");
+
+    // When: 一般社員（社内）がネットワークパスを変更
+    let response = client
+        .patch(format!("{personal_url}?user_id=2"))
+        .header("If-Match", "\"1\"")
+        .json(&json!({ "network_path": r"\\server01\restricted\personal", "updated_by": 2 }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 応答の文書では保存場所が伏せられる
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated: serde_json::Value = response.json().await.unwrap();
+    assert!(updated["network_path"].is_null());
+
+    // 変更履歴でもネットワークパスの変更前後の値が伏せられる
+    let changes_for = |id: i32, user_id: i32| {
+        client
+            .get(format!(
+                "http://{addr}/api/documents/{id}/changes?user_id={user_id}"
+            ))
+            .send()
+    };
+    let changes: serde_json::Value = changes_for(personal_id, 2)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let path_change = changes["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["field_name"] == "network_path")
+        .unwrap();
+    assert!(path_change["old_value"].is_null());
+    assert!(path_change["new_value"].is_null());
+
+    let changes: serde_json::Value = changes_for(personal_id, 1)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(changes["changes"].as_array().unwrap().iter().any(|c| {
+        c["field_name"] == "network_path" && c["new_value"] == r"\\server01\restricted\personal"
+    }));
+
+    // 閲覧できない文書の変更履歴は404
+    assert_eq!(
+        changes_for(confidential_id, 2).await.unwrap().status(),
+        StatusCode::NOT_FOUND
+    );
+
+    // When: 両方の文書を削除
+    let response = client
+        .delete(format!("{personal_url}?changed_by=1&user_id=2"))
+        .send()
+        .await
+        .unwrap();
+    let deleted: serde_json::Value = response.json().await.unwrap();
+    assert!(deleted["network_path"].is_null());
+    client
+        .delete(format!(
+            "http://{addr}/api/documents/{confidential_id}?changed_by=1"
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: ゴミ箱には閲覧できる文書だけが保存場所を伏せて表示される
+    let trash_for = |user_id: Option<i32>| {
+        let mut url = format!("http://{addr}/api/documents/trash");
+        if let Some(user_id) = user_id {
+            url.push_str(&format!("?user_id={user_id}"));
+        }
+        client.get(url).send()
+    };
+    let trash: serde_json::Value = trash_for(None).await.unwrap().json().await.unwrap();
+    assert_eq!(trash["total"], 0);
+    let trash: serde_json::Value = trash_for(Some(2)).await.unwrap().json().await.unwrap();
+    assert_eq!(trash["total"], 1);
+    assert_eq!(trash["documents"][0]["document"]["id"], personal_id);
+    assert!(trash["documents"][0]["document"]["network_path"].is_null());
+    let trash: serde_json::Value = trash_for(Some(1)).await.unwrap().json().await.unwrap();
+    assert_eq!(trash["total"], 2);
+
+    // 復元の応答でも保存場所が伏せられる
+    let response = client
+        .post(format!("{personal_url}/restore?user_id=2"))
+        .json(&json!({ "changed_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+    let restored: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(restored["is_active"], true);
+    assert!(restored["network_path"].is_null());
+}
+
+#[tokio::test]
+async fn test_optimistic_concurrency_with_etag_api() {
+    // Given: 文書があり、GETでETagを取得している
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let created: CreatedDocumentWithNumber = client
+        .post(format!("http://{addr}/api/documents"))
+        .json(&json!({
+            "title": "同時編集される文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let document_url = format!("http://{addr}/api/documents/{}", created.document.id);
+    let response = client.get(&document_url).send().await.unwrap();
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+    assert_eq!(etag, "\"1\"");
+
+    // When: If-Matchなしで更新
+    let response = client
+        .patch(&document_url)
+        .json(&json!({ "title": "条件なしの更新", "updated_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 428で拒否される
+    assert_eq!(response.status(), StatusCode::PRECONDITION_REQUIRED);
+
+    // When: 利用者Aが取得時のETagで更新し、続けて利用者Bが同じETagで更新
+    let first = client
+        .patch(&document_url)
+        .header("If-Match", &etag)
+        .json(&json!({ "title": "利用者Aの更新", "updated_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+    let second = client
+        .patch(&document_url)
+        .header("If-Match", &etag)
+        .json(&json!({ "title": "利用者Bの更新", "updated_by": 1 }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: Aの更新は新しいETagで返り、Bの更新は412と最新の文書で拒否される
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(first.headers()["etag"], "\"2\"");
+    assert_eq!(second.status(), StatusCode::PRECONDITION_FAILED);
+    let conflict: serde_json::Value = second.json().await.unwrap();
+    assert_eq!(conflict["current"]["title"], "利用者Aの更新");
+    assert_eq!(conflict["current"]["version"], 2);
+}
+
+#[tokio::test]
+async fn test_create_document_with_idempotency_key_api() {
+    // Given: Idempotency-Key付きの文書作成リクエスト
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let url = format!("http://{addr}/api/documents");
+    let body = json!({
+        "title": "再送される文書",
+        "document_type_code": "TEC",
+        "department_code": "DEV",
+        "created_by": 1,
+        "created_date": "2025-08-17"
+    });
+
+    // When: 同じキーで同じリクエストを2回送信
+    let first = client
+        .post(&url)
+        .header("Idempotency-Key", "create-2025-08-17-001")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(first.status(), StatusCode::CREATED);
+    assert!(first.headers().get("idempotent-replayed").is_none());
+    let first: CreatedDocumentWithNumber = first.json().await.unwrap();
+
+    let replay = client
+        .post(&url)
+        .header("Idempotency-Key", "create-2025-08-17-001")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 再送には最初の作成結果が返り、番号は消費されない
+    assert_eq!(replay.status(), StatusCode::CREATED);
+    assert_eq!(replay.headers()["idempotent-replayed"], "true");
+    let replay: CreatedDocumentWithNumber = replay.json().await.unwrap();
+    assert_eq!(replay.document.id, first.document.id);
+    assert_eq!(replay.document_number, "TEC-2508001");
+
+    let next: CreatedDocumentWithNumber = client
+        .post(&url)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(next.document_number, "TEC-2508002");
+
+    // When: 同じキーで内容の異なるリクエストを送信
+    let response = client
+        .post(&url)
+        .header("Idempotency-Key", "create-2025-08-17-001")
+        .json(&json!({
+            "title": "別の文書",
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 409で拒否される
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // When: 空白を含むキーを送信
+    let response = client
+        .post(&url)
+        .header("Idempotency-Key", "invalid key")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 400で拒否される
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Given: 保持期間が過ぎたキー
+    sqlx::query(
+        "UPDATE document_idempotency_keys SET expires_at = datetime('now', '-1 hour') WHERE idempotency_key = ?",
+    )
+    .bind("create-2025-08-17-001")
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // When: 同じキーで再送
+    let response = client
+        .post(&url)
+        .header("Idempotency-Key", "create-2025-08-17-001")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 新しい文書として作成される
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert!(response.headers().get("idempotent-replayed").is_none());
+    let recreated: CreatedDocumentWithNumber = response.json().await.unwrap();
+    assert_eq!(recreated.document_number, "TEC-2508003");
+}
+
+#[tokio::test]
+async fn test_create_document_with_interrupted_idempotency_key_api() {
+    // Given: 処理中のまま中断したIdempotency-Key
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let url = format!("http://{addr}/api/documents");
+    let body = json!({
+        "title": "中断された作成",
+        "document_type_code": "TEC",
+        "department_code": "DEV",
+        "created_by": 1,
+        "created_date": "2025-08-17"
+    });
+    let first: CreatedDocumentWithNumber = client
+        .post(&url)
+        .header("Idempotency-Key", "create-interrupted")
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let fingerprint: String = sqlx::query_scalar(
+        "SELECT request_fingerprint FROM document_idempotency_keys WHERE idempotency_key = ?",
+    )
+    .bind("create-interrupted")
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO document_idempotency_keys
+            (idempotency_key, request_fingerprint, expires_at, claimed_until)
+        VALUES (?, ?, datetime('now', '+1 day'), datetime('now', '+1 hour'))
+        "#,
+    )
+    .bind("create-in-progress")
+    .bind(&fingerprint)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // When: 確保の期限内に同じキーで再送
+    let response = client
+        .post(&url)
+        .header("Idempotency-Key", "create-in-progress")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 処理中として409で拒否される
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Given: 文書を作成しないまま確保の期限が過ぎた
+    sqlx::query(
+        "UPDATE document_idempotency_keys SET claimed_until = datetime('now', '-1 minute') WHERE idempotency_key = ?",
+    )
+    .bind("create-in-progress")
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // When: 同じキーで再送
+    let response = client
+        .post(&url)
+        .header("Idempotency-Key", "create-in-progress")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 確保を引き継いで新しい文書が作成される
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert!(response.headers().get("idempotent-replayed").is_none());
+    let taken_over: CreatedDocumentWithNumber = response.json().await.unwrap();
+    assert_eq!(taken_over.document_number, "TEC-2508002");
+
+    // Given: 文書の作成後、作成結果を保存する前に中断し、その後に優先度の高いルールが追加された
+    sqlx::query("UPDATE document_idempotency_keys SET response = NULL WHERE idempotency_key = ?")
+        .bind("create-interrupted")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO document_number_generation_rules
+             (rule_name, template, sequence_digits, department_code, document_type_codes,
+              effective_from, effective_until, priority)
+         VALUES ('新技術文書ルール', 'NEW-{年下2桁}{連番:4桁}', 4, 'DEV', '[\"TEC\"]',
+                 '2025-01-01', NULL, 0)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // When: 同じキーで再送
+    let response = client
+        .post(&url)
+        .header("Idempotency-Key", "create-interrupted")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 作成済みの文書が、作成時に採番したルールと連番で返り、重複して作成されない
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.headers()["idempotent-replayed"], "true");
+    let recovered: CreatedDocumentWithNumber = response.json().await.unwrap();
+    assert_eq!(recovered.document.id, first.document.id);
+    assert_eq!(recovered.document_number, "TEC-2508001");
+    assert_eq!(
+        recovered.generated_number.rule_id,
+        first.generated_number.rule_id
+    );
+    assert_eq!(
+        recovered.generated_number.template_used,
+        first.generated_number.template_used
+    );
+    assert_eq!(
+        recovered.generated_number.sequence_number,
+        first.generated_number.sequence_number
+    );
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM documents WHERE title = ?")
+        .bind("中断された作成")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    // Then: 作成結果が保存し直され、以降の再送も同じ文書を返す
+    let saved: Option<String> = sqlx::query_scalar(
+        "SELECT response FROM document_idempotency_keys WHERE idempotency_key = ?",
+    )
+    .bind("create-interrupted")
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(saved.is_some());
+}
+
+#[tokio::test]
+async fn test_bulk_create_documents_api() {
+    // Given: 一括登録する文書
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let url = format!("http://{addr}/api/documents/bulk");
+    let document = |title: &str, document_type_code: &str| {
+        json!({
+            "title": title,
+            "document_type_code": document_type_code,
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        })
+    };
+
+    // When: 全件一括モードで3件を登録
+    let response = client
+        .post(&url)
+        .json(&json!({
+            "documents": [
+                document("設計書", "TEC"),
+                document("試験計画書", "TEC"),
+                document("試験成績書", "TEC")
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 201でリクエストの順に採番される
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["mode"], "all_or_nothing");
+    assert_eq!(body["committed"], true);
+    assert_eq!(body["created_count"], 3);
+    let numbers: Vec<_> = body["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["created"]["document_number"].as_str().unwrap())
+        .collect();
+    assert_eq!(numbers, ["TEC-2508001", "TEC-2508002", "TEC-2508003"]);
+
+    // When: 全件一括モードで不正な行を含めて登録
+    let response = client
+        .post(&url)
+        .json(&json!({
+            "mode": "all_or_nothing",
+            "documents": [
+                document("報告書", "TEC"),
+                document("", "TEC"),
+                document("議事録", "UNKNOWN")
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 422で何も登録されず、全行にエラーが返る（正しい行は失敗した行を示す）
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["committed"], false);
+    assert_eq!(body["created_count"], 0);
+    assert_eq!(body["failed_count"], 3);
+    assert_eq!(body["results"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        body["results"][0]["error"],
+        "Rolled back because row 1 failed"
+    );
+    assert!(body["results"][0]["created"].is_null());
+    assert!(body["results"][1]["error"].is_string());
+    assert!(body["results"][2]["error"].is_string());
+
+    // When: ベストエフォートモードで同じ行を登録
+    let response = client
+        .post(&url)
+        .json(&json!({
+            "mode": "best_effort",
+            "documents": [
+                document("報告書", "TEC"),
+                document("", "TEC"),
+                document("議事録", "UNKNOWN")
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 200で正しい行だけが登録され、番号は全件一括の失敗で消費されていない
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["committed"], true);
+    assert_eq!(body["created_count"], 1);
+    assert_eq!(body["failed_count"], 2);
+    assert_eq!(
+        body["results"][0]["created"]["document_number"],
+        "TEC-2508004"
+    );
+    assert!(body["results"][1]["created"].is_null());
+
+    // When: 空のリストを登録
+    let response = client
+        .post(&url)
+        .json(&json!({ "documents": [] }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 400で拒否される
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_bulk_create_documents_all_or_nothing_returns_every_row() {
+    // Given: 採番・登録の途中で失敗する行を含む全件一括の登録
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    sqlx::query(
+        r#"
+        CREATE TRIGGER fail_document_insert BEFORE INSERT ON documents
+        WHEN NEW.title = '登録失敗'
+        BEGIN
+            SELECT RAISE(ABORT, 'insert rejected');
+        END
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let document = |title: &str| {
+        json!({
+            "title": title,
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        })
+    };
+
+    // When: 2行目が登録に失敗する4行を登録
+    let response = client
+        .post(format!("http://{addr}/api/documents/bulk"))
+        .json(&json!({
+            "mode": "all_or_nothing",
+            "documents": [
+                document("設計書"),
+                document("登録失敗"),
+                document("試験計画書"),
+                document("試験成績書")
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 入力の行ごとに1件ずつ結果が返り、取り消された行と未処理の行は失敗した行を示す
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["committed"], false);
+    assert_eq!(body["created_count"], 0);
+    assert_eq!(body["failed_count"], 4);
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    for (index, row) in results.iter().enumerate() {
+        assert_eq!(row["index"], index);
+        assert!(row["created"].is_null());
+        assert!(row["error"].is_string());
+    }
+    assert!(
+        results[1]["error"]
+            .as_str()
+            .unwrap()
+            .contains("insert rejected")
+    );
+    for index in [0, 2, 3] {
+        assert_eq!(results[index]["error"], "Rolled back because row 1 failed");
+    }
+
+    // Then: 何も登録されていない
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM documents WHERE title = ?")
+        .bind("設計書")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn test_bulk_create_documents_records_location_in_the_insert() {
+    // Given: 登録後に所在を書き換えると失敗する状態
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    sqlx::query(
+        r#"
+        CREATE TRIGGER fail_location_update BEFORE UPDATE OF department_code, network_path ON documents
+        BEGIN
+            SELECT RAISE(ABORT, 'location must be written by the insert');
+        END
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let document = |title: &str| {
+        json!({
+            "title": title,
+            "document_type_code": "TEC",
+            "department_code": "DEV",
+            "created_by": 1,
+            "created_date": "2025-08-17"
+        })
+    };
+
+    // When: 一括登録
+    let response = client
+        .post(format!("http://{addr}/api/documents/bulk"))
+        .json(&json!({ "documents": [document("設計書"), document("試験計画書")] }))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: 全行が部署とネットワークパス付きで登録される
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["created_count"], 2);
+    assert_eq!(body["failed_count"], 0);
+    let stored: Vec<(Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT department_code, network_path FROM documents WHERE title IN ('設計書', '試験計画書') ORDER BY id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(stored.len(), 2);
+    for (index, (department_code, network_path)) in stored.iter().enumerate() {
+        assert_eq!(department_code.as_deref(), Some("DEV"));
+        assert!(network_path.is_some());
+        assert_eq!(
+            network_path.as_deref(),
+            body["results"][index]["created"]["document"]["network_path"].as_str()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_employee_assignments_api() {
+    // Given: 開発部の課長（社員11）と、開発部の課
+    let addr = spawn_app().await;
+    let client = Client::new();
+    let section: serde_json::Value = client
+        .post(format!("http://{addr}/graphql"))
+        .json(&json!({
+            "query": r#"
+                mutation {
+                    createDepartment(input: {
+                        code: "S1", name: "システム課", kind: SECTION, parentId: 1,
+                        createdDate: "2020-04-01"
+                    }) { id kind }
+                }
+            "#
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(section["data"]["createDepartment"]["kind"], "SECTION");
+    let section_id = section["data"]["createDepartment"]["id"].as_i64().unwrap();
+    let timeline: serde_json::Value = client
+        .get(format!("http://{addr}/api/employees/11/assignments"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let current = &timeline["assignments"][0];
+    assert_eq!(current["department_code"], "DEV");
+    assert_eq!(current["assignment_type"], "primary");
+    let current_id = current["id"].as_i64().unwrap();
+
+    // When/Then: 管理者以外・操作者不明の所属の変更は拒否される
+    let transfer = json!({
+        "department_id": 2,
+        "position": "部長代理",
+        "start_date": "2024-04-01"
+    });
+    for url in [
+        format!("http://{addr}/api/employees/11/assignments"),
+        format!("http://{addr}/api/employees/11/assignments?user_id=2"),
+    ] {
+        let response = client.post(&url).json(&transfer).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+    let response = client
+        .put(format!(
+            "http://{addr}/api/employee-assignments/{current_id}?user_id=2"
+        ))
+        .json(current)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = client
+        .delete(format!(
+            "http://{addr}/api/employee-assignments/{current_id}?user_id=2"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // When/Then: 主務の期間が重なる異動は登録できない
+    let response = client
+        .post(format!(
+            "http://{addr}/api/employees/11/assignments?user_id=1"
+        ))
+        .json(&transfer)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // When: 元の主務に最終日を設定して異動し、開発部の課を兼務する
+    let mut ended = current.clone();
+    ended["end_date"] = json!("2024-03-31");
+    let response = client
+        .put(format!(
+            "http://{addr}/api/employee-assignments/{current_id}?user_id=1"
+        ))
+        .json(&ended)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client
+        .post(format!(
+            "http://{addr}/api/employees/11/assignments?user_id=1"
+        ))
+        .json(&transfer)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = client
+        .post(format!(
+            "http://{addr}/api/employees/11/assignments?user_id=1"
+        ))
+        .json(&json!({
+            "department_id": 1,
+            "section_id": section_id,
+            "assignment_type": "concurrent",
+            "start_date": "2024-04-01"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let concurrent: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(concurrent["section_code"], "S1");
+
+    // Then: 現在の社員情報は主務から求められ、履歴は時点で絞り込める
+    let employee: serde_json::Value = client
+        .get(format!("http://{addr}/api/employees/11"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(employee["department_id"], 2);
+    assert_eq!(employee["position"], "部長代理");
+    let timeline: serde_json::Value = client
+        .get(format!("http://{addr}/api/employees/11/assignments"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(timeline["assignments"].as_array().unwrap().len(), 3);
+    let past: serde_json::Value = client
+        .get(format!(
+            "http://{addr}/api/employees/11/assignments?as_of=2023-01-01"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(past["assignments"].as_array().unwrap().len(), 1);
+    assert_eq!(past["assignments"][0]["department_code"], "DEV");
+
+    // When/Then: 別の部署の課は指定できない
+    let response = client
+        .post(format!(
+            "http://{addr}/api/employees/11/assignments?user_id=1"
+        ))
+        .json(&json!({
+            "department_id": 3,
+            "section_id": section_id,
+            "assignment_type": "secondment",
+            "start_date": "2024-04-01"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // When/Then: 誤登録の所属は削除できる
+    let concurrent_id = concurrent["id"].as_i64().unwrap();
+    let response = client
+        .delete(format!(
+            "http://{addr}/api/employee-assignments/{concurrent_id}?user_id=1"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = client
+        .get(format!(
+            "http://{addr}/api/employee-assignments/{concurrent_id}"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_future_dated_primary_assignment_takes_effect_on_read() {
+    // Given: 開発部の社員11に、翌日付けで総務部（部署2）へ異動する主務を登録
+    let (addr, pool) = spawn_app_with_pool().await;
+    let client = Client::new();
+    let today = chrono::Local::now().date_naive();
+    let employee_count = |department_id: i32| {
+        let client = client.clone();
+        async move {
+            let body: serde_json::Value = client
+                .post(format!("http://{addr}/graphql"))
+                .json(&json!({
+                    "query": format!("{{ department(id: {department_id}) {{ employeeCount }} }}")
+                }))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            body["data"]["department"]["employeeCount"]
+                .as_i64()
+                .unwrap()
+        }
+    };
+    let before = (employee_count(1).await, employee_count(2).await);
+    let timeline: serde_json::Value = client
+        .get(format!("http://{addr}/api/employees/11/assignments"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let mut current = timeline["assignments"][0].clone();
+    current["end_date"] = json!(today);
+    let response = client
+        .put(format!(
+            "http://{addr}/api/employee-assignments/{}?user_id=1",
+            current["id"]
+        ))
+        .json(&current)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client
+        .post(format!(
+            "http://{addr}/api/employees/11/assignments?user_id=1"
+        ))
+        .json(&json!({
+            "department_id": 2,
+            "start_date": today.succ_opt().unwrap()
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Then: 開始日前は元の部署のまま
+    assert_eq!((employee_count(1).await, employee_count(2).await), before);
+
+    // When: 日付が進み、異動の開始日を迎える（期間を1日前にずらして再現する）
+    sqlx::query(
+        "UPDATE department_assignments SET start_date = date(start_date, '-1 day'), end_date = date(end_date, '-1 day') WHERE employee_id = 11",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // Then: 所属を書き換えなくても、読み取り時点の主務から部署が求められる
+    assert_eq!(
+        (employee_count(1).await, employee_count(2).await),
+        (before.0 - 1, before.1 + 1)
+    );
+    let employee: serde_json::Value = client
+        .get(format!("http://{addr}/api/employees/11"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(employee["department_id"], 2);
+}
@@ -2,6 +2,7 @@
 
 use chrono::{NaiveDate, Utc};
 use doc_man_db::models::document::*;
+use doc_man_db::models::{ImportanceClass, InternalExternal, PersonalInfo};
 
 #[test]
 fn test_document_create_request() {
@@ -12,9 +13,9 @@ fn test_document_create_request() {
         business_number: Some("BIZ-001".to_string()),
         created_by: 1,
         created_date: NaiveDate::from_ymd_opt(2024, 8, 19).unwrap(),
-        internal_external: Some("internal".to_string()),
-        importance_class: Some("normal".to_string()),
-        personal_info: Some("none".to_string()),
+        internal_external: Some(InternalExternal::Internal),
+        importance_class: Some(ImportanceClass::Class2),
+        personal_info: Some(PersonalInfo::None),
         notes: Some("テスト文書です".to_string()),
     };
 
@@ -29,6 +30,8 @@ fn test_document_update_request() {
     let request = UpdateDocumentRequest {
         title: Some("更新後文書".to_string()),
         document_type_id: Some(2),
+        updated_by: 1,
+        ..Default::default()
     };
 
     assert_eq!(request.title, Some("更新後文書".to_string()));
@@ -46,12 +49,13 @@ fn test_document_creation() {
         created_by: 1,
         created_by_name: Some("テストユーザー".to_string()),
         created_date: NaiveDate::from_ymd_opt(2024, 8, 19).unwrap(),
-        internal_external: Some("internal".to_string()),
-        importance_class: Some("high".to_string()),
-        personal_info: Some("none".to_string()),
+        internal_external: Some(InternalExternal::Internal),
+        importance_class: Some(ImportanceClass::Class1),
+        personal_info: Some(PersonalInfo::None),
         notes: Some("テスト内容".to_string()),
         network_path: Some("/test/path.pdf".to_string()),
         is_active: true,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -72,9 +76,9 @@ fn test_document_validation() {
         business_number: Some("BIZ-001".to_string()),
         created_by: 1,
         created_date: NaiveDate::from_ymd_opt(2024, 8, 19).unwrap(),
-        internal_external: Some("internal".to_string()),
-        importance_class: Some("normal".to_string()),
-        personal_info: Some("none".to_string()),
+        internal_external: Some(InternalExternal::Internal),
+        importance_class: Some(ImportanceClass::Class2),
+        personal_info: Some(PersonalInfo::None),
         notes: Some("有効な内容".to_string()),
     };
 
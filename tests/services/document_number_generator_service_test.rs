@@ -1,7 +1,8 @@
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use doc_man_db::models::{
-    CreateDocumentNumberGenerationRuleRequest, DocumentNumberGenerationRule, DocumentNumberRequest,
+    CreateDocumentNumberGenerationRuleRequest, DocumentNumberGenerationError,
+    DocumentNumberGenerationRule, DocumentNumberRequest, RuleSimulationRequest,
 };
 use doc_man_db::repositories::{DocumentNumberRuleRepository, RepositoryError};
 use doc_man_db::services::DocumentNumberGenerator;
@@ -32,7 +33,6 @@ impl TestDocumentNumberRuleRepository {
         self
     }
 
-    #[allow(dead_code)]
     fn with_existing_numbers(mut self, numbers: Vec<String>) -> Self {
         self.existing_numbers = numbers;
         self
@@ -50,6 +50,15 @@ impl DocumentNumberRuleRepository for TestDocumentNumberRuleRepository {
         Ok(self.rule.clone())
     }
 
+    async fn find_candidate_rules(
+        &self,
+        _document_type_code: &str,
+        _department_code: &str,
+        _date: NaiveDate,
+    ) -> Result<Vec<DocumentNumberGenerationRule>, RepositoryError> {
+        Ok(self.rule.clone().into_iter().collect())
+    }
+
     async fn get_next_sequence_number(
         &self,
         _rule_id: i32,
@@ -90,6 +99,38 @@ impl DocumentNumberRuleRepository for TestDocumentNumberRuleRepository {
     ) -> Result<(Vec<DocumentNumberGenerationRule>, i64), RepositoryError> {
         unimplemented!()
     }
+
+    async fn update_rule(
+        &self,
+        _id: i32,
+        _request: CreateDocumentNumberGenerationRuleRequest,
+    ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn retire_rule(
+        &self,
+        _id: i32,
+        _effective_until: NaiveDate,
+    ) -> Result<Option<DocumentNumberGenerationRule>, RepositoryError> {
+        unimplemented!()
+    }
+
+    async fn find_document_type_name(
+        &self,
+        _document_type_code: &str,
+    ) -> Result<Option<String>, RepositoryError> {
+        Ok(Some("技術文書".to_string()))
+    }
+
+    async fn resolve_organization_codes(
+        &self,
+        department_code: &str,
+        section_code: Option<String>,
+        _date: NaiveDate,
+    ) -> Result<(String, Option<String>), RepositoryError> {
+        Ok((department_code.to_string(), section_code))
+    }
 }
 
 #[tokio::test]
@@ -105,6 +146,7 @@ async fn test_generate_document_number_success() {
         effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
         effective_until: None,
         priority: 1,
+        reset_scope: Default::default(),
         created_at: chrono::Utc::now().naive_utc(),
         updated_at: chrono::Utc::now().naive_utc(),
     };
@@ -118,6 +160,7 @@ async fn test_generate_document_number_success() {
     let request = DocumentNumberRequest {
         document_type_code: "A".to_string(),
         department_code: "T".to_string(),
+        section_code: None,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
         created_by: 1,
     };
@@ -143,6 +186,7 @@ async fn test_generate_document_number_no_applicable_rule() {
     let request = DocumentNumberRequest {
         document_type_code: "Z".to_string(), // 存在しない文書種別
         department_code: "X".to_string(),    // 存在しない部署
+        section_code: None,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
         created_by: 1,
     };
@@ -167,6 +211,7 @@ async fn test_generate_historical_format_cta() {
         effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
         effective_until: None,
         priority: 1,
+        reset_scope: Default::default(),
         created_at: chrono::Utc::now().naive_utc(),
         updated_at: chrono::Utc::now().naive_utc(),
     };
@@ -180,6 +225,7 @@ async fn test_generate_historical_format_cta() {
     let request = DocumentNumberRequest {
         document_type_code: "A".to_string(),
         department_code: "C".to_string(),
+        section_code: None,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
         created_by: 1,
     };
@@ -194,3 +240,89 @@ async fn test_generate_historical_format_cta() {
     assert_eq!(generated.rule_id, 2);
     assert_eq!(generated.sequence_number, 8);
 }
+
+#[tokio::test]
+async fn test_generate_document_number_skips_existing_and_exhausts() {
+    // Given: 連番998・999が既に使用済みの3桁ルール
+    let rule = DocumentNumberGenerationRule {
+        id: 1,
+        rule_name: "技術部標準形式2025".to_string(),
+        template: "{部署コード}-{年下2桁}{連番:3桁}".to_string(),
+        sequence_digits: 3,
+        department_code: Some("T".to_string()),
+        document_type_codes: "[\"A\"]".to_string(),
+        effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        effective_until: None,
+        priority: 1,
+        reset_scope: Default::default(),
+        created_at: chrono::Utc::now().naive_utc(),
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let repo = TestDocumentNumberRuleRepository::new()
+        .with_rule(rule)
+        .with_sequence(998)
+        .with_existing_numbers(vec!["T-25998".to_string(), "T-25999".to_string()]);
+
+    let generator = DocumentNumberGenerator::new(repo);
+
+    let request = DocumentNumberRequest {
+        document_type_code: "A".to_string(),
+        department_code: "T".to_string(),
+        section_code: None,
+        created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
+        created_by: 1,
+    };
+
+    // When: 文書番号生成
+    let result = generator.generate_document_number(request).await;
+
+    // Then: 桁数の上限を超えるため連番枯渇エラーになる
+    assert!(matches!(
+        result,
+        Err(DocumentNumberGenerationError::SequenceExhausted)
+    ));
+}
+
+#[tokio::test]
+async fn test_simulate_lists_next_numbers_until_exhausted() {
+    // Given: 連番997・998の番号が使用済みの3桁ルール
+    let rule = DocumentNumberGenerationRule {
+        id: 1,
+        rule_name: "技術部標準形式2025".to_string(),
+        template: "{部署コード}-{年下2桁}{連番:3桁}".to_string(),
+        sequence_digits: 3,
+        department_code: Some("T".to_string()),
+        document_type_codes: "[\"A\"]".to_string(),
+        effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        effective_until: None,
+        priority: 1,
+        reset_scope: Default::default(),
+        created_at: chrono::Utc::now().naive_utc(),
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let repo = TestDocumentNumberRuleRepository::new()
+        .with_rule(rule)
+        .with_sequence(996)
+        .with_existing_numbers(vec!["T-25997".to_string(), "T-25998".to_string()]);
+
+    let generator = DocumentNumberGenerator::new(repo);
+
+    let request = RuleSimulationRequest {
+        document_type_code: "A".to_string(),
+        department_code: "T".to_string(),
+        section_code: None,
+        created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
+        count: Some(5),
+    };
+
+    // When: シミュレーション実行
+    let result = generator.simulate(&request).await.unwrap();
+
+    // Then: 使用済みの番号を読み飛ばし、連番の上限で打ち切られる
+    assert_eq!(result.selected_rule_id, Some(1));
+    assert_eq!(result.candidates.len(), 1);
+    assert_eq!(result.next_numbers, vec!["T-25996", "T-25999"]);
+    assert!(result.error.is_some());
+}
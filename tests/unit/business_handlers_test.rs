@@ -3,7 +3,7 @@ use doc_man_db::error::AppError;
 use doc_man_db::handlers::HealthHandler;
 use doc_man_db::models::{
     CreateDocumentWithNumberRequest, CreatedDocumentWithNumber, Document, DocumentSearchFilters,
-    GeneratedDocumentNumber,
+    GeneratedDocumentNumber, ImportanceClass, InternalExternal, PersonalInfo,
 };
 
 #[tokio::test]
@@ -62,8 +62,12 @@ async fn test_create_document_request_structure() {
         title: "Test Document".to_string(),
         document_type_code: "TECH".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 123,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     assert_eq!(request.title, "Test Document");
@@ -92,11 +96,20 @@ async fn test_document_not_found_error() {
 #[tokio::test]
 async fn test_document_search_filters_creation() {
     let filters = DocumentSearchFilters {
+        query: None,
         title: Some("Test".to_string()),
         document_type_id: Some(1),
         created_by: Some(123),
         created_date_from: Some(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()),
         created_date_to: Some(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()),
+        department_code: None,
+        include_historical_affiliations: false,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
+        max_clearance: None,
+        affiliation_employee_id: None,
         limit: 50,
         offset: 0,
     };
@@ -111,11 +124,20 @@ async fn test_document_search_filters_creation() {
 #[tokio::test]
 async fn test_document_search_filters_minimal() {
     let filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: None,
         created_date_to: None,
+        department_code: None,
+        include_historical_affiliations: false,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
+        max_clearance: None,
+        affiliation_employee_id: None,
         limit: 10,
         offset: 0,
     };
@@ -180,12 +202,13 @@ fn test_document_structure() {
         created_by: 123,
         created_by_name: Some("テストユーザー".to_string()),
         created_date: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
-        internal_external: Some("内部".to_string()),
-        importance_class: Some("重要".to_string()),
-        personal_info: Some("個人情報あり".to_string()),
+        internal_external: Some(InternalExternal::Internal),
+        importance_class: Some(ImportanceClass::Class1),
+        personal_info: Some(PersonalInfo::Present),
         notes: Some("テスト用ドキュメント".to_string()),
         network_path: Some("\\\\server\\docs\\T-25001.pdf".to_string()),
         is_active: true,
+        version: 1,
         created_at: now,
         updated_at: now,
     };
@@ -223,6 +246,7 @@ fn test_created_document_with_number_structure() {
             notes: None,
             network_path: None,
             is_active: true,
+            version: 1,
             created_at: now,
             updated_at: now,
         },
@@ -294,11 +318,20 @@ fn test_error_display_formats() {
 #[test]
 fn test_document_search_filters_default_values() {
     let filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: None,
         created_date_to: None,
+        department_code: None,
+        include_historical_affiliations: false,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
+        max_clearance: None,
+        affiliation_employee_id: None,
         limit: 50,
         offset: 0,
     };
@@ -314,11 +347,20 @@ fn test_document_search_filters_default_values() {
 #[test]
 fn test_document_search_filters_pagination() {
     let filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: None,
         created_date_to: None,
+        department_code: None,
+        include_historical_affiliations: false,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
+        max_clearance: None,
+        affiliation_employee_id: None,
         limit: 100,
         offset: 50,
     };
@@ -334,8 +376,12 @@ fn test_create_document_request_minimal() {
         title: "Minimal Document".to_string(),
         document_type_code: "MIN".to_string(),
         department_code: "TST".to_string(),
+        section_code: None,
         created_by: 456,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     assert_eq!(request.title, "Minimal Document");
@@ -366,6 +412,7 @@ fn test_document_minimal_fields() {
         notes: None,
         network_path: None,
         is_active: true,
+        version: 1,
         created_at: now,
         updated_at: now,
     };
@@ -389,8 +436,12 @@ fn test_create_document_request_validation_scenarios() {
         title: "Valid Title".to_string(),
         document_type_code: "VALID".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 123,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     assert!(!valid_request.title.trim().is_empty());
@@ -403,8 +454,12 @@ fn test_create_document_request_validation_scenarios() {
         title: "".to_string(),
         document_type_code: "VALID".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 123,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     assert!(empty_title_request.title.trim().is_empty()); // ビジネスロジックで検証される
@@ -418,8 +473,12 @@ fn test_document_business_logic_validation() {
         title: long_title.clone(),
         document_type_code: "TEST".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 123,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     assert_eq!(request.title.len(), 1000);
@@ -444,6 +503,7 @@ fn test_document_clone_and_debug() {
         notes: None,
         network_path: None,
         is_active: true,
+        version: 1,
         created_at: now,
         updated_at: now,
     };
@@ -0,0 +1,259 @@
+use doc_man_db::error::BusinessError;
+use doc_man_db::models::{CreateBusinessRequest, UpdateBusinessRequest};
+use doc_man_db::repositories::{BusinessRepository, SqliteBusinessRepository};
+use doc_man_db::seeds::{Environment, Seeder};
+use sqlx::SqlitePool;
+use std::path::Path;
+
+async fn create_test_business_repository() -> (SqliteBusinessRepository, SqlitePool) {
+    let pool = SqlitePool::connect(":memory:").await.unwrap();
+
+    // マイグレーションを実行
+    let migrator = sqlx::migrate::Migrator::new(Path::new("./migrations"))
+        .await
+        .unwrap();
+    migrator.run(&pool).await.unwrap();
+
+    // 作成者として社員データを投入
+    Seeder::new(pool.clone())
+        .seed_all(&Environment::Test, false, false)
+        .await
+        .unwrap();
+
+    (SqliteBusinessRepository::new(pool.clone()), pool)
+}
+
+fn business_request(business_number: Option<&str>) -> CreateBusinessRequest {
+    CreateBusinessRequest {
+        business_number: business_number.map(str::to_string),
+        name: "テスト業務".to_string(),
+        description: None,
+        customer_name: None,
+        start_date: None,
+        end_date: None,
+        status: None,
+        created_by: 1,
+    }
+}
+
+/// 指定範囲の番号を進行中の業務で埋める
+async fn fill_active_businesses(pool: &SqlitePool, from: i32, to: i32) {
+    sqlx::query(
+        r#"
+        WITH RECURSIVE numbers(n) AS (
+            SELECT ? UNION ALL SELECT n + 1 FROM numbers WHERE n < ?
+        )
+        INSERT INTO businesses (business_number, name, status, created_by)
+        SELECT CAST(n AS TEXT), '既存業務', 'active', 1 FROM numbers
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_business_number_allocated_sequentially_from_lower_bound() {
+    let (repository, _pool) = create_test_business_repository().await;
+
+    // Given: 業務が未登録
+    // When: 業務番号を指定せずに業務を2件作成
+    let first = repository.create(business_request(None)).await.unwrap();
+    let second = repository.create(business_request(None)).await.unwrap();
+
+    // Then: 下限の10000から順に割り当てられ、使用回数が記録される
+    assert_eq!(first.business_number, "10000");
+    assert_eq!(second.business_number, "10001");
+
+    let usage = repository
+        .get_business_number_usage("10000")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(usage.use_count, 1);
+    assert_eq!(usage.active_business_id, Some(first.id));
+
+    // 次の番号のプレビューでは採番位置が進まない
+    let preview = repository.generate_business_number().await.unwrap();
+    assert_eq!(preview.business_number, "10002");
+    assert_eq!(
+        repository
+            .generate_business_number()
+            .await
+            .unwrap()
+            .business_number,
+        "10002"
+    );
+    assert!(!preview.wrapped);
+    assert!(preview.capacity_warning.is_none());
+}
+
+#[tokio::test]
+async fn test_business_number_wraps_around_skipping_active_numbers() {
+    let (repository, pool) = create_test_business_repository().await;
+
+    // Given: 上限付近まで採番済みで、99990と10000を進行中の業務が保持している
+    sqlx::query("UPDATE business_number_allocator SET last_number = 99988 WHERE id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    repository
+        .create(business_request(Some("99990")))
+        .await
+        .unwrap();
+    repository
+        .create(business_request(Some("10000")))
+        .await
+        .unwrap();
+
+    // When: 自動採番で2件作成
+    let before_wrap = repository.create(business_request(None)).await.unwrap();
+    let preview = repository.generate_business_number().await.unwrap();
+    let after_wrap = repository.create(business_request(None)).await.unwrap();
+
+    // Then: 99989の次は保持中の番号を飛ばして10001へ循環する
+    assert_eq!(before_wrap.business_number, "99989");
+    assert_eq!(preview.business_number, "10001");
+    assert!(preview.wrapped);
+    assert_eq!(after_wrap.business_number, "10001");
+}
+
+#[tokio::test]
+async fn test_business_number_reused_after_business_completed() {
+    let (repository, _pool) = create_test_business_repository().await;
+
+    // Given: 番号10000を進行中の業務が保持している
+    let original = repository
+        .create(business_request(Some("10000")))
+        .await
+        .unwrap();
+
+    // When/Then: 進行中の間は同じ番号を使用できない
+    let duplicate = repository.create(business_request(Some("10000"))).await;
+    assert!(matches!(
+        duplicate,
+        Err(BusinessError::BusinessNumberExists(number)) if number == "10000"
+    ));
+
+    // When: 業務を完了した後に同じ番号で作成
+    repository
+        .update(
+            original.id,
+            UpdateBusinessRequest {
+                name: None,
+                description: None,
+                customer_name: None,
+                start_date: None,
+                end_date: None,
+                status: Some("completed".to_string()),
+            },
+            original.version,
+        )
+        .await
+        .unwrap();
+    let reused = repository
+        .create(business_request(Some("10000")))
+        .await
+        .unwrap();
+
+    // Then: 再利用され、使用回数が2になり、番号検索は進行中の業務を返す
+    let usage = repository
+        .get_business_number_usage("10000")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(usage.use_count, 2);
+    assert_eq!(usage.active_business_id, Some(reused.id));
+    assert_eq!(usage.business_ids, vec![reused.id, original.id]);
+
+    let found = repository
+        .get_by_business_number("10000")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, reused.id);
+
+    // 旧業務を再開すると番号が重複するため拒否される
+    let reopened = repository
+        .update(
+            original.id,
+            UpdateBusinessRequest {
+                name: None,
+                description: None,
+                customer_name: None,
+                start_date: None,
+                end_date: None,
+                status: Some("active".to_string()),
+            },
+            original.version + 1,
+        )
+        .await;
+    assert!(matches!(
+        reopened,
+        Err(BusinessError::BusinessNumberExists(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_business_number_capacity_warning_and_exhaustion() {
+    let (repository, pool) = create_test_business_repository().await;
+
+    // Given: 99000までを進行中の業務が保持している
+    fill_active_businesses(&pool, 10000, 99000).await;
+
+    // When: 次の番号を確認
+    let allocation = repository.generate_business_number().await.unwrap();
+
+    // Then: 空きが閾値を下回るため警告が付く
+    assert_eq!(allocation.business_number, "99001");
+    assert_eq!(allocation.remaining_capacity, 989);
+    assert!(allocation.capacity_warning.is_some());
+
+    // Given: 全番号が保持されている
+    fill_active_businesses(&pool, 99001, 99990).await;
+
+    // When/Then: 割り当て可能な番号がない
+    let result = repository.generate_business_number().await;
+    assert!(matches!(
+        result,
+        Err(BusinessError::BusinessNumberExhausted {
+            min: 10000,
+            max: 99990
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_update_business_with_stale_version_is_rejected() {
+    let (repository, _pool) = create_test_business_repository().await;
+
+    // Given: 取得時のバージョンで一度更新された業務
+    let original = repository.create(business_request(None)).await.unwrap();
+    let rename = |name: &str| UpdateBusinessRequest {
+        name: Some(name.to_string()),
+        description: None,
+        customer_name: None,
+        start_date: None,
+        end_date: None,
+        status: None,
+    };
+    let updated = repository
+        .update(original.id, rename("利用者Aの業務名"), original.version)
+        .await
+        .unwrap();
+    assert_eq!(updated.version, original.version + 1);
+
+    // When: 同じ取得時のバージョンで更新
+    let stale = repository
+        .update(original.id, rename("利用者Bの業務名"), original.version)
+        .await;
+
+    // Then: 現在のバージョンとともに拒否される
+    assert!(matches!(
+        stale,
+        Err(BusinessError::VersionMismatch { expected, current })
+            if expected == original.version && current == updated.version
+    ));
+}
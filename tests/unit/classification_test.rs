@@ -0,0 +1,51 @@
+use doc_man_db::models::{ImportanceClass, InternalExternal, PersonalInfo};
+
+#[test]
+fn test_classification_from_str_accepts_codes_and_labels() {
+    // Given/When/Then: 保存値・表示名・表記ゆれのいずれからも変換できる
+    assert_eq!(
+        "社内".parse::<InternalExternal>(),
+        Ok(InternalExternal::Internal)
+    );
+    assert_eq!(
+        " External ".parse::<InternalExternal>(),
+        Ok(InternalExternal::External)
+    );
+    assert_eq!(
+        "情報クラスⅠ".parse::<ImportanceClass>(),
+        Ok(ImportanceClass::Class1)
+    );
+    assert_eq!(
+        "情報クラスII".parse::<ImportanceClass>(),
+        Ok(ImportanceClass::Class2)
+    );
+    assert_eq!("なし".parse::<PersonalInfo>(), Ok(PersonalInfo::None));
+    assert_eq!("present".parse::<PersonalInfo>(), Ok(PersonalInfo::Present));
+}
+
+#[test]
+fn test_classification_from_str_rejects_unknown_values() {
+    // Given: 未知の値
+    let result = "極秘".parse::<ImportanceClass>();
+
+    // Then: 種別と値を含むエラーになる
+    let error = result.unwrap_err();
+    assert_eq!(error.kind, "importance_class");
+    assert_eq!(error.to_string(), "Unknown importance_class value: 極秘");
+}
+
+#[test]
+fn test_classification_labels_and_serialization() {
+    // Given/When/Then: 表示名は日本語、JSONは保存値で出力される
+    assert_eq!(InternalExternal::Internal.label(), "社内");
+    assert_eq!(ImportanceClass::Class2.to_string(), "情報クラスⅡ");
+    assert_eq!(PersonalInfo::Present.label(), "有");
+    assert_eq!(
+        serde_json::to_string(&ImportanceClass::Class1).unwrap(),
+        "\"class1\""
+    );
+    assert_eq!(
+        serde_json::from_str::<PersonalInfo>("\"無\"").unwrap(),
+        PersonalInfo::None
+    );
+}
@@ -7,6 +7,7 @@ fn test_document_number_request_creation() {
     let request = DocumentNumberRequest {
         document_type_code: "T".to_string(),
         department_code: "TECH".to_string(),
+        section_code: None,
         created_date: chrono::NaiveDate::from_ymd_opt(2024, 8, 19).unwrap(),
         created_by: 1,
     };
@@ -1,6 +1,7 @@
 use chrono::NaiveDate;
 use doc_man_db::models::{
-    CreateDocumentNumberGenerationRuleRequest, DocumentNumberRequest, DocumentValidationError,
+    CreateDocumentNumberGenerationRuleRequest, DocumentNumberGenerationRule, DocumentNumberRequest,
+    DocumentValidationError, SequenceResetScope, UpdateDocumentNumberGenerationRuleRequest,
 };
 
 #[test]
@@ -15,6 +16,7 @@ fn test_document_number_rule_creation_success() {
         effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
         effective_until: None,
         priority: 1,
+        reset_scope: Default::default(),
     };
 
     // When: バリデーション実行
@@ -36,6 +38,7 @@ fn test_document_number_rule_empty_name() {
         effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
         effective_until: None,
         priority: 1,
+        reset_scope: Default::default(),
     };
 
     // When: バリデーション実行
@@ -58,6 +61,7 @@ fn test_document_number_rule_empty_template() {
         effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
         effective_until: None,
         priority: 1,
+        reset_scope: Default::default(),
     };
 
     // When: バリデーション実行
@@ -80,6 +84,7 @@ fn test_document_number_rule_invalid_sequence_digits() {
         effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
         effective_until: None,
         priority: 1,
+        reset_scope: Default::default(),
     };
 
     // When: バリデーション実行
@@ -105,6 +110,7 @@ fn test_document_number_rule_empty_document_types() {
         effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
         effective_until: None,
         priority: 1,
+        reset_scope: Default::default(),
     };
 
     // When: バリデーション実行
@@ -124,6 +130,7 @@ fn test_document_number_request_validation_success() {
     let request = DocumentNumberRequest {
         document_type_code: "A".to_string(),
         department_code: "T".to_string(),
+        section_code: None,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
         created_by: 1,
     };
@@ -141,6 +148,7 @@ fn test_document_number_request_empty_document_type() {
     let request = DocumentNumberRequest {
         document_type_code: "".to_string(),
         department_code: "T".to_string(),
+        section_code: None,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
         created_by: 1,
     };
@@ -162,6 +170,7 @@ fn test_document_number_request_empty_department_code() {
     let request = DocumentNumberRequest {
         document_type_code: "A".to_string(),
         department_code: "".to_string(),
+        section_code: None,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
         created_by: 1,
     };
@@ -183,6 +192,7 @@ fn test_document_number_request_invalid_created_by() {
     let request = DocumentNumberRequest {
         document_type_code: "A".to_string(),
         department_code: "T".to_string(),
+        section_code: None,
         created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
         created_by: 0, // 無効（1以上であるべき）
     };
@@ -197,3 +207,79 @@ fn test_document_number_request_invalid_created_by() {
         DocumentValidationError::InvalidCreatedBy
     );
 }
+
+#[test]
+fn test_sequence_reset_scope_period() {
+    // Given: 年度末と年度初めの日付
+    let march = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+    let april = NaiveDate::from_ymd_opt(2026, 4, 1).unwrap();
+
+    // When/Then: リセット単位に応じた期間が求められる
+    assert_eq!(SequenceResetScope::Monthly.period(march), (2026, 3));
+    assert_eq!(SequenceResetScope::Yearly.period(march), (2026, 0));
+    assert_eq!(SequenceResetScope::FiscalYearly.period(march), (2025, 0));
+    assert_eq!(SequenceResetScope::FiscalYearly.period(april), (2026, 0));
+    assert_eq!(SequenceResetScope::Never.period(april), (0, 0));
+    assert_eq!(SequenceResetScope::default(), SequenceResetScope::Monthly);
+}
+
+#[test]
+fn test_document_number_rule_department_code_length() {
+    // Given: 1文字の部署コードを持つルール
+    let mut request = CreateDocumentNumberGenerationRuleRequest {
+        rule_name: "開発部標準形式".to_string(),
+        template: "{部署コード}-{年下2桁}{連番:3桁}".to_string(),
+        sequence_digits: 3,
+        department_code: Some("D".to_string()),
+        document_type_codes: vec!["TEC".to_string()],
+        effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        effective_until: None,
+        priority: 1,
+        reset_scope: Default::default(),
+    };
+
+    // When/Then: 1文字の部署コードは受け付ける
+    assert!(request.validate().is_ok());
+
+    // When/Then: 2文字以上はエラーになる
+    request.department_code = Some("DEV".to_string());
+    assert_matches::assert_matches!(
+        request.validate().unwrap_err(),
+        DocumentValidationError::InvalidDepartmentCodeLength
+    );
+}
+
+#[test]
+fn test_update_document_number_rule_clears_effective_until() {
+    // Given: 終了日が設定されたルール
+    let rule = DocumentNumberGenerationRule {
+        id: 1,
+        rule_name: "期間限定ルール".to_string(),
+        template: "{部署コード}-{年下2桁}{連番:3桁}".to_string(),
+        sequence_digits: 3,
+        department_code: Some("D".to_string()),
+        document_type_codes: r#"["TEC"]"#.to_string(),
+        effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        effective_until: NaiveDate::from_ymd_opt(2025, 12, 31),
+        priority: 1,
+        reset_scope: SequenceResetScope::Monthly,
+        created_at: chrono::Utc::now().naive_utc(),
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // When: 終了日を省略して更新
+    let omitted: UpdateDocumentNumberGenerationRuleRequest =
+        serde_json::from_str(r#"{"priority": 2}"#).unwrap();
+    let merged = omitted.apply_to(&rule).unwrap();
+
+    // Then: 終了日は変更されない
+    assert_eq!(merged.effective_until, rule.effective_until);
+
+    // When: 終了日にnullを指定して更新
+    let cleared: UpdateDocumentNumberGenerationRuleRequest =
+        serde_json::from_str(r#"{"effective_until": null}"#).unwrap();
+    let merged = cleared.apply_to(&rule).unwrap();
+
+    // Then: 終了日が消えて無期限になる
+    assert_eq!(merged.effective_until, None);
+}
@@ -159,6 +159,7 @@ async fn test_create_document_number_generation_rule_request() {
         effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
         effective_until: Some(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()),
         priority: 2,
+        reset_scope: Default::default(),
     };
 
     assert_eq!(request.rule_name, "テスト用ルール");
@@ -180,6 +181,7 @@ async fn test_document_number_generation_rule_creation() {
         effective_from: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
         effective_until: Some(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()),
         priority: 2,
+        reset_scope: Default::default(),
         created_at: DateTime::from_timestamp(1640995200, 0).unwrap().naive_utc(),
         updated_at: DateTime::from_timestamp(1640995200, 0).unwrap().naive_utc(),
     };
@@ -240,3 +242,165 @@ async fn test_repository_trait_methods() {
     // テスト成功 - 全てのメソッドが存在し、呼び出し可能
     assert!(true);
 }
+
+fn new_rule_request(
+    department_code: Option<&str>,
+    priority: i32,
+) -> CreateDocumentNumberGenerationRuleRequest {
+    CreateDocumentNumberGenerationRuleRequest {
+        rule_name: "品質文書ルール".to_string(),
+        template: "Q-{年下2桁}{連番:4桁}".to_string(),
+        sequence_digits: 4,
+        department_code: department_code.map(str::to_string),
+        document_type_codes: vec!["QMS".to_string()],
+        effective_from: NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(),
+        effective_until: None,
+        priority,
+        reset_scope: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_create_and_get_rule() {
+    let repository = create_test_rule_repository()
+        .await
+        .expect("Failed to create repository");
+
+    let created = repository
+        .create_rule(new_rule_request(Some("D"), 1))
+        .await
+        .expect("Failed to create rule");
+
+    assert_eq!(created.rule_name, "品質文書ルール");
+    assert_eq!(created.document_type_codes, r#"["QMS"]"#);
+    assert_eq!(created.parsed_document_type_codes(), vec!["QMS"]);
+
+    let fetched = repository.get_rule_by_id(created.id).await.unwrap();
+    assert_eq!(fetched.unwrap().template, "Q-{年下2桁}{連番:4桁}");
+
+    let missing = repository.get_rule_by_id(9999).await.unwrap();
+    assert!(missing.is_none());
+}
+
+#[tokio::test]
+async fn test_created_rule_is_applied_by_priority() {
+    let repository = create_test_rule_repository()
+        .await
+        .expect("Failed to create repository");
+
+    let low = repository
+        .create_rule(new_rule_request(Some("D"), 5))
+        .await
+        .unwrap();
+    let high = repository
+        .create_rule(new_rule_request(Some("D"), 2))
+        .await
+        .unwrap();
+    let generic = repository
+        .create_rule(new_rule_request(None, 1))
+        .await
+        .unwrap();
+
+    // 部署固有ルールの中で優先度が高いものが選ばれる
+    let rule = repository
+        .find_applicable_rule("QMS", "D", NaiveDate::from_ymd_opt(2025, 6, 1).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(rule.id, high.id);
+    assert_ne!(rule.id, low.id);
+
+    // 他部署は汎用ルールにフォールバック
+    let rule = repository
+        .find_applicable_rule("QMS", "SALES", NaiveDate::from_ymd_opt(2025, 6, 1).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(rule.id, generic.id);
+
+    // 文書種別コードは完全一致で判定される（"QM"は"QMS"にマッチしない）
+    let rule = repository
+        .find_applicable_rule("QM", "D", NaiveDate::from_ymd_opt(2025, 6, 1).unwrap())
+        .await
+        .unwrap();
+    assert!(rule.is_none());
+}
+
+#[tokio::test]
+async fn test_search_rules() {
+    let repository = create_test_rule_repository()
+        .await
+        .expect("Failed to create repository");
+
+    let (rules, total) = repository
+        .search_rules(Some("DEV".to_string()), None, 50, 0)
+        .await
+        .unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(rules.len(), 2);
+
+    let (_rules, total) = repository
+        .search_rules(
+            None,
+            Some(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()),
+            50,
+            0,
+        )
+        .await
+        .unwrap();
+    assert_eq!(total, 0);
+
+    let (rules, total) = repository.search_rules(None, None, 1, 0).await.unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(rules.len(), 1);
+}
+
+#[tokio::test]
+async fn test_update_and_retire_rule() {
+    let repository = create_test_rule_repository()
+        .await
+        .expect("Failed to create repository");
+
+    let created = repository
+        .create_rule(new_rule_request(Some("D"), 3))
+        .await
+        .unwrap();
+
+    let mut request = new_rule_request(Some("D"), 1);
+    request.rule_name = "品質文書ルール改".to_string();
+    let updated = repository
+        .update_rule(created.id, request)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.rule_name, "品質文書ルール改");
+    assert_eq!(updated.priority, 1);
+
+    let retired = repository
+        .retire_rule(created.id, NaiveDate::from_ymd_opt(2025, 9, 30).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        retired.effective_until,
+        Some(NaiveDate::from_ymd_opt(2025, 9, 30).unwrap())
+    );
+
+    // 廃止日以降は適用されない
+    let rule = repository
+        .find_applicable_rule("QMS", "D", NaiveDate::from_ymd_opt(2025, 10, 1).unwrap())
+        .await
+        .unwrap();
+    assert!(rule.is_none());
+
+    // 有効開始日以前の廃止日は拒否される
+    let result = repository
+        .retire_rule(created.id, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap())
+        .await;
+    assert!(matches!(result, Err(RepositoryError::Validation(_))));
+
+    let missing = repository
+        .update_rule(9999, new_rule_request(None, 1))
+        .await;
+    assert!(missing.unwrap().is_none());
+}
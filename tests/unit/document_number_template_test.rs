@@ -0,0 +1,352 @@
+use chrono::NaiveDate;
+use doc_man_db::models::{DocumentNumberGenerationRule, SequenceResetScope, TemplateCollisionKind};
+use doc_man_db::services::{
+    NumberTemplateContext, decompose_document_number, find_colliding_number,
+    find_shared_counter_collision, parse_document_number, render_document_number,
+    validate_number_template,
+};
+
+fn rule(
+    template: &str,
+    department_code: Option<&str>,
+    codes: &str,
+) -> DocumentNumberGenerationRule {
+    DocumentNumberGenerationRule {
+        id: 1,
+        rule_name: "テストルール".to_string(),
+        template: template.to_string(),
+        sequence_digits: 3,
+        department_code: department_code.map(str::to_string),
+        document_type_codes: codes.to_string(),
+        effective_from: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        effective_until: None,
+        priority: 1,
+        reset_scope: Default::default(),
+        created_at: chrono::Utc::now().naive_utc(),
+        updated_at: chrono::Utc::now().naive_utc(),
+    }
+}
+
+#[test]
+fn test_parse_document_number_with_type_code_and_month() {
+    // Given: 文書種別コード・年月・連番のテンプレート
+    let rule = rule(
+        "{文書種別コード}-{年下2桁}{月:2桁}{連番:3桁}",
+        Some("DEV"),
+        "[\"TEC\",\"BUS\"]",
+    );
+
+    // When: 番号を分解
+    let parsed = parse_document_number(&rule, "TEC-2508012").unwrap();
+
+    // Then: 各構成要素が取り出される
+    assert_eq!(parsed.document_type_code.as_deref(), Some("TEC"));
+    assert_eq!(parsed.year, Some(2025));
+    assert_eq!(parsed.month, Some(8));
+    assert_eq!(parsed.sequence_number, 12);
+    assert_eq!(parsed.department_code, None);
+}
+
+#[test]
+fn test_parse_document_number_with_free_department_code() {
+    // Given: 全部署共通の部署コード付きテンプレート
+    let rule = rule("{部署コード}-{年下2桁}{連番:3桁}", None, "[\"A\"]");
+
+    // When/Then: 部署コードが可変長で照合される
+    let parsed = parse_document_number(&rule, "QA-25007").unwrap();
+    assert_eq!(parsed.department_code.as_deref(), Some("QA"));
+    assert_eq!(parsed.year, Some(2025));
+    assert_eq!(parsed.sequence_number, 7);
+}
+
+#[test]
+fn test_parse_document_number_rejects_non_matching() {
+    // Given: 文書種別コード・年月・連番のテンプレート
+    let rule = rule(
+        "{文書種別コード}-{年下2桁}{月:2桁}{連番:3桁}",
+        None,
+        "[\"TEC\"]",
+    );
+
+    // When/Then: 形式が異なる番号は分解されない
+    assert!(parse_document_number(&rule, "BUS-2508001").is_none());
+    assert!(parse_document_number(&rule, "TEC-2513001").is_none());
+    assert!(parse_document_number(&rule, "TEC-25080001").is_none());
+    assert!(parse_document_number(&rule, "LEGACY-0001").is_none());
+}
+
+fn context(created_date: NaiveDate) -> NumberTemplateContext {
+    NumberTemplateContext {
+        document_type_code: "TEC".to_string(),
+        document_type_name: Some("技術文書".to_string()),
+        department_code: "DEV".to_string(),
+        section_code: Some("D1".to_string()),
+        created_date,
+    }
+}
+
+#[test]
+fn test_render_document_number_with_extended_placeholders() {
+    // Given: 2026年2月（令和8年、2025年度）作成の文書
+    let context = context(NaiveDate::from_ymd_opt(2026, 2, 10).unwrap());
+
+    // When/Then: 各プレースホルダーが展開される
+    let render = |template: &str| render_document_number(template, &context, 7).unwrap();
+    assert_eq!(render("{年}-{連番:3桁}"), "2026-007");
+    assert_eq!(render("{年:4桁}{月:2桁}"), "202602");
+    assert_eq!(render("{年度}/{年度下2桁}"), "2025/25");
+    assert_eq!(render("{元号}{和暦年}年"), "令和8年");
+    assert_eq!(render("{元号略}{和暦年:2桁}"), "R08");
+    assert_eq!(render("{部署コード}{課コード}"), "DEVD1");
+    assert_eq!(render("{文書種別名}"), "技術文書");
+    assert_eq!(render("{{{文書種別コード}}}-{{連番}}"), "{TEC}-{連番}");
+}
+
+#[test]
+fn test_render_document_number_at_era_boundary() {
+    // Given: 改元前後の日付
+    let heisei = context(NaiveDate::from_ymd_opt(2019, 4, 30).unwrap());
+    let reiwa = context(NaiveDate::from_ymd_opt(2019, 5, 1).unwrap());
+
+    // When/Then: 改元日から新しい元号になる（元年は1年と表記）
+    assert_eq!(
+        render_document_number("{元号}{和暦年}", &heisei, 1).unwrap(),
+        "平成31"
+    );
+    assert_eq!(
+        render_document_number("{元号略}{和暦年}", &reiwa, 1).unwrap(),
+        "R1"
+    );
+}
+
+#[test]
+fn test_render_document_number_requires_section_code() {
+    // Given: 課コードの無い文書
+    let mut context = context(NaiveDate::from_ymd_opt(2025, 8, 17).unwrap());
+    context.section_code = None;
+
+    // When/Then: 課コードを使うテンプレートは展開できない
+    assert!(render_document_number("{課コード}-{連番:3桁}", &context, 1).is_err());
+}
+
+#[test]
+fn test_validate_number_template() {
+    // Given/When/Then: 既知のプレースホルダーとエスケープした波括弧は受け付ける
+    assert!(validate_number_template("{元号略}{和暦年:2桁}-{{{課コード}}}-{連番:3桁}", 3).is_ok());
+
+    // 未知のプレースホルダー・閉じ忘れ・単独の閉じ括弧・桁数の不一致は拒否する
+    assert!(validate_number_template("{部署}-{連番:3桁}", 3).is_err());
+    assert!(validate_number_template("{年-{連番:3桁}", 3).is_err());
+    assert!(validate_number_template("A}-{連番:3桁}", 3).is_err());
+    assert!(validate_number_template("{年}-{連番:4桁}", 3).is_err());
+}
+
+#[test]
+fn test_validate_number_template_requires_one_sequence() {
+    // Given/When/Then: 連番のないテンプレートは毎回同じ番号になるため拒否する
+    assert!(validate_number_template("{部署コード}-{年}", 3).is_err());
+
+    // 連番が複数あるテンプレートも拒否する
+    assert!(validate_number_template("{連番:3桁}-{連番:3桁}", 3).is_err());
+
+    // 連番がちょうど1つなら受け付ける
+    assert!(validate_number_template("{部署コード}-{年}-{連番:3桁}", 3).is_ok());
+}
+
+#[test]
+fn test_parse_document_number_with_era_and_fiscal_year() {
+    // Given: 和暦・年度を含むテンプレート
+    let era_rule = rule(
+        "{元号略}{和暦年:2桁}-{課コード}-{連番:3桁}",
+        None,
+        "[\"TEC\"]",
+    );
+    let fiscal_rule = rule("{年度下2桁}{月:2桁}-{連番:3桁}", None, "[\"TEC\"]");
+
+    // When: 番号を分解
+    let era = parse_document_number(&era_rule, "R08-D1-012").unwrap();
+    let fiscal = parse_document_number(&fiscal_rule, "2502-003").unwrap();
+
+    // Then: 西暦年は和暦・年度から求められる
+    assert_eq!(era.era.as_deref(), Some("令和"));
+    assert_eq!(era.era_year, Some(8));
+    assert_eq!(era.year, Some(2026));
+    assert_eq!(era.section_code.as_deref(), Some("D1"));
+    assert_eq!(era.sequence_number, 12);
+    assert_eq!(fiscal.fiscal_year, Some(2025));
+    assert_eq!(fiscal.year, Some(2026));
+    assert_eq!(fiscal.month, Some(2));
+}
+
+#[test]
+fn test_parse_document_number_with_short_year_from_1900s() {
+    // Given: 年・年度を下2桁で表すテンプレート
+    let year_rule = rule(
+        "{文書種別コード}-{年下2桁}{月:2桁}{連番:3桁}",
+        None,
+        "[\"CTA\"]",
+    );
+    let fiscal_rule = rule("{年度下2桁}{月:2桁}-{連番:3桁}", None, "[\"TEC\"]");
+
+    // When: 1990年代に採番された番号を分解
+    let year = parse_document_number(&year_rule, "CTA-9512001").unwrap();
+    let fiscal = parse_document_number(&fiscal_rule, "9803-004").unwrap();
+
+    // Then: 2095年ではなく1995年として扱われる
+    assert_eq!(year.year, Some(1995));
+    assert_eq!(fiscal.fiscal_year, Some(1998));
+    assert_eq!(fiscal.year, Some(1999));
+
+    // 近い将来の年は2000年代のまま
+    let year = parse_document_number(&year_rule, "CTA-3001001").unwrap();
+    assert_eq!(year.year, Some(2030));
+}
+
+#[test]
+fn test_find_colliding_number() {
+    // Given: 部署固有ルールと、同じ文書種別を含む汎用ルール
+    let dev = rule(
+        "{文書種別コード}-{年下2桁}{月:2桁}{連番:3桁}",
+        Some("DEV"),
+        "[\"TEC\"]",
+    );
+    let generic = rule(
+        "{文書種別コード}-{年下2桁}{月:2桁}{連番:3桁}",
+        None,
+        "[\"BUS\",\"TEC\"]",
+    );
+
+    // When/Then: 同じ番号を生成し得るため、その例が返される
+    assert_eq!(
+        find_colliding_number(&dev, &generic).as_deref(),
+        Some("TEC-0000000")
+    );
+
+    // 文書種別コードが異なれば衝突しない
+    let bus = rule(
+        "{文書種別コード}-{年下2桁}{月:2桁}{連番:3桁}",
+        Some("DEV"),
+        "[\"BUS\"]",
+    );
+    assert_eq!(find_colliding_number(&dev, &bus), None);
+
+    // 部署コードが固定されたルール同士は部署が違えば衝突しない
+    let qa = rule("{部署コード}-{年}{連番:3桁}", Some("QA"), "[\"TEC\"]");
+    let hr = rule("{部署コード}-{年}{連番:3桁}", Some("HR"), "[\"TEC\"]");
+    assert_eq!(find_colliding_number(&qa, &hr), None);
+
+    // 汎用ルールの部署コードはどの部署とも衝突し得る
+    let any_department = rule("{部署コード}-{年}{連番:3桁}", None, "[\"BUS\"]");
+    assert_eq!(
+        find_colliding_number(&qa, &any_department).as_deref(),
+        Some("QA-0000000")
+    );
+
+    // 桁数の違いで長さが合わなければ衝突しない
+    let longer = rule("{部署コード}-{年}{連番:4桁}", Some("QA"), "[\"BUS\"]");
+    assert_eq!(find_colliding_number(&qa, &longer), None);
+}
+
+#[test]
+fn test_find_shared_counter_collision() {
+    // Given: 部署ごとに連番を数える汎用ルールで、テンプレートに部署コードが無い
+    let generic = rule(
+        "{文書種別コード}-{年下2桁}{月:2桁}{連番:3桁}",
+        None,
+        "[\"TEC\"]",
+    );
+
+    // When/Then: 部署間で同じ番号が生成され得る
+    assert_eq!(
+        find_shared_counter_collision(&generic),
+        Some(TemplateCollisionKind::DepartmentCounter)
+    );
+
+    // 月ごとにリセットするが月を含まないテンプレートは期間を区別できない
+    let yearly_template = rule("{部署コード}-{年}-{連番:3桁}", Some("QA"), "[\"TEC\"]");
+    assert_eq!(
+        find_shared_counter_collision(&yearly_template),
+        Some(TemplateCollisionKind::ResetPeriod)
+    );
+
+    // リセット単位に合ったテンプレートなら衝突しない
+    let mut yearly = yearly_template.clone();
+    yearly.reset_scope = SequenceResetScope::Yearly;
+    assert_eq!(find_shared_counter_collision(&yearly), None);
+
+    let mut fiscal = rule("{部署コード}-{年度}-{連番:3桁}", Some("QA"), "[\"TEC\"]");
+    fiscal.reset_scope = SequenceResetScope::FiscalYearly;
+    assert_eq!(find_shared_counter_collision(&fiscal), None);
+}
+
+#[test]
+fn test_decompose_document_number_across_rules() {
+    // Given: 旧形式（CTA部・文書種別名）と現行形式のルール
+    let mut cta = rule(
+        "CTA-{年下2桁}{月:2桁}{連番:3桁}",
+        Some("C"),
+        "[\"A\",\"B\"]",
+    );
+    cta.id = 1;
+    cta.effective_until = Some(NaiveDate::from_ymd_opt(2025, 3, 31).unwrap());
+    let mut legacy = rule("{文書種別名}-{年下2桁}{連番:3桁}", Some("T"), "[\"TEC\"]");
+    legacy.id = 2;
+    legacy.reset_scope = SequenceResetScope::Yearly;
+    let mut generic = rule("{部署コード}-{年下2桁}{月:2桁}{連番:3桁}", None, "[\"A\"]");
+    generic.id = 3;
+    generic.priority = 9;
+    let rules = vec![generic, legacy, cta];
+
+    // When: 旧CTA形式の番号を逆引き
+    let matches = decompose_document_number(&rules, "CTA-2508001", None);
+
+    // Then: 部署コードを含む汎用ルールにも一致するが、番号の時点で有効なルールが先になる
+    let ids: Vec<i32> = matches.iter().map(|m| m.rule_id).collect();
+    assert_eq!(ids, vec![3, 1]);
+    assert_eq!(matches[0].effective, Some(true));
+    assert_eq!(
+        matches[0].components.department_code.as_deref(),
+        Some("CTA")
+    );
+    assert_eq!(matches[1].effective, Some(false));
+    assert_eq!(matches[1].components.department_code.as_deref(), Some("C"));
+    assert_eq!(matches[1].components.document_type_code, None);
+    let key = matches[1].sequence_key.as_ref().unwrap();
+    assert_eq!(
+        (key.year, key.month, key.department_code.as_str()),
+        (2025, 8, "C")
+    );
+
+    // When: 文書種別名を含む旧形式の番号を逆引き
+    let matches = decompose_document_number(&rules, "技術-25001", None);
+
+    // Then: 種別名・種別コード・暦年のカウンターが求められる
+    assert_eq!(matches.len(), 1);
+    let components = &matches[0].components;
+    assert_eq!(components.document_type_name.as_deref(), Some("技術"));
+    assert_eq!(components.document_type_code.as_deref(), Some("TEC"));
+    assert_eq!(components.year, Some(2025));
+    assert_eq!(components.sequence_number, 1);
+    let key = matches[0].sequence_key.as_ref().unwrap();
+    assert_eq!((key.rule_id, key.year, key.month), (2, 2025, 0));
+    assert_eq!(matches[0].effective, Some(true));
+
+    // 部署が決まらない全部署共通ルールは、指定した部署をカウンターの部署とする
+    let mut no_department = rule("X-{年下2桁}{月:2桁}{連番:3桁}", None, "[\"A\"]");
+    no_department.id = 4;
+    let matches =
+        decompose_document_number(std::slice::from_ref(&no_department), "X-2508001", None);
+    assert!(matches[0].sequence_key.is_none());
+    let matches = decompose_document_number(
+        std::slice::from_ref(&no_department),
+        "X-2508001",
+        Some("HR"),
+    );
+    assert_eq!(
+        matches[0].sequence_key.as_ref().unwrap().department_code,
+        "HR"
+    );
+
+    // どのルールにも一致しない番号
+    assert!(decompose_document_number(&rules, "ZZZ", None).is_empty());
+}
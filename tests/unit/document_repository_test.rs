@@ -2,6 +2,7 @@
 
 use chrono::NaiveDate;
 use doc_man_db::models::document::{CreateDocumentRequest, UpdateDocumentRequest};
+use doc_man_db::models::{ImportanceClass, InternalExternal, PersonalInfo};
 
 #[test]
 fn test_create_document_request_validation() {
@@ -12,9 +13,9 @@ fn test_create_document_request_validation() {
         business_number: Some("JOB-2024-001".to_string()),
         created_by: 1,
         created_date: NaiveDate::from_ymd_opt(2024, 12, 15).unwrap(),
-        internal_external: Some("internal".to_string()),
-        importance_class: Some("class2".to_string()),
-        personal_info: Some("none".to_string()),
+        internal_external: Some(InternalExternal::Internal),
+        importance_class: Some(ImportanceClass::Class2),
+        personal_info: Some(PersonalInfo::None),
         notes: Some("テスト用文書".to_string()),
     };
 
@@ -28,6 +29,8 @@ fn test_update_document_request_creation() {
     let update_request = UpdateDocumentRequest {
         title: Some("更新後文書".to_string()),
         document_type_id: Some(2),
+        updated_by: 1,
+        ..Default::default()
     };
 
     assert_eq!(update_request.title, Some("更新後文書".to_string()));
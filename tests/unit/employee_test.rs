@@ -1,6 +1,7 @@
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use doc_man_db::models::{
-    CreateEmployeeRequest, Employee, EmployeeSearchQuery, UpdateEmployeeRequest,
+    AssignmentType, CreateEmployeeRequest, Employee, EmployeeAssignmentRequest,
+    EmployeeSearchQuery, UpdateEmployeeRequest,
 };
 
 #[test]
@@ -13,6 +14,8 @@ fn test_employee_creation() {
         email: Some("tanaka@example.com".to_string()),
         ad_username: Some("tanaka.taro".to_string()),
         department_id: Some(10),
+        section_id: None,
+        position: None,
         is_active: true,
         created_at: now,
         updated_at: now,
@@ -39,6 +42,8 @@ fn test_employee_minimal_creation() {
         email: None,
         ad_username: None,
         department_id: None,
+        section_id: None,
+        position: None,
         is_active: false,
         created_at: now,
         updated_at: now,
@@ -63,6 +68,8 @@ fn test_employee_serialization() {
         email: Some("tanaka@example.com".to_string()),
         ad_username: Some("tanaka.taro".to_string()),
         department_id: Some(10),
+        section_id: None,
+        position: None,
         is_active: true,
         created_at: now,
         updated_at: now,
@@ -280,6 +287,8 @@ fn test_employee_clone() {
         email: Some("tanaka@example.com".to_string()),
         ad_username: Some("tanaka.taro".to_string()),
         department_id: Some(10),
+        section_id: None,
+        position: None,
         is_active: true,
         created_at: now,
         updated_at: now,
@@ -302,6 +311,8 @@ fn test_employee_debug_format() {
         email: Some("tanaka@example.com".to_string()),
         ad_username: Some("tanaka.taro".to_string()),
         department_id: Some(10),
+        section_id: None,
+        position: None,
         is_active: true,
         created_at: now,
         updated_at: now,
@@ -341,3 +352,31 @@ fn test_employee_validation_scenarios() {
     assert_eq!(request_long.name.len(), 3000); // UTF-8で1文字3バイト
     assert_eq!(request_long.name, long_name);
 }
+
+#[test]
+fn test_employee_assignment_request_validation() {
+    // Given: 主務の所属リクエスト
+    let request: EmployeeAssignmentRequest = serde_json::from_value(serde_json::json!({
+        "department_id": 1,
+        "position": "課長",
+        "start_date": "2024-04-01",
+        "end_date": "2025-03-31"
+    }))
+    .unwrap();
+
+    // Then: 種別の既定は主務で、正しい期間は受け付ける
+    assert_eq!(request.assignment_type, AssignmentType::Primary);
+    assert!(request.validate().is_ok());
+
+    // When/Then: 最終日が開始日より前、または役職が空白の場合はエラー
+    let reversed = EmployeeAssignmentRequest {
+        end_date: NaiveDate::from_ymd_opt(2024, 3, 31),
+        ..request.clone()
+    };
+    assert!(reversed.validate().is_err());
+    let blank_position = EmployeeAssignmentRequest {
+        position: Some(" ".to_string()),
+        ..request
+    };
+    assert!(blank_position.validate().is_err());
+}
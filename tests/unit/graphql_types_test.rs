@@ -4,6 +4,7 @@ use doc_man_db::graphql::types::{
     GeneratedDocumentNumber, SearchDocumentsResult,
 };
 use doc_man_db::models;
+use doc_man_db::models::{ImportanceClass, InternalExternal, PersonalInfo};
 
 #[test]
 fn test_document_from_model_conversion() {
@@ -17,12 +18,13 @@ fn test_document_from_model_conversion() {
         created_by: 123,
         created_by_name: Some("テストユーザー".to_string()),
         created_date: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
-        internal_external: Some("内部".to_string()),
-        importance_class: Some("重要".to_string()),
-        personal_info: Some("個人情報あり".to_string()),
+        internal_external: Some(InternalExternal::Internal),
+        importance_class: Some(ImportanceClass::Class1),
+        personal_info: Some(PersonalInfo::Present),
         notes: Some("テスト用ドキュメント".to_string()),
         network_path: Some("\\\\server\\docs\\T-25001.pdf".to_string()),
         is_active: true,
+        version: 1,
         created_at: now,
         updated_at: now,
     };
@@ -56,6 +58,7 @@ fn test_document_date_formatting() {
         notes: None,
         network_path: None,
         is_active: true,
+        version: 1,
         created_at: now,
         updated_at: now,
     };
@@ -75,8 +78,12 @@ fn test_create_document_input_conversion() {
         title: "New Document".to_string(),
         document_type_code: "TECH".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 789,
         created_date: "2025-08-20".to_string(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     let request: models::CreateDocumentWithNumberRequest = input.into();
@@ -97,8 +104,12 @@ fn test_create_document_input_invalid_date() {
         title: "New Document".to_string(),
         document_type_code: "TECH".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 789,
         created_date: "invalid-date".to_string(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     let request: models::CreateDocumentWithNumberRequest = input.into();
@@ -117,11 +128,18 @@ fn test_create_document_input_invalid_date() {
 #[test]
 fn test_document_search_filters_conversion() {
     let graphql_filters = DocumentSearchFilters {
+        query: None,
         title: Some("Test".to_string()),
         document_type_id: Some(1),
         created_by: Some(123),
         created_date_from: Some("2025-01-01".to_string()),
         created_date_to: Some("2025-12-31".to_string()),
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: Some(50),
         offset: Some(0),
     };
@@ -146,11 +164,18 @@ fn test_document_search_filters_conversion() {
 #[test]
 fn test_document_search_filters_minimal() {
     let graphql_filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: None,
         created_date_to: None,
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: Some(10),
         offset: Some(0),
     };
@@ -169,11 +194,18 @@ fn test_document_search_filters_minimal() {
 #[test]
 fn test_document_search_filters_invalid_date_from() {
     let graphql_filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: Some("invalid-date".to_string()),
         created_date_to: None,
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: Some(10),
         offset: Some(0),
     };
@@ -186,11 +218,18 @@ fn test_document_search_filters_invalid_date_from() {
 #[test]
 fn test_document_search_filters_invalid_date_to() {
     let graphql_filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: None,
         created_date_to: Some("invalid-date".to_string()),
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: Some(10),
         offset: Some(0),
     };
@@ -239,6 +278,7 @@ fn test_created_document_with_number_conversion() {
             notes: None,
             network_path: None,
             is_active: true,
+            version: 1,
             created_at: now,
             updated_at: now,
         },
@@ -280,6 +320,7 @@ fn test_search_documents_result_creation() {
             notes: None,
             network_path: None,
             is_active: true,
+            version: 1,
             created_at: now,
             updated_at: now,
         },
@@ -298,6 +339,7 @@ fn test_search_documents_result_creation() {
             notes: None,
             network_path: None,
             is_active: true,
+            version: 1,
             created_at: now,
             updated_at: now,
         },
@@ -306,6 +348,8 @@ fn test_search_documents_result_creation() {
     let result = SearchDocumentsResult {
         documents: documents.into_iter().map(|d| d.into()).collect(),
         total: 2,
+        hits: Vec::new(),
+        aggregations: None,
     };
 
     assert_eq!(result.documents.len(), 2);
@@ -321,6 +365,8 @@ fn test_search_documents_result_empty() {
     let result = SearchDocumentsResult {
         documents: vec![],
         total: 0,
+        hits: Vec::new(),
+        aggregations: None,
     };
 
     assert_eq!(result.documents.len(), 0);
@@ -334,8 +380,12 @@ fn test_create_document_input_validation_scenarios() {
         title: "Valid Document".to_string(),
         document_type_code: "VALID".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 123,
         created_date: "2025-08-20".to_string(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     let request: models::CreateDocumentWithNumberRequest = valid_input.into();
@@ -349,8 +399,12 @@ fn test_create_document_input_validation_scenarios() {
         title: "".to_string(),
         document_type_code: "TEST".to_string(),
         department_code: "DEV".to_string(),
+        section_code: None,
         created_by: 123,
         created_date: "2025-08-20".to_string(),
+        internal_external: None,
+        importance_class: None,
+        personal_info: None,
     };
 
     let empty_request: models::CreateDocumentWithNumberRequest = empty_title_input.into();
@@ -361,11 +415,18 @@ fn test_create_document_input_validation_scenarios() {
 fn test_document_search_filters_date_edge_cases() {
     // 境界値テスト: 年始と年末
     let filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: Some("2025-01-01".to_string()),
         created_date_to: Some("2025-12-31".to_string()),
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: Some(100),
         offset: Some(0),
     };
@@ -382,11 +443,18 @@ fn test_document_search_filters_date_edge_cases() {
 
     // うるう年のテスト
     let leap_year_filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: Some("2024-02-29".to_string()),
         created_date_to: Some("2024-02-29".to_string()),
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: Some(10),
         offset: Some(0),
     };
@@ -406,11 +474,18 @@ fn test_document_search_filters_date_edge_cases() {
 fn test_document_search_filters_limit_offset_boundaries() {
     // 最大制限値のテスト
     let max_filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: None,
         created_date_to: None,
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: Some(1000),
         offset: Some(9999),
     };
@@ -421,11 +496,18 @@ fn test_document_search_filters_limit_offset_boundaries() {
 
     // 最小値のテスト
     let min_filters = DocumentSearchFilters {
+        query: None,
         title: None,
         document_type_id: None,
         created_by: None,
         created_date_from: None,
         created_date_to: None,
+        department_code: None,
+        include_historical_affiliations: None,
+        year: None,
+        importance_class: None,
+        internal_external: None,
+        business_number: None,
         limit: Some(1),
         offset: Some(0),
     };
@@ -448,14 +530,15 @@ fn test_document_conversion_with_long_strings() {
         created_by: 999999,
         created_by_name: Some("テストユーザー".to_string()),
         created_date: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
-        internal_external: Some("非常に長い内部外部分類情報".to_string()),
-        importance_class: Some("最重要機密".to_string()),
-        personal_info: Some("大量の個人情報を含む文書".to_string()),
+        internal_external: Some(InternalExternal::Internal),
+        importance_class: Some(ImportanceClass::Class1),
+        personal_info: Some(PersonalInfo::Present),
         notes: Some("非常に詳細な注釈情報".to_string()),
         network_path: Some(
             "\\\\very-long-server-name\\very\\long\\path\\to\\document.pdf".to_string(),
         ),
         is_active: true,
+        version: 1,
         created_at: now,
         updated_at: now,
     };
@@ -3,9 +3,12 @@
 mod app_test;
 mod basic_error_test;
 mod business_handlers_test;
+mod business_repository_test;
+mod classification_test;
 mod document_number_basic_test;
 mod document_number_generation_test;
 mod document_number_rule_repository_test;
+mod document_number_template_test;
 mod document_repository_test;
 mod employee_test;
 mod error_test;
@@ -13,4 +16,5 @@ mod graphql_resolvers_test;
 mod graphql_schema_test;
 mod graphql_types_test;
 mod lib_test;
+mod network_path_template_test;
 mod routes_test;
@@ -0,0 +1,56 @@
+use chrono::NaiveDate;
+use doc_man_db::models::DocumentPathContext;
+use doc_man_db::services::{render_network_path, validate_path_template};
+
+fn context() -> DocumentPathContext {
+    DocumentPathContext {
+        document_number: "TEC-2508012".to_string(),
+        department_code: "DEV".to_string(),
+        department_name: Some("開発部".to_string()),
+        document_type_code: "TEC".to_string(),
+        document_type_name: "技術文書".to_string(),
+        created_date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(),
+    }
+}
+
+#[test]
+fn test_render_network_path_appends_document_number() {
+    // Given: 文書番号を含まないテンプレート
+    let template = r"\\server01\docs\{年}\{部署名}\{文書種別名}\";
+
+    // When: パスを生成
+    let path = render_network_path(template, &context()).unwrap();
+
+    // Then: 末尾に文書番号のフォルダが付く
+    assert_eq!(path, r"\\server01\docs\2025\開発部\技術文書\TEC-2508012");
+}
+
+#[test]
+fn test_render_network_path_with_explicit_document_number() {
+    // Given: 文書番号の位置を指定したテンプレート
+    let template = r"\\server01\{部署コード}\{年下2桁}{月}\{文書番号}_{文書種別コード}";
+
+    // When/Then: 指定位置に埋め込まれ、末尾には追加されない
+    assert_eq!(
+        render_network_path(template, &context()).unwrap(),
+        r"\\server01\DEV\2508\TEC-2508012_TEC"
+    );
+}
+
+#[test]
+fn test_render_network_path_requires_department_name() {
+    // Given: 部署マスタに存在しない部署
+    let mut context = context();
+    context.department_name = None;
+
+    // When/Then: 部署名を使うテンプレートはエラー、使わなければ生成できる
+    assert!(render_network_path(r"\\server01\{部署名}", &context).is_err());
+    assert!(render_network_path(r"\\server01\{部署コード}", &context).is_ok());
+}
+
+#[test]
+fn test_validate_path_template() {
+    assert!(validate_path_template(r"\\server01\docs\{年}\{部署名}").is_ok());
+    assert!(validate_path_template(r"\\server01\docs\{年度}").is_err());
+    assert!(validate_path_template(r"\\server01\docs\{年").is_err());
+}
@@ -1,3 +1,6 @@
 // ユニットテストの統合
 
+// 構造確認用のプレースホルダーテストで定数アサーションを使用している
+#![allow(clippy::assertions_on_constants, clippy::const_is_empty)]
+
 mod unit;